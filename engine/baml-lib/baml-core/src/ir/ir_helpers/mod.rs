@@ -10,6 +10,7 @@ use crate::{
     ir::{
         repr::{IntermediateRepr, Walker},
         Class, Client, Enum, EnumValue, Field, FunctionNode, RetryPolicy, TemplateString, TestCase,
+        TypeAlias,
     },
 };
 use anyhow::Result;
@@ -30,10 +31,13 @@ pub type ClientWalker<'a> = Walker<'a, &'a Client>;
 pub type RetryPolicyWalker<'a> = Walker<'a, &'a RetryPolicy>;
 pub type TestCaseWalker<'a> = Walker<'a, (&'a FunctionNode, &'a TestCase)>;
 pub type ClassFieldWalker<'a> = Walker<'a, &'a Field>;
+pub type TypeAliasWalker<'a> = Walker<'a, &'a TypeAlias>;
 
 pub trait IRHelper {
     fn find_enum<'a>(&'a self, enum_name: &str) -> Result<EnumWalker<'a>>;
     fn find_class<'a>(&'a self, class_name: &str) -> Result<ClassWalker<'a>>;
+    fn find_type_alias<'a>(&'a self, alias_name: &str) -> Result<TypeAliasWalker<'a>>;
+    fn find_recursive_alias_target<'a>(&'a self, alias_name: &str) -> Result<&'a FieldType>;
     fn find_function<'a>(&'a self, function_name: &str) -> Result<FunctionWalker<'a>>;
     fn find_client<'a>(&'a self, client_name: &str) -> Result<ClientWalker<'a>>;
     fn find_retry_policy<'a>(&'a self, retry_policy_name: &str) -> Result<RetryPolicyWalker<'a>>;
@@ -106,6 +110,39 @@ impl IRHelper for IntermediateRepr {
         }
     }
 
+    fn find_type_alias<'a>(&'a self, alias_name: &str) -> Result<TypeAliasWalker<'a>> {
+        match self.walk_type_aliases().find(|a| a.name() == alias_name) {
+            Some(a) => Ok(a),
+            None => {
+                // Get best match.
+                let aliases = self
+                    .walk_type_aliases()
+                    .map(|a| a.name())
+                    .collect::<Vec<_>>();
+                error_not_found!("type alias", alias_name, &aliases)
+            }
+        }
+    }
+
+    fn find_recursive_alias_target<'a>(&'a self, alias_name: &str) -> Result<&'a FieldType> {
+        match self
+            .structural_recursive_alias_cycles()
+            .iter()
+            .find_map(|cycle| cycle.get(alias_name))
+        {
+            Some(target) => Ok(target),
+            None => {
+                // Get best match.
+                let aliases = self
+                    .structural_recursive_alias_cycles()
+                    .iter()
+                    .flat_map(|cycle| cycle.keys().map(String::as_str))
+                    .collect::<Vec<_>>();
+                error_not_found!("recursive type alias", alias_name, &aliases)
+            }
+        }
+    }
+
     fn find_function<'a>(&'a self, function_name: &str) -> Result<FunctionWalker<'a>> {
         match self.walk_functions().find(|f| f.name() == function_name) {
             Some(f) => match f.item.elem {
@@ -212,6 +249,11 @@ impl IRHelper for IntermediateRepr {
         value: BamlValue,
         field_type: FieldType,
     ) -> anyhow::Result<BamlValueWithMeta<FieldType>> {
+        if let FieldType::RecursiveTypeAlias(name) = &field_type {
+            let target = self.find_recursive_alias_target(name)?.clone();
+            return self.distribute_type(value, target);
+        }
+
         match value {
             BamlValue::String(s) => {
                 let literal_type = FieldType::Literal(LiteralValue::String(s.clone()));
@@ -239,7 +281,8 @@ impl IRHelper for IntermediateRepr {
             }
 
             BamlValue::Float(f)
-                if FieldType::Primitive(TypeValue::Float).is_subtype_of(&field_type) =>
+                if FieldType::Literal(LiteralValue::Float(f)).is_subtype_of(&field_type)
+                    || FieldType::Primitive(TypeValue::Float).is_subtype_of(&field_type) =>
             {
                 Ok(BamlValueWithMeta::Float(f, field_type))
             }
@@ -301,6 +344,16 @@ impl IRHelper for IntermediateRepr {
             }
 
             BamlValue::List(items) => {
+                if let Some(tuple_types) = tuple_item_types(&field_type, items.len()) {
+                    let tuple_types = tuple_types.to_vec();
+                    let mapped_items = items
+                        .into_iter()
+                        .zip(tuple_types)
+                        .map(|(i, item_type)| self.distribute_type(i, item_type))
+                        .collect::<anyhow::Result<Vec<_>>>()?;
+                    return Ok(BamlValueWithMeta::List(mapped_items, field_type));
+                }
+
                 let item_types = items
                     .iter()
                     .filter_map(|v| infer_type(v))
@@ -337,6 +390,13 @@ impl IRHelper for IntermediateRepr {
             }
             BamlValue::Media(_) => anyhow::bail!("Could not unify Media with {:?}", field_type),
 
+            BamlValue::Message(m) if FieldType::message().is_subtype_of(&field_type) => {
+                Ok(BamlValueWithMeta::Message(m, field_type))
+            }
+            BamlValue::Message(_) => {
+                anyhow::bail!("Could not unify Message with {:?}", field_type)
+            }
+
             BamlValue::Enum(name, val) => {
                 if FieldType::Enum(name.clone()).is_subtype_of(&field_type) {
                     Ok(BamlValueWithMeta::Enum(name, val, field_type))
@@ -440,6 +500,20 @@ impl IRHelper for IntermediateRepr {
 
 const UNIT_TYPE: FieldType = FieldType::Tuple(vec![]);
 
+/// The item types of the tuple of `len` items that `field_type` accepts, if
+/// any. Tuples are represented as lists in `BamlValue`, so this is how a list
+/// finds the type of each of its items.
+fn tuple_item_types(field_type: &FieldType, len: usize) -> Option<&[FieldType]> {
+    match field_type {
+        FieldType::Tuple(items) if items.len() == len => Some(items),
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => {
+            tuple_item_types(inner, len)
+        }
+        FieldType::Union(options) => options.iter().find_map(|o| tuple_item_types(o, len)),
+        _ => None,
+    }
+}
+
 /// Derive the simplest type that can categorize a given value. This is meant to be used
 /// by `distribute_type`, for dynamic fields of classes, whose types are not known statically.
 pub fn infer_type<'a>(value: &'a BamlValue) -> Option<FieldType> {
@@ -477,6 +551,7 @@ pub fn infer_type<'a>(value: &'a BamlValue) -> Option<FieldType> {
             Some(FieldType::List(Box::new(item_ty)))
         }
         BamlValue::Media(m) => Some(FieldType::Primitive(TypeValue::Media(m.media_type))),
+        BamlValue::Message(_) => Some(FieldType::message()),
         BamlValue::Enum(enum_name, _) => Some(FieldType::Enum(enum_name.clone())),
         BamlValue::Class(class_name, _) => Some(FieldType::Class(class_name.clone())),
     };
@@ -574,6 +649,20 @@ mod tests {
         assert_eq!(value.meta(), &int_type());
     }
 
+    #[test]
+    fn distribute_tuple() {
+        let ir = mk_ir();
+        let value = BamlValue::List(vec![mk_int(1), BamlValue::String("a".to_string())]);
+        let tuple_type = FieldType::Tuple(vec![int_type(), string_type()]);
+        let value = ir.distribute_type(value, tuple_type.clone()).unwrap();
+        assert_eq!(value.meta(), &tuple_type);
+        let BamlValueWithMeta::List(items, _) = value else {
+            panic!("Expected a list");
+        };
+        assert_eq!(items[0].meta(), &int_type());
+        assert_eq!(items[1].meta(), &string_type());
+    }
+
     #[test]
     fn distribute_media() {
         let ir = mk_ir();
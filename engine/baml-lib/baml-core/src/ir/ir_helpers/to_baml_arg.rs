@@ -1,6 +1,6 @@
 use baml_types::{
-    BamlMap, BamlValue, BamlValueWithMeta, Constraint, ConstraintLevel, FieldType, LiteralValue,
-    TypeValue,
+    BamlMap, BamlMediaType, BamlMessage, BamlMessagePart, BamlValue, BamlValueWithMeta, Constraint,
+    ConstraintLevel, FieldType, LiteralValue, TypeValue,
 };
 use core::result::Result;
 use std::path::PathBuf;
@@ -169,6 +169,7 @@ impl ArgCoercer {
                         Err(())
                     }
                 },
+                TypeValue::Message => self.coerce_message(ir, value, scope),
                 _ => {
                     scope.push_error(format!("Expected type {:?}, got `{}`", t, value));
                     Err(())
@@ -207,6 +208,11 @@ impl ArgCoercer {
                 (LiteralValue::String(lit), BamlValue::String(baml)) if lit == baml => {
                     value.clone()
                 }
+                (LiteralValue::Float(lit), BamlValue::Float(baml)) if lit == baml => value.clone(),
+                // Clients without a separate float type may send whole numbers as ints.
+                (LiteralValue::Float(lit), BamlValue::Int(baml)) if *lit == *baml as f64 => {
+                    BamlValue::Float(*lit)
+                }
                 (LiteralValue::Bool(lit), BamlValue::Bool(baml)) if lit == baml => value.clone(),
                 _ => {
                     scope.push_error(format!("Expected literal {:?}, got `{}`", literal, value));
@@ -263,6 +269,15 @@ impl ArgCoercer {
                     Err(())
                 }
             },
+            (FieldType::RecursiveTypeAlias(name), _) => {
+                match ir.find_recursive_alias_target(name) {
+                    Ok(target) => self.coerce_arg(ir, target, value, scope),
+                    Err(_) => {
+                        scope.push_error(format!("Type alias {} not found", name));
+                        Err(())
+                    }
+                }
+            }
             (FieldType::List(item), _) => match value {
                 BamlValue::List(arr) => {
                     let mut items = Vec::new();
@@ -278,10 +293,29 @@ impl ArgCoercer {
                     Err(())
                 }
             },
-            (FieldType::Tuple(_), _) => {
-                scope.push_error(format!("Tuples are not yet supported"));
-                Err(())
-            }
+            (FieldType::Tuple(items), _) => match value {
+                BamlValue::List(arr) if arr.len() == items.len() => {
+                    let mut values = Vec::new();
+                    for (item, v) in items.iter().zip(arr) {
+                        if let Ok(v) = self.coerce_arg(ir, item, v, scope) {
+                            values.push(v);
+                        }
+                    }
+                    Ok(BamlValue::List(values))
+                }
+                BamlValue::List(arr) => {
+                    scope.push_error(format!(
+                        "Expected a tuple of {} items, got {} items",
+                        items.len(),
+                        arr.len()
+                    ));
+                    Err(())
+                }
+                _ => {
+                    scope.push_error(format!("Expected tuple, got `{}`", value));
+                    Err(())
+                }
+            },
             (FieldType::Map(k, v), _) => {
                 if let BamlValue::Map(kv) = value {
                     let mut map = BamlMap::new();
@@ -361,6 +395,119 @@ impl ArgCoercer {
     }
 }
 
+impl ArgCoercer {
+    /// Accepts `{ role, content }`, where content is either a string or a
+    /// list of strings and media. Media parts can be passed directly, or as
+    /// `{ image { ... } }` / `{ audio { ... } }` from BAML test blocks.
+    fn coerce_message(
+        &self,
+        ir: &IntermediateRepr,
+        value: &BamlValue,
+        scope: &mut ScopeStack,
+    ) -> Result<BamlValue, ()> {
+        let kv = match value {
+            BamlValue::Message(_) => return Ok(value.clone()),
+            BamlValue::Map(kv) | BamlValue::Class(_, kv) => kv,
+            _ => {
+                scope.push_error(format!("Expected message, got `{}`", value));
+                return Err(());
+            }
+        };
+
+        for key in kv.keys() {
+            if !["role", "content"].contains(&key.as_str()) {
+                scope.push_error(format!(
+                    "Invalid property `{}` on message: `role` and `content` are the only supported properties",
+                    key
+                ));
+            }
+        }
+
+        let role = match kv.get("role") {
+            Some(BamlValue::String(role)) => role.clone(),
+            Some(other) => {
+                scope.push_error(format!(
+                    "Invalid property `role` on message: expected string, got {}",
+                    other.r#type()
+                ));
+                return Err(());
+            }
+            None => {
+                scope.push_error("Missing required property `role` on message".to_string());
+                return Err(());
+            }
+        };
+
+        let content = match kv.get("content") {
+            Some(BamlValue::String(text)) => vec![BamlMessagePart::Text(text.clone())],
+            Some(BamlValue::List(parts)) => {
+                scope.push("content".to_string());
+                let parts = parts
+                    .iter()
+                    .filter_map(|part| self.coerce_message_part(ir, part, scope))
+                    .collect();
+                scope.pop(false);
+                parts
+            }
+            Some(other) => {
+                scope.push_error(format!(
+                    "Invalid property `content` on message: expected string or list, got {}",
+                    other.r#type()
+                ));
+                return Err(());
+            }
+            None => {
+                scope.push_error("Missing required property `content` on message".to_string());
+                return Err(());
+            }
+        };
+
+        Ok(BamlValue::Message(BamlMessage::new(role, content)))
+    }
+
+    fn coerce_message_part(
+        &self,
+        ir: &IntermediateRepr,
+        part: &BamlValue,
+        scope: &mut ScopeStack,
+    ) -> Option<BamlMessagePart> {
+        match part {
+            BamlValue::String(text) => Some(BamlMessagePart::Text(text.clone())),
+            BamlValue::Media(media) => Some(BamlMessagePart::Media(media.clone())),
+            BamlValue::Map(kv) if kv.len() == 1 => {
+                let (key, media) = kv.iter().next()?;
+                let media_type = match key.as_str() {
+                    "image" => BamlMediaType::Image,
+                    "audio" => BamlMediaType::Audio,
+                    _ => {
+                        scope.push_error(format!(
+                            "Invalid message part `{}`: expected `image` or `audio`",
+                            key
+                        ));
+                        return None;
+                    }
+                };
+                match self.coerce_arg(
+                    ir,
+                    &FieldType::Primitive(TypeValue::Media(media_type)),
+                    media,
+                    scope,
+                ) {
+                    Ok(BamlValue::Media(media)) => Some(BamlMessagePart::Media(media)),
+                    _ => None,
+                }
+            }
+            _ => {
+                scope.push_error(format!(
+                    "Invalid message part: expected string, image or audio, got `{}`",
+                    part
+                ));
+                None
+            }
+        }
+    }
+}
+
 /// Search a potentially deeply-nested `BamlValue` for any failing asserts,
 /// returning the first one encountered.
 fn first_failing_assert_nested<'a>(
@@ -443,4 +590,49 @@ mod tests {
         let res = arg_coercer.coerce_arg(&ir, &type_, &value, &mut ScopeStack::new());
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_tuple_argument() {
+        let ir = make_test_ir("").unwrap();
+        let type_ = FieldType::Tuple(vec![
+            FieldType::Primitive(TypeValue::Int),
+            FieldType::Primitive(TypeValue::String),
+        ]);
+        let arg_coercer = ArgCoercer {
+            span_path: None,
+            allow_implicit_cast_to_string: false,
+        };
+
+        let value = BamlValue::List(vec![BamlValue::Int(1), BamlValue::String("a".to_string())]);
+        let res = arg_coercer.coerce_arg(&ir, &type_, &value, &mut ScopeStack::new());
+        assert_eq!(res, Ok(value));
+
+        let too_short = BamlValue::List(vec![BamlValue::Int(1)]);
+        let mut scope = ScopeStack::new();
+        let res = arg_coercer.coerce_arg(&ir, &type_, &too_short, &mut scope);
+        assert!(res.is_err());
+        assert!(scope.has_errors());
+    }
+
+    #[test]
+    fn test_float_literal_argument() {
+        let ir = make_test_ir("").unwrap();
+        let type_ = FieldType::literal_float(-1.0);
+        let arg_coercer = ArgCoercer {
+            span_path: None,
+            allow_implicit_cast_to_string: false,
+        };
+
+        let value = BamlValue::Float(-1.0);
+        let res = arg_coercer.coerce_arg(&ir, &type_, &value, &mut ScopeStack::new());
+        assert_eq!(res, Ok(value.clone()));
+
+        let whole = BamlValue::Int(-1);
+        let res = arg_coercer.coerce_arg(&ir, &type_, &whole, &mut ScopeStack::new());
+        assert_eq!(res, Ok(value));
+
+        let other = BamlValue::Float(1.0);
+        let res = arg_coercer.coerce_arg(&ir, &type_, &other, &mut ScopeStack::new());
+        assert!(res.is_err());
+    }
 }
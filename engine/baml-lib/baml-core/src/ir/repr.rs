@@ -8,6 +8,7 @@ use internal_baml_parser_database::{
     walkers::{
         ClassWalker, ClientSpec as AstClientSpec, ClientWalker, ConfigurationWalker,
         EnumValueWalker, EnumWalker, FieldWalker, FunctionWalker, TemplateStringWalker,
+        TypeAliasWalker,
     },
     Attributes, ParserDatabase, PromptAst, RetryPolicyStrategy,
 };
@@ -29,6 +30,10 @@ pub struct IntermediateRepr {
     classes: Vec<Node<Class>>,
     /// Strongly connected components of the dependency graph (finite cycles).
     finite_recursive_cycles: Vec<IndexSet<String>>,
+    type_aliases: Vec<Node<TypeAlias>>,
+    /// Cycles of type aliases that refer to each other, mapping each alias
+    /// name to the type it stands for.
+    structural_recursive_alias_cycles: Vec<IndexMap<String, FieldType>>,
     functions: Vec<Node<Function>>,
     clients: Vec<Node<Client>>,
     retry_policies: Vec<Node<RetryPolicy>>,
@@ -53,6 +58,8 @@ impl IntermediateRepr {
             enums: vec![],
             classes: vec![],
             finite_recursive_cycles: vec![],
+            type_aliases: vec![],
+            structural_recursive_alias_cycles: vec![],
             functions: vec![],
             clients: vec![],
             retry_policies: vec![],
@@ -98,6 +105,13 @@ impl IntermediateRepr {
         &self.finite_recursive_cycles
     }
 
+    /// Returns a list of all the cycles of recursive type aliases.
+    ///
+    /// Each cycle maps the name of an alias to the type it stands for.
+    pub fn structural_recursive_alias_cycles(&self) -> &[IndexMap<String, FieldType>] {
+        &self.structural_recursive_alias_cycles
+    }
+
     pub fn walk_enums<'a>(&'a self) -> impl ExactSizeIterator<Item = Walker<'a, &'a Node<Enum>>> {
         self.enums.iter().map(|e| Walker { db: self, item: e })
     }
@@ -108,6 +122,14 @@ impl IntermediateRepr {
         self.classes.iter().map(|e| Walker { db: self, item: e })
     }
 
+    pub fn walk_type_aliases<'a>(
+        &'a self,
+    ) -> impl ExactSizeIterator<Item = Walker<'a, &'a Node<TypeAlias>>> {
+        self.type_aliases
+            .iter()
+            .map(|e| Walker { db: self, item: e })
+    }
+
     pub fn function_names(&self) -> impl ExactSizeIterator<Item = &str> {
         self.functions.iter().map(|f| f.elem.name())
     }
@@ -174,6 +196,22 @@ impl IntermediateRepr {
                         .collect()
                 })
                 .collect(),
+            type_aliases: db
+                .walk_type_aliases()
+                .map(|e| e.node(db))
+                .collect::<Result<Vec<_>>>()?,
+            structural_recursive_alias_cycles: db
+                .structural_recursive_alias_cycles()
+                .iter()
+                .map(|ids| {
+                    ids.iter()
+                        .map(|id| {
+                            let alias = db.walk(*id);
+                            Ok((alias.name().to_string(), alias.resolved().repr(db)?))
+                        })
+                        .collect::<Result<IndexMap<_, _>>>()
+                })
+                .collect::<Result<Vec<_>>>()?,
             functions: db
                 .walk_functions()
                 .map(|e| e.node(db))
@@ -196,6 +234,8 @@ impl IntermediateRepr {
         // Sort each item by name.
         repr.enums.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
         repr.classes.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
+        repr.type_aliases
+            .sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
         repr.functions
             .sort_by(|a, b| a.elem.name().cmp(&b.elem.name()));
         repr.clients.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
@@ -230,6 +270,7 @@ pub struct NodeAttributes {
     ///
     ///   - @skip becomes ("skip", bool)
     ///   - @alias(...) becomes ("alias", ...)
+    ///   - @default(...) becomes ("default", ...)
     #[serde(with = "indexmap::map::serde_seq")]
     meta: IndexMap<String, Expression>,
 
@@ -268,6 +309,7 @@ fn to_ir_attributes(
             dynamic_type,
             skip,
             constraints,
+            default,
         } = attributes;
         let description = description.as_ref().and_then(|d| {
             let name = "description".to_string();
@@ -303,7 +345,15 @@ fn to_ir_attributes(
             }
         });
 
-        let meta = vec![description, alias, dynamic_type, skip]
+        let default = default.as_ref().and_then(|d| match d.repr(db) {
+            Ok(d) => Some(("default".to_string(), d)),
+            Err(_) => {
+                eprintln!("Warning, encountered an unexpected default attribute");
+                None
+            }
+        });
+
+        let meta = vec![description, alias, dynamic_type, skip, default]
             .into_iter()
             .filter_map(|s| s)
             .collect();
@@ -435,7 +485,25 @@ impl WithRepr<FieldType> for ast::FieldType {
                             _ => base_type,
                         }
                     }
-                    None => return Err(anyhow!("Field type uses unresolvable local identifier")),
+                    None => match db.find_type_alias(idn) {
+                        Some(alias) if alias.is_recursive() => {
+                            FieldType::RecursiveTypeAlias(alias.name().to_string())
+                        }
+                        // Non-recursive aliases are replaced by their type,
+                        // which takes the arity and constraints of this
+                        // reference along with it.
+                        Some(alias) => {
+                            let mut resolved = alias.resolved().clone();
+                            if arity.is_optional() {
+                                resolved = resolved.to_nullable();
+                            }
+                            resolved.extend_attributes(self.attributes().to_vec());
+                            return resolved.repr(db);
+                        }
+                        None => {
+                            return Err(anyhow!("Field type uses unresolvable local identifier"))
+                        }
+                    },
                 },
                 arity,
             ),
@@ -768,6 +836,31 @@ impl WithRepr<Class> for ClassWalker<'_> {
     }
 }
 
+#[derive(serde::Serialize, Debug)]
+pub struct TypeAlias {
+    pub name: String,
+    /// The type the alias stands for. References to recursive aliases are
+    /// kept as [`FieldType::RecursiveTypeAlias`].
+    pub r#type: Node<FieldType>,
+}
+
+impl WithRepr<TypeAlias> for TypeAliasWalker<'_> {
+    fn attributes(&self, _db: &ParserDatabase) -> NodeAttributes {
+        NodeAttributes {
+            meta: IndexMap::new(),
+            constraints: Vec::new(),
+            span: Some(self.span().clone()),
+        }
+    }
+
+    fn repr(&self, db: &ParserDatabase) -> Result<TypeAlias> {
+        Ok(TypeAlias {
+            name: self.name().to_string(),
+            r#type: self.target().node(db)?,
+        })
+    }
+}
+
 impl Class {
     pub fn inputs(&self) -> &Vec<(String, FieldType)> {
         &self.inputs
@@ -1055,6 +1148,7 @@ pub struct RetryPolicy {
     pub name: RetryPolicyId,
     pub max_retries: u32,
     pub strategy: RetryPolicyStrategy,
+    pub retry_on: Option<Vec<u16>>,
     // NB: the parser DB has a notion of "empty options" vs "no options"; we collapse
     // those here into an empty vec
     options: Vec<(String, Expression)>,
@@ -1074,6 +1168,7 @@ impl WithRepr<RetryPolicy> for ConfigurationWalker<'_> {
             name: RetryPolicyId(self.name().to_string()),
             max_retries: self.retry_policy().max_retries,
             strategy: self.retry_policy().strategy,
+            retry_on: self.retry_policy().retry_on.clone(),
             options: match &self.retry_policy().options {
                 Some(o) => o
                     .iter()
@@ -1123,7 +1218,7 @@ impl WithRepr<TestCase> for ConfigurationWalker<'_> {
         NodeAttributes {
             meta: IndexMap::new(),
             span: Some(self.span().clone()),
-            constraints: Vec::new(),
+            constraints: self.test_case().constraints.clone(),
         }
     }
 
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use baml_types::BamlValue;
+use baml_types::{BamlValue, Constraint};
 use indexmap::IndexMap;
 
 use internal_baml_parser_database::RetryPolicyStrategy;
@@ -9,7 +9,7 @@ use std::collections::{HashMap, HashSet};
 use super::{
     repr::{self, FunctionConfig, WithRepr},
     Class, Client, Enum, EnumValue, Expression, Field, FunctionNode, IRHelper, Identifier, Impl,
-    RetryPolicy, TemplateString, TestCase, Walker,
+    RetryPolicy, TemplateString, TestCase, TypeAlias, Walker,
 };
 use crate::ir::jinja_helpers::render_expression;
 
@@ -35,6 +35,7 @@ fn provider_to_env_vars(
         "azure-openai" => vec![(Some("api_key"), "AZURE_OPENAI_API_KEY")],
         "openai-generic" => vec![(Some("api_key"), "OPENAI_API_KEY")],
         "ollama" => vec![],
+        "mock" => vec![],
         other => vec![],
     }
 }
@@ -306,6 +307,11 @@ impl<'a> Walker<'a, (&'a FunctionNode, &'a TestCase)> {
         self.item.1.attributes.span.as_ref()
     }
 
+    /// The `@@check` and `@@assert` attributes on the test block.
+    pub fn constraints(&self) -> &Vec<Constraint> {
+        &self.item.1.attributes.constraints
+    }
+
     pub fn test_case_params(
         &self,
         env_values: &HashMap<String, String>,
@@ -369,6 +375,24 @@ impl<'a> Walker<'a, &'a Class> {
     }
 }
 
+impl<'a> Walker<'a, &'a TypeAlias> {
+    pub fn name(&self) -> &'a str {
+        &self.elem().name
+    }
+
+    pub fn target(&self) -> &'a baml_types::FieldType {
+        &self.elem().r#type.elem
+    }
+
+    pub fn elem(&self) -> &'a repr::TypeAlias {
+        &self.item.elem
+    }
+
+    pub fn span(&self) -> Option<&crate::Span> {
+        self.item.attributes.span.as_ref()
+    }
+}
+
 impl<'a> Walker<'a, &'a Client> {
     pub fn elem(&'a self) -> &'a repr::Client {
         &self.item.elem
@@ -426,6 +450,11 @@ impl<'a> Walker<'a, &'a RetryPolicy> {
         &self.elem().strategy
     }
 
+    /// The HTTP status codes worth retrying, or `None` to retry any failure.
+    pub fn retry_on(&self) -> Option<&[u16]> {
+        self.elem().retry_on.as_deref()
+    }
+
     pub fn span(&self) -> Option<&crate::Span> {
         self.item.attributes.span.as_ref()
     }
@@ -488,6 +517,15 @@ impl<'a> Walker<'a, &'a Field> {
             .transpose()
     }
 
+    /// The value from `@default`, used when the LLM leaves the field out.
+    pub fn default_value(&self, env_values: &HashMap<String, String>) -> Result<Option<BamlValue>> {
+        self.item
+            .attributes
+            .get("default")
+            .map(|v| v.normalize(env_values))
+            .transpose()
+    }
+
     pub fn span(&self) -> Option<&crate::Span> {
         self.item.attributes.span.as_ref()
     }
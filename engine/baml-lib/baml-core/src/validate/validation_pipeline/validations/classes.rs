@@ -1,4 +1,9 @@
-use internal_baml_schema_ast::ast::{WithName, WithSpan};
+use baml_types::{LiteralValue, TypeValue};
+use either::Either;
+use internal_baml_parser_database::ParserDatabase;
+use internal_baml_schema_ast::ast::{
+    Expression, FieldArity, FieldType, Identifier, WithName, WithSpan,
+};
 
 use super::types::validate_type;
 use crate::validate::validation_pipeline::context::Context;
@@ -14,6 +19,21 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
             let field = c.ast_field();
             if let Some(ft) = &field.expr {
                 validate_type(ctx, &ft);
+
+                let default = c
+                    .get_default_attributes()
+                    .and_then(|attributes| attributes.default_value().as_ref());
+                if let Some(default) = default {
+                    if !default_matches_type(ctx.db, ft, default) {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            &format!(
+                                "The default value `{default}` does not match the type of field `{}`.",
+                                field.name()
+                            ),
+                            default.span().clone(),
+                        ));
+                    }
+                }
             }
         }
 
@@ -45,3 +65,128 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
         defined_types.errors_mut().clear();
     }
 }
+
+/// Whether `value` is a valid `@default` for a field of type `field_type`.
+///
+/// Enum values are written as bare identifiers and class instances as maps
+/// from field names to values.
+fn default_matches_type(db: &ParserDatabase, field_type: &FieldType, value: &Expression) -> bool {
+    if field_type.is_optional() && is_null(value) {
+        return true;
+    }
+
+    match field_type {
+        FieldType::Primitive(_, primitive, ..) => match (primitive, value) {
+            (TypeValue::String, Expression::StringValue(..) | Expression::RawStringValue(..)) => {
+                true
+            }
+            (TypeValue::Int, Expression::NumericValue(n, _)) => n.parse::<i64>().is_ok(),
+            (TypeValue::Float, Expression::NumericValue(..)) => true,
+            (TypeValue::Bool, Expression::BoolValue(..)) => true,
+            (TypeValue::Null, value) => is_null(value),
+            _ => false,
+        },
+        FieldType::Literal(_, literal, ..) => match (literal, value) {
+            (LiteralValue::String(s), Expression::StringValue(v, _)) => s == v,
+            (LiteralValue::Int(i), Expression::NumericValue(n, _)) => {
+                n.parse::<i64>().ok() == Some(*i)
+            }
+            (LiteralValue::Float(f), Expression::NumericValue(n, _)) => {
+                n.parse::<f64>().ok() == Some(*f)
+            }
+            (LiteralValue::Bool(b), Expression::BoolValue(v, _)) => b == v,
+            _ => false,
+        },
+        FieldType::List(_, item_type, dims, span, _) => {
+            let Expression::Array(items, _) = value else {
+                return false;
+            };
+            if *dims > 1 {
+                let item_type = FieldType::List(
+                    FieldArity::Required,
+                    item_type.clone(),
+                    dims - 1,
+                    span.clone(),
+                    None,
+                );
+                items
+                    .iter()
+                    .all(|item| default_matches_type(db, &item_type, item))
+            } else {
+                items
+                    .iter()
+                    .all(|item| default_matches_type(db, item_type, item))
+            }
+        }
+        FieldType::Map(_, kv, ..) => match value {
+            Expression::Map(entries, _) => entries
+                .iter()
+                .all(|(_, v)| default_matches_type(db, &kv.1, v)),
+            _ => false,
+        },
+        FieldType::Tuple(_, item_types, ..) => match value {
+            Expression::Array(items, _) => {
+                items.len() == item_types.len()
+                    && item_types
+                        .iter()
+                        .zip(items)
+                        .all(|(item_type, item)| default_matches_type(db, item_type, item))
+            }
+            _ => false,
+        },
+        FieldType::Union(_, options, ..) => options
+            .iter()
+            .any(|option| default_matches_type(db, option, value)),
+        FieldType::Symbol(_, idn, _) => match db.find_type(idn) {
+            Some(Either::Left(class)) => {
+                let Expression::Map(entries, _) = value else {
+                    return false;
+                };
+                let matches_field = |(key, v): &(Expression, Expression)| {
+                    let Some(name) = map_key(key) else {
+                        return false;
+                    };
+                    class.static_fields().any(|field| {
+                        field.name() == name
+                            && field
+                                .r#type()
+                                .as_ref()
+                                .map_or(false, |ft| default_matches_type(db, ft, v))
+                    })
+                };
+                // Fields left out must be fine to leave out.
+                let has_required_fields = class.static_fields().all(|field| {
+                    entries
+                        .iter()
+                        .any(|(key, _)| map_key(key) == Some(field.name()))
+                        || field.r#type().as_ref().map_or(true, |ft| ft.is_optional())
+                        || field
+                            .get_default_attributes()
+                            .map_or(false, |attributes| attributes.default_value().is_some())
+                });
+                entries.iter().all(matches_field) && has_required_fields
+            }
+            Some(Either::Right(enm)) => match value {
+                Expression::Identifier(Identifier::Local(name, _)) => {
+                    enm.values().any(|v| v.name() == name)
+                }
+                _ => false,
+            },
+            None => db.find_type_alias(idn).map_or(false, |alias| {
+                default_matches_type(db, alias.resolved(), value)
+            }),
+        },
+    }
+}
+
+fn map_key(key: &Expression) -> Option<&str> {
+    match key {
+        Expression::StringValue(name, _) => Some(name),
+        Expression::Identifier(idn) => Some(idn.name()),
+        _ => None,
+    }
+}
+
+fn is_null(value: &Expression) -> bool {
+    matches!(value, Expression::Identifier(Identifier::Local(name, _)) if name == "null")
+}
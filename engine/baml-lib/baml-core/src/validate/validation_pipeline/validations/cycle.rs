@@ -3,12 +3,14 @@ use std::collections::{HashMap, HashSet};
 use either::Either;
 use internal_baml_diagnostics::DatamodelError;
 use internal_baml_parser_database::Tarjan;
-use internal_baml_schema_ast::ast::{FieldType, TypeExpId, WithName, WithSpan};
+use internal_baml_schema_ast::ast::{FieldType, TypeAliasId, TypeExpId, WithName, WithSpan};
 
 use crate::validate::validation_pipeline::context::Context;
 
 /// Validates if the dependency graph contains one or more infinite cycles.
 pub(super) fn validate(ctx: &mut Context<'_>) {
+    validate_type_alias_cycles(ctx);
+
     // First, build a graph of all the "required" dependencies represented as an
     // adjacency list. We're only going to consider type dependencies that can
     // actually cause infinite recursion. Unions and optionals can stop the
@@ -54,6 +56,55 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
     }
 }
 
+/// Type aliases can only refer to themselves through a list or a map, which
+/// can always be empty. Anything else, like `type A = B | int` together with
+/// `type B = A`, has no type to expand to.
+fn validate_type_alias_cycles(ctx: &mut Context<'_>) {
+    let dependency_graph = HashMap::from_iter(ctx.db.walk_type_aliases().map(|alias| {
+        let mut dependencies = HashSet::new();
+        insert_unguarded_alias_deps(alias.target(), ctx, &mut dependencies);
+
+        (alias.id, dependencies)
+    }));
+
+    for component in Tarjan::components(&dependency_graph) {
+        let cycle = component
+            .iter()
+            .map(|id| ctx.db.ast()[*id].name().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        ctx.push_error(DatamodelError::new_validation_error(
+            &format!("These aliases form a dependency cycle: {}", cycle),
+            ctx.db.ast()[component[0]].span().clone(),
+        ));
+    }
+}
+
+/// Inserts the aliases that a type refers to without going through a list or
+/// a map.
+fn insert_unguarded_alias_deps(
+    field: &FieldType,
+    ctx: &Context<'_>,
+    deps: &mut HashSet<TypeAliasId>,
+) {
+    match field {
+        FieldType::Symbol(_, ident, _) => {
+            if let Some(alias) = ctx.db.find_type_alias(ident) {
+                deps.insert(alias.id);
+            }
+        }
+
+        FieldType::Union(_, field_types, _, _) | FieldType::Tuple(_, field_types, _, _) => {
+            for f in field_types {
+                insert_unguarded_alias_deps(f, ctx, deps);
+            }
+        }
+
+        _ => {}
+    }
+}
+
 /// Inserts all the required dependencies of a field into the given set.
 ///
 /// Recursively deals with unions of unions. Can be implemented iteratively with
@@ -69,6 +120,12 @@ fn insert_required_deps(
         FieldType::Symbol(arity, ident, _) if arity.is_required() => {
             if let Some(Either::Left(class)) = ctx.db.find_type_by_str(ident.name()) {
                 deps.insert(class.id);
+            } else if let Some(alias) = ctx.db.find_type_alias(ident) {
+                // Recursive aliases always go through a list or a map, which
+                // ends the recursion.
+                if !alias.is_recursive() {
+                    insert_required_deps(id, alias.resolved(), ctx, deps);
+                }
             }
         }
 
@@ -109,6 +166,13 @@ fn insert_required_deps(
             deps.extend(union_deps);
         }
 
+        // Every item of a tuple is required.
+        FieldType::Tuple(arity, field_types, _, _) if arity.is_required() => {
+            for f in field_types {
+                insert_required_deps(id, f, ctx, deps);
+            }
+        }
+
         _ => {}
     }
 }
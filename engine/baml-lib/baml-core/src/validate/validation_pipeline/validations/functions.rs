@@ -5,7 +5,9 @@ use crate::validate::validation_pipeline::context::Context;
 use either::Either;
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Span};
 
-use internal_baml_schema_ast::ast::{FieldType, TypeExpId, WithIdentifier, WithName, WithSpan};
+use internal_baml_schema_ast::ast::{
+    FieldType, TypeAliasId, TypeExpId, WithIdentifier, WithName, WithSpan,
+};
 
 use super::types::validate_type;
 
@@ -227,6 +229,7 @@ pub(super) fn has_checks_nested(ctx: &Context<'_>, field_type: &FieldType) -> bo
 struct NestedChecks<'c> {
     ctx: &'c Context<'c>,
     visited: HashSet<TypeExpId>,
+    visited_aliases: HashSet<TypeAliasId>,
 }
 
 impl<'c> NestedChecks<'c> {
@@ -234,6 +237,7 @@ impl<'c> NestedChecks<'c> {
         Self {
             ctx,
             visited: HashSet::new(),
+            visited_aliases: HashSet::new(),
         }
     }
 
@@ -261,7 +265,14 @@ impl<'c> NestedChecks<'c> {
                             .map_or(false, |ft| self.has_checks_nested(&ft))
                     })
                 }
-                _ => false,
+                Some(Either::Right(_)) => false,
+                None => match self.ctx.db.find_type_alias(id) {
+                    // Stop recursion when dealing with recursive aliases.
+                    Some(alias) if self.visited_aliases.insert(alias.id) => {
+                        self.has_checks_nested(alias.target())
+                    }
+                    _ => false,
+                },
             },
 
             FieldType::Primitive(..) => false,
@@ -0,0 +1,8 @@
+use super::types::validate_type;
+use crate::validate::validation_pipeline::context::Context;
+
+pub(super) fn validate(ctx: &mut Context<'_>) {
+    for alias in ctx.db.walk_type_aliases() {
+        validate_type(ctx, alias.target());
+    }
+}
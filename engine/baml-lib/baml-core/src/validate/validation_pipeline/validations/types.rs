@@ -36,6 +36,8 @@ fn validate_type_exists(ctx: &mut Context<'_>, field_type: &FieldType) -> bool {
         .for_each(|f| match ctx.db.find_type(f) {
             Some(_) => {}
 
+            None if ctx.db.find_type_alias(f).is_some() => {}
+
             None => match field_type {
                 FieldType::Primitive(..) => {}
                 _ => {
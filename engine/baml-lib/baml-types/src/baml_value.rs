@@ -8,7 +8,7 @@ use serde::ser::SerializeMap;
 use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::media::BamlMediaType;
-use crate::{BamlMap, BamlMedia, ResponseCheck};
+use crate::{BamlMap, BamlMedia, BamlMessage, ResponseCheck};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum BamlValue {
@@ -19,6 +19,7 @@ pub enum BamlValue {
     Map(BamlMap<String, BamlValue>),
     List(Vec<BamlValue>),
     Media(BamlMedia),
+    Message(BamlMessage),
     Enum(String, String),
     Class(String, BamlMap<String, BamlValue>),
     Null,
@@ -56,6 +57,7 @@ impl serde::Serialize for BamlValue {
                 // }
                 // s.end()
             }
+            BamlValue::Message(m) => m.serialize(serializer),
             BamlValue::Enum(_, v) => serializer.serialize_str(v),
             BamlValue::Class(_, m) => m.serialize(serializer),
             BamlValue::Null => serializer.serialize_none(),
@@ -103,6 +105,7 @@ impl BamlValue {
                 BamlMediaType::Audio => "audio",
             }
             .into(),
+            BamlValue::Message(_) => "message".into(),
             BamlValue::Enum(e, _) => format!("enum {}", e),
             BamlValue::Class(c, _) => format!("class {}", c),
             BamlValue::Null => "null".into(),
@@ -363,6 +366,7 @@ pub enum BamlValueWithMeta<T> {
     Map(BamlMap<String, BamlValueWithMeta<T>>, T),
     List(Vec<BamlValueWithMeta<T>>, T),
     Media(BamlMedia, T),
+    Message(BamlMessage, T),
     Enum(String, String, T),
     Class(String, BamlMap<String, BamlValueWithMeta<T>>, T),
     Null(T),
@@ -393,6 +397,7 @@ impl<T> BamlValueWithMeta<T> {
                 BamlValue::List(v.into_iter().map(|v| v.value()).collect())
             }
             BamlValueWithMeta::Media(v, _) => BamlValue::Media(v),
+            BamlValueWithMeta::Message(v, _) => BamlValue::Message(v),
             BamlValueWithMeta::Enum(v, w, _) => BamlValue::Enum(v, w),
             BamlValueWithMeta::Class(n, fs, _) => {
                 BamlValue::Class(n, fs.into_iter().map(|(k, v)| (k, v.value())).collect())
@@ -410,6 +415,7 @@ impl<T> BamlValueWithMeta<T> {
             BamlValueWithMeta::Map(_, m) => m,
             BamlValueWithMeta::List(_, m) => m,
             BamlValueWithMeta::Media(_, m) => m,
+            BamlValueWithMeta::Message(_, m) => m,
             BamlValueWithMeta::Enum(_, _, m) => m,
             BamlValueWithMeta::Class(_, _, m) => m,
             BamlValueWithMeta::Null(m) => m,
@@ -425,6 +431,7 @@ impl<T> BamlValueWithMeta<T> {
             BamlValueWithMeta::Map(_, m) => m,
             BamlValueWithMeta::List(_, m) => m,
             BamlValueWithMeta::Media(_, m) => m,
+            BamlValueWithMeta::Message(_, m) => m,
             BamlValueWithMeta::Enum(_, _, m) => m,
             BamlValueWithMeta::Class(_, _, m) => m,
             BamlValueWithMeta::Null(m) => m,
@@ -453,6 +460,7 @@ impl<T> BamlValueWithMeta<T> {
                 T::default(),
             ),
             BamlValue::Media(m) => Media(m.clone(), T::default()),
+            BamlValue::Message(m) => Message(m.clone(), T::default()),
             BamlValue::Enum(n, v) => Enum(n.clone(), v.clone(), T::default()),
             BamlValue::Class(_, items) => Map(
                 items
@@ -482,6 +490,7 @@ impl<T> BamlValueWithMeta<T> {
                 BamlValueWithMeta::List(v.into_iter().map(|v| v.map_meta(f)).collect(), f(m))
             }
             BamlValueWithMeta::Media(v, m) => BamlValueWithMeta::Media(v.clone(), f(m)),
+            BamlValueWithMeta::Message(v, m) => BamlValueWithMeta::Message(v.clone(), f(m)),
             BamlValueWithMeta::Enum(v, e, m) => BamlValueWithMeta::Enum(v.clone(), e.clone(), f(m)),
             BamlValueWithMeta::Class(n, fs, m) => BamlValueWithMeta::Class(
                 n.clone(),
@@ -537,6 +546,7 @@ impl<'a, T: 'a> Iterator for BamlValueWithMetaIterator<'a, T> {
                 | BamlValueWithMeta::Float(..)
                 | BamlValueWithMeta::Bool(..)
                 | BamlValueWithMeta::Media(..)
+                | BamlValueWithMeta::Message(..)
                 | BamlValueWithMeta::Enum(..)
                 | BamlValueWithMeta::Null(..) => {}
             }
@@ -569,6 +579,7 @@ impl<T> From<&BamlValueWithMeta<T>> for BamlValue {
             }
             List(v, _) => BamlValue::List(v.into_iter().map(|v| v.into()).collect()),
             Media(v, _) => BamlValue::Media(v.clone()),
+            Message(v, _) => BamlValue::Message(v.clone()),
             Enum(enum_name, v, _) => BamlValue::Enum(enum_name.clone(), v.clone()),
             Class(class_name, v, _) => BamlValue::Class(
                 class_name.clone(),
@@ -590,6 +601,7 @@ impl<T> From<BamlValueWithMeta<T>> for BamlValue {
             Map(v, _) => BamlValue::Map(v.into_iter().map(|(k, v)| (k, v.into())).collect()),
             List(v, _) => BamlValue::List(v.into_iter().map(|v| v.into()).collect()),
             Media(v, _) => BamlValue::Media(v),
+            Message(v, _) => BamlValue::Message(v),
             Enum(enum_name, v, _) => BamlValue::Enum(enum_name, v),
             Class(class_name, v, _) => BamlValue::Class(
                 class_name,
@@ -623,6 +635,7 @@ impl Serialize for BamlValueWithMeta<Vec<ResponseCheck>> {
             }
             BamlValueWithMeta::List(v, cr) => serialize_with_checks(v, cr, serializer),
             BamlValueWithMeta::Media(v, cr) => serialize_with_checks(v, cr, serializer),
+            BamlValueWithMeta::Message(v, cr) => serialize_with_checks(v, cr, serializer),
             BamlValueWithMeta::Enum(_enum_name, v, cr) => serialize_with_checks(v, cr, serializer),
             BamlValueWithMeta::Class(_class_name, v, cr) => {
                 if cr.is_empty() {
@@ -12,6 +12,8 @@ pub enum TypeValue {
     // Char,
     Null,
     Media(BamlMediaType),
+    /// A chat message, see [`crate::BamlMessage`].
+    Message,
 }
 impl TypeValue {
     pub fn from_str(s: &str) -> Option<TypeValue> {
@@ -23,6 +25,7 @@ impl TypeValue {
             "null" => Some(TypeValue::Null),
             "image" => Some(TypeValue::Media(BamlMediaType::Image)),
             "audio" => Some(TypeValue::Media(BamlMediaType::Audio)),
+            "message" => Some(TypeValue::Message),
             _ => None,
         }
     }
@@ -38,15 +41,17 @@ impl std::fmt::Display for TypeValue {
             TypeValue::Null => write!(f, "null"),
             TypeValue::Media(BamlMediaType::Image) => write!(f, "image"),
             TypeValue::Media(BamlMediaType::Audio) => write!(f, "audio"),
+            TypeValue::Message => write!(f, "message"),
         }
     }
 }
 
 /// Subset of [`crate::BamlValue`] allowed for literal type definitions.
-#[derive(serde::Serialize, Debug, Clone, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(serde::Serialize, Debug, Clone)]
 pub enum LiteralValue {
     String(String),
     Int(i64),
+    Float(f64),
     Bool(bool),
 }
 
@@ -55,16 +60,58 @@ impl LiteralValue {
         match self {
             Self::String(_) => FieldType::string(),
             Self::Int(_) => FieldType::int(),
+            Self::Float(_) => FieldType::float(),
             Self::Bool(_) => FieldType::bool(),
         }
     }
+
+    /// Position of the variant in the declaration, used to order literals of
+    /// different kinds.
+    fn variant_index(&self) -> u8 {
+        match self {
+            Self::String(_) => 0,
+            Self::Int(_) => 1,
+            Self::Float(_) => 2,
+            Self::Bool(_) => 3,
+        }
+    }
+}
+
+// Floats have no total order of their own, so `total_cmp` is used to keep
+// literals usable as keys and in sorted collections.
+impl Ord for LiteralValue {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+            (Self::Int(a), Self::Int(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::Bool(a), Self::Bool(b)) => a.cmp(b),
+            _ => self.variant_index().cmp(&other.variant_index()),
+        }
+    }
+}
+
+impl PartialOrd for LiteralValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for LiteralValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
 }
 
+impl Eq for LiteralValue {}
+
 impl std::fmt::Display for LiteralValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LiteralValue::String(str) => write!(f, "\"{str}\""),
             LiteralValue::Int(int) => write!(f, "{int}"),
+            // Debug keeps the decimal point on whole numbers, `1.0` not `1`.
+            LiteralValue::Float(float) => write!(f, "{float:?}"),
             LiteralValue::Bool(bool) => write!(f, "{bool}"),
         }
     }
@@ -77,6 +124,9 @@ pub enum FieldType {
     Enum(String),
     Literal(LiteralValue),
     Class(String),
+    /// A reference to a type alias that refers back to itself. Aliases that
+    /// are not recursive are replaced by the type they stand for.
+    RecursiveTypeAlias(String),
     List(Box<FieldType>),
     Map(Box<FieldType>, Box<FieldType>),
     Union(Vec<FieldType>),
@@ -92,7 +142,9 @@ pub enum FieldType {
 impl std::fmt::Display for FieldType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FieldType::Enum(name) | FieldType::Class(name) => {
+            FieldType::Enum(name)
+            | FieldType::Class(name)
+            | FieldType::RecursiveTypeAlias(name) => {
                 write!(f, "{}", name)
             }
             FieldType::Primitive(t) => write!(f, "{}", t),
@@ -210,21 +262,28 @@ impl FieldType {
                     FieldType::Primitive(TypeValue::Bool),
                 ) => true,
                 (FieldType::Literal(LiteralValue::Bool(_)), _) => {
-                    self.is_subtype_of(&FieldType::Primitive(TypeValue::Bool))
+                    FieldType::Primitive(TypeValue::Bool).is_subtype_of(other)
                 }
                 (
                     FieldType::Literal(LiteralValue::Int(_)),
                     FieldType::Primitive(TypeValue::Int),
                 ) => true,
                 (FieldType::Literal(LiteralValue::Int(_)), _) => {
-                    self.is_subtype_of(&FieldType::Primitive(TypeValue::Int))
+                    FieldType::Primitive(TypeValue::Int).is_subtype_of(other)
+                }
+                (
+                    FieldType::Literal(LiteralValue::Float(_)),
+                    FieldType::Primitive(TypeValue::Float),
+                ) => true,
+                (FieldType::Literal(LiteralValue::Float(_)), _) => {
+                    FieldType::Primitive(TypeValue::Float).is_subtype_of(other)
                 }
                 (
                     FieldType::Literal(LiteralValue::String(_)),
                     FieldType::Primitive(TypeValue::String),
                 ) => true,
                 (FieldType::Literal(LiteralValue::String(_)), _) => {
-                    self.is_subtype_of(&FieldType::Primitive(TypeValue::String))
+                    FieldType::Primitive(TypeValue::String).is_subtype_of(other)
                 }
 
                 (FieldType::Union(self_items), _) => self_items
@@ -243,6 +302,7 @@ impl FieldType {
                 (FieldType::Primitive(_), _) => false,
                 (FieldType::Enum(_), _) => false,
                 (FieldType::Class(_), _) => false,
+                (FieldType::RecursiveTypeAlias(_), _) => false,
             }
         }
     }
@@ -333,6 +393,23 @@ mod tests {
         assert!(x.is_subtype_of(&y));
     }
 
+    #[test]
+    fn subtype_float_literal() {
+        let lit = FieldType::literal_float(-0.5);
+        assert!(lit.is_subtype_of(&lit));
+        assert!(lit.is_subtype_of(&FieldType::float()));
+        assert!(!lit.is_subtype_of(&mk_int()));
+        assert!(!lit.is_subtype_of(&FieldType::literal_float(0.5)));
+        assert!(!FieldType::float().is_subtype_of(&lit));
+    }
+
+    #[test]
+    fn float_literal_display() {
+        assert_eq!(LiteralValue::Float(1.0).to_string(), "1.0");
+        assert_eq!(LiteralValue::Float(-2.5).to_string(), "-2.5");
+        assert_eq!(LiteralValue::Int(-3).to_string(), "-3");
+    }
+
     #[test]
     fn subtype_media() {
         let x = FieldType::Primitive(TypeValue::Media(BamlMediaType::Audio));
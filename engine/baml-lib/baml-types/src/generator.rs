@@ -20,6 +20,12 @@ pub enum GeneratorOutputType {
 
     #[strum(serialize = "ruby/sorbet")]
     RubySorbet,
+
+    #[strum(serialize = "proto")]
+    Proto,
+
+    #[strum(serialize = "go")]
+    Go,
 }
 
 impl GeneratorOutputType {
@@ -31,6 +37,8 @@ impl GeneratorOutputType {
             Self::PythonPydantic => GeneratorDefaultClientMode::Async,
             Self::Typescript => GeneratorDefaultClientMode::Async,
             Self::RubySorbet => GeneratorDefaultClientMode::Sync,
+            Self::Proto => GeneratorDefaultClientMode::Sync,
+            Self::Go => GeneratorDefaultClientMode::Sync,
         }
     }
 
@@ -41,6 +49,8 @@ impl GeneratorOutputType {
             Self::PythonPydantic => GeneratorDefaultClientMode::Sync,
             Self::Typescript => GeneratorDefaultClientMode::Async,
             Self::RubySorbet => GeneratorDefaultClientMode::Sync,
+            Self::Proto => GeneratorDefaultClientMode::Sync,
+            Self::Go => GeneratorDefaultClientMode::Sync,
         }
     }
 }
@@ -1,6 +1,7 @@
 mod constraint;
 mod map;
 mod media;
+mod message;
 mod minijinja;
 
 mod baml_value;
@@ -13,4 +14,5 @@ pub use field_type::{FieldType, LiteralValue, TypeValue};
 pub use generator::{GeneratorDefaultClientMode, GeneratorOutputType};
 pub use map::Map as BamlMap;
 pub use media::{BamlMedia, BamlMediaContent, BamlMediaType, MediaBase64, MediaUrl};
+pub use message::{BamlMessage, BamlMessagePart};
 pub use minijinja::JinjaExpression;
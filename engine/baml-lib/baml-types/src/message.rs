@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::BamlMedia;
+
+/// One turn of a conversation, passed into a function through a `message`
+/// parameter and rendered into the prompt as its own chat message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BamlMessage {
+    pub role: String,
+    pub content: Vec<BamlMessagePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BamlMessagePart {
+    Text(String),
+    Media(BamlMedia),
+}
+
+impl BamlMessage {
+    pub fn new(role: impl Into<String>, content: Vec<BamlMessagePart>) -> Self {
+        Self {
+            role: role.into(),
+            content,
+        }
+    }
+
+    pub fn text(role: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(role, vec![BamlMessagePart::Text(text.into())])
+    }
+}
@@ -1,5 +1,5 @@
 use std::fmt;
-use crate::{BamlMedia, BamlValue};
+use crate::{BamlMedia, BamlMessage, BamlMessagePart, BamlValue};
 
 /// A wrapper around a jinja expression. The inner `String` should not contain
 /// the interpolation brackets `{{ }}`; it should be a bare expression like
@@ -30,6 +30,7 @@ impl From<BamlValue> for minijinja::Value {
                 minijinja::Value::from(list)
             }
             BamlValue::Media(i) => i.into(),
+            BamlValue::Message(m) => m.into(),
             BamlValue::Enum(_, v) => minijinja::Value::from(v),
             BamlValue::Class(_, m) => {
                 let map = m.into_iter().map(|(k, v)| (k, minijinja::Value::from(v)));
@@ -40,6 +41,23 @@ impl From<BamlValue> for minijinja::Value {
     }
 }
 
+impl From<BamlMessage> for minijinja::Value {
+    fn from(arg: BamlMessage) -> minijinja::Value {
+        let content = arg
+            .content
+            .into_iter()
+            .map(|part| match part {
+                BamlMessagePart::Text(t) => minijinja::Value::from(t),
+                BamlMessagePart::Media(m) => m.into(),
+            })
+            .collect::<Vec<_>>();
+        minijinja::Value::from_iter([
+            ("role", minijinja::Value::from(arg.role)),
+            ("content", minijinja::Value::from(content)),
+        ])
+    }
+}
+
 struct MinijinjaBamlMedia {
     media: BamlMedia,
 }
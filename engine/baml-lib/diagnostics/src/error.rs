@@ -417,6 +417,7 @@ impl DatamodelError {
             "string".to_string(),
             "image".to_string(),
             "audio".to_string(),
+            "message".to_string(),
             "null".to_string(),
         ];
         names.extend(primitives);
@@ -4,7 +4,9 @@ use indexmap::IndexMap;
 use internal_baml_core::ir::repr::IntermediateRepr;
 use internal_baml_core::ir::IRHelper;
 
-use crate::{BamlMedia, BamlValue};
+use baml_types::{BamlMessage, BamlMessagePart};
+
+use crate::{BamlMedia, BamlValue, MAGIC_CHAT_ROLE_DELIMITER};
 
 pub trait IntoMiniJinjaValue {
     fn into_minijinja_value(
@@ -32,6 +34,18 @@ impl IntoMiniJinjaValue for BamlValue {
                 minijinja::Value::from_iter(map)
             }
             BamlValue::List(l) => {
+                // A chat history renders as consecutive chat messages, so
+                // `{{ history }}` can be used in place of a loop.
+                if !l.is_empty() && l.iter().all(|v| matches!(v, BamlValue::Message(_))) {
+                    let messages = l
+                        .iter()
+                        .filter_map(|v| match v {
+                            BamlValue::Message(m) => Some(MinijinjaBamlMessage::from(m.clone())),
+                            _ => None,
+                        })
+                        .collect();
+                    return minijinja::Value::from_object(MinijinjaBamlMessageList { messages });
+                }
                 let list: Vec<minijinja::Value> = l
                     .into_iter()
                     .map(|v| v.into_minijinja_value(ir, env_vars))
@@ -39,6 +53,9 @@ impl IntoMiniJinjaValue for BamlValue {
                 minijinja::Value::from(list)
             }
             BamlValue::Media(i) => i.into_minijinja_value(ir, env_vars),
+            BamlValue::Message(m) => {
+                minijinja::Value::from_object(MinijinjaBamlMessage::from(m.clone()))
+            }
             // For enums and classes we compute the aliases from the IR, and generate custom jinja structs that print out the alias if stringified.
             BamlValue::Enum(_name, value) => {
                 minijinja::Value::from(value.clone())
@@ -140,6 +157,117 @@ impl minijinja::value::Object for MinijinjaBamlMedia {
     }
 }
 
+// Messages
+
+/// Renders as a chat role marker followed by the message content, the same
+/// output as `{{ _.role(...) }}` followed by the text and media parts.
+#[derive(Clone)]
+struct MinijinjaBamlMessage {
+    message: BamlMessage,
+}
+
+impl From<BamlMessage> for MinijinjaBamlMessage {
+    fn from(message: BamlMessage) -> MinijinjaBamlMessage {
+        MinijinjaBamlMessage { message }
+    }
+}
+
+impl std::fmt::Display for MinijinjaBamlMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let props = serde_json::json!({
+            "role": self.message.role,
+            "__baml_allow_dupe_role__": false,
+        });
+        write!(
+            f,
+            "{MAGIC_CHAT_ROLE_DELIMITER}:baml-start-baml:{props}:baml-end-baml:{MAGIC_CHAT_ROLE_DELIMITER}"
+        )?;
+        for part in &self.message.content {
+            match part {
+                BamlMessagePart::Text(text) => write!(f, "{text}")?,
+                BamlMessagePart::Media(media) => {
+                    write!(f, "{}", MinijinjaBamlMedia::from(media.clone()))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for MinijinjaBamlMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl minijinja::value::Object for MinijinjaBamlMessage {
+    fn kind(&self) -> minijinja::value::ObjectKind<'_> {
+        minijinja::value::ObjectKind::Struct(self)
+    }
+}
+
+impl minijinja::value::StructObject for MinijinjaBamlMessage {
+    fn get_field(&self, name: &str) -> Option<minijinja::Value> {
+        match name {
+            "role" => Some(minijinja::Value::from(self.message.role.clone())),
+            "content" => Some(minijinja::Value::from(
+                self.message
+                    .content
+                    .iter()
+                    .map(|part| match part {
+                        BamlMessagePart::Text(text) => minijinja::Value::from(text.clone()),
+                        BamlMessagePart::Media(media) => {
+                            minijinja::Value::from_object(MinijinjaBamlMedia::from(media.clone()))
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            )),
+            _ => None,
+        }
+    }
+
+    fn static_fields(&self) -> Option<&'static [&'static str]> {
+        Some(&["role", "content"])
+    }
+}
+
+struct MinijinjaBamlMessageList {
+    messages: Vec<MinijinjaBamlMessage>,
+}
+
+impl std::fmt::Display for MinijinjaBamlMessageList {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for message in &self.messages {
+            write!(f, "{message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for MinijinjaBamlMessageList {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl minijinja::value::Object for MinijinjaBamlMessageList {
+    fn kind(&self) -> minijinja::value::ObjectKind<'_> {
+        minijinja::value::ObjectKind::Seq(self)
+    }
+}
+
+impl minijinja::value::SeqObject for MinijinjaBamlMessageList {
+    fn get_item(&self, idx: usize) -> Option<minijinja::Value> {
+        self.messages
+            .get(idx)
+            .map(|m| minijinja::Value::from_object(m.clone()))
+    }
+
+    fn item_count(&self) -> usize {
+        self.messages.len()
+    }
+}
+
 // Enums
 
 struct MinijinjaBamlEnum {
@@ -40,7 +40,7 @@ pub struct TemplateStringMacro {
     pub template: String,
 }
 
-const MAGIC_CHAT_ROLE_DELIMITER: &'static str = "BAML_CHAT_ROLE_MAGIC_STRING_DELIMITER";
+pub(crate) const MAGIC_CHAT_ROLE_DELIMITER: &'static str = "BAML_CHAT_ROLE_MAGIC_STRING_DELIMITER";
 const MAGIC_MEDIA_DELIMITER: &'static str = "BAML_MEDIA_MAGIC_STRING_DELIMITER";
 
 fn render_minijinja(
@@ -436,12 +436,23 @@ pub fn render_prompt(
     }
 }
 
+/// Renders a template that isn't a prompt, such as a mock client's `template`
+/// option, with string `variables`. Unlike in prompts, using an undefined
+/// variable is an error.
+pub fn render_template(template: &str, variables: &[(&str, &str)]) -> anyhow::Result<String> {
+    let mut env = get_env();
+    env.set_undefined_behavior(minijinja::UndefinedBehavior::Strict);
+    let variables = variables.iter().copied().collect::<HashMap<_, _>>();
+    env.render_str(template, variables)
+        .map_err(|e| anyhow::anyhow!("{e:#}"))
+}
+
 #[cfg(test)]
 mod render_tests {
 
     use super::*;
 
-    use baml_types::{BamlMap, BamlMediaType};
+    use baml_types::{BamlMap, BamlMediaType, BamlMessage, BamlMessagePart};
     use env_logger;
     use indexmap::IndexMap;
     use std::sync::Once;
@@ -594,6 +605,102 @@ mod render_tests {
         Ok(())
     }
 
+    #[test]
+    fn render_message_history() -> anyhow::Result<()> {
+        setup_logging();
+
+        let args = BamlValue::Map(BamlMap::from([(
+            "history".to_string(),
+            BamlValue::List(vec![
+                BamlValue::Message(BamlMessage::text("user", "What is in this image?")),
+                BamlValue::Message(BamlMessage::new(
+                    "assistant",
+                    vec![
+                        BamlMessagePart::Text("It looks like".to_string()),
+                        BamlMessagePart::Media(BamlMedia::url(
+                            BamlMediaType::Image,
+                            "https://example.com/image.jpg".to_string(),
+                            None,
+                        )),
+                    ],
+                )),
+            ]),
+        )]));
+
+        let ir = make_test_ir(
+            "
+            class C {
+                
+            }
+            ",
+        )?;
+
+        let render = |template: &str| {
+            render_prompt(
+                template,
+                &args,
+                RenderContext {
+                    client: RenderContext_Client {
+                        name: "gpt4".to_string(),
+                        provider: "openai".to_string(),
+                        default_role: "system".to_string(),
+                    },
+                    output_format: OutputFormatContent::new_string(),
+                    tags: HashMap::new(),
+                },
+                &vec![],
+                &ir,
+                &HashMap::new(),
+            )
+        };
+
+        let expected = RenderedPrompt::Chat(vec![
+            RenderedChatMessage {
+                role: "system".to_string(),
+                allow_duplicate_role: false,
+                parts: vec![ChatMessagePart::Text(
+                    "You are a helpful assistant.".to_string(),
+                )],
+            },
+            RenderedChatMessage {
+                role: "user".to_string(),
+                allow_duplicate_role: false,
+                parts: vec![ChatMessagePart::Text("What is in this image?".to_string())],
+            },
+            RenderedChatMessage {
+                role: "assistant".to_string(),
+                allow_duplicate_role: false,
+                parts: vec![
+                    ChatMessagePart::Text("It looks like".to_string()),
+                    ChatMessagePart::Media(BamlMedia::url(
+                        BamlMediaType::Image,
+                        "https://example.com/image.jpg".to_string(),
+                        None,
+                    )),
+                ],
+            },
+        ]);
+
+        assert_eq!(
+            render(
+                "{{ _.chat(\"system\") }}
+                You are a helpful assistant.
+                {{ history }}"
+            )?,
+            expected
+        );
+        assert_eq!(
+            render(
+                "{{ _.chat(\"system\") }}
+                You are a helpful assistant.
+                {% for m in history %}{{ _.chat(m.role) }}{% for part in m.content %}{{ part }}{% endfor %}{% endfor %}"
+            )?,
+            expected
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn render_image_suffix() -> anyhow::Result<()> {
         setup_logging();
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use baml_types::{FieldType, LiteralValue, TypeValue};
+use serde_json::{json, Map, Value};
+
+use super::types::OutputFormatContent;
+
+impl OutputFormatContent {
+    /// JSON schema of [`Self::target`], for providers that accept a schema
+    /// natively (tool calls, structured outputs).
+    ///
+    /// Fields and enum values use their rendered names (aliases), since that
+    /// is what the parser expects to find in the response. Recursive classes
+    /// and recursive type aliases are hoisted into `$defs` and referenced by
+    /// name, everything else is inlined.
+    pub fn json_schema(&self) -> Result<Value> {
+        let mut schema = match &self.target {
+            // A recursive target still needs to be described at the top
+            // level, so inline it once and let nested references use $defs.
+            FieldType::Class(name) => self.class_schema(name)?,
+            FieldType::RecursiveTypeAlias(name) => {
+                self.type_schema(self.find_recursive_alias_target(name)?)?
+            }
+            target => self.type_schema(target)?,
+        };
+
+        let mut defs = self
+            .recursive_classes
+            .iter()
+            .map(|name| Ok((name.clone(), self.class_schema(name)?)))
+            .collect::<Result<Map<_, _>>>()?;
+        for (name, target) in self.structural_recursive_aliases.iter() {
+            defs.insert(name.clone(), self.type_schema(target)?);
+        }
+
+        if !defs.is_empty() {
+            if let Value::Object(schema) = &mut schema {
+                schema.insert("$defs".into(), Value::Object(defs));
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Whether [`Self::json_schema`] describes a JSON object. Tool parameters
+    /// must be objects, so other targets have to be wrapped by the caller.
+    pub fn json_schema_is_object(&self) -> bool {
+        let mut target = &self.target;
+        while let FieldType::Constrained { base, .. } = target {
+            target = base;
+        }
+        matches!(target, FieldType::Class(_) | FieldType::Map(..))
+    }
+
+    fn class_schema(&self, name: &str) -> Result<Value> {
+        let class = self.find_class(name)?;
+
+        let mut properties = Map::new();
+        let mut required = vec![];
+        for (field_name, field_type, description) in &class.fields {
+            let mut field_schema = self.type_schema(field_type)?;
+            if let (Some(description), Value::Object(schema)) = (description, &mut field_schema) {
+                schema.insert("description".into(), json!(description));
+            }
+            properties.insert(field_name.rendered_name().to_string(), field_schema);
+            if !field_type.is_optional() {
+                required.push(field_name.rendered_name().to_string());
+            }
+        }
+
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        }))
+    }
+
+    fn type_schema(&self, field_type: &FieldType) -> Result<Value> {
+        Ok(match field_type {
+            FieldType::Primitive(t) => match t {
+                TypeValue::String => json!({ "type": "string" }),
+                TypeValue::Int => json!({ "type": "integer" }),
+                TypeValue::Float => json!({ "type": "number" }),
+                TypeValue::Bool => json!({ "type": "boolean" }),
+                TypeValue::Null => json!({ "type": "null" }),
+                TypeValue::Media(media_type) => {
+                    anyhow::bail!("type '{media_type}' is not supported in outputs")
+                }
+                TypeValue::Message => anyhow::bail!("type 'message' is not supported in outputs"),
+            },
+            FieldType::Literal(v) => match v {
+                LiteralValue::String(s) => json!({ "type": "string", "enum": [s] }),
+                LiteralValue::Int(i) => json!({ "type": "integer", "enum": [i] }),
+                LiteralValue::Float(f) => json!({ "type": "number", "enum": [f] }),
+                LiteralValue::Bool(b) => json!({ "type": "boolean", "enum": [b] }),
+            },
+            FieldType::Enum(name) => {
+                let enm = self.find_enum(name)?;
+                let mut schema = json!({
+                    "type": "string",
+                    "enum": enm
+                        .values
+                        .iter()
+                        .map(|(name, _)| name.rendered_name())
+                        .collect::<Vec<_>>(),
+                });
+                let descriptions = enm
+                    .values
+                    .iter()
+                    .filter_map(|(name, description)| {
+                        description
+                            .as_ref()
+                            .map(|d| format!("{}: {}", name.rendered_name(), d))
+                    })
+                    .collect::<Vec<_>>();
+                if !descriptions.is_empty() {
+                    schema["description"] = json!(descriptions.join("\n"));
+                }
+                schema
+            }
+            FieldType::Class(name) if self.recursive_classes.contains(name) => {
+                json!({ "$ref": format!("#/$defs/{name}") })
+            }
+            FieldType::Class(name) => self.class_schema(name)?,
+            FieldType::RecursiveTypeAlias(name) => json!({ "$ref": format!("#/$defs/{name}") }),
+            FieldType::List(inner) => json!({
+                "type": "array",
+                "items": self.type_schema(inner)?,
+            }),
+            FieldType::Map(_, value) => json!({
+                "type": "object",
+                "additionalProperties": self.type_schema(value)?,
+            }),
+            FieldType::Tuple(items) => json!({
+                "type": "array",
+                "prefixItems": items
+                    .iter()
+                    .map(|t| self.type_schema(t))
+                    .collect::<Result<Vec<_>>>()?,
+                "minItems": items.len(),
+                "maxItems": items.len(),
+            }),
+            FieldType::Union(options) => json!({
+                "anyOf": options
+                    .iter()
+                    .map(|t| self.type_schema(t))
+                    .collect::<Result<Vec<_>>>()?,
+            }),
+            FieldType::Optional(inner) => json!({
+                "anyOf": [self.type_schema(inner)?, { "type": "null" }],
+            }),
+            FieldType::Constrained { base, .. } => self.type_schema(base)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+
+    use super::*;
+    use crate::types::{Class, Enum, Name};
+
+    #[test]
+    fn class_schema_uses_aliases() {
+        let classes = vec![Class {
+            name: Name::new("Person".to_string()),
+            fields: vec![
+                (
+                    Name::new_with_alias("name".to_string(), Some("full_name".to_string())),
+                    FieldType::string(),
+                    Some("The person's name".to_string()),
+                ),
+                (
+                    Name::new("age".to_string()),
+                    FieldType::optional(FieldType::int()),
+                    None,
+                ),
+            ],
+            constraints: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Person"))
+            .classes(classes)
+            .build();
+        assert!(content.json_schema_is_object());
+        assert_eq!(
+            content.json_schema().unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "full_name": { "type": "string", "description": "The person's name" },
+                    "age": { "anyOf": [{ "type": "integer" }, { "type": "null" }] },
+                },
+                "required": ["full_name"],
+            })
+        );
+    }
+
+    #[test]
+    fn enum_schema() {
+        let enums = vec![Enum {
+            name: Name::new("Color".to_string()),
+            values: vec![
+                (Name::new("Red".to_string()), Some("Warm".to_string())),
+                (
+                    Name::new_with_alias("Blue".to_string(), Some("BLUE".to_string())),
+                    None,
+                ),
+            ],
+            constraints: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::List(Box::new(FieldType::Enum(
+            "Color".to_string(),
+        ))))
+        .enums(enums)
+        .build();
+        assert!(!content.json_schema_is_object());
+        assert_eq!(
+            content.json_schema().unwrap(),
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "enum": ["Red", "BLUE"],
+                    "description": "Red: Warm",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn recursive_class_schema() {
+        let classes = vec![Class {
+            name: Name::new("Node".to_string()),
+            fields: vec![
+                (Name::new("data".to_string()), FieldType::int(), None),
+                (
+                    Name::new("next".to_string()),
+                    FieldType::optional(FieldType::class("Node")),
+                    None,
+                ),
+            ],
+            constraints: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Node"))
+            .classes(classes)
+            .recursive_classes(IndexSet::from_iter(["Node".to_string()]))
+            .build();
+        let node = json!({
+            "type": "object",
+            "properties": {
+                "data": { "type": "integer" },
+                "next": { "anyOf": [{ "$ref": "#/$defs/Node" }, { "type": "null" }] },
+            },
+            "required": ["data"],
+        });
+        let mut expected = node.clone();
+        expected["$defs"] = json!({ "Node": node });
+        assert_eq!(content.json_schema().unwrap(), expected);
+    }
+
+    #[test]
+    fn media_is_not_supported() {
+        let content = OutputFormatContent::target(FieldType::Primitive(TypeValue::Media(
+            baml_types::BamlMediaType::Image,
+        )))
+        .build();
+        assert!(content.json_schema().is_err());
+    }
+}
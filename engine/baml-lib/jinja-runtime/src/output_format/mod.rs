@@ -1,3 +1,4 @@
+mod json_schema;
 pub mod types;
 
 use std::str::FromStr;
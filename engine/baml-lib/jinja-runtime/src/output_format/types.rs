@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use baml_types::{Constraint, FieldType, TypeValue};
+use baml_types::{BamlValue, Constraint, FieldType, TypeValue};
 use indexmap::{IndexMap, IndexSet};
 
 #[derive(Debug)]
@@ -57,7 +57,9 @@ pub struct Class {
 pub struct OutputFormatContent {
     pub enums: Arc<IndexMap<String, Enum>>,
     pub classes: Arc<IndexMap<String, Class>>,
-    recursive_classes: Arc<IndexSet<String>>,
+    pub(super) recursive_classes: Arc<IndexSet<String>>,
+    pub structural_recursive_aliases: Arc<IndexMap<String, FieldType>>,
+    pub(super) field_defaults: Arc<IndexMap<String, IndexMap<String, BamlValue>>>,
     pub target: FieldType,
 }
 
@@ -67,6 +69,10 @@ pub struct Builder {
     classes: Vec<Class>,
     /// Order matters for this one.
     recursive_classes: IndexSet<String>,
+    /// Recursive type aliases and the types they stand for.
+    structural_recursive_aliases: IndexMap<String, FieldType>,
+    /// `@default` values by class name and then field name.
+    field_defaults: IndexMap<String, IndexMap<String, BamlValue>>,
     target: FieldType,
 }
 
@@ -76,6 +82,8 @@ impl Builder {
             enums: vec![],
             classes: vec![],
             recursive_classes: IndexSet::new(),
+            structural_recursive_aliases: IndexMap::new(),
+            field_defaults: IndexMap::new(),
             target,
         }
     }
@@ -95,6 +103,22 @@ impl Builder {
         self
     }
 
+    pub fn structural_recursive_aliases(
+        mut self,
+        structural_recursive_aliases: IndexMap<String, FieldType>,
+    ) -> Self {
+        self.structural_recursive_aliases = structural_recursive_aliases;
+        self
+    }
+
+    pub fn field_defaults(
+        mut self,
+        field_defaults: IndexMap<String, IndexMap<String, BamlValue>>,
+    ) -> Self {
+        self.field_defaults = field_defaults;
+        self
+    }
+
     pub fn target(mut self, target: FieldType) -> Self {
         self.target = target;
         self
@@ -115,6 +139,8 @@ impl Builder {
                     .collect(),
             ),
             recursive_classes: Arc::new(self.recursive_classes.into_iter().collect()),
+            structural_recursive_aliases: Arc::new(self.structural_recursive_aliases),
+            field_defaults: Arc::new(self.field_defaults),
             target: self.target,
         }
     }
@@ -318,6 +344,15 @@ impl OutputFormatContent {
                 )),
                 FieldType::Literal(_) => Some(String::from("Answer using this specific value:\n")),
                 FieldType::Enum(_) => Some(String::from("Answer with any of the categories:\n")),
+                // Recursive aliases are hoisted, so only the name follows.
+                FieldType::RecursiveTypeAlias(_) => {
+                    let type_prefix = match &options.hoisted_class_prefix {
+                        RenderSetting::Always(prefix) if !prefix.is_empty() => prefix,
+                        _ => RenderOptions::DEFAULT_TYPE_PREFIX_IN_RENDER_MESSAGE,
+                    };
+
+                    Some(format!("Answer in JSON using this {type_prefix}: "))
+                }
                 FieldType::Class(cls) => {
                     let type_prefix = match &options.hoisted_class_prefix {
                         RenderSetting::Always(prefix) if !prefix.is_empty() => prefix,
@@ -341,7 +376,9 @@ impl OutputFormatContent {
                 }
                 FieldType::Optional(_) => Some(String::from("Answer in JSON using this schema:\n")),
                 FieldType::Map(_, _) => Some(String::from("Answer in JSON using this schema:\n")),
-                FieldType::Tuple(_) => None,
+                FieldType::Tuple(_) => Some(String::from(
+                    "Answer with a JSON Array using this schema:\n",
+                )),
                 FieldType::Constrained { base, .. } => {
                     auto_prefix(base, options, output_format_content)
                 }
@@ -397,6 +434,8 @@ impl OutputFormatContent {
                 Ok(nested_class.to_owned())
             }
 
+            FieldType::RecursiveTypeAlias(alias) => Ok(alias.to_owned()),
+
             _ => self.inner_type_render(options, field_type, render_state, group_hoisted_literals),
         }
     }
@@ -421,8 +460,16 @@ impl OutputFormatContent {
                         format!("type '{media_type}' is not supported in outputs"),
                     ))
                 }
+                TypeValue::Message => {
+                    return Err(minijinja::Error::new(
+                        minijinja::ErrorKind::BadSerialization,
+                        "type 'message' is not supported in outputs",
+                    ))
+                }
             },
             FieldType::Literal(v) => v.to_string(),
+            // The definition is hoisted, see [`Self::render`].
+            FieldType::RecursiveTypeAlias(alias) => alias.to_owned(),
             FieldType::Constrained { base, .. } => {
                 self.inner_type_render(options, base, render_state, group_hoisted_literals)?
             }
@@ -484,6 +531,7 @@ impl OutputFormatContent {
             FieldType::List(inner) => {
                 let is_recursive = match inner.as_ref() {
                     FieldType::Class(nested_class) => self.recursive_classes.contains(nested_class),
+                    FieldType::RecursiveTypeAlias(_) => true,
                     _ => false,
                 };
 
@@ -519,11 +567,18 @@ impl OutputFormatContent {
                     format!("{inner_str}{}null", options.or_splitter)
                 }
             }
-            FieldType::Tuple(_) => {
-                return Err(minijinja::Error::new(
-                    minijinja::ErrorKind::BadSerialization,
-                    "Tuple type is not supported in outputs",
-                ))
+            FieldType::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|t| self.render_possibly_recursive_type(options, t, render_state, false))
+                    .collect::<Result<Vec<_>, minijinja::Error>>()?;
+
+                // Keep short tuples on one line, break up ones with nested schemas.
+                if items.iter().any(|item| item.contains('\n')) {
+                    format!("[\n  {}\n]", items.join(",\n").replace('\n', "\n  "))
+                } else {
+                    format!("[{}]", items.join(", "))
+                }
             }
             FieldType::Map(key_type, value_type) => MapRender {
                 style: &options.map_style,
@@ -601,6 +656,15 @@ impl OutputFormatContent {
             });
         }
 
+        // Hoist recursive aliases as well, they can only be referred to by name.
+        let mut alias_definitions = Vec::new();
+        for (alias, target) in self.structural_recursive_aliases.iter() {
+            let recursive_pointer =
+                self.inner_type_render(&options, target, &mut render_state, false)?;
+
+            alias_definitions.push(format!("{alias} = {recursive_pointer}"));
+        }
+
         let mut output = String::new();
 
         if enum_definitions.len() > 0 {
@@ -613,6 +677,11 @@ impl OutputFormatContent {
             output.push_str("\n\n");
         }
 
+        if alias_definitions.len() > 0 {
+            output.push_str(&alias_definitions.join("\n"));
+            output.push_str("\n\n");
+        }
+
         if let Some(p) = prefix {
             output.push_str(&p);
         }
@@ -657,6 +726,17 @@ impl OutputFormatContent {
             .get(name)
             .ok_or_else(|| anyhow::anyhow!("Class {} not found", name))
     }
+
+    /// The `@default` value of a class field, if it has one.
+    pub fn find_field_default(&self, class: &str, field: &str) -> Option<&BamlValue> {
+        self.field_defaults.get(class)?.get(field)
+    }
+
+    pub fn find_recursive_alias_target(&self, name: &str) -> Result<&FieldType> {
+        self.structural_recursive_aliases
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Recursive type alias {} not found", name))
+    }
 }
 
 #[cfg(test)]
@@ -2214,4 +2294,132 @@ Answer in JSON using this schema:
             ))
         );
     }
+
+    #[test]
+    fn render_recursive_alias() {
+        let json_value = FieldType::union(vec![
+            FieldType::int(),
+            FieldType::string(),
+            FieldType::list(FieldType::recursive_type_alias("JsonValue")),
+            FieldType::map(
+                FieldType::string(),
+                FieldType::recursive_type_alias("JsonValue"),
+            ),
+        ]);
+
+        let content = OutputFormatContent::target(FieldType::recursive_type_alias("JsonValue"))
+            .structural_recursive_aliases(IndexMap::from([("JsonValue".to_string(), json_value)]))
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(
+            rendered,
+            Some(String::from(
+r#"JsonValue = int or string or JsonValue[] or map<string, JsonValue>
+
+Answer in JSON using this schema: JsonValue"#
+            ))
+        );
+    }
+
+    #[test]
+    fn render_class_with_recursive_alias_field() {
+        let classes = vec![Class {
+            name: Name::new("Config".to_string()),
+            fields: vec![
+                (Name::new("name".to_string()), FieldType::string(), None),
+                (
+                    Name::new("values".to_string()),
+                    FieldType::recursive_type_alias("Tree"),
+                    None,
+                ),
+            ],
+            constraints: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Config"))
+            .classes(classes)
+            .structural_recursive_aliases(IndexMap::from([(
+                "Tree".to_string(),
+                FieldType::map(FieldType::string(), FieldType::recursive_type_alias("Tree")),
+            )]))
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(
+            rendered,
+            Some(String::from(
+r#"Tree = map<string, Tree>
+
+Answer in JSON using this schema:
+{
+  name: string,
+  values: Tree,
+}"#
+            ))
+        );
+    }
+
+    #[test]
+    fn render_tuple() {
+        let content = OutputFormatContent::target(FieldType::tuple(vec![
+            FieldType::string(),
+            FieldType::int(),
+        ]))
+        .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some("Answer with a JSON Array using this schema:\n[string, int]".to_string())
+        );
+    }
+
+    #[test]
+    fn render_tuple_with_class() {
+        let classes = vec![Class {
+            name: Name::new("Point".to_string()),
+            fields: vec![
+                (Name::new("x".to_string()), FieldType::int(), None),
+                (Name::new("y".to_string()), FieldType::int(), None),
+            ],
+            constraints: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::tuple(vec![
+            FieldType::string(),
+            FieldType::class("Point"),
+        ]))
+        .classes(classes)
+        .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(
+            rendered,
+            Some(String::from(
+r#"Answer with a JSON Array using this schema:
+[
+  string,
+  {
+    x: int,
+    y: int,
+  }
+]"#
+            ))
+        );
+    }
+
+    #[test]
+    fn render_numeric_literals() {
+        let content = OutputFormatContent::target(FieldType::union(vec![
+            FieldType::literal_int(-1),
+            FieldType::literal_float(0.5),
+            FieldType::literal_float(2.0),
+        ]))
+        .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some("Answer in JSON using any of these schemas:\n-1 or 0.5 or 2.0".to_string())
+        );
+    }
 }
@@ -73,6 +73,7 @@ impl Type {
             (Type::Number, _) => false,
 
             (Type::Literal(LiteralValue::Int(_)), Type::Int | Type::Number) => true,
+            (Type::Literal(LiteralValue::Float(_)), Type::Float | Type::Number) => true,
             (Type::Literal(LiteralValue::Bool(_)), Type::Bool) => true,
             (Type::Literal(LiteralValue::String(_)), Type::String) => true,
             (Type::Literal(_), _) => false,
@@ -158,7 +158,9 @@ pub(super) fn pick_best(
                         cond.conditions().flags.iter().any(|f| {
                             matches!(
                                 f,
-                                Flag::OptionalDefaultFromNoValue | Flag::DefaultFromNoValue
+                                Flag::OptionalDefaultFromNoValue
+                                    | Flag::DefaultFromNoValue
+                                    | Flag::DefaultFromAttribute
                             )
                         })
                     });
@@ -166,7 +168,9 @@ pub(super) fn pick_best(
                         cond.conditions().flags.iter().any(|f| {
                             matches!(
                                 f,
-                                Flag::OptionalDefaultFromNoValue | Flag::DefaultFromNoValue
+                                Flag::OptionalDefaultFromNoValue
+                                    | Flag::DefaultFromNoValue
+                                    | Flag::DefaultFromAttribute
                             )
                         })
                     });
@@ -12,7 +12,13 @@ use crate::{
     jsonish,
 };
 
-use super::{coerce_primitive::coerce_int, ParsingContext, ParsingError};
+use super::{
+    coerce_primitive::{coerce_float, coerce_int},
+    ParsingContext, ParsingError,
+};
+
+/// Relative difference under which a number still matches a float literal.
+const FLOAT_LITERAL_TOLERANCE: f64 = 1e-9;
 
 impl TypeCoercer for LiteralValue {
     fn coerce(
@@ -60,6 +66,22 @@ impl TypeCoercer for LiteralValue {
                 }
             }
 
+            LiteralValue::Float(literal_float) => {
+                let BamlValueWithFlags::Float(coerced_float) =
+                    coerce_float(ctx, target, Some(value))?
+                else {
+                    unreachable!("coerce_float returned a non-float value");
+                };
+
+                // Allow for rounding noise, `0.1 + 0.2` should still match `0.3`.
+                let tolerance = FLOAT_LITERAL_TOLERANCE * literal_float.abs().max(1.0);
+                if (coerced_float.value() - literal_float).abs() <= tolerance {
+                    Ok(BamlValueWithFlags::Float(coerced_float))
+                } else {
+                    Err(ctx.error_unexpected_type(target, value))
+                }
+            }
+
             LiteralValue::Bool(literal_bool) => {
                 let BamlValueWithFlags::Bool(coerced_bool) = coerce_bool(ctx, target, Some(value))?
                 else {
@@ -40,6 +40,7 @@ impl TypeCoercer for TypeValue {
             TypeValue::Null => coerce_null(ctx, target, value),
             TypeValue::Media(BamlMediaType::Image) => Err(ctx.error_image_not_supported()),
             TypeValue::Media(BamlMediaType::Audio) => Err(ctx.error_audio_not_supported()),
+            TypeValue::Message => Err(ctx.error_message_not_supported()),
         }
     }
 }
@@ -167,7 +168,7 @@ fn float_from_comma_separated(value: &str) -> Option<f64> {
     without_currency.parse::<f64>().ok()
 }
 
-fn coerce_float(
+pub(super) fn coerce_float(
     ctx: &ParsingContext,
     target: &FieldType,
     value: Option<&crate::jsonish::Value>,
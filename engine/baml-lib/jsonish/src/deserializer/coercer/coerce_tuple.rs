@@ -0,0 +1,70 @@
+use anyhow::Result;
+use internal_baml_core::ir::FieldType;
+
+use crate::deserializer::{
+    deserialize_flags::{DeserializerConditions, Flag},
+    types::BamlValueWithFlags,
+};
+
+use super::{DefaultValue, ParsingContext, ParsingError, TypeCoercer};
+
+pub(super) fn coerce_tuple(
+    ctx: &ParsingContext,
+    tuple_target: &FieldType,
+    value: Option<&crate::jsonish::Value>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    log::debug!(
+        "scope: {scope} :: coercing to: {name} (current: {current})",
+        name = tuple_target.to_string(),
+        scope = ctx.display_scope(),
+        current = value.map(|v| v.r#type()).unwrap_or("<null>".into())
+    );
+
+    let FieldType::Tuple(item_types) = tuple_target else {
+        unreachable!("coerce_tuple called with non-tuple type {tuple_target}");
+    };
+
+    let Some(value) = value else {
+        return Err(ctx.error_unexpected_null(tuple_target));
+    };
+
+    let crate::jsonish::Value::Array(arr) = value else {
+        return Err(ctx.error_unexpected_type(tuple_target, value));
+    };
+
+    let mut flags = DeserializerConditions::new();
+    let mut items = Vec::with_capacity(item_types.len());
+    let mut errors = Vec::new();
+
+    for (i, item_type) in item_types.iter().enumerate() {
+        let item_ctx = ctx.enter_scope(&format!("{i}"));
+        let parsed = match arr.get(i) {
+            Some(item) => item_type.coerce(&item_ctx, item_type, Some(item)),
+            None => Err(item_ctx.error_unexpected_null(item_type)),
+        };
+
+        match parsed {
+            Ok(v) => items.push(v),
+            Err(e) => match item_type.default_value(arr.get(i).map(|_| &e)) {
+                Some(v) => items.push(v),
+                // While streaming, items that haven't arrived yet are null.
+                None if ctx.allow_partials => items.push(BamlValueWithFlags::Null(
+                    DeserializerConditions::new().with_flag(Flag::OptionalDefaultFromNoValue),
+                )),
+                None => errors.push(e),
+            },
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(
+            ctx.error_merge_multiple(&format!("Failed to parse {}", tuple_target), errors.iter())
+        );
+    }
+
+    for (i, extra) in arr.iter().enumerate().skip(item_types.len()) {
+        flags.add_flag(Flag::ExtraKey(format!("{i}"), extra.clone()));
+    }
+
+    Ok(BamlValueWithFlags::List(flags, items))
+}
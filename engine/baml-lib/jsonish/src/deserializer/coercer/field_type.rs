@@ -10,8 +10,8 @@ use crate::deserializer::{
 
 use super::{
     array_helper, coerce_array::coerce_array, coerce_map::coerce_map,
-    coerce_optional::coerce_optional, coerce_union::coerce_union, ir_ref::IrRef, ParsingContext,
-    ParsingError,
+    coerce_optional::coerce_optional, coerce_tuple::coerce_tuple, coerce_union::coerce_union,
+    ir_ref::IrRef, ParsingContext, ParsingError,
 };
 
 impl TypeCoercer for FieldType {
@@ -79,11 +79,18 @@ impl TypeCoercer for FieldType {
                 FieldType::Enum(e) => IrRef::Enum(e).coerce(ctx, target, value),
                 FieldType::Literal(l) => l.coerce(ctx, target, value),
                 FieldType::Class(c) => IrRef::Class(c).coerce(ctx, target, value),
+                FieldType::RecursiveTypeAlias(name) => {
+                    let alias_target = ctx
+                        .of
+                        .find_recursive_alias_target(name)
+                        .map_err(|e| ctx.error_internal(e))?;
+                    alias_target.coerce(ctx, alias_target, value)
+                }
                 FieldType::List(_) => coerce_array(ctx, self, value),
                 FieldType::Union(_) => coerce_union(ctx, self, value),
                 FieldType::Optional(_) => coerce_optional(ctx, self, value),
                 FieldType::Map(_, _) => coerce_map(ctx, self, value),
-                FieldType::Tuple(_) => Err(ctx.error_internal("Tuple not supported")),
+                FieldType::Tuple(_) => coerce_tuple(ctx, self, value),
                 FieldType::Constrained { base, .. } => {
                     let mut coerced_value = base.coerce(ctx, base, value)?;
                     let constraint_results = run_user_checks(&coerced_value.clone().into(), &self)
@@ -163,6 +170,8 @@ impl DefaultValue for FieldType {
             FieldType::Enum(e) => None,
             FieldType::Literal(_) => None,
             FieldType::Class(_) => None,
+            // Needs the alias definitions to know what the default would be.
+            FieldType::RecursiveTypeAlias(_) => None,
             FieldType::List(_) => Some(BamlValueWithFlags::List(get_flags(), Vec::new())),
             FieldType::Union(items) => items.iter().find_map(|i| i.default_value(error)),
             FieldType::Primitive(TypeValue::Null) | FieldType::Optional(_) => {
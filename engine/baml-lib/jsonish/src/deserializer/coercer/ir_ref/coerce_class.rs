@@ -1,5 +1,5 @@
 use anyhow::Result;
-use baml_types::{BamlMap, Constraint};
+use baml_types::{BamlMap, BamlValue, Constraint};
 use internal_baml_core::ir::FieldType;
 use internal_baml_jinja::types::{Class, Name};
 
@@ -190,49 +190,61 @@ impl TypeCoercer for Class {
                                     field_name.real_name(),
                                     e
                                 );
-                                t.default_value(Some(e))
+                                t.default_value(Some(e)).map(Ok)
                             }
                             // If we're missing a field, thats ok!
-                            None => Some(BamlValueWithFlags::Null(
-                                DeserializerConditions::new()
-                                    .with_flag(Flag::OptionalDefaultFromNoValue),
-                            )),
+                            None => {
+                                default_from_attribute(ctx, self, field_name, t).or_else(|| {
+                                    Some(Ok(BamlValueWithFlags::Null(
+                                        DeserializerConditions::new()
+                                            .with_flag(Flag::OptionalDefaultFromNoValue),
+                                    )))
+                                })
+                            }
                         };
 
                         if let Some(next) = next {
-                            optional_values
-                                .insert(field_name.real_name().to_string(), Some(Ok(next)));
+                            optional_values.insert(field_name.real_name().to_string(), Some(next));
                         }
                     }
                 } else {
                     if let Some(v) = required_values.get(field_name.real_name()) {
                         let next = match v {
                             Some(Ok(_)) => None,
-                            Some(Err(e)) => t.default_value(Some(e)).or_else(|| {
-                                if ctx.allow_partials {
-                                    Some(BamlValueWithFlags::Null(
-                                        DeserializerConditions::new()
-                                            .with_flag(Flag::OptionalDefaultFromNoValue),
-                                    ))
-                                } else {
-                                    None
-                                }
-                            }),
-                            None => t.default_value(None).or_else(|| {
-                                if ctx.allow_partials {
-                                    Some(BamlValueWithFlags::Null(
-                                        DeserializerConditions::new()
-                                            .with_flag(Flag::OptionalDefaultFromNoValue),
-                                    ))
-                                } else {
-                                    None
-                                }
-                            }),
+                            Some(Err(e)) => t
+                                .default_value(Some(e))
+                                .or_else(|| {
+                                    if ctx.allow_partials {
+                                        Some(BamlValueWithFlags::Null(
+                                            DeserializerConditions::new()
+                                                .with_flag(Flag::OptionalDefaultFromNoValue),
+                                        ))
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .map(Ok),
+                            None => {
+                                default_from_attribute(ctx, self, field_name, t).or_else(|| {
+                                    t.default_value(None)
+                                        .or_else(|| {
+                                            if ctx.allow_partials {
+                                                Some(BamlValueWithFlags::Null(
+                                                    DeserializerConditions::new().with_flag(
+                                                        Flag::OptionalDefaultFromNoValue,
+                                                    ),
+                                                ))
+                                            } else {
+                                                None
+                                            }
+                                        })
+                                        .map(Ok)
+                                })
+                            }
                         };
 
                         if let Some(next) = next {
-                            required_values
-                                .insert(field_name.real_name().to_string(), Some(Ok(next)));
+                            required_values.insert(field_name.real_name().to_string(), Some(next));
                         }
                     }
                 }
@@ -379,6 +391,136 @@ pub fn apply_constraints(
     res
 }
 
+/// The `@default` of a field the LLM left out, coerced to the field type.
+///
+/// While streaming, a missing field may just not have arrived yet, so the
+/// default is only used once the whole response is in. A default that does
+/// not coerce is reported as the field's error rather than dropped.
+fn default_from_attribute(
+    ctx: &ParsingContext,
+    class: &Class,
+    field_name: &Name,
+    t: &FieldType,
+) -> Option<Result<BamlValueWithFlags, ParsingError>> {
+    if ctx.allow_partials {
+        return None;
+    }
+
+    let default = ctx
+        .of
+        .find_field_default(class.name.real_name(), field_name.real_name())?;
+
+    let scope = ctx.enter_scope(field_name.real_name());
+    let parsed = rendered_default(&scope, default, t)
+        .and_then(|value| t.coerce(&scope, t, Some(&value)))
+        .map(|mut parsed| {
+            parsed.add_flag(Flag::DefaultFromAttribute);
+            parsed
+        });
+    Some(parsed)
+}
+
+/// Turns a `@default` value into what the LLM would have sent for it.
+///
+/// Defaults are written with the names used in BAML, but the coercers match
+/// against the names the LLM sees, so class fields and enum values with an
+/// `@alias` are renamed here.
+fn rendered_default(
+    ctx: &ParsingContext,
+    value: &BamlValue,
+    t: &FieldType,
+) -> Result<crate::jsonish::Value, ParsingError> {
+    use crate::jsonish::Value;
+
+    match (t, value) {
+        (FieldType::Optional(inner), _) if !matches!(value, BamlValue::Null) => {
+            rendered_default(ctx, value, inner)
+        }
+        (FieldType::Constrained { base, .. }, _) => rendered_default(ctx, value, base),
+        (FieldType::RecursiveTypeAlias(name), _) => {
+            match ctx.of.find_recursive_alias_target(name) {
+                Ok(target) => rendered_default(ctx, value, target),
+                Err(e) => Err(ctx.error_internal(e)),
+            }
+        }
+        (FieldType::Union(options), _) => options
+            .iter()
+            .find(|option| fits_default(ctx, value, option))
+            .map_or_else(
+                || untyped_default(ctx, value),
+                |option| rendered_default(ctx, value, option),
+            ),
+        (FieldType::Class(name), BamlValue::Class(_, fields) | BamlValue::Map(fields)) => {
+            let class = ctx.of.find_class(name).map_err(|e| ctx.error_internal(e))?;
+            let fields = fields
+                .iter()
+                .map(|(key, value)| {
+                    match class.fields.iter().find(|(f, ..)| f.real_name() == key) {
+                        Some((f, field_type, _)) => Ok((
+                            f.rendered_name().to_string(),
+                            rendered_default(ctx, value, field_type)?,
+                        )),
+                        None => Ok((key.clone(), untyped_default(ctx, value)?)),
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Value::Object(fields))
+        }
+        (FieldType::Enum(name), BamlValue::Enum(_, v) | BamlValue::String(v)) => {
+            let rendered = ctx
+                .of
+                .find_enum(name)
+                .ok()
+                .and_then(|e| e.values.iter().find(|(n, _)| n.real_name() == v))
+                .map_or(v.as_str(), |(n, _)| n.rendered_name());
+            Ok(Value::String(rendered.to_string()))
+        }
+        (FieldType::List(item), BamlValue::List(items)) => Ok(Value::Array(
+            items
+                .iter()
+                .map(|v| rendered_default(ctx, v, item))
+                .collect::<Result<_, _>>()?,
+        )),
+        (FieldType::Map(_, item), BamlValue::Map(entries)) => Ok(Value::Object(
+            entries
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), rendered_default(ctx, v, item)?)))
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => untyped_default(ctx, value),
+    }
+}
+
+fn untyped_default(
+    ctx: &ParsingContext,
+    value: &BamlValue,
+) -> Result<crate::jsonish::Value, ParsingError> {
+    serde_json::to_value(value)
+        .and_then(serde_json::from_value::<crate::jsonish::Value>)
+        .map_err(|e| ctx.error_internal(e))
+}
+
+/// Whether a `@default` value has the shape of one option of a union, so its
+/// names can be rendered for that option.
+fn fits_default(ctx: &ParsingContext, value: &BamlValue, t: &FieldType) -> bool {
+    match (t, value) {
+        (FieldType::Class(name), BamlValue::Class(_, fields) | BamlValue::Map(fields)) => {
+            ctx.of.find_class(name).is_ok_and(|class| {
+                fields
+                    .keys()
+                    .all(|key| class.fields.iter().any(|(f, ..)| f.real_name() == key))
+            })
+        }
+        (FieldType::Enum(name), BamlValue::Enum(_, v) | BamlValue::String(v)) => ctx
+            .of
+            .find_enum(name)
+            .is_ok_and(|e| e.values.iter().any(|(n, _)| n.real_name() == v)),
+        (FieldType::List(_), BamlValue::List(_)) => true,
+        (FieldType::Map(..), BamlValue::Map(_)) => true,
+        _ => false,
+    }
+}
+
 fn update_map<'a>(
     required_values: &'a mut BamlMap<String, Option<Result<BamlValueWithFlags, ParsingError>>>,
     optional_values: &'a mut BamlMap<String, Option<Result<BamlValueWithFlags, ParsingError>>>,
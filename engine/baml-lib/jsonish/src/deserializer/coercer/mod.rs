@@ -4,6 +4,7 @@ mod coerce_literal;
 mod coerce_map;
 mod coerce_optional;
 mod coerce_primitive;
+mod coerce_tuple;
 mod coerce_union;
 mod field_type;
 mod ir_ref;
@@ -139,6 +140,14 @@ impl ParsingContext<'_> {
         }
     }
 
+    pub(crate) fn error_message_not_supported(&self) -> ParsingError {
+        ParsingError {
+            reason: "Message type is not supported here".to_string(),
+            scope: self.scope.clone(),
+            causes: vec![],
+        }
+    }
+
     pub(crate) fn error_map_must_have_string_key(&self, key_type: &FieldType) -> ParsingError {
         ParsingError {
             reason: format!("Maps may only have strings for keys, but got {}", key_type),
@@ -33,6 +33,8 @@ pub enum Flag {
     DefaultFromNoValue,
     DefaultButHadValue(crate::jsonish::Value),
     OptionalDefaultFromNoValue,
+    /// The field was missing and its `@default` was used instead.
+    DefaultFromAttribute,
 
     // String -> X convertions.
     StringToBool(String),
@@ -88,6 +90,7 @@ impl DeserializerConditions {
                 Flag::DefaultFromNoValue => None,
                 Flag::DefaultButHadValue(_) => None,
                 Flag::OptionalDefaultFromNoValue => None,
+                Flag::DefaultFromAttribute => None,
                 Flag::StringToBool(_) => None,
                 Flag::StringToNull(_) => None,
                 Flag::StringToChar(_) => None,
@@ -145,6 +148,9 @@ impl std::fmt::Display for Flag {
             Flag::DefaultFromNoValue => {
                 write!(f, "Default value")?;
             }
+            Flag::DefaultFromAttribute => {
+                write!(f, "Default value from @default")?;
+            }
             Flag::ObjectFromFixedJson(fixes) => {
                 write!(f, "JSON (Fixed {} mistakes)", fixes.len())?;
             }
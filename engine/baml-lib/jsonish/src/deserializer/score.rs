@@ -38,6 +38,7 @@ impl WithScore for Flag {
             Flag::OptionalDefaultFromNoValue => 1,
             Flag::DefaultFromNoValue => 100,
             Flag::DefaultButHadValue(_) => 110,
+            Flag::DefaultFromAttribute => 20,
             Flag::ObjectFromFixedJson(_) => 0,
             Flag::ObjectFromMarkdown(s) => *s,
             Flag::DefaultButHadUnparseableValue(_) => 2,
@@ -9,14 +9,16 @@ mod test_class;
 mod test_class_2;
 mod test_code;
 mod test_constraints;
+mod test_defaults;
 mod test_enum;
 mod test_lists;
 mod test_literals;
 mod test_maps;
 mod test_partials;
+mod test_tuples;
 mod test_unions;
 
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use std::{
     collections::{HashMap, HashSet},
     path::PathBuf,
@@ -55,15 +57,44 @@ fn render_output_format(
     output: &FieldType,
     env_values: &HashMap<String, String>,
 ) -> Result<OutputFormatContent> {
-    let (enums, classes, recursive_classes) = relevant_data_models(ir, output, env_values)?;
+    let (enums, classes, recursive_classes, structural_recursive_aliases) =
+        relevant_data_models(ir, output, env_values)?;
+    let field_defaults = field_defaults(ir, &classes, env_values)?;
 
     Ok(OutputFormatContent::target(output.clone())
         .enums(enums)
         .classes(classes)
         .recursive_classes(recursive_classes)
+        .structural_recursive_aliases(structural_recursive_aliases)
+        .field_defaults(field_defaults)
         .build())
 }
 
+fn field_defaults(
+    ir: &IntermediateRepr,
+    classes: &[Class],
+    env_values: &HashMap<String, String>,
+) -> Result<IndexMap<String, IndexMap<String, BamlValue>>> {
+    let mut field_defaults = IndexMap::new();
+
+    for class in classes {
+        let walker = ir.find_class(class.name.real_name())?;
+
+        let mut defaults = IndexMap::new();
+        for field in walker.walk_fields() {
+            if let Some(default) = field.default_value(env_values)? {
+                defaults.insert(field.name().to_string(), default);
+            }
+        }
+
+        if !defaults.is_empty() {
+            field_defaults.insert(class.name.real_name().to_string(), defaults);
+        }
+    }
+
+    Ok(field_defaults)
+}
+
 fn find_existing_class_field<'a>(
     class_name: &str,
     field_name: &str,
@@ -125,11 +156,17 @@ fn relevant_data_models<'a>(
     ir: &'a IntermediateRepr,
     output: &'a FieldType,
     env_values: &HashMap<String, String>,
-) -> Result<(Vec<Enum>, Vec<Class>, IndexSet<String>)> {
+) -> Result<(
+    Vec<Enum>,
+    Vec<Class>,
+    IndexSet<String>,
+    IndexMap<String, FieldType>,
+)> {
     let mut checked_types: HashSet<String> = HashSet::new();
     let mut enums = Vec::new();
     let mut classes: Vec<Class> = Vec::new();
     let mut recursive_classes = IndexSet::new();
+    let mut structural_recursive_aliases = IndexMap::new();
     let mut start: Vec<baml_types::FieldType> = vec![output.clone()];
 
     while !start.is_empty() {
@@ -231,6 +268,24 @@ fn relevant_data_models<'a>(
                     });
                 }
             }
+            (FieldType::RecursiveTypeAlias(name), _) => {
+                if checked_types.insert(output.to_string()) {
+                    // Every alias in the cycle is rendered alongside this one,
+                    // so collect them all at once.
+                    for cycle in ir.structural_recursive_alias_cycles() {
+                        if cycle.contains_key(name) {
+                            for (alias, target) in cycle {
+                                if structural_recursive_aliases
+                                    .insert(alias.to_owned(), target.to_owned())
+                                    .is_none()
+                                {
+                                    start.push(target.to_owned());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
             (FieldType::Literal(_), _) => {}
             (FieldType::Primitive(_), _constraints) => {}
             (FieldType::Constrained { .. }, _) => {
@@ -239,7 +294,12 @@ fn relevant_data_models<'a>(
         }
     }
 
-    Ok((enums, classes, recursive_classes))
+    Ok((
+        enums,
+        classes,
+        recursive_classes,
+        structural_recursive_aliases,
+    ))
 }
 
 const EMPTY_FILE: &str = r#"
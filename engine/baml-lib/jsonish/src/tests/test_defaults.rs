@@ -0,0 +1,129 @@
+use super::*;
+
+const FOO_WITH_DEFAULTS: &str = r#"
+enum Status {
+  Active
+  Inactive
+}
+
+class Foo {
+  name string
+  retries int @default(3)
+  ratio float @default(1)
+  status Status @default(Active)
+  tags string[] @default(["a", "b"])
+  note string? @default("none")
+}
+"#;
+
+test_deserializer!(
+    test_missing_fields_use_defaults,
+    FOO_WITH_DEFAULTS,
+    r#"{"name": "foo"}"#,
+    FieldType::Class("Foo".to_string()),
+    {
+        "name": "foo",
+        "retries": 3,
+        "ratio": 1.0,
+        "status": "Active",
+        "tags": ["a", "b"],
+        "note": "none"
+    }
+);
+
+test_deserializer!(
+    test_present_fields_ignore_defaults,
+    FOO_WITH_DEFAULTS,
+    r#"{"name": "foo", "retries": 5, "ratio": 0.5, "status": "Inactive", "tags": [], "note": null}"#,
+    FieldType::Class("Foo".to_string()),
+    {
+        "name": "foo",
+        "retries": 5,
+        "ratio": 0.5,
+        "status": "Inactive",
+        "tags": [],
+        "note": null
+    }
+);
+
+test_failing_deserializer!(
+    test_defaults_do_not_replace_required_fields,
+    FOO_WITH_DEFAULTS,
+    r#"{"retries": 5}"#,
+    FieldType::Class("Foo".to_string())
+);
+
+const BAR_WITH_DEFAULT: &str = r#"
+class Bar {
+  a int
+  b int @default(0)
+}
+"#;
+
+test_deserializer_with_expected_score!(
+    test_default_is_scored,
+    BAR_WITH_DEFAULT,
+    r#"{"a": 1}"#,
+    FieldType::Class("Bar".to_string()),
+    20
+);
+
+test_partial_deserializer!(
+    test_partial_does_not_use_defaults,
+    BAR_WITH_DEFAULT,
+    r#"{"a": 1}"#,
+    FieldType::Class("Bar".to_string()),
+    {"a": 1, "b": null}
+);
+
+test_deserializer!(
+    test_class_default,
+    r#"
+class Inner {
+  x int
+}
+
+class Outer {
+  id int
+  inner Inner @default({ x 1 })
+}
+"#,
+    r#"{"id": 7}"#,
+    FieldType::Class("Outer".to_string()),
+    {"id": 7, "inner": {"x": 1}}
+);
+
+test_deserializer!(
+    test_class_default_with_aliased_field,
+    r#"
+class Inner {
+  x int @alias("ex")
+}
+
+class Outer {
+  id int
+  inner Inner @default({ x 1 })
+}
+"#,
+    r#"{"id": 7}"#,
+    FieldType::Class("Outer".to_string()),
+    {"id": 7, "inner": {"x": 1}}
+);
+
+test_deserializer!(
+    test_enum_default_with_aliased_value,
+    r#"
+enum Status {
+  Active @alias("on")
+  Inactive @alias("off")
+}
+
+class Foo {
+  name string
+  status Status @default(Inactive)
+}
+"#,
+    r#"{"name": "foo"}"#,
+    FieldType::Class("Foo".to_string()),
+    {"name": "foo", "status": "Inactive"}
+);
@@ -209,3 +209,61 @@ test_deserializer!(
     ]),
     "TWO"
 );
+
+test_deserializer!(
+    test_literal_float,
+    EMPTY_FILE,
+    "0.5",
+    FieldType::Literal(LiteralValue::Float(0.5)),
+    0.5
+);
+
+test_deserializer!(
+    test_literal_float_negative,
+    EMPTY_FILE,
+    "The value is -2.75",
+    FieldType::Literal(LiteralValue::Float(-2.75)),
+    -2.75
+);
+
+test_deserializer!(
+    test_literal_float_from_whole_number,
+    EMPTY_FILE,
+    "3",
+    FieldType::Literal(LiteralValue::Float(3.0)),
+    3.0
+);
+
+test_deserializer!(
+    test_literal_float_from_quoted_string,
+    EMPTY_FILE,
+    r#""0.50""#,
+    FieldType::Literal(LiteralValue::Float(0.5)),
+    0.5
+);
+
+test_deserializer!(
+    test_literal_float_from_fraction,
+    EMPTY_FILE,
+    "1/4",
+    FieldType::Literal(LiteralValue::Float(0.25)),
+    0.25
+);
+
+test_failing_deserializer!(
+    test_literal_float_mismatch,
+    EMPTY_FILE,
+    "0.51",
+    FieldType::Literal(LiteralValue::Float(0.5))
+);
+
+test_deserializer!(
+    test_union_literal_float_picks_match,
+    EMPTY_FILE,
+    "-0.5",
+    FieldType::Union(vec![
+        FieldType::Literal(LiteralValue::Float(0.5)),
+        FieldType::Literal(LiteralValue::Float(-0.5)),
+    ]),
+    -0.5
+);
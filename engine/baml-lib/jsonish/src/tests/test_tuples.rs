@@ -0,0 +1,70 @@
+use super::*;
+
+test_deserializer!(
+    test_tuple,
+    "",
+    r#"[1, "hello"]"#,
+    FieldType::tuple(vec![FieldType::int(), FieldType::string()]),
+    [1, "hello"]
+);
+
+test_deserializer!(
+    test_tuple_with_class,
+    r#"
+    class Foo {
+        a int
+    }"#,
+    r#"The answer is [{"a": 1}, true]"#,
+    FieldType::tuple(vec![FieldType::class("Foo"), FieldType::bool()]),
+    [{"a": 1}, true]
+);
+
+test_deserializer!(
+    test_tuple_ignores_extra_items,
+    "",
+    r#"[1, "hello", 3]"#,
+    FieldType::tuple(vec![FieldType::int(), FieldType::string()]),
+    [1, "hello"]
+);
+
+test_deserializer!(
+    test_tuple_missing_optional_item,
+    "",
+    r#"[1]"#,
+    FieldType::tuple(vec![FieldType::int(), FieldType::string().as_optional()]),
+    [1, null]
+);
+
+test_deserializer!(
+    test_list_of_tuples,
+    "",
+    r#"[["a", 1], ["b", 2]]"#,
+    FieldType::tuple(vec![FieldType::string(), FieldType::int()]).as_list(),
+    [["a", 1], ["b", 2]]
+);
+
+test_failing_deserializer!(
+    test_tuple_missing_item,
+    "",
+    r#"[1]"#,
+    FieldType::tuple(vec![FieldType::int(), FieldType::string()])
+);
+
+test_failing_deserializer!(
+    test_tuple_from_object,
+    "",
+    r#"{"a": 1}"#,
+    FieldType::tuple(vec![FieldType::int(), FieldType::string()])
+);
+
+test_partial_deserializer!(
+    test_partial_tuple,
+    "",
+    r#"[1, "hel"#,
+    FieldType::tuple(vec![
+        FieldType::int(),
+        FieldType::string(),
+        FieldType::int()
+    ]),
+    [1, "hel", null]
+);
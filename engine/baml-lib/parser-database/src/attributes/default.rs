@@ -0,0 +1,11 @@
+use crate::{context::Context, types::Attributes};
+
+pub(super) fn visit_default_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    match ctx
+        .visit_default_arg_with_idx("default")
+        .map(|(_, value)| value)
+    {
+        Ok(default) => attributes.add_default(default.clone()),
+        Err(err) => ctx.push_error(err), // not flattened for error handing legacy reasons
+    };
+}
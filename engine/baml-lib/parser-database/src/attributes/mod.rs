@@ -1,7 +1,8 @@
-use internal_baml_schema_ast::ast::{Top, TopId, TypeExpId, TypeExpressionBlock};
+use internal_baml_schema_ast::ast::{Top, TopId, TypeExpId, TypeExpressionBlock, ValExpId};
 
 mod alias;
 mod constraint;
+mod default;
 mod description;
 mod to_string_attribute;
 use crate::interner::StringId;
@@ -26,6 +27,9 @@ pub struct Attributes {
 
     /// @check and @assert attributes attached to the node.
     pub constraints: Vec<Constraint>,
+
+    /// Value used for a class field when the LLM leaves it out.
+    pub default: Option<Expression>,
 }
 
 impl Attributes {
@@ -68,6 +72,16 @@ impl Attributes {
     pub fn set_skip(&mut self) {
         self.skip.replace(true);
     }
+
+    /// Set the default value.
+    pub fn add_default(&mut self, default: Expression) {
+        self.default.replace(default);
+    }
+
+    /// Get the default value.
+    pub fn default_value(&self) -> &Option<Expression> {
+        &self.default
+    }
 }
 pub(super) fn resolve_attributes(ctx: &mut Context<'_>) {
     for top in ctx.ast.iter_tops() {
@@ -78,11 +92,43 @@ pub(super) fn resolve_attributes(ctx: &mut Context<'_>) {
             (TopId::Enum(enum_id), Top::Enum(ast_enum)) => {
                 resolve_type_exp_block_attributes(enum_id, ast_enum, ctx, SubType::Enum)
             }
+            (TopId::TestCase(test_id), Top::TestCase(_)) => {
+                resolve_test_case_attributes(test_id, ctx)
+            }
+            (
+                TopId::Function(block_id)
+                | TopId::Client(block_id)
+                | TopId::Generator(block_id)
+                | TopId::RetryPolicy(block_id),
+                _,
+            ) => {
+                // No block attributes are supported here, so this reports any that are present.
+                ctx.assert_all_attributes_processed(block_id.into());
+                ctx.validate_visited_attributes();
+            }
             _ => (),
         }
     }
 }
 
+fn resolve_test_case_attributes(test_id: ValExpId, ctx: &mut Context<'_>) {
+    let mut attributes = Attributes::default();
+
+    ctx.assert_all_attributes_processed(test_id.into());
+    while let Some((attribute_name, span)) =
+        ctx.visit_repeated_attr_from_names(&["assert", "check"])
+    {
+        constraint::visit_constraint_attributes(attribute_name, span, &mut attributes, ctx);
+        ctx.validate_visited_arguments();
+    }
+    ctx.validate_visited_attributes();
+
+    // The test case is missing if its fields failed to validate.
+    if let Some(test_case) = ctx.types.test_cases.get_mut(&test_id) {
+        test_case.constraints = attributes.constraints;
+    }
+}
+
 fn resolve_type_exp_block_attributes<'db>(
     type_id: TypeExpId,
     ast_typexpr: &'db TypeExpressionBlock,
@@ -114,7 +160,16 @@ fn resolve_type_exp_block_attributes<'db>(
 
             for (field_idx, field) in ast_typexpr.iter_fields() {
                 ctx.assert_all_attributes_processed((type_id, field_idx).into());
-                if let Some(attrs) = to_string_attribute::visit(ctx, &field.span, false) {
+                let mut attrs = to_string_attribute::visit(ctx, &field.span, false);
+                // Only class fields can have a default, enum values can't.
+                if ctx.visit_optional_single_attr("default") {
+                    default::visit_default_attribute(
+                        attrs.get_or_insert_with(Default::default),
+                        ctx,
+                    );
+                    ctx.validate_visited_arguments();
+                }
+                if let Some(attrs) = attrs {
                     class_attributes.field_serilizers.insert(field_idx, attrs);
                 }
                 ctx.validate_visited_attributes();
@@ -62,6 +62,9 @@ use names::Names;
 ///
 /// Validations are carried out in the following order:
 ///
+/// - Type references are rewritten to the fully qualified name of the class,
+///   enum or type alias they refer to, using the namespace and the imports of
+///   their file.
 /// - The AST is walked a first time to resolve names: to each relevant
 ///   identifier, we attach an ID that can be used to reference the
 ///   corresponding item (model, enum, field, ...)
@@ -88,7 +91,7 @@ impl ParserDatabase {
     /// Create a new, empty ParserDatabase.
     pub fn new() -> Self {
         ParserDatabase {
-            ast: ast::SchemaAst { tops: vec![] },
+            ast: ast::SchemaAst::new(),
             interner: Default::default(),
             names: Default::default(),
             types: Default::default(),
@@ -98,10 +101,14 @@ impl ParserDatabase {
     /// See the docs on [ParserDatabase](/struct.ParserDatabase.html).
     pub fn add_ast(&mut self, ast: SchemaAst) {
         self.ast.tops.extend(ast.tops);
+        self.ast.file_scopes.extend(ast.file_scopes);
     }
 
     /// See the docs on [ParserDatabase](/struct.ParserDatabase.html).
     pub fn validate(&mut self, diag: &mut Diagnostics) -> Result<(), Diagnostics> {
+        // Type references can only be qualified once every file is known.
+        names::qualify_type_references(&mut self.ast, diag);
+
         let mut ctx = Context::new(
             &self.ast,
             &mut self.interner,
@@ -267,6 +274,32 @@ mod test {
         Ok(())
     }
 
+    fn assert_structural_alias_cycles(
+        baml: &'static str,
+        expected: &[&[&str]],
+    ) -> Result<(), Diagnostics> {
+        let mut db = ParserDatabase::new();
+        let source = SourceFile::new_static(PathBuf::from("test.baml"), baml);
+        let (ast, mut diag) = parse_schema(&source.path_buf(), &source)?;
+
+        db.add_ast(ast);
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+
+        assert_eq!(
+            db.structural_recursive_alias_cycles()
+                .iter()
+                .map(|ids| Vec::from_iter(ids.iter().map(|id| db.ast()[*id].name.to_string())))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|cycle| Vec::from_iter(cycle.iter().map(ToString::to_string)))
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn find_simple_recursive_class() -> Result<(), Diagnostics> {
         assert_finite_cycles(
@@ -479,4 +512,287 @@ mod test {
             &[&["RecMap"]],
         )
     }
+
+    #[test]
+    fn find_class_cycle_through_alias() -> Result<(), Diagnostics> {
+        assert_finite_cycles(
+            r#"
+                type Children = Node[]
+
+                class Node {
+                    data int
+                    children Children
+                }
+            "#,
+            &[&["Node"]],
+        )
+    }
+
+    #[test]
+    fn find_recursive_aliases() -> Result<(), Diagnostics> {
+        assert_structural_alias_cycles(
+            r#"
+                type JsonValue = int | string | bool | JsonValue[] | map<string, JsonValue>
+
+                type A = B[]
+                type B = map<string, A>
+
+                type NotRecursive = A | string
+            "#,
+            &[&["JsonValue"], &["A", "B"]],
+        )
+    }
+
+    #[test]
+    fn resolve_nested_aliases() -> Result<(), Diagnostics> {
+        let mut db = ParserDatabase::new();
+        let source = SourceFile::new_static(
+            PathBuf::from("test.baml"),
+            r#"
+                type Id = string
+                type Ids = Id[]
+                type MaybeIds = Ids?
+            "#,
+        );
+        let (ast, mut diag) = parse_schema(&source.path_buf(), &source)?;
+
+        db.add_ast(ast);
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+
+        let alias = db.find_type_alias_by_str("MaybeIds").unwrap();
+        assert!(!alias.is_recursive());
+        assert_eq!(alias.resolved().to_string(), "string[]?");
+
+        Ok(())
+    }
+
+    fn validate_files(files: &[(&str, &'static str)]) -> Result<ParserDatabase, Diagnostics> {
+        let root_path = PathBuf::from("baml_src");
+        let mut db = ParserDatabase::new();
+        let mut diag = Diagnostics::new(root_path.clone());
+
+        for (path, baml) in files {
+            let source = SourceFile::new_static(root_path.join(path), baml);
+            let (ast, file_diag) = parse_schema(&root_path, &source)?;
+            diag.push(file_diag);
+            db.add_ast(ast);
+        }
+
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+
+        Ok(db)
+    }
+
+    fn field_type_names(db: &ParserDatabase, class: &str) -> Vec<String> {
+        match db.find_type_by_str(class) {
+            Some(Either::Left(class)) => class
+                .static_fields()
+                .map(|field| field.r#type().as_ref().unwrap().to_string())
+                .collect(),
+            _ => panic!("Class `{class}` not found"),
+        }
+    }
+
+    #[test]
+    fn resolve_namespaced_types() -> Result<(), Diagnostics> {
+        let db = validate_files(&[
+            (
+                "billing/invoice.baml",
+                r#"
+                    namespace billing
+
+                    enum Status {
+                        Paid
+                        Due
+                    }
+
+                    class Invoice {
+                        status Status
+                    }
+                "#,
+            ),
+            (
+                "shipping/invoice.baml",
+                r#"
+                    namespace shipping
+
+                    class Invoice {
+                        carrier string
+                    }
+                "#,
+            ),
+            (
+                "main.baml",
+                r#"
+                    import billing.Invoice
+
+                    class Order {
+                        invoice Invoice
+                        shipment shipping.Invoice?
+                    }
+                "#,
+            ),
+        ])?;
+
+        assert_eq!(
+            field_type_names(&db, "billing.Invoice"),
+            vec!["billing.Status"]
+        );
+        assert_eq!(
+            field_type_names(&db, "Order"),
+            vec!["billing.Invoice", "shipping.Invoice?"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn namespace_import() -> Result<(), Diagnostics> {
+        let db = validate_files(&[
+            (
+                "billing/invoice.baml",
+                r#"
+                    namespace billing
+
+                    class Invoice {
+                        total float
+                    }
+                "#,
+            ),
+            (
+                "main.baml",
+                r#"
+                    import billing
+
+                    class Order {
+                        invoice Invoice
+                    }
+                "#,
+            ),
+        ])?;
+
+        assert_eq!(field_type_names(&db, "Order"), vec!["billing.Invoice"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn subdirectories_without_namespace_stay_flat() -> Result<(), Diagnostics> {
+        let db = validate_files(&[
+            (
+                "fiddle-examples/invoice.baml",
+                r#"
+                    class Invoice {
+                        lines Line[]
+                    }
+                "#,
+            ),
+            (
+                "shared/line.baml",
+                r#"
+                    class Line {
+                        amount float
+                    }
+                "#,
+            ),
+            (
+                "main.baml",
+                r#"
+                    class Order {
+                        invoice Invoice
+                    }
+                "#,
+            ),
+        ])?;
+
+        assert_eq!(field_type_names(&db, "Invoice"), vec!["Line[]"]);
+        assert_eq!(field_type_names(&db, "Order"), vec!["Invoice"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn namespaced_types_across_directories() -> Result<(), Diagnostics> {
+        let db = validate_files(&[
+            (
+                "billing/invoice.baml",
+                r#"
+                    namespace billing
+
+                    import shipping.Address
+
+                    class Invoice {
+                        ship_to Address
+                        lines billing.tax.Rate[]
+                    }
+                "#,
+            ),
+            (
+                "billing/tax/rate.baml",
+                r#"
+                    namespace billing.tax
+
+                    class Rate {
+                        percent float
+                    }
+                "#,
+            ),
+            (
+                "shipping/address.baml",
+                r#"
+                    namespace shipping
+
+                    class Address {
+                        street string
+                    }
+                "#,
+            ),
+        ])?;
+
+        assert_eq!(
+            field_type_names(&db, "billing.Invoice"),
+            vec!["shipping.Address", "billing.tax.Rate[]"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_namespace_imports() {
+        let Err(diag) = validate_files(&[
+            (
+                "billing/invoice.baml",
+                "namespace billing\n\nclass Invoice {\n  total float\n}\n",
+            ),
+            (
+                "shipping/invoice.baml",
+                "namespace shipping\n\nclass Invoice {\n  carrier string\n}\n",
+            ),
+            (
+                "main.baml",
+                "import billing\nimport shipping\n\nclass Order {\n  invoice Invoice\n}\n",
+            ),
+        ]) else {
+            panic!("Expected `Invoice` to be ambiguous");
+        };
+
+        assert!(diag
+            .errors()
+            .iter()
+            .any(|e| e.message().contains("`Invoice` is ambiguous")));
+    }
+
+    #[test]
+    fn unknown_import() {
+        let Err(diag) = validate_files(&[("main.baml", "import billing.Invoice\n")]) else {
+            panic!("Expected the import to fail");
+        };
+
+        assert!(diag
+            .errors()
+            .iter()
+            .any(|e| e.message().contains("Cannot import `billing.Invoice`")));
+    }
 }
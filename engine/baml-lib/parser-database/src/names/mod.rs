@@ -1,3 +1,4 @@
+mod namespaces;
 mod validate_reserved_names;
 
 use crate::{
@@ -8,6 +9,7 @@ use crate::{
 use baml_types::FieldType;
 use internal_baml_schema_ast::ast::{ConfigBlockProperty, Expression, Field, WithIdentifier};
 
+pub(crate) use namespaces::qualify_type_references;
 use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use validate_reserved_names::*;
 
@@ -90,6 +92,14 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             (_, ast::Top::Class(_)) => {
                 unreachable!("Encountered impossible class declaration during parsing")
             }
+            (ast::TopId::TypeAlias(_), ast::Top::TypeAlias(type_alias)) => {
+                validate_type_alias_name(type_alias, ctx.diagnostics);
+
+                Some(either::Left(&mut names.tops))
+            }
+            (_, ast::Top::TypeAlias(_)) => {
+                unreachable!("Encountered impossible type alias declaration during parsing")
+            }
             (ast::TopId::TemplateString(_), ast::Top::TemplateString(template_string)) => {
                 validate_template_string_name(template_string, ctx.diagnostics);
                 validate_attribute_identifiers(template_string, ctx);
@@ -0,0 +1,127 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use internal_baml_diagnostics::{DatamodelError, Diagnostics};
+use internal_baml_schema_ast::ast::{self, WithName, WithSpan};
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+/// What a single file can refer to without qualifying the name.
+struct Scope {
+    namespace: Vec<String>,
+    /// `import billing.Invoice` makes `Invoice` refer to `billing.Invoice`.
+    imported_types: HashMap<String, String>,
+    /// `import billing` makes every type in `billing` visible.
+    imported_namespaces: Vec<String>,
+}
+
+/// Rewrites the type references of every item to the fully qualified name of
+/// the class, enum or type alias they refer to, so that the later passes only
+/// ever deal with qualified names.
+///
+/// A plain name is looked up in the namespace of its file, in the imported
+/// types and in the imported namespaces, and then in the root namespace. A
+/// name with dots is always fully qualified. Names that can't be resolved are
+/// left alone and reported when types are resolved.
+pub(crate) fn qualify_type_references(ast: &mut ast::SchemaAst, diagnostics: &mut Diagnostics) {
+    let types: HashSet<String> = ast
+        .tops
+        .iter()
+        .filter(|top| top.is_namespaced())
+        .map(|top| top.name().to_string())
+        .collect();
+
+    let namespaces: HashSet<String> = ast
+        .file_scopes
+        .iter()
+        .flat_map(|scope| (1..=scope.namespace.len()).map(|len| scope.namespace[..len].join(".")))
+        .collect();
+
+    let scopes: HashMap<PathBuf, Scope> = ast
+        .file_scopes
+        .iter()
+        .map(|file_scope| {
+            let mut scope = Scope {
+                namespace: file_scope.namespace.clone(),
+                imported_types: HashMap::default(),
+                imported_namespaces: Vec::new(),
+            };
+
+            for import in &file_scope.imports {
+                let path = import.path.name();
+
+                if types.contains(path) {
+                    let short_name = path.rsplit('.').next().unwrap_or(path);
+                    match scope.imported_types.get(short_name) {
+                        Some(existing) if existing != path => {
+                            diagnostics.push_error(DatamodelError::new_validation_error(
+                                &format!(
+                                    "`{path}` conflicts with the import of `{existing}`. Refer to one of them by its full name instead."
+                                ),
+                                import.span.clone(),
+                            ))
+                        }
+                        _ => {
+                            scope
+                                .imported_types
+                                .insert(short_name.to_string(), path.to_string());
+                        }
+                    }
+                } else if namespaces.contains(path) {
+                    scope.imported_namespaces.push(path.to_string());
+                } else {
+                    diagnostics.push_error(DatamodelError::new_validation_error(
+                        &format!("Cannot import `{path}`: there is no type or namespace with that name."),
+                        import.path.span().clone(),
+                    ));
+                }
+            }
+
+            (file_scope.path.clone(), scope)
+        })
+        .collect();
+
+    for top in ast.tops.iter_mut() {
+        let Some(scope) = scopes.get(top.span().file.path_buf()) else {
+            continue;
+        };
+
+        for field_type in top.field_types_mut() {
+            for idn in field_type.flat_idns_mut() {
+                let ast::Identifier::Local(name, span) = idn else {
+                    continue;
+                };
+                if name.contains('.') {
+                    continue;
+                }
+
+                let mut candidates = BTreeSet::new();
+                if !scope.namespace.is_empty() {
+                    candidates.insert(format!("{}.{name}", scope.namespace.join(".")));
+                }
+                candidates.extend(scope.imported_types.get(name.as_str()).cloned());
+                candidates.extend(
+                    scope
+                        .imported_namespaces
+                        .iter()
+                        .map(|namespace| format!("{namespace}.{name}")),
+                );
+                candidates.retain(|candidate| types.contains(candidate));
+
+                match candidates.len() {
+                    0 => {}
+                    1 => *name = candidates.pop_first().unwrap_or_default(),
+                    _ => diagnostics.push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "`{name}` is ambiguous, it could refer to {}. Use the full name instead.",
+                            candidates
+                                .iter()
+                                .map(|candidate| format!("`{candidate}`"))
+                                .collect::<Vec<_>>()
+                                .join(" or ")
+                        ),
+                        span.clone(),
+                    )),
+                }
+            }
+        }
+    }
+}
@@ -44,6 +44,13 @@ pub(crate) fn validate_class_name(
     validate_name("class", ast_class.identifier(), diagnostics, true);
 }
 
+pub(crate) fn validate_type_alias_name(
+    ast_type_alias: &ast::TypeAlias,
+    diagnostics: &mut Diagnostics,
+) {
+    validate_name("type alias", ast_type_alias.identifier(), diagnostics, true);
+}
+
 pub(crate) fn validate_class_field_name<T>(
     ast_class_field: &ast::Field<T>,
     diagnostics: &mut Diagnostics,
@@ -104,12 +111,6 @@ fn validate_name(
             "env.* is reserved.",
             span.clone(),
         )),
-        ast::Identifier::Ref(_, span) => Err(DatamodelError::new_name_error(
-            _type,
-            "Namespace imports (using '.') are not yet supported.",
-            span.clone(),
-        )),
-
         ast::Identifier::Invalid(_, span) | ast::Identifier::String(_, span) => {
             Err(DatamodelError::new_name_error(
                 _type,
@@ -117,7 +118,9 @@ fn validate_name(
                 span.clone(),
             ))
         }
-        ast::Identifier::Local(val, span) => if require_upper_case && !val.chars().next().unwrap().is_uppercase() {
+        // Namespaced types are named after the last segment of their path.
+        ast::Identifier::Ref(ast::RefIdentifier { name: val, .. }, span)
+        | ast::Identifier::Local(val, span) => if require_upper_case && !val.chars().next().unwrap().is_uppercase() {
             Err(DatamodelError::new_name_error(
                 _type,
                 "Must start with an uppercase letter.",
@@ -3,12 +3,11 @@
 use std::{
     cmp,
     collections::{HashMap, HashSet},
+    hash::Hash,
 };
 
-use internal_baml_schema_ast::ast::TypeExpId;
-
 /// Dependency graph represented as an adjacency list.
-type Graph = HashMap<TypeExpId, HashSet<TypeExpId>>;
+type Graph<V> = HashMap<V, HashSet<V>>;
 
 /// State of each node for Tarjan's algorithm.
 #[derive(Clone, Copy)]
@@ -32,20 +31,24 @@ struct NodeState {
 /// This struct is simply bookkeeping for the algorithm, it can be implemented
 /// with just function calls but the recursive one would need 6 parameters which
 /// is pretty ugly.
-pub struct Tarjan<'g> {
+///
+/// Nodes can be any ordered ID type, which lets the same implementation find
+/// cycles between classes ([`internal_baml_schema_ast::ast::TypeExpId`]) and
+/// between type aliases ([`internal_baml_schema_ast::ast::TypeAliasId`]).
+pub struct Tarjan<'g, V> {
     /// Ref to the depdenency graph.
-    graph: &'g Graph,
+    graph: &'g Graph<V>,
     /// Node number counter.
     index: usize,
     /// Nodes are placed on a stack in the order in which they are visited.
-    stack: Vec<TypeExpId>,
+    stack: Vec<V>,
     /// State of each node.
-    state: HashMap<TypeExpId, NodeState>,
+    state: HashMap<V, NodeState>,
     /// Strongly connected components.
-    components: Vec<Vec<TypeExpId>>,
+    components: Vec<Vec<V>>,
 }
 
-impl<'g> Tarjan<'g> {
+impl<'g, V: Copy + Eq + Hash + Ord> Tarjan<'g, V> {
     /// Unvisited node marker.
     ///
     /// Technically we should use [`Option<usize>`] and [`None`] for
@@ -60,7 +63,7 @@ impl<'g> Tarjan<'g> {
     /// Loops through all the nodes in the graph and visits them if they haven't
     /// been visited already. When the algorithm is done, [`Self::components`]
     /// will contain all the cycles in the graph.
-    pub fn components(graph: &'g Graph) -> Vec<Vec<TypeExpId>> {
+    pub fn components(graph: &'g Graph<V>) -> Vec<Vec<V>> {
         let mut tarjans = Self {
             graph,
             index: 0,
@@ -102,7 +105,7 @@ impl<'g> Tarjan<'g> {
     ///
     /// This is where the "algorithm" runs. Could be implemented iteratively if
     /// needed at some point.
-    fn strong_connect(&mut self, node_id: TypeExpId) {
+    fn strong_connect(&mut self, node_id: V) {
         // Initialize node state. This node has not yet been visited so we don't
         // have to grab the state from the hash map. And if we did, then we'd
         // have to fight the borrow checker by taking mut refs and read-only
@@ -37,8 +37,12 @@ pub(crate) fn visit_retry_policy<'db>(
     let mut max_reties = None;
 
     let mut strategy = Some(RetryPolicyStrategy::ConstantDelay(
-        super::ContantDelayStrategy { delay_ms: 200 },
+        super::ContantDelayStrategy {
+            delay_ms: 200,
+            jitter: 0.0,
+        },
     ));
+    let mut retry_on = None;
     let mut options = None;
 
     config
@@ -65,6 +69,28 @@ pub(crate) fn visit_retry_policy<'db>(
                     None => {}
                 }
             }
+            ("retry_on", Some(val)) => {
+                match coerce_array(
+                    val,
+                    &|v, diagnostics| coerce::integer(v, diagnostics).map(|c| (c, v.span())),
+                    ctx.diagnostics,
+                ) {
+                    Some(codes) => {
+                        let mut valid = Vec::new();
+                        for (code, span) in codes {
+                            match u16::try_from(code).ok().filter(|c| (100..600).contains(c)) {
+                                Some(code) => valid.push(code),
+                                None => ctx.push_error(DatamodelError::new_validation_error(
+                                    &format!("`{code}` is not an HTTP status code"),
+                                    span.clone(),
+                                )),
+                            }
+                        }
+                        retry_on = Some(valid);
+                    }
+                    None => {}
+                }
+            }
             ("options", Some(val)) => {
                 match coerce_map(val, &coerce::string_with_span, ctx.diagnostics) {
                     Some(val) => {
@@ -80,7 +106,7 @@ pub(crate) fn visit_retry_policy<'db>(
             (name, Some(_)) => ctx.push_error(DatamodelError::new_property_not_known_error(
                 name,
                 f.identifier().span().clone(),
-                ["max_retries", "strategy", "options"].to_vec(),
+                ["max_retries", "strategy", "retry_on", "options"].to_vec(),
             )),
         });
     match (max_reties, strategy) {
@@ -90,6 +116,7 @@ pub(crate) fn visit_retry_policy<'db>(
                 RetryPolicy {
                     max_retries,
                     strategy,
+                    retry_on,
                     options,
                 },
             );
@@ -117,6 +144,7 @@ fn visit_strategy(
     let mut delay_ms = None;
     let mut max_delay_ms = None;
     let mut multiplier = None;
+    let mut jitter = None;
 
     val.iter()
         .for_each(|(name_and_span, val)| match name_and_span.0 {
@@ -136,9 +164,20 @@ fn visit_strategy(
                 Some(_val) => multiplier = Some((_val, val.span())),
                 None => {}
             },
+            "jitter" => match coerce::float(val, diagnostics) {
+                Some(_val) if (0.0..=1.0).contains(&_val) => jitter = Some(_val),
+                Some(_val) => diagnostics.push_error(
+                    internal_baml_diagnostics::DatamodelError::new_validation_error(
+                        "The `jitter` option must be between 0 and 1",
+                        val.span().clone(),
+                    ),
+                ),
+                None => {}
+            },
             _ => {}
         });
 
+    let jitter = jitter.map(|v| v as f32).unwrap_or(0.0);
     match r#type {
         Some(("constant_delay", _)) => {
             match multiplier {
@@ -163,6 +202,7 @@ fn visit_strategy(
               }
             Some(RetryPolicyStrategy::ConstantDelay(ContantDelayStrategy {
                 delay_ms: delay_ms.unwrap_or(200) as u32,
+                jitter,
             }))
         }
         Some(("exponential_backoff", _)) => Some(RetryPolicyStrategy::ExponentialBackoff(
@@ -170,6 +210,7 @@ fn visit_strategy(
                 delay_ms: delay_ms.unwrap_or(200) as u32,
                 multiplier: multiplier.map(|(v, _)| v as f32).unwrap_or(1.5),
                 max_delay_ms: max_delay_ms.map(|(v, _)| v as u32).unwrap_or(10000),
+                jitter,
             },
         )),
         Some((name, span)) => {
@@ -304,6 +345,7 @@ pub(crate) fn visit_test_case<'db>(
                     functions,
                     args,
                     args_field_span: args_field_span.clone(),
+                    constraints: Vec::new(),
                 },
             );
         }
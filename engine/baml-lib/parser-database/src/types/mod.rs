@@ -5,6 +5,7 @@ use crate::coerce;
 use crate::types::configurations::visit_test_case;
 use crate::{context::Context, DatamodelError};
 
+use baml_types::Constraint;
 use indexmap::IndexMap;
 use internal_baml_diagnostics::Span;
 use internal_baml_prompt_parser::ast::{ChatBlock, PrinterBlock, Variable};
@@ -32,6 +33,10 @@ pub(super) fn resolve_types(ctx: &mut Context<'_>) {
                 visit_class(idx, model, ctx);
             }
             (_, ast::Top::Class(_)) => unreachable!("Class misconfigured"),
+            (ast::TopId::TypeAlias(idx), ast::Top::TypeAlias(type_alias)) => {
+                visit_type_alias(idx, type_alias, ctx)
+            }
+            (_, ast::Top::TypeAlias(_)) => unreachable!("TypeAlias misconfigured"),
             (ast::TopId::TemplateString(idx), ast::Top::TemplateString(template_string)) => {
                 visit_template_string(idx, template_string, ctx)
             }
@@ -58,6 +63,8 @@ pub(super) fn resolve_types(ctx: &mut Context<'_>) {
             _ => {}
         }
     }
+
+    resolve_type_aliases(ctx);
 }
 #[derive(Debug, Clone)]
 /// Variables used inside of raw strings.
@@ -138,6 +145,8 @@ pub struct TestCase {
     // The span is the span of the argument (the expression has its own span)
     pub args: IndexMap<String, (Span, Expression)>,
     pub args_field_span: Span,
+    /// @@check and @@assert attributes, evaluated against the function's parsed output.
+    pub constraints: Vec<Constraint>,
 }
 
 #[derive(Debug, Clone)]
@@ -171,6 +180,8 @@ pub struct RetryPolicy {
     pub max_retries: u32,
     /// The strategy to use.
     pub strategy: RetryPolicyStrategy,
+    /// The HTTP status codes worth retrying. Any failure is retried if unset.
+    pub retry_on: Option<Vec<u16>>,
     /// Any additional options.
     pub options: Option<Vec<((String, Span), Expression)>>,
 }
@@ -189,6 +200,8 @@ pub enum RetryPolicyStrategy {
 pub struct ContantDelayStrategy {
     /// The delay in milliseconds.
     pub delay_ms: u32,
+    /// How much of the delay is randomized, from 0 to 1.
+    pub jitter: f32,
 }
 
 #[derive(Debug, Clone, Copy, serde::Serialize)]
@@ -200,6 +213,8 @@ pub struct ExponentialBackoffStrategy {
     pub multiplier: f32,
     /// The maximum delay in milliseconds.
     pub max_delay_ms: u32,
+    /// How much of the delay is randomized, from 0 to 1.
+    pub jitter: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -237,6 +252,22 @@ pub(super) struct Types {
     /// Merge-Find Set or something like that.
     pub(super) finite_recursive_cycles: Vec<Vec<ast::TypeExpId>>,
 
+    /// Aliases that each type alias refers to directly.
+    pub(super) type_alias_dependencies: HashMap<ast::TypeAliasId, HashSet<ast::TypeAliasId>>,
+
+    /// Cycles of type aliases that refer to each other.
+    ///
+    /// Unlike class cycles these include every alias edge, so an alias is
+    /// recursive if and only if it belongs to one of these cycles. Cycles that
+    /// can't terminate (not going through a list or map) are reported as
+    /// errors in the validation pipeline.
+    pub(super) structural_recursive_alias_cycles: Vec<Vec<ast::TypeAliasId>>,
+
+    /// The type each alias stands for, with every non-recursive alias it
+    /// refers to replaced by its own resolved type. References to recursive
+    /// aliases are kept as symbols.
+    pub(super) resolved_type_aliases: HashMap<ast::TypeAliasId, ast::FieldType>,
+
     pub(super) function: HashMap<ast::ValExpId, FunctionType>,
 
     pub(super) client_properties: HashMap<ast::ValExpId, ClientProperties>,
@@ -271,6 +302,175 @@ fn visit_template_string<'db>(
     );
 }
 
+fn visit_type_alias<'db>(
+    alias_id: ast::TypeAliasId,
+    type_alias: &'db ast::TypeAlias,
+    ctx: &mut Context<'db>,
+) {
+    let dependencies = type_alias
+        .value()
+        .flat_idns()
+        .iter()
+        .filter_map(|idn| find_type_alias_id(idn.name(), ctx))
+        .collect();
+
+    ctx.types
+        .type_alias_dependencies
+        .insert(alias_id, dependencies);
+}
+
+fn find_type_alias_id(name: &str, ctx: &Context<'_>) -> Option<ast::TypeAliasId> {
+    ctx.interner
+        .lookup(name)
+        .and_then(|name_id| ctx.names.tops.get(&name_id))
+        .and_then(|top_id| top_id.as_type_alias_id())
+}
+
+/// Finds the recursive aliases, resolves the type behind every alias and
+/// replaces alias names in the dependencies of classes, enums, functions and
+/// template strings with the classes and enums the aliases point to.
+fn resolve_type_aliases(ctx: &mut Context<'_>) {
+    ctx.types.structural_recursive_alias_cycles =
+        crate::Tarjan::components(&ctx.types.type_alias_dependencies);
+
+    let recursive = ctx
+        .types
+        .structural_recursive_alias_cycles
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+
+    let alias_ids = ctx
+        .ast
+        .iter_tops()
+        .filter_map(|(top_id, _)| top_id.as_type_alias_id())
+        .collect::<Vec<_>>();
+
+    for alias_id in &alias_ids {
+        let resolved = resolve_field_type(ctx.ast[*alias_id].value(), &recursive, ctx);
+        ctx.types.resolved_type_aliases.insert(*alias_id, resolved);
+    }
+
+    // Class and enum names reachable from each alias, through any number of
+    // other aliases.
+    let alias_deps = alias_ids
+        .iter()
+        .map(|alias_id| {
+            let mut deps = HashSet::new();
+            let mut visited = HashSet::from([*alias_id]);
+            let mut stack = vec![*alias_id];
+            while let Some(id) = stack.pop() {
+                for idn in ctx.ast[id].value().flat_idns() {
+                    match find_type_alias_id(idn.name(), ctx) {
+                        Some(dep) => {
+                            if visited.insert(dep) {
+                                stack.push(dep);
+                            }
+                        }
+                        None => {
+                            deps.insert(idn.name().to_string());
+                        }
+                    }
+                }
+            }
+            (ctx.ast[*alias_id].name().to_string(), deps)
+        })
+        .collect::<HashMap<_, _>>();
+
+    if alias_deps.is_empty() {
+        return;
+    }
+
+    let expand = |deps: &mut HashSet<String>| {
+        let aliases = deps
+            .iter()
+            .filter(|dep| alias_deps.contains_key(*dep))
+            .cloned()
+            .collect::<Vec<_>>();
+        for alias in aliases {
+            deps.remove(&alias);
+            deps.extend(alias_deps[&alias].iter().cloned());
+        }
+    };
+
+    ctx.types
+        .class_dependencies
+        .values_mut()
+        .chain(ctx.types.enum_dependencies.values_mut())
+        .for_each(expand);
+    for function in ctx.types.function.values_mut() {
+        expand(&mut function.dependencies.0);
+        expand(&mut function.dependencies.1);
+    }
+    ctx.types
+        .template_strings
+        .values_mut()
+        .for_each(|template| expand(&mut template.type_dependencies));
+}
+
+/// Replaces every reference to a non-recursive alias in `field_type` with the
+/// type the alias resolves to.
+///
+/// Arity and attributes of the reference are merged into the resolved type,
+/// so `Alias?` is optional even if the alias itself is not.
+fn resolve_field_type(
+    field_type: &ast::FieldType,
+    recursive: &HashSet<ast::TypeAliasId>,
+    ctx: &Context<'_>,
+) -> ast::FieldType {
+    match field_type {
+        ast::FieldType::Symbol(arity, idn, attrs) => match find_type_alias_id(idn.name(), ctx) {
+            Some(alias_id) if !recursive.contains(&alias_id) => {
+                let mut resolved = resolve_field_type(ctx.ast[alias_id].value(), recursive, ctx);
+                if arity.is_optional() {
+                    resolved = resolved.to_nullable();
+                }
+                if let Some(attrs) = attrs {
+                    resolved.extend_attributes(attrs.clone());
+                }
+                resolved
+            }
+            _ => field_type.clone(),
+        },
+        ast::FieldType::List(arity, inner, dims, span, attrs) => ast::FieldType::List(
+            *arity,
+            Box::new(resolve_field_type(inner, recursive, ctx)),
+            *dims,
+            span.clone(),
+            attrs.clone(),
+        ),
+        ast::FieldType::Tuple(arity, items, span, attrs) => ast::FieldType::Tuple(
+            *arity,
+            items
+                .iter()
+                .map(|item| resolve_field_type(item, recursive, ctx))
+                .collect(),
+            span.clone(),
+            attrs.clone(),
+        ),
+        ast::FieldType::Union(arity, items, span, attrs) => ast::FieldType::Union(
+            *arity,
+            items
+                .iter()
+                .map(|item| resolve_field_type(item, recursive, ctx))
+                .collect(),
+            span.clone(),
+            attrs.clone(),
+        ),
+        ast::FieldType::Map(arity, kv, span, attrs) => ast::FieldType::Map(
+            *arity,
+            Box::new((
+                resolve_field_type(&kv.0, recursive, ctx),
+                resolve_field_type(&kv.1, recursive, ctx),
+            )),
+            span.clone(),
+            attrs.clone(),
+        ),
+        ast::FieldType::Primitive(..) | ast::FieldType::Literal(..) => field_type.clone(),
+    }
+}
+
 fn visit_enum<'db>(
     enm_id: ast::TypeExpId,
     enm: &'db ast::TypeExpressionBlock,
@@ -13,6 +13,7 @@ mod r#enum;
 mod field;
 mod function;
 mod template_string;
+mod type_alias;
 
 use baml_types::TypeValue;
 pub use client::*;
@@ -21,7 +22,10 @@ use either::Either;
 pub use field::*;
 pub use function::{FunctionWalker, ClientSpec};
 pub use template_string::TemplateStringWalker;
-use internal_baml_schema_ast::ast::{FieldType, Identifier, TopId, TypeExpId, WithName};
+pub use type_alias::TypeAliasWalker;
+use internal_baml_schema_ast::ast::{
+    FieldType, Identifier, TopId, TypeAliasId, TypeExpId, WithName,
+};
 pub use r#class::*;
 pub use r#enum::*;
 
@@ -88,6 +92,21 @@ impl<'db> crate::ParserDatabase {
         }
     }
 
+    /// Find a type alias by name.
+    pub fn find_type_alias_by_str(&'db self, name: &str) -> Option<TypeAliasWalker<'db>> {
+        self.find_top_by_str(name)
+            .and_then(|top_id| top_id.as_type_alias_id())
+            .map(|alias_id| self.walk(alias_id))
+    }
+
+    /// Find a type alias by name.
+    pub fn find_type_alias(&'db self, idn: &Identifier) -> Option<TypeAliasWalker<'db>> {
+        match idn {
+            Identifier::Local(local, _) => self.find_type_alias_by_str(local),
+            _ => None,
+        }
+    }
+
     /// Find a model by name.
     pub fn find_class(&'db self, idn: &Identifier) -> Option<ClassWalker<'db>> {
         self.find_type(idn).and_then(|either| match either {
@@ -133,6 +152,11 @@ impl<'db> crate::ParserDatabase {
         &self.types.finite_recursive_cycles
     }
 
+    /// Returns all the cycles of type aliases that refer to each other.
+    pub fn structural_recursive_alias_cycles(&self) -> &[Vec<TypeAliasId>] {
+        &self.types.structural_recursive_alias_cycles
+    }
+
     /// Traverse a schema element by id.
     pub fn walk<I>(&self, id: I) -> Walker<'_, I> {
         Walker { db: self, id }
@@ -142,6 +166,7 @@ impl<'db> crate::ParserDatabase {
     pub fn valid_type_names(&'db self) -> Vec<String> {
         let mut names: Vec<String> = self.walk_classes().map(|c| c.name().to_string()).collect();
         names.extend(self.walk_enums().map(|e| e.name().to_string()));
+        names.extend(self.walk_type_aliases().map(|a| a.name().to_string()));
         // Add primitive types
         names.extend(
             vec!["string", "int", "float", "bool", "true", "false"]
@@ -192,6 +217,17 @@ impl<'db> crate::ParserDatabase {
             })
     }
 
+    /// Walk all type aliases in the schema.
+    pub fn walk_type_aliases(&self) -> impl Iterator<Item = TypeAliasWalker<'_>> {
+        self.ast()
+            .iter_tops()
+            .filter_map(|(top_id, _)| top_id.as_type_alias_id())
+            .map(move |top_id| Walker {
+                db: self,
+                id: top_id,
+            })
+    }
+
     /// Walk all template strings in the schema.
     pub fn walk_templates(&self) -> impl Iterator<Item = TemplateStringWalker<'_>> {
         self.ast()
@@ -254,7 +290,12 @@ impl<'db> crate::ParserDatabase {
         let r = match ft {
             FieldType::Symbol(arity, idn, ..) => {
                 let mut t = match self.find_type(idn) {
-                    None => Type::Undefined,
+                    None => match self.find_type_alias(idn) {
+                        // Jinja types can't express recursion.
+                        Some(alias) if alias.is_recursive() => Type::Unknown,
+                        Some(alias) => self.to_jinja_type(alias.resolved()),
+                        None => Type::Undefined,
+                    },
                     Some(Either::Left(_)) => Type::ClassRef(idn.to_string()),
                     Some(Either::Right(_)) => Type::String,
                 };
@@ -304,7 +345,7 @@ impl<'db> crate::ParserDatabase {
                     TypeValue::Float => Type::Float,
                     TypeValue::Bool => Type::Bool,
                     TypeValue::Null => Type::None,
-                    TypeValue::Media(_) => Type::Unknown,
+                    TypeValue::Media(_) | TypeValue::Message => Type::Unknown,
                 };
                 if arity.is_optional() || matches!(t, Type::None) {
                     t = Type::None | t;
@@ -0,0 +1,46 @@
+use internal_baml_schema_ast::ast::{self, WithIdentifier, WithSpan};
+
+use super::Walker;
+
+/// A `type` alias declaration in the schema.
+pub type TypeAliasWalker<'db> = Walker<'db, ast::TypeAliasId>;
+
+impl<'db> TypeAliasWalker<'db> {
+    /// The AST node.
+    pub fn ast_type_alias(self) -> &'db ast::TypeAlias {
+        &self.db.ast()[self.id]
+    }
+
+    /// The type on the right hand side of the alias, as written.
+    pub fn target(self) -> &'db ast::FieldType {
+        self.ast_type_alias().value()
+    }
+
+    /// The target type with every non-recursive alias it refers to replaced
+    /// by the type behind it.
+    pub fn resolved(self) -> &'db ast::FieldType {
+        &self.db.types.resolved_type_aliases[&self.id]
+    }
+
+    /// Whether this alias refers back to itself, directly or through other
+    /// aliases.
+    pub fn is_recursive(self) -> bool {
+        self.db
+            .types
+            .structural_recursive_alias_cycles
+            .iter()
+            .any(|cycle| cycle.contains(&self.id))
+    }
+}
+
+impl<'db> WithIdentifier for TypeAliasWalker<'db> {
+    fn identifier(&self) -> &ast::Identifier {
+        self.ast_type_alias().identifier()
+    }
+}
+
+impl<'db> WithSpan for TypeAliasWalker<'db> {
+    fn span(&self) -> &internal_baml_diagnostics::Span {
+        self.ast_type_alias().span()
+    }
+}
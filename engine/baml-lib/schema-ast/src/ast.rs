@@ -6,14 +6,17 @@ mod config;
 
 mod expression;
 mod field;
+mod file_scope;
 
 mod identifier;
+mod import;
 mod indentation_type;
 mod newline_type;
 
 mod template_string;
 mod top;
 mod traits;
+mod type_alias;
 mod type_expression_block;
 mod value_expression_block;
 pub(crate) use self::comment::Comment;
@@ -23,13 +26,16 @@ pub use attribute::{Attribute, AttributeContainer, AttributeId};
 pub use config::ConfigBlockProperty;
 pub use expression::{Expression, RawString};
 pub use field::{Field, FieldArity, FieldType};
+pub use file_scope::FileScope;
 pub use identifier::{Identifier, RefIdentifier};
+pub use import::Import;
 pub use indentation_type::IndentationType;
 pub use internal_baml_diagnostics::Span;
 pub use newline_type::NewlineType;
 pub use template_string::TemplateString;
 pub use top::Top;
 pub use traits::{WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan};
+pub use type_alias::TypeAlias;
 pub use type_expression_block::{FieldId, SubType, TypeExpressionBlock};
 pub use value_expression_block::{
     BlockArg, BlockArgs, ValueExprBlock, ValueExprBlockType,
@@ -48,6 +54,8 @@ pub use value_expression_block::{
 pub struct SchemaAst {
     /// All models, enums, composite types, datasources, generators and type aliases.
     pub tops: Vec<Top>,
+    /// Namespace and imports of every file the tops come from.
+    pub file_scopes: Vec<FileScope>,
 }
 
 impl Default for SchemaAst {
@@ -58,7 +66,10 @@ impl Default for SchemaAst {
 
 impl SchemaAst {
     pub fn new() -> Self {
-        SchemaAst { tops: Vec::new() }
+        SchemaAst {
+            tops: Vec::new(),
+            file_scopes: Vec::new(),
+        }
     }
 
     /// Iterate over all the top-level items in the schema.
@@ -128,6 +139,27 @@ impl std::ops::Index<TemplateStringId> for SchemaAst {
     }
 }
 
+/// An opaque identifier for a type alias in a schema AST. Use the
+/// `schema[type_alias_id]` syntax to resolve the id to an `ast::TypeAlias`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TypeAliasId(u32);
+
+impl From<u32> for TypeAliasId {
+    fn from(id: u32) -> Self {
+        TypeAliasId(id)
+    }
+}
+
+impl std::ops::Index<TypeAliasId> for SchemaAst {
+    type Output = TypeAlias;
+
+    fn index(&self, index: TypeAliasId) -> &Self::Output {
+        self.tops[index.0 as usize]
+            .as_type_alias()
+            .expect("expected type alias")
+    }
+}
+
 /// An identifier for a top-level item in a schema AST. Use the `schema[top_id]`
 /// syntax to resolve the id to an `ast::Top`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -154,6 +186,9 @@ pub enum TopId {
     TestCase(ValExpId),
 
     RetryPolicy(ValExpId),
+
+    // A type alias
+    TypeAlias(TypeAliasId),
 }
 
 impl TopId {
@@ -208,6 +243,14 @@ impl TopId {
             _ => None,
         }
     }
+
+    /// Try to interpret the top as a type alias.
+    pub fn as_type_alias_id(self) -> Option<TypeAliasId> {
+        match self {
+            TopId::TypeAlias(id) => Some(id),
+            _ => None,
+        }
+    }
 }
 
 impl std::ops::Index<TopId> for SchemaAst {
@@ -223,6 +266,7 @@ impl std::ops::Index<TopId> for SchemaAst {
             TopId::Generator(ValExpId(idx)) => idx,
             TopId::TestCase(ValExpId(idx)) => idx,
             TopId::RetryPolicy(ValExpId(idx)) => idx,
+            TopId::TypeAlias(TypeAliasId(idx)) => idx,
         };
 
         &self.tops[idx as usize]
@@ -239,5 +283,6 @@ fn top_idx_to_top_id(top_idx: usize, top: &Top) -> TopId {
         Top::Generator(_) => TopId::Generator(ValExpId(top_idx as u32)),
         Top::TestCase(_) => TopId::TestCase(ValExpId(top_idx as u32)),
         Top::RetryPolicy(_) => TopId::RetryPolicy(ValExpId(top_idx as u32)),
+        Top::TypeAlias(_) => TopId::TypeAlias(TypeAliasId(top_idx as u32)),
     }
 }
@@ -66,6 +66,8 @@ pub enum AttributeContainer {
     ClassField(super::TypeExpId, super::FieldId),
     Enum(super::TypeExpId),
     EnumValue(super::TypeExpId, super::FieldId),
+    /// A function, client, generator, retry policy or test block.
+    ValueExprBlock(super::ValExpId),
 }
 
 impl From<super::TypeExpId> for AttributeContainer {
@@ -74,6 +76,12 @@ impl From<super::TypeExpId> for AttributeContainer {
     }
 }
 
+impl From<super::ValExpId> for AttributeContainer {
+    fn from(v: super::ValExpId) -> Self {
+        Self::ValueExprBlock(v)
+    }
+}
+
 impl From<(super::TypeExpId, super::FieldId)> for AttributeContainer {
     fn from((enm, val): (super::TypeExpId, super::FieldId)) -> Self {
         Self::EnumValue(enm, val)
@@ -103,6 +111,7 @@ impl Index<AttributeContainer> for super::SchemaAst {
             AttributeContainer::EnumValue(enum_id, value_idx) => {
                 &self[enum_id][value_idx].attributes
             }
+            AttributeContainer::ValueExprBlock(block_id) => &self[block_id].attributes,
         }
     }
 }
@@ -205,6 +205,24 @@ impl FieldType {
         }
     }
 
+    /// Mutable version of [`FieldType::flat_idns`].
+    pub fn flat_idns_mut(&mut self) -> Vec<&mut Identifier> {
+        match self {
+            FieldType::Symbol(_, idn, ..) => vec![idn],
+            FieldType::Union(_, f, _, _) => f.iter_mut().flat_map(|t| t.flat_idns_mut()).collect(),
+            FieldType::Tuple(_, f, ..) => f.iter_mut().flat_map(|t| t.flat_idns_mut()).collect(),
+            FieldType::Map(_, kv, ..) => {
+                let (key, value) = &mut **kv;
+                let mut idns = value.flat_idns_mut();
+                idns.extend(key.flat_idns_mut());
+                idns
+            }
+            FieldType::List(_, t, ..) => t.flat_idns_mut(),
+            FieldType::Primitive(..) => vec![],
+            FieldType::Literal(..) => vec![],
+        }
+    }
+
     pub fn attributes(&self) -> &[Attribute] {
         match self {
             FieldType::Symbol(.., attr)
@@ -380,12 +398,7 @@ impl std::fmt::Display for FieldType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             FieldType::Symbol(arity, idn, ..) => {
-                write!(
-                    f,
-                    "{:#?}{}",
-                    idn,
-                    if arity.is_optional() { "?" } else { "" }
-                )
+                write!(f, "{}{}", idn, if arity.is_optional() { "?" } else { "" })
             }
             FieldType::Union(arity, ft, ..) => {
                 let ft = ft.iter().map(|t| t.to_string()).collect::<Vec<_>>();
@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use super::Import;
+
+/// Name resolution information for a single `.baml` file.
+///
+/// A file declares its classes, enums and type aliases in the root namespace
+/// unless it starts with a `namespace` declaration. The declared namespace
+/// must match the directory of the file relative to `baml_src`, so the types
+/// in `baml_src/billing/invoice.baml` become `billing.Invoice` etc. once that
+/// file declares `namespace billing`.
+#[derive(Debug, Clone)]
+pub struct FileScope {
+    /// The path of the file, as found in the spans of its items.
+    pub path: PathBuf,
+
+    /// The namespace the file declares its types in. Empty for the root
+    /// namespace.
+    pub namespace: Vec<String>,
+
+    /// The `import` statements of the file.
+    pub imports: Vec<Import>,
+}
+
+impl FileScope {
+    /// The namespace matching the directory of a file, given the directory
+    /// the BAML sources live in. `None` for files outside of `root_path`.
+    pub fn directory_namespace(root_path: &Path, path: &Path) -> Option<Vec<String>> {
+        let dir = path.strip_prefix(root_path).ok()?.parent()?;
+
+        Some(
+            dir.components()
+                .filter_map(|component| match component {
+                    std::path::Component::Normal(segment) => {
+                        Some(segment.to_string_lossy().into_owned())
+                    }
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    /// Prefixes `name` with the namespace of the file.
+    pub fn qualify(&self, name: &str) -> String {
+        if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{name}", self.namespace.join("."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_from_directory() {
+        let root = Path::new("baml_src");
+
+        assert_eq!(
+            FileScope::directory_namespace(root, Path::new("baml_src/main.baml")),
+            Some(vec![])
+        );
+        assert_eq!(
+            FileScope::directory_namespace(root, Path::new("baml_src/billing/invoice.baml")),
+            Some(vec!["billing".to_string()])
+        );
+        assert_eq!(
+            FileScope::directory_namespace(root, Path::new("baml_src/billing/tax/rates.baml")),
+            Some(vec!["billing".to_string(), "tax".to_string()])
+        );
+        assert_eq!(
+            FileScope::directory_namespace(root, Path::new("other/billing/invoice.baml")),
+            None
+        );
+    }
+}
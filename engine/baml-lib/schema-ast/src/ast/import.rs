@@ -0,0 +1,33 @@
+use super::{Identifier, Span, WithIdentifier, WithSpan};
+
+/// An `import` statement.
+///
+/// ```ignore
+/// import billing.Invoice
+/// import shipping
+/// ```
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// What is imported, either a single type or a whole namespace.
+    ///
+    /// ```ignore
+    /// import billing.Invoice
+    ///        ^^^^^^^^^^^^^^^
+    /// ```
+    pub path: Identifier,
+
+    /// The location of this import in the text representation.
+    pub span: Span,
+}
+
+impl WithIdentifier for Import {
+    fn identifier(&self) -> &Identifier {
+        &self.path
+    }
+}
+
+impl WithSpan for Import {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+}
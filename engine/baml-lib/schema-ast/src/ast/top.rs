@@ -1,6 +1,6 @@
 use super::{
-    traits::WithSpan, Identifier, Span, TemplateString, TypeExpressionBlock, ValueExprBlock,
-    WithIdentifier,
+    traits::WithSpan, FieldType, Identifier, Span, TemplateString, TypeAlias, TypeExpressionBlock,
+    ValueExprBlock, WithIdentifier,
 };
 
 /// Enum for distinguishing between top-level entries
@@ -24,6 +24,9 @@ pub enum Top {
     TestCase(ValueExprBlock),
 
     RetryPolicy(ValueExprBlock),
+
+    // A type alias, e.g. `type Graph = map<string, string[]>`
+    TypeAlias(TypeAlias),
 }
 
 impl Top {
@@ -39,6 +42,7 @@ impl Top {
             Top::Generator(_) => "generator",
             Top::TestCase(_) => "test_case",
             Top::RetryPolicy(_) => "retry_policy",
+            Top::TypeAlias(_) => "type_alias",
         }
     }
 
@@ -68,6 +72,66 @@ impl Top {
             _ => None,
         }
     }
+
+    pub fn as_type_alias(&self) -> Option<&TypeAlias> {
+        match self {
+            Top::TypeAlias(alias) => Some(alias),
+            _ => None,
+        }
+    }
+}
+
+impl Top {
+    /// Whether the item is declared in the namespace of its file. Everything
+    /// else lives in the root namespace.
+    pub fn is_namespaced(&self) -> bool {
+        matches!(self, Top::Enum(_) | Top::Class(_) | Top::TypeAlias(_))
+    }
+
+    /// Every type written in the item: class fields, alias targets and the
+    /// arguments and return types of functions and template strings.
+    pub fn field_types_mut(&mut self) -> Vec<&mut FieldType> {
+        match self {
+            Top::Class(class) => class
+                .fields
+                .iter_mut()
+                .filter_map(|field| field.expr.as_mut())
+                .collect(),
+            Top::TypeAlias(alias) => vec![&mut alias.value],
+            Top::Function(func) => {
+                let mut types = func
+                    .input
+                    .iter_mut()
+                    .flat_map(|input| input.args.iter_mut().map(|(_, arg)| &mut arg.field_type))
+                    .collect::<Vec<_>>();
+                types.extend(func.output.as_mut().map(|output| &mut output.field_type));
+                types
+            }
+            Top::TemplateString(template) => template
+                .input
+                .iter_mut()
+                .flat_map(|input| input.args.iter_mut().map(|(_, arg)| &mut arg.field_type))
+                .collect(),
+            Top::Enum(_)
+            | Top::Client(_)
+            | Top::Generator(_)
+            | Top::TestCase(_)
+            | Top::RetryPolicy(_) => vec![],
+        }
+    }
+
+    pub(crate) fn identifier_mut(&mut self) -> &mut Identifier {
+        match self {
+            Top::Enum(x) | Top::Class(x) => &mut x.name,
+            Top::Function(x)
+            | Top::Client(x)
+            | Top::Generator(x)
+            | Top::TestCase(x)
+            | Top::RetryPolicy(x) => &mut x.name,
+            Top::TemplateString(x) => &mut x.name,
+            Top::TypeAlias(x) => &mut x.name,
+        }
+    }
 }
 
 impl WithIdentifier for Top {
@@ -83,6 +147,7 @@ impl WithIdentifier for Top {
             Top::Generator(x) => x.identifier(),
             Top::TestCase(x) => x.identifier(),
             Top::RetryPolicy(x) => x.identifier(),
+            Top::TypeAlias(x) => x.identifier(),
         }
     }
 }
@@ -98,6 +163,7 @@ impl WithSpan for Top {
             Top::Generator(gen) => gen.span(),
             Top::TestCase(test) => test.span(),
             Top::RetryPolicy(retry) => retry.span(),
+            Top::TypeAlias(alias) => alias.span(),
         }
     }
 }
@@ -0,0 +1,51 @@
+use super::{Comment, FieldType, Identifier, Span, WithDocumentation, WithIdentifier, WithSpan};
+
+/// A type alias declaration.
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    /// The name of the alias.
+    ///
+    /// ```ignore
+    /// type Graph = map<string, string[]>
+    ///      ^^^^^
+    /// ```
+    pub name: Identifier,
+
+    /// The type that the alias stands for.
+    ///
+    /// ```ignore
+    /// type Graph = map<string, string[]>
+    ///              ^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub value: FieldType,
+
+    /// The documentation for this alias.
+    pub(crate) documentation: Option<Comment>,
+
+    /// The location of this alias in the text representation.
+    pub span: Span,
+}
+
+impl TypeAlias {
+    pub fn value(&self) -> &FieldType {
+        &self.value
+    }
+}
+
+impl WithIdentifier for TypeAlias {
+    fn identifier(&self) -> &Identifier {
+        &self.name
+    }
+}
+
+impl WithSpan for TypeAlias {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl WithDocumentation for TypeAlias {
+    fn documentation(&self) -> Option<&str> {
+        self.documentation.as_ref().map(|doc| doc.text.as_str())
+    }
+}
@@ -0,0 +1,34 @@
+use super::{
+    helpers::{parsing_catch_all, Pair},
+    parse_identifier::parse_identifier,
+    Rule,
+};
+use crate::ast::*;
+use internal_baml_diagnostics::{DatamodelError, Diagnostics};
+
+pub(crate) fn parse_import(
+    pair: Pair<'_>,
+    diagnostics: &mut Diagnostics,
+) -> Result<Import, DatamodelError> {
+    let pair_span = pair.as_span();
+    let mut path: Option<Identifier> = None;
+
+    for current in pair.into_inner() {
+        match current.as_rule() {
+            Rule::IMPORT_KEYWORD => {}
+            Rule::identifier => path = Some(parse_identifier(current, diagnostics)),
+            _ => parsing_catch_all(current, "import"),
+        }
+    }
+
+    match path {
+        Some(path @ (Identifier::Local(..) | Identifier::Ref(..))) => Ok(Import {
+            path,
+            span: diagnostics.span(pair_span),
+        }),
+        _ => Err(DatamodelError::new_validation_error(
+            "Invalid import syntax. Valid syntax is `import billing.Invoice` or `import billing`",
+            diagnostics.span(pair_span),
+        )),
+    }
+}
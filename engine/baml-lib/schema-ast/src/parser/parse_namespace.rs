@@ -0,0 +1,37 @@
+use super::{
+    helpers::{parsing_catch_all, Pair},
+    parse_identifier::parse_identifier,
+    Rule,
+};
+use crate::ast::*;
+use internal_baml_diagnostics::{DatamodelError, Diagnostics};
+
+/// Parses `namespace billing.tax` into the segments of the namespace and the
+/// span of the declaration.
+pub(crate) fn parse_namespace(
+    pair: Pair<'_>,
+    diagnostics: &mut Diagnostics,
+) -> Result<(Vec<String>, Span), DatamodelError> {
+    let pair_span = pair.as_span();
+    let mut path: Option<Identifier> = None;
+
+    for current in pair.into_inner() {
+        match current.as_rule() {
+            Rule::NAMESPACE_KEYWORD => {}
+            Rule::identifier => path = Some(parse_identifier(current, diagnostics)),
+            _ => parsing_catch_all(current, "namespace"),
+        }
+    }
+
+    let span = diagnostics.span(pair_span);
+    match path {
+        Some(Identifier::Local(name, _)) => Ok((vec![name], span)),
+        Some(Identifier::Ref(RefIdentifier { full_name, .. }, _)) => {
+            Ok((full_name.split('.').map(str::to_string).collect(), span))
+        }
+        _ => Err(DatamodelError::new_validation_error(
+            "Invalid namespace syntax. Valid syntax is `namespace billing` or `namespace billing.tax`",
+            span,
+        )),
+    }
+}
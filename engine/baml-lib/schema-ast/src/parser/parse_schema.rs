@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::{
-    parse_template_string::parse_template_string,
+    parse_import::parse_import, parse_namespace::parse_namespace,
+    parse_template_string::parse_template_string, parse_type_alias::parse_type_alias,
     parse_type_expression_block::parse_type_expression_block,
     parse_value_expression_block::parse_value_expression_block, BAMLParser, Rule,
 };
@@ -51,6 +52,8 @@ pub fn parse_schema(
             pretty_print(datamodel.clone(), 0);
 
             let mut top_level_definitions = Vec::new();
+            let mut imports = Vec::new();
+            let mut namespace = None;
 
             let mut pending_block_comment = None;
             let mut pairs = datamodel.into_inner().peekable();
@@ -105,6 +108,35 @@ pub fn parse_schema(
                         }
                     }
 
+                    Rule::type_alias => {
+                        match parse_type_alias(
+                            current,
+                            pending_block_comment.take(),
+                            &mut diagnostics,
+                        ) {
+                            Ok(alias) => top_level_definitions.push(Top::TypeAlias(alias)),
+                            Err(e) => diagnostics.push_error(e),
+                        }
+                    }
+
+                    Rule::namespace_declaration => {
+                        match parse_namespace(current, &mut diagnostics) {
+                            Ok((_, span)) if namespace.is_some() => {
+                                diagnostics.push_error(DatamodelError::new_validation_error(
+                                    "A file can only declare one namespace.",
+                                    span,
+                                ))
+                            }
+                            Ok(declared) => namespace = Some(declared),
+                            Err(e) => diagnostics.push_error(e),
+                        }
+                    }
+
+                    Rule::import_statement => match parse_import(current, &mut diagnostics) {
+                        Ok(import) => imports.push(import),
+                        Err(e) => diagnostics.push_error(e),
+                    },
+
                     Rule::EOI => {}
                     Rule::CATCH_ALL => {
                         diagnostics.push_error(DatamodelError::new_validation_error(
@@ -131,9 +163,25 @@ pub fn parse_schema(
                 }
             }
 
+            let file_scope = FileScope {
+                path: source.path_buf().clone(),
+                namespace: match namespace {
+                    Some((namespace, span)) => {
+                        validate_namespace(root_path, source, namespace, span, &mut diagnostics)
+                    }
+                    None => Vec::new(),
+                },
+                imports,
+            };
+
+            for top in top_level_definitions.iter_mut() {
+                qualify_top_name(top, &file_scope, &mut diagnostics);
+            }
+
             Ok((
                 SchemaAst {
                     tops: top_level_definitions,
+                    file_scopes: vec![file_scope],
                 },
                 diagnostics,
             ))
@@ -165,6 +213,189 @@ pub fn parse_schema(
     }
 }
 
+/// Checks a `namespace` declaration against the directory of its file and
+/// returns the namespace the types of the file are declared in.
+fn validate_namespace(
+    root_path: &Path,
+    source: &SourceFile,
+    namespace: Vec<String>,
+    span: Span,
+    diagnostics: &mut Diagnostics,
+) -> Vec<String> {
+    if let Some(segment) = namespace
+        .iter()
+        .find(|segment| !is_valid_namespace_segment(segment))
+    {
+        diagnostics.push_error(DatamodelError::new_validation_error(
+            &format!(
+                "`{segment}` is not a valid namespace. Namespaces must start with a lowercase \
+                 letter and only contain letters, numbers and `_`."
+            ),
+            span,
+        ));
+        return Vec::new();
+    }
+    if let Some(segment) = namespace
+        .iter()
+        .find(|segment| RESERVED_NAMESPACES.contains(&segment.as_str()))
+    {
+        diagnostics.push_error(DatamodelError::new_validation_error(
+            &format!(
+                "`{segment}` can't be used as a namespace, since generated clients use it as a \
+                 keyword or module name."
+            ),
+            span,
+        ));
+        return Vec::new();
+    }
+
+    // Files outside of the BAML source directory, like the ones in tests, can
+    // declare any namespace.
+    match FileScope::directory_namespace(root_path, source.path_buf()) {
+        Some(directory) if directory.is_empty() => {
+            diagnostics.push_error(DatamodelError::new_validation_error(
+                "Files directly in the BAML source directory are in the root namespace. Move \
+                 the file into a directory named after the namespace.",
+                span,
+            ))
+        }
+        Some(directory) if directory != namespace => {
+            diagnostics.push_error(DatamodelError::new_validation_error(
+                &format!(
+                    "The namespace of a file must match its directory. Expected `namespace {}`.",
+                    directory.join(".")
+                ),
+                span,
+            ))
+        }
+        _ => {}
+    }
+
+    namespace
+}
+
+/// Puts classes, enums and type aliases in the namespace of their file.
+/// Namespaces are only declared with `namespace`, so names can't be written
+/// with dots.
+fn qualify_top_name(top: &mut Top, file_scope: &FileScope, diagnostics: &mut Diagnostics) {
+    let identifier = top.identifier_mut();
+
+    let (name, span) = match identifier {
+        Identifier::Local(name, span) => (name.clone(), span.clone()),
+        Identifier::Ref(_, span) => {
+            diagnostics.push_error(DatamodelError::new_validation_error(
+                "Names can't contain '.'. Declare a `namespace` at the top of the file instead.",
+                span.clone(),
+            ));
+            return;
+        }
+        _ => return,
+    };
+
+    if !top.is_namespaced() || file_scope.namespace.is_empty() {
+        return;
+    }
+
+    *top.identifier_mut() = Identifier::Ref(
+        RefIdentifier {
+            path: file_scope.namespace.clone(),
+            full_name: file_scope.qualify(&name),
+            name,
+        },
+        span,
+    );
+}
+
+/// Namespaces become classes in Python and namespaces in TypeScript, so they
+/// can't be keywords of either language or names the generated modules use.
+const RESERVED_NAMESPACES: &[&str] = &[
+    // Python keywords.
+    "and",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "break",
+    "class",
+    "continue",
+    "def",
+    "del",
+    "elif",
+    "else",
+    "except",
+    "finally",
+    "for",
+    "from",
+    "global",
+    "if",
+    "import",
+    "in",
+    "is",
+    "lambda",
+    "nonlocal",
+    "not",
+    "or",
+    "pass",
+    "raise",
+    "return",
+    "try",
+    "while",
+    "with",
+    "yield",
+    // TypeScript reserved words.
+    "case",
+    "catch",
+    "const",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "function",
+    "implements",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "typeof",
+    "var",
+    "void",
+    // Modules of and imported by the generated clients.
+    "baml_client",
+    "baml_py",
+    "types",
+    "partial_types",
+    "type_builder",
+    "globals",
+    "tracing",
+    "async_client",
+    "sync_client",
+    "inlinedbaml",
+    "typing",
+    "typing_extensions",
+    "pydantic",
+];
+
+fn is_valid_namespace_segment(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -205,6 +436,142 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_type_alias() {
+        let input = r#"
+            type Graph = map<string, string[]>
+
+            type JsonValue = int | string | JsonValue[] | map<string, JsonValue>
+        "#;
+
+        let root_path = "type_alias.baml";
+        let source = SourceFile::new_static(root_path.into(), input);
+
+        let (schema_ast, diagnostics) = parse_schema(&root_path.into(), &source).unwrap();
+        assert_eq!(diagnostics.errors().len(), 0);
+        assert_eq!(schema_ast.tops.len(), 2);
+
+        match &schema_ast.tops[0] {
+            Top::TypeAlias(alias) => {
+                assert_eq!(alias.name(), "Graph");
+                assert!(matches!(alias.value(), FieldType::Map(..)));
+            }
+            _ => panic!("Expected a type alias"),
+        }
+        match &schema_ast.tops[1] {
+            Top::TypeAlias(alias) => {
+                assert_eq!(alias.name(), "JsonValue");
+                match alias.value() {
+                    FieldType::Union(_, types, ..) => assert_eq!(types.len(), 4),
+                    other => panic!("Expected a union, got {other:?}"),
+                }
+            }
+            _ => panic!("Expected a type alias"),
+        }
+    }
+
+    #[test]
+    fn test_namespaced_file() {
+        let input = r##"
+            namespace billing
+
+            import shipping.Address
+            import payments
+
+            class Invoice {
+                address Address
+            }
+
+            function CreateInvoice(text: string) -> Invoice {
+                client GPT4
+                prompt #"{{ text }}"#
+            }
+        "##;
+
+        let root_path = std::path::PathBuf::from("baml_src");
+        let source = SourceFile::new_static("baml_src/billing/invoice.baml".into(), input);
+
+        let (schema_ast, diagnostics) = parse_schema(&root_path, &source).unwrap();
+        assert_eq!(diagnostics.errors().len(), 0);
+
+        let file_scope = &schema_ast.file_scopes[0];
+        assert_eq!(file_scope.namespace, vec!["billing"]);
+        let imports = file_scope
+            .imports
+            .iter()
+            .map(|import| import.path.name())
+            .collect::<Vec<_>>();
+        assert_eq!(imports, vec!["shipping.Address", "payments"]);
+
+        // Types are namespaced, functions are not.
+        assert_eq!(schema_ast.tops[0].name(), "billing.Invoice");
+        assert_eq!(schema_ast.tops[1].name(), "CreateInvoice");
+    }
+
+    #[test]
+    fn test_subdirectory_without_namespace() {
+        let input = r#"
+            class Invoice {
+                id string
+            }
+        "#;
+
+        let root_path = std::path::PathBuf::from("baml_src");
+        let source = SourceFile::new_static("baml_src/fiddle-examples/invoice.baml".into(), input);
+
+        let (schema_ast, diagnostics) = parse_schema(&root_path, &source).unwrap();
+        assert_eq!(diagnostics.errors().len(), 0);
+        assert!(schema_ast.file_scopes[0].namespace.is_empty());
+        assert_eq!(schema_ast.tops[0].name(), "Invoice");
+    }
+
+    #[test]
+    fn test_namespace_must_match_directory() {
+        let root_path = std::path::PathBuf::from("baml_src");
+
+        for path in ["baml_src/shipping/invoice.baml", "baml_src/invoice.baml"] {
+            let source = SourceFile::new_static(path.into(), "namespace billing\n");
+            let (_, diagnostics) = parse_schema(&root_path, &source).unwrap();
+            assert_eq!(diagnostics.errors().len(), 1, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_reserved_namespace() {
+        let root_path = std::path::PathBuf::from("baml_src");
+
+        for namespace in ["global", "from", "import", "async", "baml_py", "types"] {
+            let source = SourceFile::from((
+                format!("baml_src/{namespace}/invoice.baml").into(),
+                format!("namespace {namespace}\n"),
+            ));
+            let (_, diagnostics) = parse_schema(&root_path, &source).unwrap();
+            assert_eq!(diagnostics.errors().len(), 1, "{namespace}");
+        }
+
+        let source = SourceFile::new_static(
+            "baml_src/billing/types_v2/invoice.baml".into(),
+            "namespace billing.types_v2\n",
+        );
+        let (_, diagnostics) = parse_schema(&root_path, &source).unwrap();
+        assert_eq!(diagnostics.errors().len(), 0);
+    }
+
+    #[test]
+    fn test_dotted_declaration_name() {
+        let input = r#"
+            class billing.Invoice {
+                id string
+            }
+        "#;
+
+        let root_path = "dotted.baml";
+        let source = SourceFile::new_static(root_path.into(), input);
+
+        let (_, diagnostics) = parse_schema(&root_path.into(), &source).unwrap();
+        assert_eq!(diagnostics.errors().len(), 1);
+    }
+
     #[test]
     fn test_example() {
         let input = r##"
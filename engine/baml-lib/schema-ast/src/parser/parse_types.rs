@@ -127,7 +127,7 @@ fn parse_base_type(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<Fiel
             Rule::identifier => {
                 let identifier = parse_identifier(current.clone(), diagnostics);
                 let field_type = match current.as_str() {
-                    "string" | "int" | "float" | "bool" | "image" | "audio" => {
+                    "string" | "int" | "float" | "bool" | "image" | "audio" | "message" => {
                         FieldType::Primitive(
                             FieldArity::Required,
                             TypeValue::from_str(identifier.name()).expect("Invalid type value"),
@@ -208,23 +208,30 @@ fn parse_literal_type(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<F
             None => unreachable!("quoted string literal has no string content"),
         },
 
-        Rule::numeric_literal => match literal_type.as_str().parse::<i64>() {
-            Ok(int) => LiteralValue::Int(int),
-
-            // This should only be a float because of how the pest grammar is defined.
-            Err(_e) => {
-                diagnostics.push_error(DatamodelError::new_validation_error(
-                    format!(
-                        "Float literal values are not supported: {}",
-                        literal_type.as_str()
-                    )
-                    .as_str(),
-                    span,
-                ));
-
-                return None;
+        Rule::numeric_literal => {
+            let literal = literal_type.as_str();
+
+            // The grammar only produces an optional minus sign followed by
+            // digits with an optional fractional part.
+            if literal.contains('.') {
+                match literal.parse::<f64>() {
+                    Ok(float) => LiteralValue::Float(float),
+                    Err(_e) => unreachable!("float literal {literal} is not a valid f64"),
+                }
+            } else {
+                match literal.parse::<i64>() {
+                    Ok(int) => LiteralValue::Int(int),
+                    Err(_e) => {
+                        diagnostics.push_error(DatamodelError::new_validation_error(
+                            format!("Integer literal is out of range: {literal}").as_str(),
+                            span,
+                        ));
+
+                        return None;
+                    }
+                }
             }
-        },
+        }
         _ => unreachable_rule!(literal_type, Rule::literal_type),
     };
 
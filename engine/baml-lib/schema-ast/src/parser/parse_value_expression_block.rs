@@ -1,5 +1,6 @@
 use super::{
     helpers::{parsing_catch_all, Pair},
+    parse_attribute::parse_attribute,
     parse_comments::*,
     parse_field::parse_value_expr,
     parse_identifier::parse_identifier,
@@ -17,7 +18,7 @@ pub(crate) fn parse_value_expression_block(
 ) -> Result<ValueExprBlock, DatamodelError> {
     let pair_span = pair.as_span();
     let mut name: Option<Identifier> = None;
-    let attributes: Vec<Attribute> = Vec::new();
+    let mut attributes: Vec<Attribute> = Vec::new();
     let mut input = None;
     let mut output = None;
     let mut fields: Vec<Field<Expression>> = vec![];
@@ -84,6 +85,9 @@ pub(crate) fn parse_value_expression_block(
                             pending_field_comment = None;
                         }
 
+                        Rule::block_attribute => {
+                            attributes.push(parse_attribute(item, false, diagnostics));
+                        }
                         Rule::comment_block => pending_field_comment = Some(item),
                         Rule::empty_lines => {}
                         Rule::BLOCK_LEVEL_CATCH_ALL => {
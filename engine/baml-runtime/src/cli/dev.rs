@@ -1,12 +1,13 @@
 use anyhow::Result;
 use notify_debouncer_full::{new_debouncer, notify::*};
-use std::ops::DerefMut;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use crate::{cli::generate::GenerateArgs, BamlRuntime};
 
-use super::serve::Server;
+use super::serve::{
+    ApiKeys, Limits, Server, DEFAULT_BATCH_CONCURRENCY, DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS,
+};
 
 #[derive(clap::Args, Clone, Debug)]
 pub struct DevArgs {
@@ -64,14 +65,34 @@ Thanks for trying out BAML!
             .watcher()
             .watch(self.from.as_path(), RecursiveMode::Recursive)?;
 
-        let (server, tcp_listener) = t.block_on(Server::new(self.from.clone(), self.port))?;
+        let (server, tcp_listener) = t.block_on(Server::new(
+            self.from.clone(),
+            self.port,
+            DEFAULT_BATCH_CONCURRENCY,
+            Duration::from_secs(DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS),
+            false,
+            ApiKeys::default(),
+            Limits::default(),
+        ))?;
 
         let _ = GenerateArgs {
             from: self.from.clone(),
             no_version_check: false,
         }
         .run(defaults);
-        t.spawn(server.clone().serve(tcp_listener));
+        let serving = server.clone();
+        t.spawn(async move {
+            // The server handles Ctrl-C itself, so once it has shut down there's
+            // nothing left to do, even though we're still watching for changes.
+            let code = match serving.serve(tcp_listener).await {
+                Ok(()) => 0,
+                Err(e) => {
+                    log::error!("Failed to serve: {:?}", e);
+                    1
+                }
+            };
+            std::process::exit(code);
+        });
 
         // print all events and errors
         t.block_on(async {
@@ -110,8 +131,8 @@ Thanks for trying out BAML!
                             }
                         );
                         let start = Instant::now();
-                        match BamlRuntime::from_directory(&self.from, std::env::vars().collect()) {
-                            Ok(mut new_runtime) => {
+                        match server.reload().await {
+                            Ok(_) => {
                                 let elapsed = start.elapsed();
                                 let _ = GenerateArgs {
                                     from: self.from.clone(),
@@ -119,10 +140,6 @@ Thanks for trying out BAML!
                                 }
                                 .run(defaults);
 
-                                std::mem::swap(
-                                    server.b.write().await.deref_mut(),
-                                    &mut new_runtime,
-                                );
                                 log::info!(
                                     "Reloaded runtime in {}ms ({})",
                                     elapsed.as_millis(),
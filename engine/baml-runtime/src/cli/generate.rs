@@ -61,6 +61,14 @@ impl GenerateArgs {
                     // this has no meaning
                     GeneratorDefaultClientMode::Sync
                 }
+                internal_baml_core::configuration::GeneratorOutputType::Proto => {
+                    // this has no meaning
+                    GeneratorDefaultClientMode::Sync
+                }
+                internal_baml_core::configuration::GeneratorOutputType::Go => {
+                    // this has no meaning
+                    GeneratorDefaultClientMode::Sync
+                }
             };
             // Normally `baml_client` is added via the generator, but since we're not running the generator, we need to add it manually.
             let output_dir_relative_to_baml_src = PathBuf::from("..");
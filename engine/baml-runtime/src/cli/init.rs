@@ -91,6 +91,8 @@ impl InitArgs {
                     Some(s) => format!("{} clients via OpenAPI", s),
                     None => "REST clients".to_string(),
                 },
+                GeneratorOutputType::Proto => "gRPC clients".to_string(),
+                GeneratorOutputType::Go => "Go clients".to_string(),
             }
         );
         log::info!(
@@ -100,6 +102,8 @@ impl InitArgs {
                 GeneratorOutputType::Typescript => "typescript",
                 GeneratorOutputType::RubySorbet => "ruby",
                 GeneratorOutputType::OpenApi => "openapi",
+                GeneratorOutputType::Proto => "grpc",
+                GeneratorOutputType::Go => "go",
             }
         );
 
@@ -113,7 +117,10 @@ fn generate_main_baml_content(
     openapi_client_type: Option<&str>,
 ) -> String {
     let default_client_mode = match output_type {
-        GeneratorOutputType::OpenApi | GeneratorOutputType::RubySorbet => "".to_string(),
+        GeneratorOutputType::OpenApi
+        | GeneratorOutputType::RubySorbet
+        | GeneratorOutputType::Proto
+        | GeneratorOutputType::Go => "".to_string(),
         GeneratorOutputType::PythonPydantic | GeneratorOutputType::Typescript => format!(
             r#"
     // Valid values: "sync", "async"
@@ -183,7 +190,7 @@ fn generate_main_baml_content(
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "{output_type}"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -221,7 +228,7 @@ mod tests {
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "python/pydantic"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -250,7 +257,7 @@ generator target {{
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "typescript"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -279,7 +286,7 @@ generator target {{
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "ruby/sorbet"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -304,7 +311,7 @@ generator target {{
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "rest/openapi"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -333,7 +340,7 @@ generator target {{
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "rest/openapi"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -362,7 +369,7 @@ generator target {{
 // your choice. You can have multiple generators if you use multiple languages.
 // Just ensure that the output_dir is different for each generator.
 generator target {{
-    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi"
+    // Valid values: "python/pydantic", "typescript", "ruby/sorbet", "rest/openapi", "proto", "go"
     output_type "rest/openapi"
 
     // Where the generated code will be saved (relative to baml_src/)
@@ -0,0 +1,246 @@
+//! Named API keys, loaded from the file passed to `--api-keys`, each of which
+//! may only call some BAML functions.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+use baml_types::BamlValue;
+use serde::Deserialize;
+
+use super::error::BamlError;
+
+/// The endpoints a key can be restricted to. Batches and jobs count as `call`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum Endpoint {
+    Call,
+    Stream,
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endpoint::Call => write!(f, "call"),
+            Endpoint::Stream => write!(f, "stream"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApiKeysFile {
+    keys: Vec<ApiKeyEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApiKeyEntry {
+    name: String,
+    key: String,
+    /// Glob patterns of the functions the key may call; any function if unset.
+    functions: Option<Vec<String>>,
+    /// The endpoints the key may use; any endpoint if unset.
+    endpoints: Option<Vec<Endpoint>>,
+}
+
+#[derive(Debug)]
+pub(super) struct ApiKey {
+    name: String,
+    key: String,
+    functions: Option<Vec<glob::Pattern>>,
+    endpoints: Option<Vec<Endpoint>>,
+}
+
+/// Every key from `--api-keys`. Empty if the flag wasn't passed.
+#[derive(Debug, Default)]
+pub(crate) struct ApiKeys {
+    keys: Vec<Arc<ApiKey>>,
+}
+
+impl ApiKeys {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read API keys from {}", path.display()))?;
+        Self::parse(&contents)
+            .with_context(|| format!("Failed to load API keys from {}", path.display()))
+    }
+
+    pub(super) fn parse(contents: &str) -> Result<Self> {
+        let file: ApiKeysFile = serde_json::from_str(contents)?;
+
+        let mut keys = Vec::with_capacity(file.keys.len());
+        for entry in file.keys {
+            if keys.iter().any(|k: &Arc<ApiKey>| k.name == entry.name) {
+                anyhow::bail!("API key name {} is used more than once", entry.name);
+            }
+            if keys.iter().any(|k: &Arc<ApiKey>| k.key == entry.key) {
+                anyhow::bail!("API key {} has the same key as another one", entry.name);
+            }
+            let functions = entry
+                .functions
+                .map(|patterns| {
+                    patterns
+                        .iter()
+                        .map(|p| {
+                            glob::Pattern::new(p)
+                                .with_context(|| format!("Invalid glob pattern: {p}"))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()
+                .with_context(|| format!("Invalid functions for API key {}", entry.name))?;
+            keys.push(Arc::new(ApiKey {
+                name: entry.name,
+                key: entry.key,
+                functions,
+                endpoints: entry.endpoints,
+            }));
+        }
+        Ok(Self { keys })
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub(super) fn contains(&self, name: &str) -> bool {
+        self.keys.iter().any(|k| k.name == name)
+    }
+
+    pub(super) fn find(&self, key: &str) -> Option<Arc<ApiKey>> {
+        self.keys.iter().find(|k| k.key == key).cloned()
+    }
+}
+
+/// Who a request comes from: one of the `--api-keys`, or anyone allowed to
+/// do anything (i.e. with `BAML_PASSWORD`, or when auth is off).
+#[derive(Clone, Debug, Default)]
+pub(super) struct Caller(pub(super) Option<Arc<ApiKey>>);
+
+impl Caller {
+    pub(super) fn key_name(&self) -> Option<&str> {
+        self.0.as_ref().map(|key| key.name.as_str())
+    }
+
+    pub(super) fn authorize(&self, function: &str, endpoint: Endpoint) -> Result<(), BamlError> {
+        let Some(key) = &self.0 else {
+            return Ok(());
+        };
+        if let Some(endpoints) = &key.endpoints {
+            if !endpoints.contains(&endpoint) {
+                return Err(BamlError::Forbidden {
+                    message: format!("API key {} may not use /{}", key.name, endpoint),
+                });
+            }
+        }
+        if let Some(functions) = &key.functions {
+            if !functions.iter().any(|p| p.matches(function)) {
+                return Err(BamlError::Forbidden {
+                    message: format!("API key {} may not call {}", key.name, function),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the caller may do anything, including administering the
+    /// server: `BAML_PASSWORD`, a key with neither `functions` nor
+    /// `endpoints`, or anyone when auth is off.
+    pub(super) fn is_unrestricted(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|key| key.functions.is_none() && key.endpoints.is_none())
+    }
+
+    /// Tags for the traces of the calls this caller makes, so that usage can
+    /// be attributed to a key.
+    pub(super) fn tags(&self) -> HashMap<String, BamlValue> {
+        self.key_name()
+            .map(|name| {
+                (
+                    "api_key_name".to_string(),
+                    BamlValue::String(name.to_string()),
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ApiKeys {
+        ApiKeys::parse(
+            r#"{
+                "keys": [
+                    { "name": "admin", "key": "sk-baml-admin" },
+                    {
+                        "name": "billing",
+                        "key": "sk-baml-billing",
+                        "functions": ["Extract*", "Classify"],
+                        "endpoints": ["call"]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn unrestricted_key_can_call_anything() {
+        let caller = Caller(keys().find("sk-baml-admin"));
+        assert_eq!(caller.key_name(), Some("admin"));
+        assert!(caller.authorize("Anything", Endpoint::Stream).is_ok());
+    }
+
+    #[test]
+    fn scoped_key_is_limited_to_its_functions_and_endpoints() {
+        let caller = Caller(keys().find("sk-baml-billing"));
+        assert!(caller.authorize("ExtractInvoice", Endpoint::Call).is_ok());
+        assert!(caller.authorize("Classify", Endpoint::Call).is_ok());
+        assert!(matches!(
+            caller.authorize("Summarize", Endpoint::Call),
+            Err(BamlError::Forbidden { .. })
+        ));
+        assert!(matches!(
+            caller.authorize("ExtractInvoice", Endpoint::Stream),
+            Err(BamlError::Forbidden { .. })
+        ));
+    }
+
+    #[test]
+    fn only_keys_without_scopes_are_unrestricted() {
+        assert!(Caller(keys().find("sk-baml-admin")).is_unrestricted());
+        assert!(!Caller(keys().find("sk-baml-billing")).is_unrestricted());
+        assert!(Caller::default().is_unrestricted());
+    }
+
+    #[test]
+    fn unknown_key_is_not_found() {
+        assert!(keys().find("sk-baml-nope").is_none());
+    }
+
+    #[test]
+    fn tags_name_the_key() {
+        let caller = Caller(keys().find("sk-baml-billing"));
+        assert_eq!(
+            caller.tags().get("api_key_name"),
+            Some(&BamlValue::String("billing".to_string()))
+        );
+        assert!(Caller::default().tags().is_empty());
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        let err = ApiKeys::parse(
+            r#"{ "keys": [
+                { "name": "a", "key": "sk-baml-same" },
+                { "name": "b", "key": "sk-baml-same" }
+            ] }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("same key"));
+    }
+}
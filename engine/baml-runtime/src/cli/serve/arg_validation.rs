@@ -1,4 +1,4 @@
-use baml_types::{BamlMediaContent, BamlValue};
+use baml_types::{BamlMediaContent, BamlMessagePart, BamlValue};
 
 use super::error::BamlError;
 use core::result::Result;
@@ -31,6 +31,14 @@ impl BamlServeValidate for BamlValue {
             }
             Ok(())
           }
+          BamlValue::Message(m) => {
+            for part in &m.content {
+              if let BamlMessagePart::Media(media) = part {
+                BamlValue::Media(media.clone()).validate_for_baml_serve()?;
+              }
+            }
+            Ok(())
+          }
           BamlValue::Class(_, fields) => {
             for (_, v) in fields {
               v.validate_for_baml_serve()?;
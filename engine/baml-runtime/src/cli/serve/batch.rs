@@ -0,0 +1,357 @@
+//! Runs a function over many sets of arguments, either while the client
+//! waits (`/batch/:msg`) or in the background (`/jobs`).
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use futures::StreamExt;
+use internal_baml_core::ir::IRHelper;
+use serde::{Deserialize, Serialize};
+
+use crate::internal::llm_client::ResponseBamlValue;
+
+use super::{
+    api_keys::{Caller, Endpoint},
+    error::BamlError,
+    json_response::Json,
+    parse_options, Server,
+};
+
+/// How many calls of a batch run at the same time, unless the server or the
+/// request asks for fewer.
+pub(crate) const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// How long a finished job can still be fetched.
+const FINISHED_JOB_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// The outcome of one set of arguments in a batch.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum BatchItemResult {
+    Ok { result: ResponseBamlValue },
+    Err(BamlError),
+}
+
+#[derive(Deserialize)]
+pub(super) struct BatchQuery {
+    concurrency: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct CreateJob {
+    function: String,
+    args: Vec<serde_json::Value>,
+    concurrency: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JobStatus {
+    Running,
+    Completed,
+}
+
+struct Job {
+    id: String,
+    function: String,
+    /// The API key that started the job. Only it can fetch the job.
+    owner: Option<String>,
+    state: Mutex<JobState>,
+}
+
+struct JobState {
+    results: Vec<Option<BatchItemResult>>,
+    completed: usize,
+    finished_at: Option<Instant>,
+}
+
+#[derive(Serialize)]
+struct JobView<'a> {
+    id: &'a str,
+    function: &'a str,
+    status: JobStatus,
+    total: usize,
+    completed: usize,
+    /// Only set once every call has finished, in the order of the arguments.
+    results: Option<Vec<&'a BatchItemResult>>,
+}
+
+impl Job {
+    fn record(&self, idx: usize, result: BatchItemResult) {
+        let mut state = self.state.lock().unwrap();
+        state.results[idx] = Some(result);
+        state.completed += 1;
+        if state.completed == state.results.len() {
+            state.finished_at = Some(Instant::now());
+        }
+    }
+
+    fn is_owned_by(&self, caller: &Caller) -> bool {
+        self.owner.as_deref() == caller.key_name()
+    }
+
+    fn is_expired(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .finished_at
+            .is_some_and(|at| at.elapsed() > FINISHED_JOB_RETENTION)
+    }
+
+    fn to_response(&self, status: StatusCode) -> Response {
+        let state = self.state.lock().unwrap();
+        let done = state.completed == state.results.len();
+        let view = JobView {
+            id: &self.id,
+            function: &self.function,
+            status: if done {
+                JobStatus::Completed
+            } else {
+                JobStatus::Running
+            },
+            total: state.results.len(),
+            completed: state.completed,
+            results: done.then(|| state.results.iter().flatten().collect()),
+        };
+        (status, Json(view)).into_response()
+    }
+}
+
+/// Jobs started with `POST /jobs`, kept in memory.
+#[derive(Default)]
+pub(super) struct Jobs {
+    jobs: DashMap<String, Arc<Job>>,
+}
+
+impl Server {
+    /// Calls `b_fn` once per element of `items`, at most `concurrency` at a
+    /// time, and hands each result to `on_result` along with its index.
+    async fn run_batch(
+        self: Arc<Self>,
+        b_fn: String,
+        items: Vec<serde_json::Value>,
+        concurrency: Option<usize>,
+        caller: Caller,
+        mut on_result: impl FnMut(usize, BatchItemResult),
+    ) {
+        let concurrency = concurrency
+            .unwrap_or(self.batch_concurrency)
+            .clamp(1, self.batch_concurrency);
+        let mut results = futures::stream::iter(items.into_iter().enumerate())
+            .map(|(idx, b_args)| {
+                let server = self.clone();
+                let b_fn = b_fn.clone();
+                let caller = caller.clone();
+                async move {
+                    // Each call counts against the caller's and the function's
+                    // limits, as if it had been sent on its own.
+                    let result = async {
+                        let _admission = server.admit(&caller, &b_fn, Endpoint::Call)?;
+                        let b_options = parse_options(&b_args)?;
+                        server
+                            .baml_call_result(b_fn, b_args, b_options, caller)
+                            .await
+                    }
+                    .await;
+                    match result {
+                        Ok(result) => (idx, BatchItemResult::Ok { result }),
+                        Err(e) => (idx, BatchItemResult::Err(e)),
+                    }
+                }
+            })
+            .buffer_unordered(concurrency);
+
+        while let Some((idx, result)) = results.next().await {
+            on_result(idx, result);
+        }
+    }
+
+    pub(super) async fn baml_batch_axum(
+        self: Arc<Self>,
+        extract::Path(b_fn): extract::Path<String>,
+        extract::Query(query): extract::Query<BatchQuery>,
+        extract::Extension(caller): extract::Extension<Caller>,
+        extract::Json(body): extract::Json<serde_json::Value>,
+    ) -> Response {
+        if let Err(e) = caller.authorize(&b_fn, Endpoint::Call) {
+            return e.into_response();
+        }
+        let serde_json::Value::Array(items) = body else {
+            return BamlError::InvalidArgument {
+                message: format!(
+                    "POST data must be a JSON array, with one map of arguments for BAML function {b_fn} per call"
+                ),
+            }
+            .into_response();
+        };
+
+        let mut results = Vec::with_capacity(items.len());
+        results.resize_with(items.len(), || None);
+        self.run_batch(b_fn, items, query.concurrency, caller, |idx, result| {
+            results[idx] = Some(result)
+        })
+        .await;
+
+        (
+            StatusCode::OK,
+            Json(results.into_iter().flatten().collect::<Vec<_>>()),
+        )
+            .into_response()
+    }
+
+    pub(super) async fn create_job_axum(
+        self: Arc<Self>,
+        extract::Extension(caller): extract::Extension<Caller>,
+        extract::Json(body): extract::Json<serde_json::Value>,
+    ) -> Response {
+        let request = match serde_json::from_value::<CreateJob>(body) {
+            Ok(request) => request,
+            Err(e) => {
+                return BamlError::InvalidArgument {
+                    message: format!(
+                        "POST data must be a JSON map with `function` and `args`: {}",
+                        e
+                    ),
+                }
+                .into_response()
+            }
+        };
+        if let Err(e) = caller.authorize(&request.function, Endpoint::Call) {
+            return e.into_response();
+        }
+        {
+            let locked = self.b.read().await;
+            if let Err(e) = locked.inner.ir.find_function(&request.function) {
+                return BamlError::InvalidArgument {
+                    message: e.to_string(),
+                }
+                .into_response();
+            }
+        }
+
+        self.jobs.jobs.retain(|_, job| !job.is_expired());
+
+        let job = Arc::new(Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            function: request.function.clone(),
+            owner: caller.key_name().map(str::to_string),
+            state: Mutex::new(JobState {
+                results: request.args.iter().map(|_| None).collect(),
+                completed: 0,
+                finished_at: request.args.is_empty().then(Instant::now),
+            }),
+        });
+        self.jobs.jobs.insert(job.id.clone(), job.clone());
+
+        let running = job.clone();
+        let batch = self.run_batch(
+            request.function,
+            request.args,
+            request.concurrency,
+            caller,
+            move |idx, result| running.record(idx, result),
+        );
+        tokio::spawn(batch);
+
+        job.to_response(StatusCode::ACCEPTED)
+    }
+
+    pub(super) async fn get_job_axum(
+        self: Arc<Self>,
+        extract::Path(id): extract::Path<String>,
+        extract::Extension(caller): extract::Extension<Caller>,
+    ) -> Response {
+        // Jobs of other keys are reported as missing, so that their ids can't
+        // be probed.
+        match self.jobs.jobs.get(&id) {
+            Some(job) if job.is_owned_by(&caller) && !job.is_expired() => {
+                job.to_response(StatusCode::OK)
+            }
+            _ => BamlError::NotFound {
+                message: format!("No job with id {id}"),
+            }
+            .into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::serve::api_keys::ApiKeys;
+
+    fn job(total: usize) -> Job {
+        Job {
+            id: "job".into(),
+            function: "Extract".into(),
+            owner: Some("billing".into()),
+            state: Mutex::new(JobState {
+                results: (0..total).map(|_| None).collect(),
+                completed: 0,
+                finished_at: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn job_completes_once_every_item_is_recorded() {
+        let job = job(2);
+        job.record(
+            1,
+            BatchItemResult::Err(BamlError::InvalidArgument {
+                message: "bad".into(),
+            }),
+        );
+        assert!(job.state.lock().unwrap().finished_at.is_none());
+
+        job.record(
+            0,
+            BatchItemResult::Err(BamlError::ClientError {
+                message: "down".into(),
+            }),
+        );
+        let state = job.state.lock().unwrap();
+        assert_eq!(state.completed, 2);
+        assert!(state.finished_at.is_some());
+        assert!(matches!(
+            state.results[0],
+            Some(BatchItemResult::Err(BamlError::ClientError { .. }))
+        ));
+    }
+
+    #[test]
+    fn jobs_can_only_be_fetched_by_their_owner() {
+        let api_keys = ApiKeys::parse(
+            r#"{
+                "keys": [
+                    { "name": "admin", "key": "sk-baml-admin" },
+                    { "name": "billing", "key": "sk-baml-billing" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let job = job(1);
+        assert!(job.is_owned_by(&Caller(api_keys.find("sk-baml-billing"))));
+        assert!(!job.is_owned_by(&Caller(api_keys.find("sk-baml-admin"))));
+        assert!(!job.is_owned_by(&Caller::default()));
+    }
+
+    #[test]
+    fn failed_items_serialize_like_errors() {
+        let item = BatchItemResult::Err(BamlError::ClientError {
+            message: "down".into(),
+        });
+        assert_eq!(
+            serde_json::to_value(&item).unwrap(),
+            serde_json::json!({ "error": "client_error", "message": "down" })
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use axum::response::{IntoResponse, Response};
-use http::StatusCode;
+use http::{header, HeaderValue, StatusCode};
 use internal_baml_core::ir::scope_diagnostics::ScopeStack;
 use serde::Serialize;
 use serde_json::json;
@@ -31,6 +31,20 @@ pub enum BamlError {
     /// this is the catch-all for unclassified errors.
     #[serde(rename_all = "snake_case")]
     InternalError { message: String },
+    /// Something the request refers to (e.g. a job) doesn't exist.
+    #[serde(rename_all = "snake_case")]
+    NotFound { message: String },
+    /// The API key used isn't allowed to do what the request asks.
+    #[serde(rename_all = "snake_case")]
+    Forbidden { message: String },
+    /// The request exceeds a limit from `--limits`. Sent with a `Retry-After`
+    /// header instead of in the body.
+    #[serde(rename_all = "snake_case")]
+    RateLimited {
+        message: String,
+        #[serde(skip)]
+        retry_after_secs: u64,
+    },
 }
 
 impl BamlError {
@@ -63,6 +77,7 @@ impl BamlError {
                     crate::internal::llm_client::ErrorCode::Other(_)
                     | crate::internal::llm_client::ErrorCode::InvalidAuthentication
                     | crate::internal::llm_client::ErrorCode::NotSupported
+                    | crate::internal::llm_client::ErrorCode::Timeout
                     | crate::internal::llm_client::ErrorCode::RateLimited
                     | crate::internal::llm_client::ErrorCode::ServerError
                     | crate::internal::llm_client::ErrorCode::ServiceUnavailable
@@ -87,15 +102,36 @@ impl BamlError {
     }
 }
 
+impl BamlError {
+    pub(super) fn status_code(&self) -> StatusCode {
+        match self {
+            BamlError::InvalidArgument { .. } => StatusCode::BAD_REQUEST,
+            BamlError::ClientError { .. } => StatusCode::BAD_GATEWAY,
+            BamlError::ValidationFailure { .. } => StatusCode::INTERNAL_SERVER_ERROR, // ??? - FIXME
+            BamlError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            BamlError::NotFound { .. } => StatusCode::NOT_FOUND,
+            BamlError::Forbidden { .. } => StatusCode::FORBIDDEN,
+            BamlError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// Adds the headers that go with this error to `response`.
+    pub(super) fn add_headers(&self, response: &mut Response) {
+        if let BamlError::RateLimited {
+            retry_after_secs, ..
+        } = self
+        {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(*retry_after_secs));
+        }
+    }
+}
+
 impl IntoResponse for BamlError {
     fn into_response(self) -> Response {
-        (
-            match &self {
-                BamlError::InvalidArgument { .. } => StatusCode::BAD_REQUEST,
-                BamlError::ClientError { .. } => StatusCode::BAD_GATEWAY,
-                BamlError::ValidationFailure { .. } => StatusCode::INTERNAL_SERVER_ERROR, // ??? - FIXME
-                BamlError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
-            },
+        let mut response = (
+            self.status_code(),
             Json(match serde_json::to_value(&self) {
                 Ok(serde_json::Value::Object(mut v)) => {
                     v.insert(
@@ -112,6 +148,8 @@ impl IntoResponse for BamlError {
                 }),
             }),
         )
-            .into_response()
+            .into_response();
+        self.add_headers(&mut response);
+        response
     }
 }
@@ -0,0 +1,1183 @@
+//! gRPC (`POST /baml.BamlService/<method>`): the service described by the
+//! `baml.proto` that the `proto` output type generates, so that clients in any
+//! language with gRPC support can call BAML functions with typed messages.
+//!
+//! Requests and responses are encoded against the same schema that the
+//! generator renders, built from the BAML files being served whenever they're
+//! loaded, so there's no separate protobuf build step to keep in sync. Only
+//! uncompressed messages are supported.
+
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::Body,
+    extract,
+    response::{IntoResponse, Response},
+};
+use baml_types::{BamlMediaContent, BamlValue};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use http::{header, HeaderMap, HeaderValue};
+use http_body::Frame;
+use internal_baml_codegen::proto::{Field, Label, MessageKind, ProtoSchema, ProtoType, SERVICE};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::{BamlRuntime, FunctionResult, RuntimeContextManager};
+
+use super::{
+    api_keys::{Caller, Endpoint},
+    error::BamlError,
+    parse_args, Server,
+};
+
+/// gRPC status codes: see https://grpc.github.io/grpc/core/md_doc_statuscodes.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Code {
+    Ok = 0,
+    InvalidArgument = 3,
+    NotFound = 5,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    Unauthenticated = 16,
+}
+
+/// The protobuf schema of the functions being served, or why it couldn't be
+/// built.
+pub(super) type Schema = Result<Arc<ProtoSchema>, String>;
+
+pub(super) fn build_schema(runtime: &BamlRuntime) -> Schema {
+    ProtoSchema::try_from(runtime.inner.ir.as_ref())
+        .map(Arc::new)
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Whether `headers` are those of a gRPC request, which expects failures as
+/// gRPC statuses rather than HTTP ones.
+pub(super) fn is_grpc(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/grpc"))
+}
+
+/// The response to a gRPC request without valid credentials.
+pub(super) fn unauthenticated(message: &str) -> Response {
+    Status::new(Code::Unauthenticated, message).into_response()
+}
+
+/// How a call ended, sent in the `grpc-status` and `grpc-message` trailers.
+#[derive(Debug)]
+struct Status {
+    code: Code,
+    message: String,
+}
+
+impl Status {
+    fn new(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn ok() -> Self {
+        Self::new(Code::Ok, "")
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("grpc-status", HeaderValue::from(self.code as u32));
+        if !self.message.is_empty() {
+            if let Ok(message) = HeaderValue::from_str(&percent_encode(&self.message)) {
+                headers.insert("grpc-message", message);
+            }
+        }
+        headers
+    }
+}
+
+impl From<BamlError> for Status {
+    fn from(e: BamlError) -> Self {
+        match e {
+            BamlError::InvalidArgument { message } => Self::new(Code::InvalidArgument, message),
+            BamlError::ClientError { message } => Self::new(Code::Unavailable, message),
+            BamlError::ValidationFailure { message, .. } => Self::new(Code::Internal, message),
+            BamlError::InternalError { message } => Self::new(Code::Internal, message),
+            BamlError::NotFound { message } => Self::new(Code::NotFound, message),
+            BamlError::Forbidden { message } => Self::new(Code::PermissionDenied, message),
+            BamlError::RateLimited { message, .. } => Self::new(Code::ResourceExhausted, message),
+        }
+    }
+}
+
+/// A call that fails before it starts gets a "trailers-only" response: the
+/// status goes in the headers, and there's no body.
+impl IntoResponse for Status {
+    fn into_response(self) -> Response {
+        let mut response = Body::empty().into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/grpc"),
+        );
+        response.headers_mut().extend(self.headers());
+        response
+    }
+}
+
+/// `grpc-message` is percent-encoded, so that it can hold any UTF-8.
+fn percent_encode(message: &str) -> String {
+    message
+        .bytes()
+        .map(|b| match b {
+            b'%' => "%25".to_string(),
+            0x20..=0x7e => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// A response body: length-prefixed messages, then the call's status as
+/// trailers.
+struct GrpcBody {
+    frames: UnboundedReceiver<Frame<Bytes>>,
+}
+
+impl http_body::Body for GrpcBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.get_mut()
+            .frames
+            .poll_recv(cx)
+            .map(|frame| frame.map(Ok))
+    }
+}
+
+impl Server {
+    pub(super) async fn grpc_axum(
+        self: Arc<Self>,
+        extract::Path(method): extract::Path<String>,
+        extract::Extension(caller): extract::Extension<Caller>,
+        body: Bytes,
+    ) -> Response {
+        match self.grpc(method, caller, body).await {
+            Ok(response) => response,
+            Err(status) => status.into_response(),
+        }
+    }
+
+    async fn grpc(
+        self: Arc<Self>,
+        method: String,
+        caller: Caller,
+        body: Bytes,
+    ) -> Result<Response, Status> {
+        let (runtime, schema) = {
+            // Reloads swap the schema while they hold the runtime's lock, so
+            // the two match.
+            let runtime = self.b.read().await;
+            (runtime.clone(), self.grpc_schema.read().unwrap().clone())
+        };
+        let schema = schema.map_err(|e| {
+            Status::new(
+                Code::Internal,
+                format!("Failed to build the protobuf schema: {e}"),
+            )
+        })?;
+        let Some(rpc) = schema.methods.get(&method) else {
+            return Err(Status::new(
+                Code::Unimplemented,
+                format!("No method {SERVICE}/{method}"),
+            ));
+        };
+        let function = rpc.function.clone();
+        let streaming = rpc.streaming;
+        let response_message = rpc.response.clone();
+
+        let endpoint = if streaming {
+            Endpoint::Stream
+        } else {
+            Endpoint::Call
+        };
+        let admission = self.admit(&caller, &function, endpoint)?;
+
+        let args = decode(&schema, &rpc.request, unframe(&body)?).map_err(|e| {
+            Status::new(
+                Code::InvalidArgument,
+                format!("Invalid {}: {e}", rpc.request),
+            )
+        })?;
+        let args = parse_args(&function, args)?;
+
+        let ctx_mgr = RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
+        ctx_mgr.upsert_tags(caller.tags());
+
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        if !streaming {
+            let start = Instant::now();
+            let (result, _trace_id) = runtime
+                .call_function(function.clone(), &args, &ctx_mgr, None, None)
+                .await;
+            let value = self.finish_call(&function, result, start)?;
+            let message = encode_message(&schema, &response_message, &(&value).into())
+                .map_err(|e| Status::new(Code::Internal, e))?;
+            let _ = sender.send(Frame::data(message));
+            let _ = sender.send(Frame::trailers(Status::ok().headers()));
+        } else {
+            tokio::spawn(async move {
+                let _admission = admission;
+                let start = Instant::now();
+                let result =
+                    match runtime.stream_function(function.clone(), &args, &ctx_mgr, None, None) {
+                        Ok(mut result_stream) => {
+                            let partials = sender.clone();
+                            let partial_schema = schema.clone();
+                            let partial_message = response_message.clone();
+                            let on_event = move |partial: FunctionResult| {
+                                let Ok(value) = partial.result_with_constraints_content() else {
+                                    return;
+                                };
+                                match encode_message(
+                                    &partial_schema,
+                                    &partial_message,
+                                    &stream_response("partial", value.into()),
+                                ) {
+                                    Ok(message) => {
+                                        let _ = partials.send(Frame::data(message));
+                                    }
+                                    Err(e) => log::debug!("Skipping partial result: {}", e),
+                                }
+                            };
+                            result_stream
+                                .run(Some(on_event), &ctx_mgr, None, None)
+                                .await
+                                .0
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                let status = match self.finish_call(&function, result, start) {
+                    Ok(value) => match encode_message(
+                        &schema,
+                        &response_message,
+                        &stream_response("final", (&value).into()),
+                    ) {
+                        Ok(message) => {
+                            let _ = sender.send(Frame::data(message));
+                            Status::ok()
+                        }
+                        Err(e) => Status::new(Code::Internal, e),
+                    },
+                    Err(e) => e.into(),
+                };
+                let _ = sender.send(Frame::trailers(status.headers()));
+            });
+        }
+
+        let mut response = Body::new(GrpcBody { frames: receiver }).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/grpc"),
+        );
+        Ok(response)
+    }
+}
+
+/// One of the messages a streaming RPC sends: `{ partial: value }` or
+/// `{ final: value }`.
+fn stream_response(field: &str, value: BamlValue) -> BamlValue {
+    BamlValue::Map(std::iter::once((field.to_string(), value)).collect())
+}
+
+/// Reads the one message in a request body, after its 5-byte prefix: a
+/// compressed flag, and the message's length.
+fn unframe(body: &[u8]) -> Result<&[u8], Status> {
+    if body.len() < 5 {
+        return Err(Status::new(
+            Code::InvalidArgument,
+            "The request must be a length-prefixed message",
+        ));
+    }
+    if body[0] != 0 {
+        return Err(Status::new(
+            Code::Unimplemented,
+            "Compressed messages are not supported",
+        ));
+    }
+    let length = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+    if body.len() - 5 != length {
+        return Err(Status::new(
+            Code::InvalidArgument,
+            "The request must be exactly one message",
+        ));
+    }
+    Ok(&body[5..])
+}
+
+/// Encodes `value` as the message `name`, with its length prefix.
+fn encode_message(schema: &ProtoSchema, name: &str, value: &BamlValue) -> Result<Bytes, String> {
+    let mut message = BytesMut::new();
+    encode(schema, name, value, &mut message)?;
+
+    let mut framed = BytesMut::with_capacity(message.len() + 5);
+    framed.put_u8(0);
+    framed.put_u32(message.len() as u32);
+    framed.put(message);
+    Ok(framed.freeze())
+}
+
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const LEN: u8 = 2;
+const FIXED32: u8 = 5;
+
+/// How deeply messages may nest in a request, the same limit protobuf's own
+/// parsers use.
+const MAX_DEPTH: usize = 100;
+
+fn read_varint(buf: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        if !buf.has_remaining() {
+            return Err("Message ended in the middle of a varint".to_string());
+        }
+        let byte = buf.get_u8();
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Varint is longer than 10 bytes".to_string())
+}
+
+fn read_bytes<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let length = read_varint(buf)? as usize;
+    if buf.len() < length {
+        return Err("Message ended in the middle of a field".to_string());
+    }
+    let (bytes, rest) = buf.split_at(length);
+    *buf = rest;
+    Ok(bytes)
+}
+
+/// Skips a field that isn't in the schema, as protobuf parsers do.
+fn skip(wire_type: u8, buf: &mut &[u8]) -> Result<(), String> {
+    let length = match wire_type {
+        VARINT => return read_varint(buf).map(|_| ()),
+        FIXED64 => 8,
+        LEN => return read_bytes(buf).map(|_| ()),
+        FIXED32 => 4,
+        _ => return Err(format!("Unsupported wire type {wire_type}")),
+    };
+    if buf.len() < length {
+        return Err("Message ended in the middle of a field".to_string());
+    }
+    buf.advance(length);
+    Ok(())
+}
+
+/// Decodes the message `name` into the JSON the HTTP endpoints take: classes
+/// as objects, enums by value name, media as `{ url }` or `{ base64 }`, and so
+/// on.
+fn decode(schema: &ProtoSchema, name: &str, buf: &[u8]) -> Result<Value, String> {
+    decode_message(schema, name, buf, 0)
+}
+
+/// Decodes a message nested `depth` messages deep in the request.
+fn decode_message(
+    schema: &ProtoSchema,
+    name: &str,
+    mut buf: &[u8],
+    depth: usize,
+) -> Result<Value, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("Messages are nested more than {MAX_DEPTH} deep"));
+    }
+
+    let message = schema
+        .messages
+        .get(name)
+        .ok_or_else(|| format!("No message {name}"))?;
+
+    let mut values: Vec<Vec<Value>> = vec![vec![]; message.fields.len()];
+    while buf.has_remaining() {
+        let key = read_varint(&mut buf)?;
+        let (number, wire_type) = (key >> 3, (key & 7) as u8);
+        match message
+            .fields
+            .iter()
+            .position(|f| u64::from(f.number) == number)
+        {
+            Some(i) => {
+                let field = &message.fields[i];
+                decode_field(schema, field, wire_type, &mut buf, &mut values[i], depth)
+                    .map_err(|e| format!("{}: {e}", field.name))?;
+            }
+            None => skip(wire_type, &mut buf)?,
+        }
+    }
+
+    let mut fields = message.fields.iter().zip(values);
+    Ok(match message.kind {
+        MessageKind::Class | MessageKind::Media(_) | MessageKind::Stream => Value::Object(
+            fields
+                .filter_map(|(f, v)| Some((f.name.clone(), field_value(f, v)?)))
+                .collect(),
+        ),
+        MessageKind::Wrapper => fields
+            .next()
+            .and_then(|(f, v)| field_value(f, v))
+            .unwrap_or(Value::Null),
+        // The last of a union's fields to be set wins.
+        MessageKind::Union => fields
+            .rfind(|(_, v)| !v.is_empty())
+            .and_then(|(f, v)| field_value(f, v))
+            .unwrap_or(Value::Null),
+        MessageKind::Tuple => Value::Array(
+            fields
+                .map(|(f, v)| field_value(f, v).unwrap_or(Value::Null))
+                .collect(),
+        ),
+        MessageKind::Null => Value::Null,
+    })
+}
+
+/// Decodes one occurrence of `field` into `out`.
+fn decode_field(
+    schema: &ProtoSchema,
+    field: &Field,
+    wire_type: u8,
+    buf: &mut &[u8],
+    out: &mut Vec<Value>,
+    depth: usize,
+) -> Result<(), String> {
+    match &field.r#type {
+        ProtoType::Map(value_type) => {
+            if wire_type != LEN {
+                return Err(format!("Unexpected wire type {wire_type}"));
+            }
+            let mut entry = read_bytes(buf)?;
+            let (mut key, mut value) = (Value::String(String::new()), None);
+            while entry.has_remaining() {
+                let entry_key = read_varint(&mut entry)?;
+                let wire_type = (entry_key & 7) as u8;
+                match entry_key >> 3 {
+                    1 => {
+                        key =
+                            decode_value(schema, &ProtoType::String, wire_type, &mut entry, depth)?
+                    }
+                    2 => {
+                        value = Some(decode_value(
+                            schema, value_type, wire_type, &mut entry, depth,
+                        )?)
+                    }
+                    _ => skip(wire_type, &mut entry)?,
+                }
+            }
+            let value = value
+                .or_else(|| default_value(value_type))
+                .unwrap_or(Value::Null);
+            out.push(Value::Array(vec![key, value]));
+        }
+        // Repeated numbers may be packed together in one field.
+        ProtoType::Int64 | ProtoType::Double | ProtoType::Bool | ProtoType::Enum(_)
+            if field.label == Label::Repeated && wire_type == LEN =>
+        {
+            let mut packed = read_bytes(buf)?;
+            let wire_type = match field.r#type {
+                ProtoType::Double => FIXED64,
+                _ => VARINT,
+            };
+            while packed.has_remaining() {
+                out.push(decode_value(
+                    schema,
+                    &field.r#type,
+                    wire_type,
+                    &mut packed,
+                    depth,
+                )?);
+            }
+        }
+        t => out.push(decode_value(schema, t, wire_type, buf, depth)?),
+    }
+    Ok(())
+}
+
+fn decode_value(
+    schema: &ProtoSchema,
+    t: &ProtoType,
+    wire_type: u8,
+    buf: &mut &[u8],
+    depth: usize,
+) -> Result<Value, String> {
+    let expected = match t {
+        ProtoType::String | ProtoType::Message(_) | ProtoType::Map(_) => LEN,
+        ProtoType::Int64 | ProtoType::Bool | ProtoType::Enum(_) => VARINT,
+        ProtoType::Double => FIXED64,
+    };
+    if wire_type != expected {
+        return Err(format!("Unexpected wire type {wire_type}"));
+    }
+    Ok(match t {
+        ProtoType::String => String::from_utf8(read_bytes(buf)?.to_vec())
+            .map_err(|_| "Strings must be valid UTF-8".to_string())?
+            .into(),
+        ProtoType::Int64 => (read_varint(buf)? as i64).into(),
+        ProtoType::Bool => (read_varint(buf)? != 0).into(),
+        ProtoType::Double => {
+            if buf.len() < 8 {
+                return Err("Message ended in the middle of a field".to_string());
+            }
+            f64::from_bits(buf.get_u64_le()).into()
+        }
+        ProtoType::Enum(name) => match read_varint(buf)? {
+            // The `_UNSPECIFIED` value.
+            0 => Value::Null,
+            number => schema
+                .enums
+                .get(name)
+                .and_then(|values| values.get(number as usize - 1))
+                .ok_or_else(|| format!("{number} is not a value of {name}"))?
+                .clone()
+                .into(),
+        },
+        ProtoType::Message(name) => decode_message(schema, name, read_bytes(buf)?, depth + 1)?,
+        ProtoType::Map(_) => unreachable!("maps are only fields"),
+    })
+}
+
+/// The value of `field`, given every occurrence of it in a message, or `None`
+/// if it's absent.
+fn field_value(field: &Field, mut values: Vec<Value>) -> Option<Value> {
+    if let ProtoType::Map(_) = field.r#type {
+        return Some(Value::Object(
+            values
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    Value::Array(mut kv) if kv.len() == 2 => {
+                        let value = kv.pop()?;
+                        Some((kv.pop()?.as_str()?.to_string(), value))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        ));
+    }
+    match field.label {
+        Label::Repeated => Some(Value::Array(values)),
+        // The last occurrence of a field wins.
+        Label::Optional => values.pop(),
+        Label::Implicit => values.pop().or_else(|| default_value(&field.r#type)),
+    }
+}
+
+/// What an absent field means, if it isn't null.
+fn default_value(t: &ProtoType) -> Option<Value> {
+    match t {
+        ProtoType::String => Some("".into()),
+        ProtoType::Int64 => Some(0.into()),
+        ProtoType::Double => Some(0.0.into()),
+        ProtoType::Bool => Some(false.into()),
+        ProtoType::Map(_) => Some(Value::Object(Default::default())),
+        ProtoType::Enum(_) | ProtoType::Message(_) => None,
+    }
+}
+
+fn put_key(buf: &mut BytesMut, number: u32, wire_type: u8) {
+    put_varint(buf, (u64::from(number) << 3) | u64::from(wire_type));
+}
+
+fn put_varint(buf: &mut BytesMut, mut value: u64) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+fn put_bytes(buf: &mut BytesMut, number: u32, bytes: &[u8]) {
+    put_key(buf, number, LEN);
+    put_varint(buf, bytes.len() as u64);
+    buf.put_slice(bytes);
+}
+
+/// Encodes `value` as the fields of the message `name`.
+fn encode(
+    schema: &ProtoSchema,
+    name: &str,
+    value: &BamlValue,
+    buf: &mut BytesMut,
+) -> Result<(), String> {
+    let message = schema
+        .messages
+        .get(name)
+        .ok_or_else(|| format!("No message {name}"))?;
+
+    match (message.kind, value) {
+        (
+            MessageKind::Class | MessageKind::Stream,
+            BamlValue::Class(_, fields) | BamlValue::Map(fields),
+        ) => {
+            for field in &message.fields {
+                if let Some(value) = fields.get(&field.name) {
+                    encode_field(schema, field, value, buf)?;
+                }
+            }
+        }
+        (MessageKind::Wrapper, value) => encode_field(schema, &message.fields[0], value, buf)?,
+        (MessageKind::Union, value) => {
+            let field = message
+                .fields
+                .iter()
+                .find(|f| accepts(schema, &f.r#type, value))
+                .ok_or_else(|| format!("Expected a {name}, got a {}", value.r#type()))?;
+            encode_field(schema, field, value, buf)?;
+        }
+        (MessageKind::Tuple, BamlValue::List(items)) if items.len() == message.fields.len() => {
+            for (field, item) in message.fields.iter().zip(items) {
+                encode_field(schema, field, item, buf)?;
+            }
+        }
+        (MessageKind::Media(media_type), BamlValue::Media(media))
+            if media.media_type == media_type =>
+        {
+            let (url, base64) = match &media.content {
+                BamlMediaContent::Url(url) => (Some(&url.url), None),
+                BamlMediaContent::Base64(base64) => (None, Some(&base64.base64)),
+                BamlMediaContent::File(_) => {
+                    return Err(format!("Can't send a {media_type} file over gRPC"))
+                }
+            };
+            for (field, value) in message
+                .fields
+                .iter()
+                .zip([url, base64, media.mime_type.as_ref()])
+            {
+                if let Some(value) = value {
+                    put_bytes(buf, field.number, value.as_bytes());
+                }
+            }
+        }
+        (MessageKind::Null, BamlValue::Null) => {}
+        (_, value) => return Err(format!("Expected a {name}, got a {}", value.r#type())),
+    }
+    Ok(())
+}
+
+fn encode_field(
+    schema: &ProtoSchema,
+    field: &Field,
+    value: &BamlValue,
+    buf: &mut BytesMut,
+) -> Result<(), String> {
+    match (field.label, &field.r#type, value) {
+        // Null is an absent field.
+        (_, _, BamlValue::Null) => {}
+        (_, ProtoType::Map(value_type), BamlValue::Map(entries)) => {
+            for (key, value) in entries {
+                let mut entry = BytesMut::new();
+                put_bytes(&mut entry, 1, key.as_bytes());
+                encode_value(schema, 2, value_type, value, &mut entry)?;
+                put_bytes(buf, field.number, &entry);
+            }
+        }
+        // Parsers accept repeated numbers whether or not they're packed, so
+        // we don't bother packing them.
+        (Label::Repeated, t, BamlValue::List(items)) => {
+            for item in items {
+                encode_value(schema, field.number, t, item, buf)?;
+            }
+        }
+        (Label::Repeated, ..) | (_, ProtoType::Map(_), _) => {
+            return Err(format!(
+                "{}: expected a {}, got a {}",
+                field.name,
+                if field.label == Label::Repeated {
+                    "list"
+                } else {
+                    "map"
+                },
+                value.r#type()
+            ))
+        }
+        (_, t, value) => encode_value(schema, field.number, t, value, buf)?,
+    }
+    Ok(())
+}
+
+fn encode_value(
+    schema: &ProtoSchema,
+    number: u32,
+    t: &ProtoType,
+    value: &BamlValue,
+    buf: &mut BytesMut,
+) -> Result<(), String> {
+    match (t, value) {
+        (ProtoType::String, BamlValue::String(s)) => put_bytes(buf, number, s.as_bytes()),
+        (ProtoType::Int64, BamlValue::Int(i)) => {
+            put_key(buf, number, VARINT);
+            put_varint(buf, *i as u64);
+        }
+        (ProtoType::Double, BamlValue::Float(f)) => {
+            put_key(buf, number, FIXED64);
+            buf.put_u64_le(f.to_bits());
+        }
+        (ProtoType::Double, BamlValue::Int(i)) => {
+            put_key(buf, number, FIXED64);
+            buf.put_u64_le((*i as f64).to_bits());
+        }
+        (ProtoType::Bool, BamlValue::Bool(b)) => {
+            put_key(buf, number, VARINT);
+            put_varint(buf, u64::from(*b));
+        }
+        (ProtoType::Enum(name), BamlValue::Enum(_, v) | BamlValue::String(v)) => {
+            let index = schema
+                .enums
+                .get(name)
+                .and_then(|values| values.iter().position(|value| value == v))
+                .ok_or_else(|| format!("{v} is not a value of {name}"))?;
+            put_key(buf, number, VARINT);
+            put_varint(buf, index as u64 + 1);
+        }
+        (ProtoType::Message(name), value) => {
+            let mut message = BytesMut::new();
+            encode(schema, name, value, &mut message)?;
+            put_bytes(buf, number, &message);
+        }
+        (t, value) => return Err(format!("Expected a {t:?}, got a {}", value.r#type())),
+    }
+    Ok(())
+}
+
+/// Whether `value` can be encoded as a `t`: used to pick which of a union's
+/// fields to set.
+fn accepts(schema: &ProtoSchema, t: &ProtoType, value: &BamlValue) -> bool {
+    match (t, value) {
+        (ProtoType::String, BamlValue::String(_))
+        | (ProtoType::Int64, BamlValue::Int(_))
+        | (ProtoType::Double, BamlValue::Float(_))
+        | (ProtoType::Bool, BamlValue::Bool(_)) => true,
+        (ProtoType::Enum(name), BamlValue::Enum(e, _)) => e == name,
+        (ProtoType::Message(name), value) => {
+            let Some(message) = schema.messages.get(name) else {
+                return false;
+            };
+            match (message.kind, value) {
+                (MessageKind::Class, BamlValue::Class(c, _)) => c == name,
+                (MessageKind::Wrapper, value) => {
+                    let field = &message.fields[0];
+                    match (field.label, &field.r#type, value) {
+                        (Label::Optional, _, BamlValue::Null) => true,
+                        (Label::Repeated, _, BamlValue::List(_)) => true,
+                        (_, ProtoType::Map(_), BamlValue::Map(_)) => true,
+                        (Label::Repeated, ..) | (_, ProtoType::Map(_), _) => false,
+                        (_, t, value) => accepts(schema, t, value),
+                    }
+                }
+                (MessageKind::Union, value) => message
+                    .fields
+                    .iter()
+                    .any(|f| accepts(schema, &f.r#type, value)),
+                (MessageKind::Tuple, BamlValue::List(items)) => items.len() == message.fields.len(),
+                (MessageKind::Media(media_type), BamlValue::Media(media)) => {
+                    media.media_type == media_type
+                }
+                (MessageKind::Null, BamlValue::Null) => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use internal_baml_core::ir::repr::make_test_ir;
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> ProtoSchema {
+        let ir = make_test_ir(
+            r##"
+client<llm> GPT4 {
+  provider openai
+  options {
+    model gpt-4o
+    api_key env.OPENAI_API_KEY
+  }
+}
+
+enum Category {
+  Refund
+  CancelOrder
+}
+
+class Ticket {
+  title string
+  category Category
+  tags string[]
+  priority int?
+  scores map<string, float>
+  answer int | string
+}
+
+function Triage(ticket: Ticket, limit: int) -> Ticket {
+  client GPT4
+  prompt #""#
+}
+"##,
+        )
+        .expect("Valid source");
+        ProtoSchema::try_from(&ir).unwrap()
+    }
+
+    fn ticket() -> BamlValue {
+        let ticket = json!({
+            "title": "Refund, please",
+            "category": "Refund",
+            "tags": ["billing", "urgent"],
+            "priority": 2,
+            "scores": { "anger": 0.5 },
+            "answer": "none",
+        });
+        let BamlValue::Map(mut fields) = serde_json::from_value(ticket).unwrap() else {
+            unreachable!()
+        };
+        fields.insert(
+            "category".to_string(),
+            BamlValue::Enum("Category".to_string(), "Refund".to_string()),
+        );
+        BamlValue::Class("Ticket".to_string(), fields)
+    }
+
+    #[test]
+    fn round_trips_messages() {
+        let schema = schema();
+        let request = BamlValue::Map(
+            [
+                ("ticket".to_string(), ticket()),
+                ("limit".to_string(), BamlValue::Int(-3)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let framed = encode_message(&schema, "TriageRequest", &request).unwrap();
+        let decoded = decode(&schema, "TriageRequest", unframe(&framed).unwrap()).unwrap();
+        assert_eq!(
+            decoded,
+            json!({
+                "ticket": {
+                    "title": "Refund, please",
+                    "category": "Refund",
+                    "tags": ["billing", "urgent"],
+                    "priority": 2,
+                    "scores": { "anger": 0.5 },
+                    "answer": "none",
+                },
+                "limit": -3,
+            })
+        );
+    }
+
+    #[test]
+    fn fills_in_absent_fields() {
+        let schema = schema();
+        // Just `title = "hi"` (field 1, length-delimited).
+        let decoded = decode(&schema, "Ticket", &[0x0a, 0x02, b'h', b'i']).unwrap();
+        assert_eq!(decoded, json!({ "title": "hi", "tags": [], "scores": {} }));
+    }
+
+    #[test]
+    fn decodes_packed_repeated_fields() {
+        let mut schema = schema();
+        schema.messages.insert(
+            "Ints".to_string(),
+            internal_baml_codegen::proto::Message {
+                kind: MessageKind::Wrapper,
+                fields: vec![Field {
+                    name: "value".to_string(),
+                    number: 1,
+                    label: Label::Repeated,
+                    r#type: ProtoType::Int64,
+                }],
+            },
+        );
+        // Field 1, packed: 1, 150.
+        let decoded = decode(&schema, "Ints", &[0x0a, 0x03, 0x01, 0x96, 0x01]).unwrap();
+        assert_eq!(decoded, json!([1, 150]));
+    }
+
+    /// Adds a message with `fields` to `schema`.
+    fn add_message(
+        schema: &mut ProtoSchema,
+        name: &str,
+        kind: MessageKind,
+        fields: Vec<(&str, u32, Label, ProtoType)>,
+    ) {
+        let fields = fields
+            .into_iter()
+            .map(|(name, number, label, r#type)| Field {
+                name: name.to_string(),
+                number,
+                label,
+                r#type,
+            })
+            .collect();
+        schema.messages.insert(
+            name.to_string(),
+            internal_baml_codegen::proto::Message { kind, fields },
+        );
+    }
+
+    /// Checks that `value` encodes to `wire`, and that `wire` decodes to
+    /// `decoded`.
+    fn assert_wire(schema: &ProtoSchema, name: &str, value: Value, wire: &[u8], decoded: Value) {
+        let value: BamlValue = serde_json::from_value(value).unwrap();
+        let mut buf = BytesMut::new();
+        encode(schema, name, &value, &mut buf).unwrap();
+        assert_eq!(&buf[..], wire, "encoding {name}");
+        assert_eq!(
+            decode(schema, name, wire).unwrap(),
+            decoded,
+            "decoding {name}"
+        );
+    }
+
+    // The expected bytes below are the examples from the protobuf encoding
+    // guide (https://protobuf.dev/programming-guides/encoding/), and bytes
+    // worked out from its rules for the types the guide has no examples of.
+    #[test]
+    fn matches_the_reference_encoding() {
+        let mut schema = schema();
+        add_message(
+            &mut schema,
+            "Test1",
+            MessageKind::Class,
+            vec![("a", 1, Label::Implicit, ProtoType::Int64)],
+        );
+        add_message(
+            &mut schema,
+            "Test2",
+            MessageKind::Class,
+            vec![("b", 2, Label::Implicit, ProtoType::String)],
+        );
+        add_message(
+            &mut schema,
+            "Test3",
+            MessageKind::Class,
+            vec![(
+                "c",
+                3,
+                Label::Optional,
+                ProtoType::Message("Test1".to_string()),
+            )],
+        );
+        add_message(
+            &mut schema,
+            "Scalars",
+            MessageKind::Class,
+            vec![
+                ("d", 1, Label::Implicit, ProtoType::Double),
+                ("e", 2, Label::Implicit, ProtoType::Bool),
+            ],
+        );
+        add_message(
+            &mut schema,
+            "Scores",
+            MessageKind::Class,
+            vec![(
+                "scores",
+                5,
+                Label::Implicit,
+                ProtoType::Map(Box::new(ProtoType::Double)),
+            )],
+        );
+        add_message(
+            &mut schema,
+            "Categories",
+            MessageKind::Wrapper,
+            vec![(
+                "value",
+                1,
+                Label::Repeated,
+                ProtoType::Enum("Category".to_string()),
+            )],
+        );
+
+        assert_wire(
+            &schema,
+            "Test1",
+            json!({ "a": 150 }),
+            &[0x08, 0x96, 0x01],
+            json!({ "a": 150 }),
+        );
+        assert_wire(
+            &schema,
+            "Test2",
+            json!({ "b": "testing" }),
+            &[0x12, 0x07, 0x74, 0x65, 0x73, 0x74, 0x69, 0x6e, 0x67],
+            json!({ "b": "testing" }),
+        );
+        assert_wire(
+            &schema,
+            "Test3",
+            json!({ "c": { "a": 150 } }),
+            &[0x1a, 0x03, 0x08, 0x96, 0x01],
+            json!({ "c": { "a": 150 } }),
+        );
+        // Negative int64s are sign-extended to ten bytes.
+        assert_wire(
+            &schema,
+            "Test1",
+            json!({ "a": -2 }),
+            &[
+                0x08, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+            ],
+            json!({ "a": -2 }),
+        );
+        assert_wire(
+            &schema,
+            "Scalars",
+            json!({ "d": 1.0, "e": true }),
+            &[0x09, 0, 0, 0, 0, 0, 0, 0xf0, 0x3f, 0x10, 0x01],
+            json!({ "d": 1.0, "e": true }),
+        );
+        // Map entries are messages with the key in field 1 and the value in 2.
+        assert_wire(
+            &schema,
+            "Scores",
+            json!({ "scores": { "a": 0.5 } }),
+            &[
+                0x2a, 0x0c, 0x0a, 0x01, 0x61, 0x11, 0, 0, 0, 0, 0, 0, 0xe0, 0x3f,
+            ],
+            json!({ "scores": { "a": 0.5 } }),
+        );
+        // Enum values are numbered from 1, after `_UNSPECIFIED`.
+        assert_wire(
+            &schema,
+            "Categories",
+            json!(["CancelOrder", "Refund"]),
+            &[0x08, 0x02, 0x08, 0x01],
+            json!(["CancelOrder", "Refund"]),
+        );
+        // The same values packed, as proto3 encoders send them by default.
+        assert_eq!(
+            decode(&schema, "Categories", &[0x0a, 0x02, 0x02, 0x01]).unwrap(),
+            json!(["CancelOrder", "Refund"])
+        );
+    }
+
+    #[test]
+    fn skips_unknown_fields_of_every_wire_type() {
+        let mut schema = schema();
+        add_message(
+            &mut schema,
+            "Test1",
+            MessageKind::Class,
+            vec![("a", 1, Label::Implicit, ProtoType::Int64)],
+        );
+        let wire = [
+            &[0x48, 0x01][..],               // Field 9, varint 1.
+            &[0x51, 1, 2, 3, 4, 5, 6, 7, 8], // Field 10, fixed64.
+            &[0x5a, 0x02, 0xaa, 0xbb],       // Field 11, two bytes.
+            &[0x65, 1, 2, 3, 4],             // Field 12, fixed32.
+            &[0x08, 0x96, 0x01],             // Field 1, varint 150.
+        ]
+        .concat();
+        assert_eq!(
+            decode(&schema, "Test1", &wire).unwrap(),
+            json!({ "a": 150 })
+        );
+        // Groups were removed in proto3.
+        assert!(decode(&schema, "Test1", &[0x4b, 0x4c]).is_err());
+    }
+
+    #[test]
+    fn rejects_deeply_nested_messages() {
+        let mut schema = schema();
+        add_message(
+            &mut schema,
+            "Node",
+            MessageKind::Class,
+            vec![(
+                "next",
+                1,
+                Label::Optional,
+                ProtoType::Message("Node".to_string()),
+            )],
+        );
+        // `depth` messages, each the `next` of the one before.
+        let nested = |depth: usize| {
+            let mut wire = BytesMut::new();
+            for _ in 1..depth {
+                let mut outer = BytesMut::new();
+                outer.put_u8(0x0a);
+                put_varint(&mut outer, wire.len() as u64);
+                outer.put(wire);
+                wire = outer;
+            }
+            wire
+        };
+
+        assert!(decode(&schema, "Node", &nested(MAX_DEPTH + 1)).is_ok());
+        assert!(decode(&schema, "Node", &nested(MAX_DEPTH + 2))
+            .unwrap_err()
+            .contains("nested more than 100 deep"));
+    }
+
+    #[test]
+    fn picks_the_union_member_for_a_value() {
+        let schema = schema();
+        let mut buf = BytesMut::new();
+        encode(&schema, "OneOf_Int_Or_String", &BamlValue::Int(7), &mut buf).unwrap();
+        // Field 1 (`int`), varint 7.
+        assert_eq!(&buf[..], &[0x08, 0x07]);
+    }
+
+    #[test]
+    fn rejects_compressed_and_partial_requests() {
+        assert_eq!(
+            unframe(&[1, 0, 0, 0, 0]).unwrap_err().code,
+            Code::Unimplemented
+        );
+        assert_eq!(
+            unframe(&[0, 0, 0, 0, 2, 0x08]).unwrap_err().code,
+            Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn rejects_unauthenticated_calls_with_a_grpc_status() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/grpc+proto"),
+        );
+        assert!(is_grpc(&headers));
+
+        let response = unauthenticated("Invalid API key");
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["grpc-status"], "16");
+    }
+
+    #[test]
+    fn percent_encodes_messages() {
+        assert_eq!(percent_encode("50% off: ¡sí!"), "50%25 off: %C2%A1s%C3%AD!");
+    }
+}
@@ -0,0 +1,307 @@
+//! Limits from the file passed to `--limits`: how often, and how many at a
+//! time, each API key may call functions and each function may be called, and
+//! how many requests per minute may be sent to each client.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::internal::llm_client::rate_limit::{self, TokenBucket};
+
+use super::{
+    api_keys::{ApiKeys, Caller},
+    error::BamlError,
+};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LimitsFile {
+    /// By API key name.
+    #[serde(default)]
+    keys: HashMap<String, Limit>,
+    /// By function name.
+    #[serde(default)]
+    functions: HashMap<String, Limit>,
+    /// By client name.
+    #[serde(default)]
+    clients: HashMap<String, ClientLimit>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Limit {
+    requests_per_minute: Option<u32>,
+    burst: Option<u32>,
+    max_concurrent_requests: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClientLimit {
+    requests_per_minute: u32,
+    burst: Option<u32>,
+}
+
+struct Limiter {
+    /// What the limit applies to, for error messages.
+    subject: String,
+    bucket: Option<TokenBucket>,
+    max_concurrent: Option<usize>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// Holds a request's place against `max_concurrent_requests` until dropped.
+pub(super) struct Admission {
+    _slots: Vec<Slot>,
+}
+
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Limiter {
+    fn new(subject: String, limit: Limit) -> Result<Self> {
+        if limit.requests_per_minute == Some(0) || limit.max_concurrent_requests == Some(0) {
+            anyhow::bail!("Limits for {subject} must be greater than 0");
+        }
+        if limit.burst.is_some() && limit.requests_per_minute.is_none() {
+            anyhow::bail!("Limits for {subject} set burst without requests_per_minute");
+        }
+        Ok(Self {
+            subject,
+            bucket: limit
+                .requests_per_minute
+                .map(|rpm| TokenBucket::per_minute(rpm, limit.burst)),
+            max_concurrent: limit.max_concurrent_requests,
+            in_flight: Default::default(),
+        })
+    }
+
+    fn take_slot(&self) -> Result<Option<Slot>, BamlError> {
+        let Some(max) = self.max_concurrent else {
+            return Ok(None);
+        };
+        let taken = self
+            .in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < max).then_some(n + 1)
+            });
+        match taken {
+            Ok(_) => Ok(Some(Slot(self.in_flight.clone()))),
+            Err(_) => Err(BamlError::RateLimited {
+                message: format!("{} already has {} requests in flight", self.subject, max),
+                retry_after_secs: 1,
+            }),
+        }
+    }
+
+    fn take_token(&self) -> Result<(), BamlError> {
+        match self.bucket.as_ref().map(TokenBucket::try_acquire) {
+            Some(Err(wait)) => Err(BamlError::RateLimited {
+                message: format!("{} is over its requests_per_minute", self.subject),
+                retry_after_secs: wait.as_secs_f64().ceil() as u64,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn refund_token(&self) {
+        if let Some(bucket) = &self.bucket {
+            bucket.refund();
+        }
+    }
+}
+
+/// Every limit from `--limits`. Empty if the flag wasn't passed.
+#[derive(Default)]
+pub(crate) struct Limits {
+    keys: HashMap<String, Limiter>,
+    functions: HashMap<String, Limiter>,
+}
+
+impl Limits {
+    /// Loads the limits in `path`, and starts applying its client limits to
+    /// every request this process sends.
+    pub(crate) fn load(path: &Path, api_keys: &ApiKeys) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read limits from {}", path.display()))?;
+        let (limits, clients) = Self::parse(&contents, api_keys)
+            .with_context(|| format!("Failed to load limits from {}", path.display()))?;
+        for (client, bucket) in clients {
+            rate_limit::limit_client(&client, bucket);
+        }
+        Ok(limits)
+    }
+
+    fn parse(contents: &str, api_keys: &ApiKeys) -> Result<(Self, Vec<(String, TokenBucket)>)> {
+        let file: LimitsFile = serde_json::from_str(contents)?;
+
+        let keys = file
+            .keys
+            .into_iter()
+            .map(|(name, limit)| {
+                if !api_keys.contains(&name) {
+                    anyhow::bail!("No API key named {name} in --api-keys");
+                }
+                Ok((
+                    name.clone(),
+                    Limiter::new(format!("API key {name}"), limit)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let functions = file
+            .functions
+            .into_iter()
+            .map(|(name, limit)| {
+                Ok((
+                    name.clone(),
+                    Limiter::new(format!("Function {name}"), limit)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let clients = file
+            .clients
+            .into_iter()
+            .map(|(name, limit)| {
+                if limit.requests_per_minute == 0 {
+                    anyhow::bail!("Limits for client {name} must be greater than 0");
+                }
+                let bucket = TokenBucket::per_minute(limit.requests_per_minute, limit.burst);
+                Ok((name, bucket))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok((Self { keys, functions }, clients))
+    }
+
+    /// Checks that `caller` may call `function` now, and if so, counts the
+    /// request as in flight until the returned admission is dropped.
+    pub(super) fn admit(&self, caller: &Caller, function: &str) -> Result<Admission, BamlError> {
+        let limiters = caller
+            .key_name()
+            .and_then(|name| self.keys.get(name))
+            .into_iter()
+            .chain(self.functions.get(function))
+            .collect::<Vec<_>>();
+
+        // Take every slot before any token, so that a request turned away for
+        // being one too many at a time doesn't use up its rate limit.
+        let mut slots = Vec::new();
+        for limiter in &limiters {
+            slots.extend(limiter.take_slot()?);
+        }
+        for (i, limiter) in limiters.iter().enumerate() {
+            if let Err(e) = limiter.take_token() {
+                // The request isn't let through, so it shouldn't count against
+                // the limits that would have allowed it.
+                for taken in &limiters[..i] {
+                    taken.refund_token();
+                }
+                return Err(e);
+            }
+        }
+        Ok(Admission { _slots: slots })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_keys() -> ApiKeys {
+        ApiKeys::parse(r#"{ "keys": [{ "name": "billing", "key": "sk-baml-billing" }] }"#).unwrap()
+    }
+
+    fn limits(contents: &str) -> (Limits, ApiKeys) {
+        let api_keys = api_keys();
+        let (limits, _) = Limits::parse(contents, &api_keys).unwrap();
+        (limits, api_keys)
+    }
+
+    #[test]
+    fn limits_concurrent_requests_per_key() {
+        let (limits, api_keys) =
+            limits(r#"{ "keys": { "billing": { "max_concurrent_requests": 1 } } }"#);
+        let billing = Caller(api_keys.find("sk-baml-billing"));
+
+        let first = limits.admit(&billing, "Extract").unwrap();
+        assert!(matches!(
+            limits.admit(&billing, "Extract"),
+            Err(BamlError::RateLimited {
+                retry_after_secs: 1,
+                ..
+            })
+        ));
+        // Other callers aren't limited by the key's limit.
+        assert!(limits.admit(&Caller::default(), "Extract").is_ok());
+
+        drop(first);
+        assert!(limits.admit(&billing, "Extract").is_ok());
+    }
+
+    #[test]
+    fn limits_requests_per_minute_per_function() {
+        let (limits, _) =
+            limits(r#"{ "functions": { "Extract": { "requests_per_minute": 60, "burst": 2 } } }"#);
+        assert!(limits.admit(&Caller::default(), "Extract").is_ok());
+        assert!(limits.admit(&Caller::default(), "Extract").is_ok());
+        assert!(matches!(
+            limits.admit(&Caller::default(), "Extract"),
+            Err(BamlError::RateLimited {
+                retry_after_secs: 1,
+                ..
+            })
+        ));
+        assert!(limits.admit(&Caller::default(), "Classify").is_ok());
+    }
+
+    #[test]
+    fn rejected_requests_release_their_slots() {
+        let (limits, _) = limits(
+            r#"{ "functions": { "Extract": { "requests_per_minute": 1, "max_concurrent_requests": 1 } } }"#,
+        );
+        drop(limits.admit(&Caller::default(), "Extract").unwrap());
+        assert!(limits.admit(&Caller::default(), "Extract").is_err());
+        assert_eq!(
+            limits.functions["Extract"].in_flight.load(Ordering::SeqCst),
+            0
+        );
+    }
+
+    #[test]
+    fn rejected_requests_refund_earlier_tokens() {
+        let (limits, api_keys) = limits(
+            r#"{
+                "keys": { "billing": { "requests_per_minute": 60, "burst": 2 } },
+                "functions": { "Extract": { "requests_per_minute": 60, "burst": 1 } }
+            }"#,
+        );
+        let billing = Caller(api_keys.find("sk-baml-billing"));
+
+        assert!(limits.admit(&billing, "Extract").is_ok());
+        // Turned away by the function's limit, so the key keeps its token.
+        assert!(limits.admit(&billing, "Extract").is_err());
+        assert!(limits.admit(&billing, "Classify").is_ok());
+        assert!(limits.admit(&billing, "Classify").is_err());
+    }
+
+    #[test]
+    fn rejects_limits_for_unknown_keys() {
+        let err = Limits::parse(r#"{ "keys": { "nobody": {} } }"#, &api_keys())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("No API key named nobody"));
+    }
+}
@@ -0,0 +1,427 @@
+//! Metrics served at `/metrics`, in the Prometheus text format.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+use axum::{
+    extract::{self, MatchedPath},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    internal::llm_client::{orchestrator::ExecutionScope, LLMResponse},
+    FunctionResult,
+};
+
+use super::error::BamlError;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Counter,
+    Histogram,
+}
+
+/// Every metric we export: its name, what it's for, and its kind.
+const METRICS: [(&str, &str, Kind); 10] = [
+    (
+        "baml_http_requests_total",
+        "HTTP requests handled, by route and status code.",
+        Kind::Counter,
+    ),
+    (
+        "baml_http_request_duration_seconds",
+        "Time spent handling HTTP requests, by route.",
+        Kind::Histogram,
+    ),
+    (
+        "baml_function_calls_total",
+        "BAML function calls, by function and outcome.",
+        Kind::Counter,
+    ),
+    (
+        "baml_function_duration_seconds",
+        "Time spent in BAML function calls, including retries and fallbacks, by function.",
+        Kind::Histogram,
+    ),
+    (
+        "baml_parse_failures_total",
+        "BAML function calls whose LLM response could not be parsed, by function.",
+        Kind::Counter,
+    ),
+    (
+        "baml_llm_requests_total",
+        "Requests sent to LLM clients, by client and outcome.",
+        Kind::Counter,
+    ),
+    (
+        "baml_llm_request_duration_seconds",
+        "Time spent waiting on LLM clients, by client.",
+        Kind::Histogram,
+    ),
+    (
+        "baml_llm_tokens_total",
+        "Tokens reported by LLM clients, by client and kind (prompt or output).",
+        Kind::Counter,
+    ),
+    (
+        "baml_retries_total",
+        "LLM requests made by a retry policy after a failure, by function.",
+        Kind::Counter,
+    ),
+    (
+        "baml_fallbacks_total",
+        "LLM requests made by a fallback client after a failure, by function.",
+        Kind::Counter,
+    ),
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    /// Per bucket of [`LATENCY_BUCKETS`], how many observations fit in it.
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, le) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= le {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct State {
+    counters: BTreeMap<&'static str, BTreeMap<Labels, u64>>,
+    histograms: BTreeMap<&'static str, BTreeMap<Labels, Histogram>>,
+}
+
+/// Metrics collected by the server since it started.
+#[derive(Default)]
+pub(super) struct Metrics {
+    state: Mutex<State>,
+    /// The functions in `baml_src`. Calls to any other name are counted as
+    /// `unknown`, so that made up names can't create new series.
+    functions: RwLock<HashSet<String>>,
+}
+
+impl Metrics {
+    fn inc(&self, name: &'static str, labels: Labels, by: u64) {
+        let mut state = self.state.lock().unwrap();
+        *state
+            .counters
+            .entry(name)
+            .or_default()
+            .entry(labels)
+            .or_default() += by;
+    }
+
+    fn observe(&self, name: &'static str, labels: Labels, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .histograms
+            .entry(name)
+            .or_default()
+            .entry(labels)
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Sets the functions that get their own `function` label, on startup and
+    /// on every reload.
+    pub(super) fn set_functions<'a>(&self, functions: impl IntoIterator<Item = &'a str>) {
+        *self.functions.write().unwrap() = functions.into_iter().map(str::to_string).collect();
+    }
+
+    pub(super) fn record_request(&self, route: &str, status: u16, duration: Duration) {
+        self.inc(
+            "baml_http_requests_total",
+            vec![("route", route.to_string()), ("status", status.to_string())],
+            1,
+        );
+        self.observe(
+            "baml_http_request_duration_seconds",
+            vec![("route", route.to_string())],
+            duration,
+        );
+    }
+
+    /// Records a function call that ended with `outcome`, and each of the LLM
+    /// requests it made.
+    pub(super) fn record_function_call<T>(
+        &self,
+        function: &str,
+        result: Option<&FunctionResult>,
+        outcome: &Result<T, BamlError>,
+        duration: Duration,
+    ) {
+        let function = if self.functions.read().unwrap().contains(function) {
+            function
+        } else {
+            "unknown"
+        };
+        let function_label = || vec![("function", function.to_string())];
+
+        let outcome = match outcome {
+            Ok(_) => "success",
+            Err(BamlError::InvalidArgument { .. }) => "invalid_argument",
+            Err(BamlError::ClientError { .. }) => "client_error",
+            Err(BamlError::ValidationFailure { .. }) => {
+                self.inc("baml_parse_failures_total", function_label(), 1);
+                "validation_failure"
+            }
+            Err(BamlError::InternalError { .. }) => "internal_error",
+            Err(BamlError::NotFound { .. }) => "not_found",
+            Err(BamlError::Forbidden { .. }) => "forbidden",
+            Err(BamlError::RateLimited { .. }) => "rate_limited",
+        };
+        self.inc(
+            "baml_function_calls_total",
+            vec![
+                ("function", function.to_string()),
+                ("outcome", outcome.to_string()),
+            ],
+            1,
+        );
+        self.observe("baml_function_duration_seconds", function_label(), duration);
+
+        let Some(result) = result else {
+            return;
+        };
+        for (scope, response, _, _) in result.event_chain() {
+            for scope in &scope.scope {
+                match scope {
+                    ExecutionScope::Retry(_, count, _) if *count > 0 => {
+                        self.inc("baml_retries_total", function_label(), 1)
+                    }
+                    ExecutionScope::Fallback(_, idx) if *idx > 0 => {
+                        self.inc("baml_fallbacks_total", function_label(), 1)
+                    }
+                    _ => {}
+                }
+            }
+
+            let (client, outcome, latency) = match response {
+                LLMResponse::Success(s) => {
+                    for (kind, tokens) in [
+                        ("prompt", s.metadata.prompt_tokens),
+                        ("output", s.metadata.output_tokens),
+                    ] {
+                        if let Some(tokens) = tokens {
+                            self.inc(
+                                "baml_llm_tokens_total",
+                                vec![("client", s.client.clone()), ("kind", kind.to_string())],
+                                tokens,
+                            );
+                        }
+                    }
+                    (&s.client, "success".to_string(), s.latency)
+                }
+                LLMResponse::LLMFailure(e) => (&e.client, e.code.to_u16().to_string(), e.latency),
+                // These never reached a client.
+                LLMResponse::UserFailure(_) | LLMResponse::InternalFailure(_) => continue,
+            };
+            self.inc(
+                "baml_llm_requests_total",
+                vec![("client", client.clone()), ("outcome", outcome)],
+                1,
+            );
+            self.observe(
+                "baml_llm_request_duration_seconds",
+                vec![("client", client.clone())],
+                latency,
+            );
+        }
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub(super) fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+        for (name, help, kind) in METRICS {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            match kind {
+                Kind::Counter => {
+                    let _ = writeln!(out, "# TYPE {name} counter");
+                    for (labels, value) in state.counters.get(name).into_iter().flatten() {
+                        let _ = writeln!(out, "{name}{} {value}", format_labels(labels, None));
+                    }
+                }
+                Kind::Histogram => {
+                    let _ = writeln!(out, "# TYPE {name} histogram");
+                    for (labels, histogram) in state.histograms.get(name).into_iter().flatten() {
+                        for (le, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                            let _ = writeln!(
+                                out,
+                                "{name}_bucket{} {count}",
+                                format_labels(labels, Some(&le.to_string()))
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{name}_bucket{} {}",
+                            format_labels(labels, Some("+Inf")),
+                            histogram.count
+                        );
+                        let _ = writeln!(
+                            out,
+                            "{name}_sum{} {}",
+                            format_labels(labels, None),
+                            histogram.sum
+                        );
+                        let _ = writeln!(
+                            out,
+                            "{name}_count{} {}",
+                            format_labels(labels, None),
+                            histogram.count
+                        );
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    pub(super) fn to_response(&self) -> Response {
+        (
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/plain; version=0.0.4"),
+            )],
+            self.render(),
+        )
+            .into_response()
+    }
+}
+
+fn format_labels(labels: &[(&'static str, String)], le: Option<&str>) -> String {
+    let labels = labels
+        .iter()
+        .map(|(k, v)| (*k, v.as_str()))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(k, v)| {
+            let v = v
+                .replace('\\', r"\\")
+                .replace('"', "\\\"")
+                .replace('\n', r"\n");
+            format!("{k}=\"{v}\"")
+        })
+        .collect::<Vec<_>>();
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels.join(","))
+    }
+}
+
+/// Counts every request and how long it took, by the route it matched.
+pub(super) async fn track_requests(
+    metrics: &Metrics,
+    request: extract::Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let start = std::time::Instant::now();
+    let response = next.run(request).await;
+    metrics.record_request(&route, response.status().as_u16(), start.elapsed());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_histograms() {
+        let metrics = Metrics::default();
+        metrics.record_request("/call/:msg", 200, Duration::from_millis(30));
+        metrics.record_request("/call/:msg", 200, Duration::from_millis(300));
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("# TYPE baml_http_requests_total counter\n"));
+        assert!(
+            rendered.contains("baml_http_requests_total{route=\"/call/:msg\",status=\"200\"} 2\n")
+        );
+        assert!(rendered.contains(
+            "baml_http_request_duration_seconds_bucket{route=\"/call/:msg\",le=\"0.05\"} 1\n"
+        ));
+        assert!(rendered.contains(
+            "baml_http_request_duration_seconds_bucket{route=\"/call/:msg\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(
+            rendered.contains("baml_http_request_duration_seconds_count{route=\"/call/:msg\"} 2\n")
+        );
+    }
+
+    #[test]
+    fn counts_parse_failures() {
+        let metrics = Metrics::default();
+        metrics.set_functions(["Extract"]);
+        metrics.record_function_call::<()>(
+            "Extract",
+            None,
+            &Err(BamlError::ValidationFailure {
+                prompt: "".into(),
+                raw_output: "".into(),
+                message: "".into(),
+            }),
+            Duration::from_millis(10),
+        );
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("baml_parse_failures_total{function=\"Extract\"} 1\n"));
+        assert!(rendered.contains(
+            "baml_function_calls_total{function=\"Extract\",outcome=\"validation_failure\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn counts_calls_to_unknown_functions_together() {
+        let metrics = Metrics::default();
+        metrics.set_functions(["Extract"]);
+        for function in ["Nope", "AlsoNope"] {
+            metrics.record_function_call::<()>(
+                function,
+                None,
+                &Err(BamlError::InvalidArgument { message: "".into() }),
+                Duration::from_millis(10),
+            );
+        }
+
+        let rendered = metrics.render();
+        assert!(rendered.contains(
+            "baml_function_calls_total{function=\"unknown\",outcome=\"invalid_argument\"} 2\n"
+        ));
+        assert!(!rendered.contains("Nope"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(
+            format_labels(&[("client", "a\"b\\c".to_string())], None),
+            r#"{client="a\"b\\c"}"#
+        );
+    }
+}
@@ -1,13 +1,28 @@
+mod api_keys;
 mod arg_validation;
+mod batch;
 mod error;
+mod grpc;
 mod json_response;
+mod limits;
+mod metrics;
+mod openai;
 mod ping;
+mod reload;
+mod shutdown;
+mod websocket;
+pub(crate) use api_keys::ApiKeys;
+pub(crate) use batch::DEFAULT_BATCH_CONCURRENCY;
 use error::BamlError;
 use indexmap::IndexMap;
 use internal_baml_codegen::GeneratorArgs;
 use json_response::Json;
+use limits::Admission;
+pub(crate) use limits::Limits;
+pub(crate) use shutdown::DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS;
 
 use anyhow::{Context, Result};
+use api_keys::{Caller, Endpoint};
 use arg_validation::BamlServeValidate;
 use axum::{
     extract::{self},
@@ -20,7 +35,11 @@ use axum::{
     routing::{any, get, post},
 };
 use axum_extra::{
-    headers::{self, authorization::Basic, Authorization, Header},
+    headers::{
+        self,
+        authorization::{Basic, Bearer},
+        Authorization, Header,
+    },
     TypedHeader,
 };
 use baml_types::{BamlValue, GeneratorDefaultClientMode};
@@ -28,7 +47,14 @@ use core::pin::Pin;
 use futures::Stream;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{path::PathBuf, sync::Arc, task::Poll};
+use std::{
+    collections::HashMap,
+    future::IntoFuture,
+    path::{Path, PathBuf},
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
 use tokio::{net::TcpListener, sync::RwLock};
 use tokio_stream::StreamExt;
 
@@ -36,9 +62,10 @@ use crate::{
     client_registry::ClientRegistry,
     errors::ExposedError,
     internal::llm_client::{LLMResponse, ResponseBamlValue},
+    runtime_interface::ExperimentalTracingInterface,
     BamlRuntime, FunctionResult, RuntimeContextManager,
 };
-use internal_baml_codegen::openapi::OpenApiSchema;
+use internal_baml_codegen::{openapi::OpenApiSchema, proto};
 
 #[derive(clap::Args, Clone, Debug)]
 pub struct ServeArgs {
@@ -54,6 +81,40 @@ pub struct ServeArgs {
         default_value_t = false
     )]
     no_version_check: bool,
+    #[arg(
+        long,
+        help = "maximum number of calls of a batch or job that run at the same time",
+        default_value_t = batch::DEFAULT_BATCH_CONCURRENCY
+    )]
+    batch_concurrency: usize,
+    #[arg(
+        long,
+        help = "seconds that in-flight requests get to finish after SIGTERM or Ctrl-C",
+        default_value_t = shutdown::DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS
+    )]
+    shutdown_grace_period: u64,
+    #[arg(
+        long,
+        help = "reload baml_src whenever a file in it changes",
+        default_value_t = false
+    )]
+    watch: bool,
+    #[arg(
+        long,
+        help = "allow reloading baml_src with POST /_admin/reload, by callers with an unrestricted API key",
+        default_value_t = false
+    )]
+    admin_reload: bool,
+    #[arg(
+        long,
+        help = "path/to/api_keys.json, with named API keys and what each may call"
+    )]
+    api_keys: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "path/to/limits.json, with rate and concurrency limits per API key, function and client"
+    )]
+    limits: Option<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -110,7 +171,31 @@ Thanks for trying out BAML!
 
         let t: Arc<tokio::runtime::Runtime> = BamlRuntime::get_tokio_singleton()?;
 
-        let (server, tcp_listener) = t.block_on(Server::new(self.from.clone(), self.port))?;
+        let api_keys = match &self.api_keys {
+            Some(path) => ApiKeys::load(path)?,
+            None => ApiKeys::default(),
+        };
+        let limits = match &self.limits {
+            Some(path) => Limits::load(path, &api_keys)?,
+            None => Limits::default(),
+        };
+
+        let (server, tcp_listener) = t.block_on(Server::new(
+            self.from.clone(),
+            self.port,
+            self.batch_concurrency,
+            Duration::from_secs(self.shutdown_grace_period),
+            self.admin_reload,
+            api_keys,
+            limits,
+        ))?;
+
+        // Reloads stop when this is dropped.
+        let _watcher = if self.watch {
+            Some(server.clone().watch(t.handle().clone())?)
+        } else {
+            None
+        };
 
         t.block_on(server.serve(tcp_listener))?;
 
@@ -126,7 +211,43 @@ Thanks for trying out BAML!
 pub(super) struct Server {
     src_dir: PathBuf,
     port: u16,
-    pub(super) b: Arc<RwLock<BamlRuntime>>,
+    /// Requests clone the runtime out of the lock, so that a reload can swap
+    /// it without waiting for them to finish.
+    pub(super) b: Arc<RwLock<Arc<BamlRuntime>>>,
+    reloading: tokio::sync::Mutex<()>,
+    batch_concurrency: usize,
+    shutdown_grace_period: Duration,
+    /// Whether `POST /_admin/reload` is served.
+    admin_reload: bool,
+    jobs: batch::Jobs,
+    metrics: metrics::Metrics,
+    /// The protobuf schema of the runtime in `b`, for gRPC. Only swapped
+    /// while holding `b`'s write lock.
+    grpc_schema: std::sync::RwLock<grpc::Schema>,
+    /// The `baml_src` the runtime in `b` was loaded from, for chat completions
+    /// whose `model` is a client. Only swapped while holding `b`'s write lock.
+    client_chat: std::sync::RwLock<Arc<openai::ClientChat>>,
+    api_keys: ApiKeys,
+    limits: Limits,
+}
+
+/// Loads the runtime in `src_dir`, along with the files it was loaded from.
+fn load_runtime(src_dir: &Path) -> Result<(BamlRuntime, openai::ClientChat)> {
+    let src_dir = BamlRuntime::parse_baml_src_path(src_dir)?;
+    let files = crate::baml_src_files(&src_dir)?
+        .into_iter()
+        .map(|path| {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            Ok((path.to_string_lossy().into_owned(), contents))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+    let runtime = BamlRuntime::from_file_content(
+        &src_dir.to_string_lossy(),
+        &files,
+        std::env::vars().collect(),
+    )?;
+    Ok((runtime, openai::ClientChat::new(src_dir, files)))
 }
 
 #[derive(Debug)]
@@ -158,10 +279,16 @@ impl Header for XBamlApiKey {
 }
 
 async fn status_handler(
+    server: Arc<Server>,
     basic_creds: Option<TypedHeader<Authorization<Basic>>>,
+    bearer: Option<TypedHeader<Authorization<Bearer>>>,
     baml_api_key: Option<TypedHeader<XBamlApiKey>>,
 ) -> Response {
-    match Server::enforce_auth(basic_creds.as_deref(), baml_api_key.as_deref()) {
+    match server.enforce_auth(
+        basic_creds.as_deref(),
+        bearer.as_deref(),
+        baml_api_key.as_deref(),
+    ) {
         AuthEnforcementMode::EnforceAndFail(e) => (
             StatusCode::FORBIDDEN,
             Json(json!({
@@ -172,12 +299,13 @@ async fn status_handler(
                 },
             })),
         ),
-        AuthEnforcementMode::EnforceAndPass => (
+        AuthEnforcementMode::EnforceAndPass(caller) => (
             StatusCode::OK,
             Json(json!({
                 "authz": {
                     "enforcement": "active",
-                    "outcome": "pass"
+                    "outcome": "pass",
+                    "api_key": caller.key_name(),
                 },
             })),
         ),
@@ -195,12 +323,20 @@ async fn status_handler(
 
 enum AuthEnforcementMode {
     NoEnforcement,
-    EnforceAndPass,
+    EnforceAndPass(Caller),
     EnforceAndFail(String),
 }
 
 impl Server {
-    pub async fn new(src_dir: PathBuf, port: u16) -> Result<(Arc<Self>, TcpListener)> {
+    pub async fn new(
+        src_dir: PathBuf,
+        port: u16,
+        batch_concurrency: usize,
+        shutdown_grace_period: Duration,
+        admin_reload: bool,
+        api_keys: ApiKeys,
+        limits: Limits,
+    ) -> Result<(Arc<Self>, TcpListener)> {
         let tcp_listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port))
             .await
             .context(format!(
@@ -208,57 +344,78 @@ impl Server {
                 port
             ))?;
 
+        let (runtime, client_chat) = load_runtime(&src_dir)?;
+        let metrics = metrics::Metrics::default();
+        metrics.set_functions(runtime.function_names());
+        let grpc_schema = grpc::build_schema(&runtime);
+
         Ok((
             Arc::new(Self {
                 src_dir: src_dir.clone(),
                 port,
-                b: Arc::new(RwLock::new(BamlRuntime::from_directory(
-                    &src_dir,
-                    std::env::vars().collect(),
-                )?)),
+                b: Arc::new(RwLock::new(Arc::new(runtime))),
+                reloading: Default::default(),
+                batch_concurrency: batch_concurrency.max(1),
+                shutdown_grace_period,
+                admin_reload,
+                jobs: Default::default(),
+                metrics,
+                grpc_schema: std::sync::RwLock::new(grpc_schema),
+                client_chat: std::sync::RwLock::new(Arc::new(client_chat)),
+                api_keys,
+                limits,
             }),
             tcp_listener,
         ))
     }
 
     fn enforce_auth(
+        &self,
         basic_creds: Option<&Authorization<Basic>>,
+        bearer: Option<&Authorization<Bearer>>,
         baml_api_key: Option<&XBamlApiKey>,
     ) -> AuthEnforcementMode {
-        let Ok(password) = std::env::var("BAML_PASSWORD") else {
-            log::warn!("BAML_PASSWORD not set, skipping auth check");
-            return AuthEnforcementMode::NoEnforcement;
-        };
-
-        if !password.starts_with("sk-baml") {
-            log::warn!("We recommend using BAML_PASSWORD=sk-baml-... so that static analysis tools can detect if you accidentally commit and push your password.")
+        let password = std::env::var("BAML_PASSWORD").ok();
+        match &password {
+            None if self.api_keys.is_empty() => {
+                log::warn!("BAML_PASSWORD not set, skipping auth check");
+                return AuthEnforcementMode::NoEnforcement;
+            }
+            Some(password) if !password.starts_with("sk-baml") => {
+                log::warn!("We recommend using BAML_PASSWORD=sk-baml-... so that static analysis tools can detect if you accidentally commit and push your password.")
+            }
+            _ => {}
         }
 
-        if let Some(XBamlApiKey(baml_api_key)) = baml_api_key {
-            return if *baml_api_key == password {
-                AuthEnforcementMode::EnforceAndPass
-            } else {
-                AuthEnforcementMode::EnforceAndFail(format!("Incorrect x-baml-api-key"))
-            };
-        }
+        let (provided, incorrect) = if let Some(XBamlApiKey(baml_api_key)) = baml_api_key {
+            (baml_api_key.as_str(), "Incorrect x-baml-api-key")
+        } else if let Some(Authorization(basic_creds)) = basic_creds {
+            (
+                basic_creds.password(),
+                "Incorrect password provided in basic auth",
+            )
+        } else if let Some(Authorization(bearer)) = bearer {
+            // OpenAI's clients send their API key this way.
+            (bearer.token(), "Incorrect bearer token")
+        } else {
+            return AuthEnforcementMode::EnforceAndFail("No authorization metadata".to_owned());
+        };
 
-        if let Some(Authorization(basic_creds)) = basic_creds {
-            return if basic_creds.password() == password {
-                AuthEnforcementMode::EnforceAndPass
-            } else {
-                AuthEnforcementMode::EnforceAndFail(format!(
-                    "Incorrect password provided in basic auth"
-                ))
-            };
+        if password.as_deref() == Some(provided) {
+            return AuthEnforcementMode::EnforceAndPass(Caller::default());
+        }
+        match self.api_keys.find(provided) {
+            Some(key) => AuthEnforcementMode::EnforceAndPass(Caller(Some(key))),
+            None => AuthEnforcementMode::EnforceAndFail(incorrect.to_owned()),
         }
-
-        AuthEnforcementMode::EnforceAndFail("No authorization metadata".to_owned())
     }
 
     async fn auth_middleware(
+        extract::State(server): extract::State<Arc<Server>>,
         basic_auth: Option<TypedHeader<Authorization<Basic>>>,
+        bearer: Option<TypedHeader<Authorization<Bearer>>>,
         baml_api_key: Option<TypedHeader<XBamlApiKey>>,
-        request: extract::Request,
+        mut request: extract::Request,
         next: Next,
     ) -> Response {
         log::debug!("Handling request for {}", request.uri());
@@ -267,11 +424,22 @@ impl Server {
         if request.uri() == "/_debug/ping" || request.uri() == "/_debug/status" {
             return next.run(request).await;
         }
-        if let AuthEnforcementMode::EnforceAndFail(e) =
-            Server::enforce_auth(basic_auth.as_deref(), baml_api_key.as_deref())
-        {
-            return (StatusCode::FORBIDDEN, format!("{}\n", e.trim())).into_response();
-        }
+        let caller = match server.enforce_auth(
+            basic_auth.as_deref(),
+            bearer.as_deref(),
+            baml_api_key.as_deref(),
+        ) {
+            AuthEnforcementMode::EnforceAndFail(e) if grpc::is_grpc(request.headers()) => {
+                return grpc::unauthenticated(e.trim());
+            }
+            AuthEnforcementMode::EnforceAndFail(e) => {
+                return (StatusCode::FORBIDDEN, format!("{}\n", e.trim())).into_response();
+            }
+            AuthEnforcementMode::EnforceAndPass(caller) => caller,
+            AuthEnforcementMode::NoEnforcement => Caller::default(),
+        };
+        // Handlers that call functions check what the caller may call.
+        request.extensions_mut().insert(caller);
 
         // log::info!(
         //     "incoming request triggering middleware, basic auth is {:?} and x-baml-api-key is {:?}",
@@ -287,19 +455,73 @@ impl Server {
         let app = axum::Router::new();
 
         let app = app.route("/_debug/ping", any(ping::ping_handler));
-        let app = app.route("/_debug/status", any(status_handler));
+        let s = self.clone();
+        let app = app.route(
+            "/_debug/status",
+            any(move |basic_creds, bearer, baml_api_key| {
+                status_handler(s.clone(), basic_creds, bearer, baml_api_key)
+            }),
+        );
+
+        let app = if self.admin_reload {
+            let s = self.clone();
+            app.route(
+                "/_admin/reload",
+                post(move |caller| s.clone().reload_axum(caller)),
+            )
+        } else {
+            app
+        };
 
         let s = self.clone();
         let app = app.route(
             "/call/:msg",
-            post(move |b_fn, b_args| s.clone().baml_call_axum(b_fn, b_args)),
+            post(move |b_fn, caller, b_args| s.clone().baml_call_axum(b_fn, caller, b_args)),
         );
 
         let s = self.clone();
         let app = app.route(
             "/stream/:msg",
-            post(move |b_fn, b_args| s.clone().baml_stream_axum2(b_fn, b_args)),
+            post(move |b_fn, caller, b_args| s.clone().baml_stream_axum2(b_fn, caller, b_args)),
         );
+        let s = self.clone();
+        let app = app.route(
+            "/batch/:msg",
+            post(move |b_fn, query, caller, b_args| {
+                s.clone().baml_batch_axum(b_fn, query, caller, b_args)
+            }),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            "/jobs",
+            post(move |caller, body| s.clone().create_job_axum(caller, body)),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            "/jobs/:id",
+            get(move |id, caller| s.clone().get_job_axum(id, caller)),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            "/ws",
+            get(move |caller, ws| s.clone().websocket_axum(caller, ws)),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            "/v1/chat/completions",
+            post(move |caller, request| s.clone().chat_completions_axum(caller, request)),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            &format!("/{}.{}/:method", proto::PACKAGE, proto::SERVICE),
+            post(move |method, caller, body| s.clone().grpc_axum(method, caller, body)),
+        );
+
         let s = self.clone();
         let app = app.route("/docs", get(move || s.clone().docs_handler()));
 
@@ -309,25 +531,48 @@ impl Server {
             get(move || s.clone().openapi_json_handler()),
         );
 
-        let service = axum::serve(
-            tcp_listener,
-            app.layer(axum::middleware::from_fn(Server::auth_middleware)),
+        let s = self.clone();
+        let app = app.route(
+            "/metrics",
+            get(move || async move { s.metrics.to_response() }),
         );
-        // TODO: we do not handle this ourselves, because tokio's default
-        // handling is pretty good on unix.
-        //
-        // Not totally sure if this WAI on
-        // windows, but there are some potential pitfalls that we can run into
-        // if we try to handle this ourselves, see
-        // https://docs.rs/tokio/latest/tokio/signal/fn.ctrl_c.html#caveats
-        //
-        // Namely- we need to ensure resilient delivery of Ctrl-C to everything, and I
-        // suspect we need to do a bit of work to ensure that we handle that bookkeeping
-        // correctly. Shutting down the BAML runtime, tokio runtime, _and_ axum is not
-        // super straightforward, because I don't know how much is handled for us
-        // out of the box.
-        //
-        // .with_graceful_shutdown(signal::ctrl_c());
+
+        let s = self.clone();
+        let app = app
+            .layer(axum::middleware::from_fn_with_state(
+                self.clone(),
+                Server::auth_middleware,
+            ))
+            .layer(axum::middleware::from_fn(
+                move |request: extract::Request, next: Next| {
+                    let s = s.clone();
+                    async move { metrics::track_requests(&s.metrics, request, next).await }
+                },
+            ));
+
+        // Once asked to shut down, stop accepting connections and wait for the
+        // ones we have to finish, for up to `shutdown_grace_period`.
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+        let grace_period = self.shutdown_grace_period;
+        let service = axum::serve(tcp_listener, app).with_graceful_shutdown(async move {
+            shutdown::signal().await;
+            log::info!(
+                "Shutting down; waiting up to {:?} for in-flight requests to finish",
+                grace_period
+            );
+            let _ = shutdown_tx.send(());
+        });
+        let grace_period_elapsed = async move {
+            match shutdown_rx.await {
+                // A second Ctrl-C skips the rest of the grace period.
+                Ok(()) => tokio::select! {
+                    _ = tokio::time::sleep(grace_period) => {}
+                    _ = shutdown::signal() => {}
+                },
+                Err(_) => std::future::pending().await,
+            }
+        };
+
         log::info!(
             r#"BAML-over-HTTP listening on port {}, serving from {}
 
@@ -340,93 +585,155 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
             self.port,
         );
 
-        service.await?;
+        tokio::select! {
+            result = service.into_future() => result?,
+            _ = grace_period_elapsed => {
+                log::warn!("Cancelling requests that are still in flight");
+            }
+        }
+
+        let b = self.b.clone();
+        match tokio::task::spawn_blocking(move || b.blocking_read().flush()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => log::warn!("Failed to flush traces: {:?}", e),
+            Err(e) => log::warn!("Failed to flush traces: {:?}", e),
+        }
 
         Ok(())
     }
 
+    /// Checks that `caller` may call `b_fn` through `endpoint`, and isn't over
+    /// any of its limits. The request counts as in flight until the returned
+    /// admission is dropped.
+    fn admit(
+        &self,
+        caller: &Caller,
+        b_fn: &str,
+        endpoint: Endpoint,
+    ) -> Result<Admission, BamlError> {
+        caller.authorize(b_fn, endpoint)?;
+        self.limits.admit(caller, b_fn)
+    }
+
     async fn baml_call(
         self: Arc<Self>,
         b_fn: String,
         b_args: serde_json::Value,
         b_options: Option<BamlOptions>,
+        caller: Caller,
     ) -> Response {
-        let args = match parse_args(&b_fn, b_args) {
-            Ok(args) => args,
-            Err(e) => return e.into_response(),
-        };
+        match self.baml_call_result(b_fn, b_args, b_options, caller).await {
+            Ok(parsed) => (StatusCode::OK, Json::<ResponseBamlValue>(parsed)).into_response(),
+            Err(e) => e.into_response(),
+        }
+    }
+
+    async fn baml_call_result(
+        self: Arc<Self>,
+        b_fn: String,
+        b_args: serde_json::Value,
+        b_options: Option<BamlOptions>,
+        caller: Caller,
+    ) -> Result<ResponseBamlValue, BamlError> {
+        let args = parse_args(&b_fn, b_args)?;
 
         let ctx_mgr = RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
+        ctx_mgr.upsert_tags(caller.tags());
         let client_registry = b_options.and_then(|options| options.client_registry);
 
-        let locked = self.b.read().await;
-        let (result, _trace_id) = locked
-            .call_function(b_fn, &args, &ctx_mgr, None, client_registry.as_ref())
+        let runtime = self.b.read().await.clone();
+        let start = std::time::Instant::now();
+        let (result, _trace_id) = runtime
+            .call_function(
+                b_fn.clone(),
+                &args,
+                &ctx_mgr,
+                None,
+                client_registry.as_ref(),
+            )
             .await;
 
-        match result {
-            Ok(function_result) => match function_result.llm_response() {
-                LLMResponse::Success(_) => match function_result.result_with_constraints_content() {
-                    // Just because the LLM returned 2xx doesn't mean that it returned parse-able content!
-                    Ok(parsed) => {
-                        (StatusCode::OK, Json::<ResponseBamlValue>(parsed.clone())).into_response()
-                    }
-                    Err(e) => {
-                        if let Some(ExposedError::ValidationError {
-                            prompt,
-                            raw_output: raw_response,
-                            message,
-                        }) = e.downcast_ref::<ExposedError>()
-                        {
-                            BamlError::ValidationFailure {
-                                message: message.clone(),
-                                prompt: prompt.clone(),
-                                raw_output: raw_response.clone(),
-                            }
-                            .into_response()
-                        } else {
-                            BamlError::InternalError {
-                                message: format!("Error parsing: {:?}", e),
+        self.finish_call(&b_fn, result, start)
+    }
+
+    /// Turns the result of calling `b_fn` into what we send back, and records
+    /// it in the server's metrics.
+    fn finish_call(
+        &self,
+        b_fn: &str,
+        result: Result<FunctionResult>,
+        start: std::time::Instant,
+    ) -> Result<ResponseBamlValue, BamlError> {
+        let (function_result, outcome) = match result {
+            Ok(function_result) => {
+                let outcome = match function_result.llm_response() {
+                    LLMResponse::Success(_) => {
+                        match function_result.result_with_constraints_content() {
+                            // Just because the LLM returned 2xx doesn't mean that it returned parse-able content!
+                            Ok(parsed) => Ok(parsed.clone()),
+                            Err(e) => {
+                                log::debug!("Error parsing content: {:?}", e);
+                                if let Some(ExposedError::ValidationError {
+                                    prompt,
+                                    raw_output: raw_response,
+                                    message,
+                                }) = e.downcast_ref::<ExposedError>()
+                                {
+                                    Err(BamlError::ValidationFailure {
+                                        message: message.clone(),
+                                        prompt: prompt.clone(),
+                                        raw_output: raw_response.clone(),
+                                    })
+                                } else {
+                                    Err(BamlError::InternalError {
+                                        message: format!("Error parsing: {:?}", e),
+                                    })
+                                }
                             }
-                            .into_response()
                         }
                     }
-                },
-                LLMResponse::LLMFailure(failure) => BamlError::ClientError {
-                    message: format!("{:?}", failure.message),
-                }
-                .into_response(),
-                LLMResponse::UserFailure(message) => BamlError::InvalidArgument {
-                    message: message.clone(),
-                }
-                .into_response(),
-                LLMResponse::InternalFailure(message) => BamlError::InternalError {
-                    message: message.clone(),
-                }
-                .into_response(),
-            },
-            Err(e) => BamlError::from_anyhow(e).into_response(),
-        }
+                    LLMResponse::LLMFailure(failure) => {
+                        log::debug!("LLMResponse::LLMFailure: {:?}", failure);
+                        Err(BamlError::ClientError {
+                            message: format!("{:?}", failure.message),
+                        })
+                    }
+                    LLMResponse::UserFailure(message) => Err(BamlError::InvalidArgument {
+                        message: message.clone(),
+                    }),
+                    LLMResponse::InternalFailure(message) => Err(BamlError::InternalError {
+                        message: message.clone(),
+                    }),
+                };
+                (Some(function_result), outcome)
+            }
+            Err(e) => (None, Err(BamlError::from_anyhow(e))),
+        };
+
+        self.metrics.record_function_call(
+            b_fn,
+            function_result.as_ref(),
+            &outcome,
+            start.elapsed(),
+        );
+        outcome
     }
 
     async fn baml_call_axum(
         self: Arc<Self>,
         extract::Path(b_fn): extract::Path<String>,
+        extract::Extension(caller): extract::Extension<Caller>,
         extract::Json(b_args): extract::Json<serde_json::Value>,
     ) -> Response {
-        let mut b_options = None;
-        if let Some(options_value) = b_args.get("__baml_options__") {
-            match serde_json::from_value::<BamlOptions>(options_value.clone()) {
-                Ok(opts) => b_options = Some(opts),
-                Err(_) => {
-                    return BamlError::InvalidArgument {
-                        message: "Failed to parse __baml_options__".to_string(),
-                    }
-                    .into_response()
-                }
-            }
-        }
-        self.baml_call(b_fn, b_args, b_options).await
+        let _admission = match self.admit(&caller, &b_fn, Endpoint::Call) {
+            Ok(admission) => admission,
+            Err(e) => return e.into_response(),
+        };
+        let b_options = match parse_options(&b_args) {
+            Ok(b_options) => b_options,
+            Err(e) => return e.into_response(),
+        };
+        self.baml_call(b_fn, b_args, b_options, caller).await
     }
 
     fn baml_stream(
@@ -434,6 +741,8 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
         b_fn: String,
         b_args: serde_json::Value,
         b_options: Option<BamlOptions>,
+        caller: Caller,
+        admission: Admission,
     ) -> Response {
         let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
 
@@ -445,11 +754,14 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
         let client_registry = b_options.and_then(|options| options.client_registry);
 
         tokio::spawn(async move {
+            let _admission = admission;
             let ctx_mgr =
                 RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
+            ctx_mgr.upsert_tags(caller.tags());
 
+            let start = std::time::Instant::now();
             let result_stream = self.b.read().await.stream_function(
-                b_fn,
+                b_fn.clone(),
                 &args,
                 &ctx_mgr,
                 None,
@@ -458,12 +770,13 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
 
             match result_stream {
                 Ok(mut result_stream) => {
+                    let partials = sender.clone();
                     let (result, _trace_id) = result_stream
                         .run(
                             Some(move |result| {
                                 // If the receiver is closed (either because it called close or it was dropped),
                                 // we can't really do anything
-                                match sender.send(result) {
+                                match partials.send(StreamEvent::Partial(result)) {
                                     Ok(_) => (),
                                     Err(e) => {
                                         log::error!("Error sending result to receiver: {:?}", e);
@@ -476,65 +789,17 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
                         )
                         .await;
 
-                    match result {
-                        Ok(function_result) => match function_result.llm_response() {
-                            LLMResponse::Success(_) => match function_result.result_with_constraints_content() {
-                                // Just because the LLM returned 2xx doesn't mean that it returned parse-able content!
-                                Ok(parsed) => {
-                                    (StatusCode::OK, Json::<ResponseBamlValue>(parsed.clone()))
-                                        .into_response()
-                                }
-
-                                Err(e) => {
-                                    log::debug!("Error parsing content: {:?}", e);
-                                    if let Some(ExposedError::ValidationError {
-                                        prompt,
-                                        raw_output: raw_response,
-                                        message,
-                                    }) = e.downcast_ref::<ExposedError>()
-                                    {
-                                        BamlError::ValidationFailure {
-                                            message: message.clone(),
-                                            prompt: prompt.clone(),
-                                            raw_output: raw_response.clone(),
-                                        }
-                                        .into_response()
-                                    } else {
-                                        BamlError::InternalError {
-                                            message: format!("Error parsing: {:?}", e),
-                                        }
-                                        .into_response()
-                                    }
-                                }
-                            },
-                            LLMResponse::LLMFailure(failure) => {
-                                log::debug!("LLMResponse::LLMFailure: {:?}", failure);
-                                BamlError::ClientError {
-                                    message: format!("{:?}", failure.message),
-                                }
-                                .into_response()
-                            }
-                            LLMResponse::UserFailure(message) => BamlError::InvalidArgument {
-                                message: message.clone(),
-                            }
-                            .into_response(),
-                            LLMResponse::InternalFailure(message) => BamlError::InternalError {
-                                message: message.clone(),
-                            }
-                            .into_response(),
-                        },
-                        Err(e) => BamlError::from_anyhow(e).into_response(),
-                    }
+                    let _ = sender.send(StreamEvent::Final(self.finish_call(&b_fn, result, start)));
                 }
-                Err(e) => BamlError::InternalError {
-                    message: format!("Error starting stream: {:?}", e),
+                Err(e) => {
+                    let _ = sender.send(StreamEvent::Final(Err(BamlError::InternalError {
+                        message: format!("Error starting stream: {:?}", e),
+                    })));
                 }
-                .into_response(),
             }
         });
 
-        // TODO: streaming is broken. the above should return first.
-        let stream = Box::pin(EventStream { receiver }).map(|bv| Event::default().json_data(bv));
+        let stream = Box::pin(EventStream { receiver });
 
         Sse::new(stream)
             .keep_alive(KeepAlive::default())
@@ -545,21 +810,18 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
     async fn baml_stream_axum2(
         self: Arc<Self>,
         extract::Path(path): extract::Path<String>,
+        extract::Extension(caller): extract::Extension<Caller>,
         extract::Json(body): extract::Json<serde_json::Value>,
     ) -> Response {
-        let mut b_options = None;
-        if let Some(options_value) = body.get("__baml_options__") {
-            match serde_json::from_value::<BamlOptions>(options_value.clone()) {
-                Ok(opts) => b_options = Some(opts),
-                Err(_) => {
-                    return BamlError::InvalidArgument {
-                        message: "Failed to parse __baml_options__".to_string(),
-                    }
-                    .into_response()
-                }
-            }
-        }
-        self.baml_stream(path, body, b_options)
+        let admission = match self.admit(&caller, &path, Endpoint::Stream) {
+            Ok(admission) => admission,
+            Err(e) => return e.into_response(),
+        };
+        let b_options = match parse_options(&body) {
+            Ok(b_options) => b_options,
+            Err(e) => return e.into_response(),
+        };
+        self.baml_stream(path, body, b_options, caller, admission)
     }
 
     /// Serve an HTML page that loads swagger-ui from local static files.
@@ -633,29 +895,61 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
     }
 }
 
+/// What a `/stream` request produces: partial results while the function
+/// runs, then its outcome.
+enum StreamEvent {
+    Partial(FunctionResult),
+    Final(Result<ResponseBamlValue, BamlError>),
+}
+
+/// Sends partial results as unnamed events, then the outcome as a `final`
+/// event with the parsed result, or an `error` event.
 struct EventStream {
-    receiver: tokio::sync::mpsc::UnboundedReceiver<FunctionResult>,
+    receiver: tokio::sync::mpsc::UnboundedReceiver<StreamEvent>,
 }
 
 impl Stream for EventStream {
-    type Item = BamlValue;
+    type Item = Result<Event, axum::Error>;
 
     fn poll_next(
         mut self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        match self.receiver.poll_recv(cx) {
-            Poll::Ready(Some(item)) => match item.result_with_constraints_content() {
-                // TODO: not sure if this is the correct way to implement this.
-                Ok(parsed) => Poll::Ready(Some(parsed.into())),
-                Err(_) => Poll::Pending,
-            },
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+        loop {
+            let event = match self.receiver.poll_recv(cx) {
+                Poll::Ready(Some(StreamEvent::Partial(partial))) => {
+                    match partial.result_with_constraints_content() {
+                        Ok(parsed) => Event::default().json_data(BamlValue::from(parsed)),
+                        // Partials that can't be parsed yet are skipped.
+                        Err(_) => continue,
+                    }
+                }
+                Poll::Ready(Some(StreamEvent::Final(Ok(parsed)))) => {
+                    Event::default().event("final").json_data(parsed)
+                }
+                Poll::Ready(Some(StreamEvent::Final(Err(e)))) => {
+                    Event::default().event("error").json_data(e)
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            return Poll::Ready(Some(event));
         }
     }
 }
 
+/// Parses the `__baml_options__` passed along with the arguments, if any.
+fn parse_options(b_args: &serde_json::Value) -> Result<Option<BamlOptions>, BamlError> {
+    match b_args.get("__baml_options__") {
+        Some(options_value) => serde_json::from_value::<BamlOptions>(options_value.clone())
+            .map(Some)
+            .map_err(|_| BamlError::InvalidArgument {
+                message: "Failed to parse __baml_options__".to_string(),
+            }),
+        None => Ok(None),
+    }
+}
+
 fn parse_args(
     b_fn: &str,
     b_args: serde_json::Value,
@@ -706,3 +1000,27 @@ fn parse_args(
 
     Ok(args)
 }
+
+/// A server for `baml_src` with `main.baml` in it, for tests that go through
+/// the endpoints' handlers.
+#[cfg(test)]
+pub(super) async fn test_server(test: &str, main_baml: &str, api_keys: ApiKeys) -> Arc<Server> {
+    let src_dir = std::env::temp_dir()
+        .join(format!("baml_serve_test_{}_{}", test, std::process::id()))
+        .join("baml_src");
+    std::fs::create_dir_all(&src_dir).unwrap();
+    std::fs::write(src_dir.join("main.baml"), main_baml).unwrap();
+
+    let (server, _tcp_listener) = Server::new(
+        src_dir,
+        0,
+        DEFAULT_BATCH_CONCURRENCY,
+        Duration::ZERO,
+        true,
+        api_keys,
+        Limits::default(),
+    )
+    .await
+    .unwrap();
+    server
+}
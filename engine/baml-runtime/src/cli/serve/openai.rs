@@ -0,0 +1,940 @@
+//! An OpenAI-compatible `POST /v1/chat/completions`, so that tools which
+//! already speak OpenAI's protocol can call BAML functions: `model` names the
+//! function, the last user message holds its arguments (or, for a function
+//! that takes a `message[]`, the whole conversation is its argument), and the
+//! assistant's message holds its result.
+//!
+//! `model` can also name a client, to send it the conversation as it is, or
+//! be `<function>/<client>` to call a function with another client.
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use baml_types::{BamlValue, BamlValueWithMeta, FieldType, TypeValue};
+use indexmap::IndexMap;
+use internal_baml_core::ir::IRHelper;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    client_registry::ClientRegistry,
+    internal::llm_client::{LLMResponse, ResponseBamlValue},
+    BamlRuntime, FunctionResult, RuntimeContextManager,
+};
+
+use super::{
+    api_keys::{Caller, Endpoint},
+    error::BamlError,
+    json_response::Json,
+    limits::Admission,
+    parse_args, Server,
+};
+
+/// The function a conversation is sent through when `model` names a client.
+const CLIENT_CHAT_FUNCTION: &str = "BamlServeClientChat";
+
+/// The `baml_src` a runtime was loaded from. The first time `model` names a
+/// client, a runtime with [`CLIENT_CHAT_FUNCTION`] added to it is compiled
+/// from these files, so that the conversation goes through the same clients,
+/// retry policies and limits as function calls do.
+pub(super) struct ClientChat {
+    src_dir: PathBuf,
+    files: HashMap<String, String>,
+    runtime: tokio::sync::OnceCell<Arc<BamlRuntime>>,
+}
+
+impl ClientChat {
+    pub(super) fn new(src_dir: PathBuf, files: HashMap<String, String>) -> Self {
+        Self {
+            src_dir,
+            files,
+            runtime: Default::default(),
+        }
+    }
+
+    /// The runtime with [`CLIENT_CHAT_FUNCTION`]. Its own client is `client`,
+    /// but every call sets the client with a [`ClientRegistry`] anyway.
+    async fn runtime(&self, client: &str) -> Result<Arc<BamlRuntime>, BamlError> {
+        self.runtime
+            .get_or_try_init(|| {
+                let mut files = self.files.clone();
+                files.insert(
+                    self.src_dir
+                        .join("__baml_serve_client_chat.baml")
+                        .to_string_lossy()
+                        .into_owned(),
+                    format!(
+                        r##"
+function {CLIENT_CHAT_FUNCTION}(messages: message[]) -> string {{
+  client {client}
+  prompt #"{{{{ messages }}}}"#
+}}
+"##
+                    ),
+                );
+                let src_dir = self.src_dir.to_string_lossy().into_owned();
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        BamlRuntime::from_file_content(&src_dir, &files, std::env::vars().collect())
+                    })
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|runtime| runtime)
+                    .map(Arc::new)
+                    .map_err(|e| BamlError::InternalError {
+                        message: format!("Error loading a runtime to chat with clients: {e:?}"),
+                    })
+                }
+            })
+            .await
+            .cloned()
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct ChatCompletionRequest {
+    /// The function to call, optionally followed by `/` and the name of the
+    /// client to call it with instead of its own, or a client to send the
+    /// messages to as they are.
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+    stream_options: Option<StreamOptions>,
+}
+
+#[derive(Deserialize)]
+struct StreamOptions {
+    #[serde(default)]
+    include_usage: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: Option<MessageContent>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: ImageUrl,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct ImageUrl {
+    url: String,
+}
+
+impl ChatMessage {
+    fn text(&self) -> Result<String, BamlError> {
+        match &self.content {
+            None => Ok(String::new()),
+            Some(MessageContent::Text(text)) => Ok(text.clone()),
+            Some(MessageContent::Parts(parts)) => parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text { text } => Ok(text.as_str()),
+                    ContentPart::ImageUrl { .. } | ContentPart::Other => {
+                        Err(BamlError::InvalidArgument {
+                            message: "Only text content is supported in messages".to_string(),
+                        })
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|texts| texts.concat()),
+        }
+    }
+
+    /// The message as a BAML `message`, with its images as `image` parts.
+    fn to_baml_message(&self) -> Result<serde_json::Value, BamlError> {
+        let content = match &self.content {
+            None => serde_json::Value::String(String::new()),
+            Some(MessageContent::Text(text)) => serde_json::Value::String(text.clone()),
+            Some(MessageContent::Parts(parts)) => parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text { text } => Ok(serde_json::Value::String(text.clone())),
+                    ContentPart::ImageUrl { image_url } => {
+                        Ok(serde_json::json!({ "image": { "url": image_url.url } }))
+                    }
+                    ContentPart::Other => Err(BamlError::InvalidArgument {
+                        message: "Only text and image content is supported in messages".to_string(),
+                    }),
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(serde_json::json!({ "role": self.role, "content": content }))
+    }
+}
+
+/// What every response to one request has in common.
+#[derive(Serialize)]
+struct Completion {
+    id: String,
+    created: u64,
+    model: String,
+}
+
+impl Completion {
+    fn new(model: &str) -> Self {
+        Self {
+            id: format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            model: model.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ChatCompletion<'a> {
+    #[serde(flatten)]
+    completion: &'a Completion,
+    object: &'static str,
+    choices: [Choice; 1],
+    usage: Usage,
+}
+
+#[derive(Serialize)]
+struct Choice {
+    index: u32,
+    message: AssistantMessage,
+    finish_reason: &'static str,
+}
+
+#[derive(Serialize)]
+struct AssistantMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct ChatCompletionChunk<'a> {
+    #[serde(flatten)]
+    completion: &'a Completion,
+    object: &'static str,
+    /// Empty in the chunk that only carries `usage`.
+    choices: Vec<ChunkChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+}
+
+#[derive(Serialize)]
+struct ChunkChoice {
+    index: u32,
+    delta: Delta,
+    finish_reason: Option<&'static str>,
+}
+
+#[derive(Default, Serialize)]
+struct Delta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Usage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    total_tokens: u64,
+}
+
+impl Usage {
+    /// Adds up the tokens of every LLM request made for `result`, including
+    /// retries and fallbacks.
+    fn of(result: &FunctionResult) -> Self {
+        let mut usage = Self::default();
+        for (_, response, _, _) in result.event_chain() {
+            if let LLMResponse::Success(s) = response {
+                usage.prompt_tokens += s.metadata.prompt_tokens.unwrap_or(0);
+                usage.completion_tokens += s.metadata.output_tokens.unwrap_or(0);
+            }
+        }
+        usage.total_tokens = usage.prompt_tokens + usage.completion_tokens;
+        usage
+    }
+}
+
+/// An error in the shape OpenAI's clients expect, with the kind of
+/// [`BamlError`] as its `type`.
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorObject,
+}
+
+#[derive(Serialize)]
+struct ErrorObject {
+    message: String,
+    #[serde(rename = "type")]
+    kind: String,
+    code: Option<String>,
+}
+
+impl From<&BamlError> for ErrorBody {
+    fn from(e: &BamlError) -> Self {
+        let value = serde_json::to_value(e).unwrap_or_default();
+        let field = |name: &str| value[name].as_str().unwrap_or_default().to_string();
+        Self {
+            error: ErrorObject {
+                message: field("message"),
+                kind: field("error"),
+                code: None,
+            },
+        }
+    }
+}
+
+fn error_response(e: BamlError) -> Response {
+    let mut response = (e.status_code(), Json(ErrorBody::from(&e))).into_response();
+    e.add_headers(&mut response);
+    response
+}
+
+/// The message content for a function's result: strings as they are, and
+/// anything else as JSON.
+fn content(value: &ResponseBamlValue) -> Result<String, BamlError> {
+    match value {
+        BamlValueWithMeta::String(text, checks) if checks.is_empty() => Ok(text.clone()),
+        value => serde_json::to_string(value).map_err(|e| BamlError::InternalError {
+            message: format!("Error serializing result: {:?}", e),
+        }),
+    }
+}
+
+fn is_string(field_type: &FieldType) -> bool {
+    match field_type {
+        FieldType::Primitive(TypeValue::String) => true,
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => is_string(inner),
+        _ => false,
+    }
+}
+
+/// Whether `field_type` is `message[]`, which takes the whole conversation.
+fn is_message_list(field_type: &FieldType) -> bool {
+    match field_type {
+        FieldType::List(item) => matches!(**item, FieldType::Primitive(TypeValue::Message)),
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => {
+            is_message_list(inner)
+        }
+        _ => false,
+    }
+}
+
+/// Maps `messages` onto the arguments of `function`, which takes `params`. A
+/// function whose only argument is a `message[]` gets every message.
+/// Otherwise, the last user message holds the arguments: either as a JSON
+/// object of the arguments by name or, if the function takes one argument,
+/// as that argument.
+fn messages_to_args(
+    function: &str,
+    params: &[(String, FieldType)],
+    messages: &[ChatMessage],
+) -> Result<IndexMap<String, BamlValue>, BamlError> {
+    if let [(name, field_type)] = params {
+        if is_message_list(field_type) {
+            let messages = messages
+                .iter()
+                .map(ChatMessage::to_baml_message)
+                .collect::<Result<_, _>>()?;
+            return parse_args(
+                function,
+                serde_json::Value::Object(serde_json::Map::from_iter([(
+                    name.clone(),
+                    serde_json::Value::Array(messages),
+                )])),
+            );
+        }
+    }
+
+    let text = messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .ok_or_else(|| BamlError::InvalidArgument {
+            message: "messages must include a user message".to_string(),
+        })?
+        .text()?;
+
+    let args = match (params, serde_json::from_str::<serde_json::Value>(&text)) {
+        ([], _) => serde_json::Map::new(),
+        (_, Ok(serde_json::Value::Object(args)))
+            if args.keys().all(|k| params.iter().any(|(name, _)| name == k)) =>
+        {
+            args
+        }
+        ([(name, field_type)], parsed) => {
+            let value = match parsed {
+                Ok(value) if !is_string(field_type) => value,
+                _ => serde_json::Value::String(text),
+            };
+            serde_json::Map::from_iter([(name.clone(), value)])
+        }
+        _ => {
+            return Err(BamlError::InvalidArgument {
+                message: format!(
+                    "BAML function {function} takes {} arguments; the last user message must be a JSON object of them, by name",
+                    params.len()
+                ),
+            })
+        }
+    };
+    parse_args(function, serde_json::Value::Object(args))
+}
+
+/// Sends a streamed completion as OpenAI's chunks, whose contents the client
+/// concatenates. String results are sent as they're generated; anything else
+/// is sent in one chunk once it's complete, since partial JSON isn't a prefix
+/// of the final JSON.
+struct ChunkWriter {
+    completion: Completion,
+    /// The content sent so far.
+    sent: Mutex<String>,
+    sender: UnboundedSender<Event>,
+}
+
+impl ChunkWriter {
+    fn send(&self, choices: Vec<ChunkChoice>, usage: Option<Usage>) {
+        let chunk = ChatCompletionChunk {
+            completion: &self.completion,
+            object: "chat.completion.chunk",
+            choices,
+            usage,
+        };
+        match Event::default().json_data(chunk) {
+            // If the client has gone away, there's no one to send it to.
+            Ok(event) => {
+                let _ = self.sender.send(event);
+            }
+            Err(e) => log::error!("Error serializing chunk: {:?}", e),
+        }
+    }
+
+    fn send_delta(&self, delta: Delta, finish_reason: Option<&'static str>) {
+        self.send(
+            vec![ChunkChoice {
+                index: 0,
+                delta,
+                finish_reason,
+            }],
+            None,
+        );
+    }
+
+    fn start(&self) {
+        self.send_delta(
+            Delta {
+                role: Some("assistant"),
+                content: Some(String::new()),
+            },
+            None,
+        );
+    }
+
+    /// Sends whatever `content` adds to what has been sent. Returns false if
+    /// `content` doesn't start with what has been sent.
+    fn send_content(&self, content: &str) -> bool {
+        let mut sent = self.sent.lock().unwrap();
+        let Some(delta) = content.strip_prefix(sent.as_str()) else {
+            return false;
+        };
+        if !delta.is_empty() {
+            self.send_delta(
+                Delta {
+                    role: None,
+                    content: Some(delta.to_string()),
+                },
+                None,
+            );
+            sent.push_str(delta);
+        }
+        true
+    }
+
+    fn partial(&self, result: &FunctionResult) {
+        if let Ok(BamlValueWithMeta::String(text, checks)) =
+            result.result_with_constraints_content()
+        {
+            if checks.is_empty() {
+                self.send_content(text);
+            }
+        }
+    }
+
+    fn finish(&self, result: Result<String, BamlError>, usage: Option<Usage>) {
+        match result {
+            Ok(content) => {
+                if !self.send_content(&content) {
+                    log::warn!(
+                        "Streamed content for {} doesn't match its final result",
+                        self.completion.id
+                    );
+                }
+                self.send_delta(Delta::default(), Some("stop"));
+                if usage.is_some() {
+                    self.send(vec![], usage);
+                }
+            }
+            Err(e) => match Event::default().json_data(ErrorBody::from(&e)) {
+                Ok(event) => {
+                    let _ = self.sender.send(event);
+                }
+                Err(e) => log::error!("Error serializing error: {:?}", e),
+            },
+        }
+        let _ = self.sender.send(Event::default().data("[DONE]"));
+    }
+}
+
+impl Server {
+    pub(super) async fn chat_completions_axum(
+        self: Arc<Self>,
+        extract::Extension(caller): extract::Extension<Caller>,
+        extract::Json(request): extract::Json<ChatCompletionRequest>,
+    ) -> Response {
+        let (function, client) = match request.model.split_once('/') {
+            Some((function, client)) => (function, Some(client)),
+            None => (request.model.as_str(), None),
+        };
+        let endpoint = if request.stream {
+            Endpoint::Stream
+        } else {
+            Endpoint::Call
+        };
+        let admission = match self.admit(&caller, function, endpoint) {
+            Ok(admission) => admission,
+            Err(e) => return error_response(e),
+        };
+
+        let (mut runtime, client_chat) = {
+            let runtime = self.b.read().await;
+            (runtime.clone(), self.client_chat.read().unwrap().clone())
+        };
+        let resolved = match resolve_request(&runtime, function, client, &request.messages) {
+            Ok(resolved) => resolved,
+            Err(e) => return error_response(e),
+        };
+        let (function, args, client_registry) = match resolved {
+            Resolved::Function {
+                args,
+                client_registry,
+            } => (function, args, client_registry),
+            Resolved::Client {
+                args,
+                client_registry,
+            } => match client_chat.runtime(function).await {
+                Ok(client_runtime) => {
+                    runtime = client_runtime;
+                    (CLIENT_CHAT_FUNCTION, args, Some(client_registry))
+                }
+                Err(e) => return error_response(e),
+            },
+        };
+
+        let completion = Completion::new(&request.model);
+        if request.stream {
+            let include_usage = request.stream_options.is_some_and(|o| o.include_usage);
+            return self.chat_completion_stream(
+                runtime,
+                completion,
+                function.to_string(),
+                args,
+                client_registry,
+                caller,
+                admission,
+                include_usage,
+            );
+        }
+
+        let ctx_mgr = RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
+        ctx_mgr.upsert_tags(caller.tags());
+
+        let start = Instant::now();
+        let (result, _trace_id) = runtime
+            .call_function(
+                function.to_string(),
+                &args,
+                &ctx_mgr,
+                None,
+                client_registry.as_ref(),
+            )
+            .await;
+        let usage = result.as_ref().map(Usage::of).unwrap_or_default();
+
+        match self
+            .finish_call(function, result, start)
+            .and_then(|value| content(&value))
+        {
+            Ok(content) => Json(ChatCompletion {
+                completion: &completion,
+                object: "chat.completion",
+                choices: [Choice {
+                    index: 0,
+                    message: AssistantMessage {
+                        role: "assistant",
+                        content,
+                    },
+                    finish_reason: "stop",
+                }],
+                usage,
+            })
+            .into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn chat_completion_stream(
+        self: Arc<Self>,
+        runtime: Arc<BamlRuntime>,
+        completion: Completion,
+        function: String,
+        args: IndexMap<String, BamlValue>,
+        client_registry: Option<ClientRegistry>,
+        caller: Caller,
+        admission: Admission,
+        include_usage: bool,
+    ) -> Response {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let chunks = Arc::new(ChunkWriter {
+            completion,
+            sent: Default::default(),
+            sender,
+        });
+        chunks.start();
+
+        tokio::spawn(async move {
+            let _admission = admission;
+            let ctx_mgr =
+                RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
+            ctx_mgr.upsert_tags(caller.tags());
+
+            let start = Instant::now();
+            let result = match runtime.stream_function(
+                function.clone(),
+                &args,
+                &ctx_mgr,
+                None,
+                client_registry.as_ref(),
+            ) {
+                Ok(mut result_stream) => {
+                    let partials = chunks.clone();
+                    let on_event = move |partial: FunctionResult| partials.partial(&partial);
+                    result_stream
+                        .run(Some(on_event), &ctx_mgr, None, None)
+                        .await
+                        .0
+                }
+                Err(e) => Err(e),
+            };
+            let usage = result.as_ref().map(Usage::of).unwrap_or_default();
+
+            chunks.finish(
+                self.finish_call(&function, result, start)
+                    .and_then(|value| content(&value)),
+                include_usage.then_some(usage),
+            );
+        });
+
+        let events = futures::stream::poll_fn(move |cx| {
+            receiver
+                .poll_recv(cx)
+                .map(|event| event.map(Ok::<_, Infallible>))
+        });
+        Sse::new(events)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    }
+}
+
+/// What a request's `model` names.
+enum Resolved {
+    /// A function, called with its own client or the one in `client_registry`.
+    Function {
+        args: IndexMap<String, BamlValue>,
+        client_registry: Option<ClientRegistry>,
+    },
+    /// A client, to send the messages in `args` to through
+    /// [`CLIENT_CHAT_FUNCTION`].
+    Client {
+        args: IndexMap<String, BamlValue>,
+        client_registry: ClientRegistry,
+    },
+}
+
+/// Finds the function, and the client if any, that `model` names, and the
+/// function's arguments in `messages`. A `model` that names a client instead
+/// gets every message.
+fn resolve_request(
+    runtime: &BamlRuntime,
+    function: &str,
+    client: Option<&str>,
+    messages: &[ChatMessage],
+) -> Result<Resolved, BamlError> {
+    let ir = runtime.inner.ir.as_ref();
+    let walker = match ir.find_function(function) {
+        Ok(walker) => walker,
+        Err(_) if client.is_none() && ir.find_client(function).is_ok() => {
+            let mut client_registry = ClientRegistry::new();
+            client_registry.set_primary(function.to_string());
+            let messages_param = [(
+                "messages".to_string(),
+                FieldType::List(Box::new(FieldType::Primitive(TypeValue::Message))),
+            )];
+            return Ok(Resolved::Client {
+                args: messages_to_args(CLIENT_CHAT_FUNCTION, &messages_param, messages)?,
+                client_registry,
+            });
+        }
+        Err(_) => {
+            return Err(BamlError::NotFound {
+                message: format!(
+                    "model must name a BAML function or client; there is no function or client {function}"
+                ),
+            })
+        }
+    };
+    let client_registry = match client {
+        Some(client) => {
+            ir.find_client(client).map_err(|_| BamlError::NotFound {
+                message: format!("There is no client {client}"),
+            })?;
+            let mut client_registry = ClientRegistry::new();
+            client_registry.set_primary(client.to_string());
+            Some(client_registry)
+        }
+        None => None,
+    };
+    let args = messages_to_args(function, walker.inputs(), messages)?;
+    Ok(Resolved::Function {
+        args,
+        client_registry,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_messages(json: serde_json::Value) -> Vec<ChatMessage> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn string() -> FieldType {
+        FieldType::Primitive(TypeValue::String)
+    }
+
+    #[test]
+    fn maps_last_user_message_onto_single_argument() {
+        let messages = parse_messages(serde_json::json!([
+            { "role": "system", "content": "ignored" },
+            { "role": "user", "content": "first" },
+            { "role": "assistant", "content": "ignored" },
+            { "role": "user", "content": [
+                { "type": "text", "text": "Hello, " },
+                { "type": "text", "text": "world" }
+            ] }
+        ]));
+        let args = messages_to_args("Greet", &[("text".to_string(), string())], &messages);
+        assert_eq!(
+            args.unwrap()["text"],
+            BamlValue::String("Hello, world".to_string())
+        );
+    }
+
+    #[test]
+    fn string_argument_is_not_parsed_as_json() {
+        let messages = parse_messages(serde_json::json!([{ "role": "user", "content": "42" }]));
+        let args = messages_to_args("Echo", &[("text".to_string(), string())], &messages);
+        assert_eq!(args.unwrap()["text"], BamlValue::String("42".to_string()));
+
+        let int = FieldType::Primitive(TypeValue::Int);
+        let args = messages_to_args("Double", &[("n".to_string(), int)], &messages);
+        assert_eq!(args.unwrap()["n"], BamlValue::Int(42));
+    }
+
+    #[test]
+    fn maps_json_object_onto_arguments_by_name() {
+        let params = [("a".to_string(), string()), ("b".to_string(), string())];
+        let messages = parse_messages(serde_json::json!([
+            { "role": "user", "content": r#"{ "a": "x", "b": "y" }"# }
+        ]));
+        let args = messages_to_args("Join", &params, &messages).unwrap();
+        assert_eq!(args["a"], BamlValue::String("x".to_string()));
+        assert_eq!(args["b"], BamlValue::String("y".to_string()));
+
+        let messages = parse_messages(serde_json::json!([{ "role": "user", "content": "x y" }]));
+        assert!(matches!(
+            messages_to_args("Join", &params, &messages),
+            Err(BamlError::InvalidArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn maps_whole_conversation_onto_message_list() {
+        let messages = parse_messages(serde_json::json!([
+            { "role": "system", "content": "Be brief." },
+            { "role": "user", "content": "Hi" },
+            { "role": "assistant", "content": "Hello!" },
+            { "role": "user", "content": [
+                { "type": "text", "text": "What's this?" },
+                { "type": "image_url", "image_url": { "url": "https://example.com/a.png" } }
+            ] }
+        ]));
+        let message_list = FieldType::List(Box::new(FieldType::Primitive(TypeValue::Message)));
+        let args = messages_to_args(
+            "Chat",
+            &[("conversation".to_string(), message_list)],
+            &messages,
+        )
+        .unwrap();
+        let BamlValue::List(conversation) = &args["conversation"] else {
+            panic!(
+                "Expected a list of messages, got {:?}",
+                args["conversation"]
+            );
+        };
+        assert_eq!(conversation.len(), 4);
+        assert_eq!(
+            conversation[0],
+            BamlValue::Map(IndexMap::from_iter([
+                ("role".to_string(), BamlValue::String("system".to_string())),
+                (
+                    "content".to_string(),
+                    BamlValue::String("Be brief.".to_string())
+                ),
+            ]))
+        );
+        let BamlValue::Map(last) = &conversation[3] else {
+            panic!("Expected a message, got {:?}", conversation[3]);
+        };
+        assert!(matches!(&last["content"], BamlValue::List(parts) if parts.len() == 2));
+    }
+
+    #[test]
+    fn rejects_non_text_content() {
+        let messages = parse_messages(serde_json::json!([{ "role": "user", "content": [
+            { "type": "image_url", "image_url": { "url": "https://example.com/a.png" } }
+        ] }]));
+        assert!(matches!(
+            messages_to_args("Describe", &[("text".to_string(), string())], &messages),
+            Err(BamlError::InvalidArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn content_is_json_unless_a_string() {
+        assert_eq!(
+            content(&BamlValueWithMeta::String("hi".to_string(), vec![])).unwrap(),
+            "hi"
+        );
+        assert_eq!(
+            content(&BamlValueWithMeta::List(
+                vec![BamlValueWithMeta::Int(1, vec![])],
+                vec![]
+            ))
+            .unwrap(),
+            "[1]"
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_the_conversation_to_a_client() {
+        let server = super::super::test_server(
+            "openai_client_chat",
+            r##"
+client<llm> Echo {
+  provider mock
+  options {
+    template #"{{ prompt }}"#
+  }
+}
+
+function Shout(text: string) -> string {
+  client Echo
+  prompt #"{{ text | upper }}"#
+}
+"##,
+            Default::default(),
+        )
+        .await;
+        let complete = |model: &str| {
+            let request = serde_json::from_value(serde_json::json!({
+                "model": model,
+                "messages": [
+                    { "role": "system", "content": "Be brief." },
+                    { "role": "user", "content": "Hi" }
+                ]
+            }))
+            .unwrap();
+            server.clone().chat_completions_axum(
+                extract::Extension(Caller::default()),
+                extract::Json(request),
+            )
+        };
+        let content = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            body["choices"][0]["message"]["content"].clone()
+        };
+
+        assert_eq!(content(complete("Echo").await).await, "Be brief.\nHi");
+        // Functions still take precedence, with the last user message.
+        assert_eq!(content(complete("Shout").await).await, "HI");
+
+        let response = complete("Nobody").await;
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn streams_only_what_extends_the_sent_content() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let chunks = ChunkWriter {
+            completion: Completion::new("Greet"),
+            sent: Default::default(),
+            sender,
+        };
+        assert!(chunks.send_content("Hel"));
+        assert!(chunks.send_content("Hello"));
+        assert!(chunks.send_content("Hello"));
+        assert!(!chunks.send_content("Goodbye"));
+        assert_eq!(*chunks.sent.lock().unwrap(), "Hello");
+
+        let mut sent = 0;
+        while receiver.try_recv().is_ok() {
+            sent += 1;
+        }
+        assert_eq!(sent, 2);
+    }
+}
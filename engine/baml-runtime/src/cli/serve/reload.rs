@@ -0,0 +1,226 @@
+//! Picking up changes to `baml_src` without restarting the server, either on
+//! `POST /_admin/reload` (with `--admin-reload`) or, with `--watch`, whenever
+//! a file changes.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use axum::{
+    extract,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode, Watcher},
+    DebounceEventResult, Debouncer, FileIdMap,
+};
+use serde::Serialize;
+
+use crate::{BamlRuntime, DiagnosticsError, InternalRuntimeInterface};
+
+use super::{api_keys::Caller, error::BamlError, json_response::Json, Server};
+
+#[derive(Serialize)]
+struct ReloadResponse {
+    reloaded: bool,
+    /// Errors that kept `baml_src` from being reloaded, or warnings if it was.
+    diagnostics: String,
+}
+
+impl Server {
+    /// Re-parses `baml_src` and, if it has no errors, swaps in the new runtime.
+    ///
+    /// Requests that are already running keep the runtime they started with;
+    /// only requests that start after the swap use the new one.
+    pub(crate) async fn reload(&self) -> Result<Arc<BamlRuntime>> {
+        // Reloads that overlap must swap in the order they read `baml_src`,
+        // or an older runtime could replace a newer one.
+        let _reloading = self.reloading.lock().await;
+
+        // Compiling `baml_src` can take a while, so it's kept off the threads
+        // that serve requests.
+        let src_dir = self.src_dir.clone();
+        let (runtime, client_chat) =
+            tokio::task::spawn_blocking(move || super::load_runtime(&src_dir)).await??;
+        let runtime = Arc::new(runtime);
+        self.metrics.set_functions(runtime.function_names());
+        let grpc_schema = super::grpc::build_schema(&runtime);
+        let mut current = self.b.write().await;
+        *self.grpc_schema.write().unwrap() = grpc_schema;
+        *self.client_chat.write().unwrap() = Arc::new(client_chat);
+        *current = runtime.clone();
+        Ok(runtime)
+    }
+
+    pub(super) async fn reload_axum(
+        self: Arc<Self>,
+        extract::Extension(caller): extract::Extension<Caller>,
+    ) -> Response {
+        if !caller.is_unrestricted() {
+            return BamlError::Forbidden {
+                message: format!(
+                    "API key {} may not reload baml_src",
+                    caller.key_name().unwrap_or_default()
+                ),
+            }
+            .into_response();
+        }
+        match self.reload().await {
+            Ok(runtime) => {
+                log::info!("Reloaded {}", self.src_dir.display());
+                (
+                    StatusCode::OK,
+                    Json(ReloadResponse {
+                        reloaded: true,
+                        diagnostics: runtime.inner.diagnostics().warnings_to_pretty_string(),
+                    }),
+                )
+                    .into_response()
+            }
+            Err(e) => match e.downcast_ref::<DiagnosticsError>() {
+                Some(diagnostics) => {
+                    log::warn!("Not reloading, baml_src has errors:\n{}", diagnostics);
+                    (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        Json(ReloadResponse {
+                            reloaded: false,
+                            diagnostics: diagnostics.to_pretty_string(),
+                        }),
+                    )
+                        .into_response()
+                }
+                None => BamlError::from_anyhow(e).into_response(),
+            },
+        }
+    }
+
+    /// Reloads whenever a file in `baml_src` changes, for as long as the
+    /// returned watcher is kept alive.
+    pub(super) fn watch(
+        self: Arc<Self>,
+        handle: tokio::runtime::Handle,
+    ) -> Result<Debouncer<RecommendedWatcher, FileIdMap>> {
+        let src_dir = self.src_dir.clone();
+        let mut debouncer = new_debouncer(
+            Duration::from_millis(200),
+            None,
+            move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    log::debug!(
+                        "Reloading - {}",
+                        match events.len() {
+                            1 => "1 file changed".to_string(),
+                            n => format!("{} files changed", n),
+                        }
+                    );
+                    match handle.block_on(self.reload()) {
+                        Ok(_) => log::info!("Reloaded {}", self.src_dir.display()),
+                        Err(e) => log::warn!("Failed to reload runtime: {:?}", e),
+                    }
+                }
+                Err(errors) => {
+                    log::warn!(
+                        "Encountered errors while watching {}: {:?}",
+                        self.src_dir.display(),
+                        errors
+                    );
+                }
+            },
+        )?;
+        debouncer
+            .watcher()
+            .watch(src_dir.as_path(), RecursiveMode::Recursive)?;
+        Ok(debouncer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{api_keys::ApiKeys, test_server};
+    use super::*;
+
+    const MAIN_BAML: &str = r##"
+client<llm> Echo {
+  provider mock
+  options {
+    response "hi"
+  }
+}
+
+function Greet(name: string) -> string {
+  client Echo
+  prompt #"Hello, {{ name }}"#
+}
+"##;
+
+    async fn reload(server: &Arc<Server>, caller: Caller) -> (StatusCode, serde_json::Value) {
+        let response = server.clone().reload_axum(extract::Extension(caller)).await;
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn function_names(server: &Server) -> Vec<String> {
+        server
+            .b
+            .read()
+            .await
+            .function_names()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn swaps_in_the_new_runtime() {
+        let server = test_server("reload_swaps", MAIN_BAML, Default::default()).await;
+        std::fs::write(
+            server.src_dir.join("main.baml"),
+            format!(
+                r##"{MAIN_BAML}
+function Wave(name: string) -> string {{
+  client Echo
+  prompt #"Wave at {{{{ name }}}}"#
+}}
+"##
+            ),
+        )
+        .unwrap();
+
+        let (status, body) = reload(&server, Caller::default()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reloaded"], true);
+        assert_eq!(function_names(&server).await, vec!["Greet", "Wave"]);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_old_runtime_on_errors() {
+        let server = test_server("reload_errors", MAIN_BAML, Default::default()).await;
+        std::fs::write(
+            server.src_dir.join("main.baml"),
+            format!("{MAIN_BAML}\nfunction Broken(\n"),
+        )
+        .unwrap();
+
+        let (status, body) = reload(&server, Caller::default()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["reloaded"], false);
+        assert!(!body["diagnostics"].as_str().unwrap().is_empty());
+        assert_eq!(function_names(&server).await, vec!["Greet"]);
+    }
+
+    #[tokio::test]
+    async fn forbids_restricted_keys() {
+        let api_keys = ApiKeys::parse(
+            r#"{ "keys": [{ "name": "app", "key": "sk-baml-app", "endpoints": ["call"] }] }"#,
+        )
+        .unwrap();
+        let caller = Caller(api_keys.find("sk-baml-app"));
+        let server = test_server("reload_forbidden", MAIN_BAML, api_keys).await;
+
+        let (status, _) = reload(&server, caller).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
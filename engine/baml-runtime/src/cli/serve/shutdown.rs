@@ -0,0 +1,37 @@
+//! Stopping the server when it's asked to, e.g. by Ctrl-C or by Kubernetes.
+
+/// How long in-flight requests get to finish once the server has been asked
+/// to shut down, unless `--shutdown-grace-period` says otherwise. This leaves
+/// time to flush traces within Kubernetes' default 30s termination grace
+/// period.
+pub(crate) const DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS: u64 = 25;
+
+/// Resolves once the process receives Ctrl-C (SIGINT) or SIGTERM.
+pub(super) async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("Failed to listen for Ctrl-C: {:?}", e);
+            std::future::pending::<()>().await
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                log::error!("Failed to listen for SIGTERM: {:?}", e);
+                std::future::pending::<()>().await
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
@@ -0,0 +1,302 @@
+//! Streaming over a WebSocket (`GET /ws`): a client can run many function
+//! streams over one connection, each under an id of its choosing, and cancel
+//! any of them before it finishes.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use axum::{
+    extract::{
+        self,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+};
+use baml_types::BamlValue;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::mpsc::UnboundedSender, task::AbortHandle};
+
+use crate::{internal::llm_client::ResponseBamlValue, FunctionResult, RuntimeContextManager};
+
+use super::{
+    api_keys::{Caller, Endpoint},
+    error::BamlError,
+    parse_args, parse_options, Server,
+};
+
+/// What the client sends, as JSON text messages.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ClientMessage {
+    /// Starts streaming `function`. `args` may include `__baml_options__`, as
+    /// with `/stream`.
+    Start {
+        id: String,
+        function: String,
+        #[serde(default)]
+        args: serde_json::Map<String, serde_json::Value>,
+    },
+    /// Stops the stream `id`, and the LLM request it's waiting on.
+    Cancel { id: String },
+}
+
+/// What the server sends, as JSON text messages.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Partial {
+        id: String,
+        value: BamlValue,
+    },
+    /// The last message for a stream that succeeded, with its check results.
+    Final {
+        id: String,
+        value: ResponseBamlValue,
+    },
+    /// The last message for a stream that failed. Without an `id` if the
+    /// message that caused it couldn't be read.
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(flatten)]
+        error: BamlError,
+    },
+    /// The last message for a stream that was cancelled.
+    Cancelled {
+        id: String,
+    },
+}
+
+/// The streams running on a connection, by id.
+type Streams = Arc<Mutex<HashMap<String, AbortHandle>>>;
+
+impl Server {
+    pub(super) async fn websocket_axum(
+        self: Arc<Self>,
+        extract::Extension(caller): extract::Extension<Caller>,
+        ws: WebSocketUpgrade,
+    ) -> Response {
+        ws.on_upgrade(move |socket| self.handle_websocket(socket, caller))
+    }
+
+    async fn handle_websocket(self: Arc<Self>, socket: WebSocket, caller: Caller) {
+        let (mut sink, mut incoming) = socket.split();
+
+        // Streams send their messages through this channel, so that each
+        // stream's messages arrive in the order it sent them.
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<ServerMessage>();
+        let writer = tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let text = match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        log::error!("Error serializing {:?}: {:?}", message, e);
+                        continue;
+                    }
+                };
+                if sink.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let streams: Streams = Default::default();
+        while let Some(Ok(message)) = incoming.next().await {
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                // axum answers pings for us.
+                Message::Binary(_) | Message::Ping(_) | Message::Pong(_) => continue,
+            };
+            match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(ClientMessage::Start { id, function, args }) => self
+                    .clone()
+                    .start_stream(id, function, args, &caller, &sender, &streams),
+                Ok(ClientMessage::Cancel { id }) => {
+                    let message = match streams.lock().unwrap().remove(&id) {
+                        Some(stream) => {
+                            stream.abort();
+                            ServerMessage::Cancelled { id }
+                        }
+                        None => ServerMessage::Error {
+                            error: BamlError::NotFound {
+                                message: format!("No stream {id} is running"),
+                            },
+                            id: Some(id),
+                        },
+                    };
+                    let _ = sender.send(message);
+                }
+                Err(e) => {
+                    let _ = sender.send(ServerMessage::Error {
+                        id: None,
+                        error: BamlError::InvalidArgument {
+                            message: format!("Invalid message: {}", e),
+                        },
+                    });
+                }
+            }
+        }
+
+        // The client has gone away, so no one is waiting for what's left.
+        for (_, stream) in streams.lock().unwrap().drain() {
+            stream.abort();
+        }
+        writer.abort();
+    }
+
+    fn start_stream(
+        self: Arc<Self>,
+        id: String,
+        function: String,
+        args: serde_json::Map<String, serde_json::Value>,
+        caller: &Caller,
+        sender: &UnboundedSender<ServerMessage>,
+        streams: &Streams,
+    ) {
+        // Hold the lock until the stream is recorded, so that it can't remove
+        // itself before then. A duplicate id is rejected before admission, so
+        // that it doesn't use up the caller's limits.
+        let mut running = streams.lock().unwrap();
+        if running.contains_key(&id) {
+            let _ = sender.send(ServerMessage::Error {
+                error: BamlError::InvalidArgument {
+                    message: format!("A stream with id {id} is already running"),
+                },
+                id: Some(id),
+            });
+            return;
+        }
+
+        let args = serde_json::Value::Object(args);
+        let prepared = self
+            .admit(caller, &function, Endpoint::Stream)
+            .and_then(|admission| {
+                let options = parse_options(&args)?;
+                Ok((admission, options, parse_args(&function, args)?))
+            });
+        let (admission, b_options, args) = match prepared {
+            Ok(prepared) => prepared,
+            Err(error) => {
+                let _ = sender.send(ServerMessage::Error {
+                    id: Some(id),
+                    error,
+                });
+                return;
+            }
+        };
+
+        let caller = caller.clone();
+        let sender = sender.clone();
+        let streams = streams.clone();
+        let stream_id = id.clone();
+        let task = tokio::spawn(async move {
+            let _admission = admission;
+            let ctx_mgr =
+                RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
+            ctx_mgr.upsert_tags(caller.tags());
+            let client_registry = b_options.and_then(|options| options.client_registry);
+
+            let runtime = self.b.read().await.clone();
+            let start = Instant::now();
+            let result = match runtime.stream_function(
+                function.clone(),
+                &args,
+                &ctx_mgr,
+                None,
+                client_registry.as_ref(),
+            ) {
+                Ok(mut result_stream) => {
+                    let partials = sender.clone();
+                    let partial_id = stream_id.clone();
+                    let on_event = move |partial: FunctionResult| {
+                        if let Ok(value) = partial.result_with_constraints_content() {
+                            let _ = partials.send(ServerMessage::Partial {
+                                id: partial_id.clone(),
+                                value: value.into(),
+                            });
+                        }
+                    };
+                    result_stream
+                        .run(Some(on_event), &ctx_mgr, None, None)
+                        .await
+                        .0
+                }
+                Err(e) => Err(e),
+            };
+
+            let message = match self.finish_call(&function, result, start) {
+                Ok(value) => ServerMessage::Final {
+                    id: stream_id.clone(),
+                    value,
+                },
+                Err(error) => ServerMessage::Error {
+                    id: Some(stream_id.clone()),
+                    error,
+                },
+            };
+            // Once the client hears that the stream is done, it may reuse its id.
+            streams.lock().unwrap().remove(&stream_id);
+            let _ = sender.send(message);
+        });
+        running.insert(id, task.abort_handle());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_client_messages() {
+        let start: ClientMessage = serde_json::from_str(
+            r#"{ "type": "start", "id": "1", "function": "Extract", "args": { "text": "hi" } }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            start,
+            ClientMessage::Start { id, function, args }
+                if id == "1" && function == "Extract" && args.contains_key("text")
+        ));
+
+        let cancel: ClientMessage =
+            serde_json::from_str(r#"{ "type": "cancel", "id": "1" }"#).unwrap();
+        assert!(matches!(cancel, ClientMessage::Cancel { id } if id == "1"));
+
+        assert!(
+            serde_json::from_str::<ClientMessage>(r#"{ "type": "pause", "id": "1" }"#).is_err()
+        );
+    }
+
+    #[test]
+    fn tags_server_messages_with_their_stream() {
+        let partial = ServerMessage::Partial {
+            id: "1".to_string(),
+            value: BamlValue::String("Hel".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&partial).unwrap(),
+            serde_json::json!({ "type": "partial", "id": "1", "value": "Hel" })
+        );
+
+        let error = ServerMessage::Error {
+            id: Some("1".to_string()),
+            error: BamlError::NotFound {
+                message: "No stream 1 is running".to_string(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "type": "error",
+                "id": "1",
+                "error": "not_found",
+                "message": "No stream 1 is running",
+            })
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use anyhow::{Context, Result};
+use baml_types::BamlValue;
+use colored::*;
+use futures::StreamExt;
+use std::path::PathBuf;
+use web_time::{Duration, Instant};
+
+use crate::{
+    internal::ir_features::WithInternal, runtime_interface::ExperimentalTracingInterface,
+    BamlRuntime, TestCaseReport, TestFailReason, TestReport, TestResponse, TestStatus,
+};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct TestArgs {
+    #[arg(long, help = "path/to/baml_src", default_value = "./baml_src")]
+    pub from: PathBuf,
+    #[arg(
+        long = "function",
+        short = 'f',
+        help = "only run tests for functions matching this glob (may be repeated)"
+    )]
+    functions: Vec<String>,
+    #[arg(
+        long = "test",
+        short = 't',
+        help = "only run tests whose name matches this glob (may be repeated)"
+    )]
+    tests: Vec<String>,
+    #[arg(
+        long,
+        help = "maximum number of tests to run at the same time",
+        default_value = "10"
+    )]
+    parallel: usize,
+    #[arg(long, help = "list the selected tests without running them")]
+    list: bool,
+    #[arg(long, help = "write a JUnit XML report of the results to this path")]
+    junit_xml: Option<PathBuf>,
+    #[arg(long, help = "write a JSON report of the results to this path")]
+    json_report: Option<PathBuf>,
+}
+
+/// A single `test` block selected for execution.
+#[derive(Clone, Debug)]
+pub(crate) struct TestCaseRef {
+    pub(crate) function_name: String,
+    pub(crate) test_name: String,
+}
+
+impl std::fmt::Display for TestCaseRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}", self.function_name, self.test_name)
+    }
+}
+
+/// The result of running a single test, along with how long it took.
+pub(crate) struct TestOutcome {
+    pub(crate) case: TestCaseRef,
+    pub(crate) elapsed: Duration,
+    pub(crate) response: Result<TestResponse>,
+}
+
+impl TestOutcome {
+    pub(crate) fn status(&self) -> TestStatus<'_> {
+        match &self.response {
+            Ok(response) => response.status(),
+            Err(e) => TestStatus::Fail(TestFailReason::TestUnspecified(e)),
+        }
+    }
+
+    pub(crate) fn passed(&self) -> bool {
+        matches!(self.status(), TestStatus::Pass)
+    }
+
+    pub(crate) fn report(&self) -> TestCaseReport {
+        TestCaseReport::new(
+            &self.case.function_name,
+            &self.case.test_name,
+            self.elapsed,
+            &self.response,
+        )
+    }
+}
+
+impl TestArgs {
+    pub fn run(&self) -> Result<()> {
+        let runtime = BamlRuntime::from_directory(&self.from, std::env::vars().collect())
+            .context("Failed to build BAML runtime")?;
+
+        let selected = self.select_tests(&runtime)?;
+        if selected.is_empty() {
+            log::warn!("No tests matched the given filters");
+            return Ok(());
+        }
+
+        if self.list {
+            for case in &selected {
+                println!("{case}");
+            }
+            return Ok(());
+        }
+
+        log::info!(
+            "Running {} with up to {} at a time",
+            match selected.len() {
+                1 => "1 test".to_string(),
+                n => format!("{n} tests"),
+            },
+            self.parallel.max(1)
+        );
+
+        let t = BamlRuntime::get_tokio_singleton()?;
+        let start = Instant::now();
+        let outcomes = t.block_on(run_tests(&runtime, selected, self.parallel));
+        let elapsed = start.elapsed();
+
+        if let Err(e) = runtime.flush() {
+            log::warn!("Failed to flush traces: {:?}", e);
+        }
+
+        print_summary(&outcomes);
+        self.write_reports(&outcomes, elapsed)?;
+
+        let failed = outcomes.iter().filter(|o| !o.passed()).count();
+        if failed > 0 {
+            anyhow::bail!("{failed} of {} tests failed", outcomes.len());
+        }
+
+        Ok(())
+    }
+
+    fn write_reports(&self, outcomes: &[TestOutcome], elapsed: Duration) -> Result<()> {
+        if self.junit_xml.is_none() && self.json_report.is_none() {
+            return Ok(());
+        }
+
+        let report = TestReport::new(outcomes.iter().map(TestOutcome::report).collect(), elapsed);
+        if let Some(path) = &self.junit_xml {
+            std::fs::write(path, report.to_junit_xml())
+                .with_context(|| format!("Failed to write JUnit report to {}", path.display()))?;
+            log::info!("Wrote JUnit report to {}", path.display());
+        }
+        if let Some(path) = &self.json_report {
+            std::fs::write(path, report.to_json()?)
+                .with_context(|| format!("Failed to write JSON report to {}", path.display()))?;
+            log::info!("Wrote JSON report to {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    fn select_tests(&self, runtime: &BamlRuntime) -> Result<Vec<TestCaseRef>> {
+        let function_patterns = compile_patterns(&self.functions)?;
+        let test_patterns = compile_patterns(&self.tests)?;
+
+        Ok(runtime
+            .inner
+            .walk_tests()
+            .map(|walker| TestCaseRef {
+                function_name: walker.item.0.elem.name().to_string(),
+                test_name: walker.item.1.elem.name.clone(),
+            })
+            .filter(|case| {
+                matches_any(&function_patterns, &case.function_name)
+                    && matches_any(&test_patterns, &case.test_name)
+            })
+            .collect())
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).with_context(|| format!("Invalid glob pattern: {p}")))
+        .collect()
+}
+
+/// An empty pattern list matches everything.
+fn matches_any(patterns: &[glob::Pattern], name: &str) -> bool {
+    patterns.is_empty() || patterns.iter().any(|p| p.matches(name))
+}
+
+pub(crate) async fn run_tests(
+    runtime: &BamlRuntime,
+    cases: Vec<TestCaseRef>,
+    parallel: usize,
+) -> Vec<TestOutcome> {
+    futures::stream::iter(cases)
+        .map(|case| run_one(runtime, case))
+        .buffered(parallel.max(1))
+        .collect()
+        .await
+}
+
+async fn run_one(runtime: &BamlRuntime, case: TestCaseRef) -> TestOutcome {
+    // Every test gets its own context manager: spans are tracked as a stack,
+    // so sharing one across concurrent tests would interleave them.
+    let ctx = runtime.create_ctx_manager(BamlValue::String("baml-cli".to_string()), None);
+
+    let start = Instant::now();
+    let (response, _span) = runtime
+        .run_test(
+            &case.function_name,
+            &case.test_name,
+            &ctx,
+            None::<fn(crate::FunctionResult)>,
+        )
+        .await;
+    let outcome = TestOutcome {
+        case,
+        elapsed: start.elapsed(),
+        response,
+    };
+
+    log_outcome(&outcome);
+    outcome
+}
+
+fn log_outcome(outcome: &TestOutcome) {
+    let elapsed = format!("({}ms)", outcome.elapsed.as_millis()).dimmed();
+    match outcome.status() {
+        TestStatus::Pass => log::info!("{} {} {}", "PASSED".green(), outcome.case, elapsed),
+        TestStatus::Fail(reason) => log::error!(
+            "{} {} {}\n{}",
+            "FAILED".red(),
+            outcome.case,
+            elapsed,
+            describe_failure(&reason)
+        ),
+    }
+}
+
+pub(crate) fn describe_failure(reason: &TestFailReason) -> String {
+    match reason {
+        TestFailReason::TestUnspecified(e) => format!("Unable to run test: {e:#}"),
+        TestFailReason::TestLLMFailure(response) => format!("LLM failure: {response}"),
+        TestFailReason::TestParseFailure(e) => format!("Failed to parse LLM response: {e:#}"),
+        TestFailReason::TestAssertFailure(failed) => failed.to_string(),
+    }
+}
+
+fn print_summary(outcomes: &[TestOutcome]) {
+    let passed = outcomes.iter().filter(|o| o.passed()).count();
+    let failed = outcomes.len() - passed;
+
+    if failed > 0 {
+        println!("\n{}", "Failed tests:".red());
+        for outcome in outcomes.iter().filter(|o| !o.passed()) {
+            println!("  {}", outcome.case);
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed",
+        passed.to_string().green(),
+        if failed > 0 {
+            failed.to_string().red()
+        } else {
+            failed.to_string().normal()
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BAML_SRC: &str = r##"
+client<llm> Echo {
+  provider mock
+  options {
+    template #"{{ last_message }}"#
+  }
+}
+
+function Greet(name: string) -> string {
+  client Echo
+  prompt #"Hello, {{ name }}"#
+}
+
+function Shout(name: string) -> string {
+  client Echo
+  prompt #"{{ name }}!"#
+}
+
+test GreetAlice {
+  functions [Greet]
+  args {
+    name "Alice"
+  }
+  @@assert({{ this == "Hello, Alice" }})
+}
+
+test GreetBob {
+  functions [Greet]
+  args {
+    name "Bob"
+  }
+  @@assert({{ this == "Hi, Bob" }})
+}
+
+test ShoutAlice {
+  functions [Shout]
+  args {
+    name "Alice"
+  }
+}
+"##;
+
+    fn args(from: PathBuf, functions: &[&str], tests: &[&str]) -> TestArgs {
+        TestArgs {
+            from,
+            functions: functions.iter().map(|f| f.to_string()).collect(),
+            tests: tests.iter().map(|t| t.to_string()).collect(),
+            parallel: 10,
+            list: false,
+            junit_xml: None,
+            json_report: None,
+        }
+    }
+
+    /// Writes [`BAML_SRC`] to a fresh `baml_src` directory, in a directory
+    /// named after `test`.
+    fn baml_src(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("baml_cli_test_{}_{}", test, std::process::id()))
+            .join("baml_src");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.baml"), BAML_SRC).unwrap();
+        dir
+    }
+
+    fn selected(functions: &[&str], tests: &[&str]) -> Vec<String> {
+        let runtime = BamlRuntime::from_file_content(
+            "baml_src",
+            &[("main.baml", BAML_SRC)].into(),
+            std::collections::HashMap::<&str, &str>::new(),
+        )
+        .unwrap();
+        args(PathBuf::new(), functions, tests)
+            .select_tests(&runtime)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn filters_tests_by_function_and_test_name() {
+        assert_eq!(
+            selected(&[], &[]),
+            ["Greet::GreetAlice", "Greet::GreetBob", "Shout::ShoutAlice"]
+        );
+        assert_eq!(
+            selected(&["Greet"], &[]),
+            ["Greet::GreetAlice", "Greet::GreetBob"]
+        );
+        assert_eq!(
+            selected(&[], &["*Alice"]),
+            ["Greet::GreetAlice", "Shout::ShoutAlice"]
+        );
+        // Function and test filters must both match.
+        assert_eq!(selected(&["Shout"], &["Greet*"]), Vec::<String>::new());
+        // Repeated filters match any of them.
+        assert_eq!(
+            selected(&["Shout", "G*"], &["*Bob", "ShoutAlice"]),
+            ["Greet::GreetBob", "Shout::ShoutAlice"]
+        );
+        assert!(compile_patterns(&["[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn fails_when_a_test_fails() {
+        let dir = baml_src("fails");
+        let all = args(dir.clone(), &[], &[]).run();
+        let passing = args(dir.clone(), &[], &["*Alice"]).run();
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        assert_eq!(all.unwrap_err().to_string(), "1 of 3 tests failed");
+        passing.unwrap();
+    }
+}
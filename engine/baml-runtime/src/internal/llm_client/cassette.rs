@@ -0,0 +1,594 @@
+//! Record/replay of LLM requests ("cassettes").
+//!
+//! When `BAML_CASSETTE_MODE` is set, every request made by a primitive client is
+//! keyed by a hash of everything that shapes the response: the client name,
+//! model, rendered prompt, request options, output mode and, unless the output
+//! mode is `prompt`, the output schema sent as a tool or response format.
+//! Responses are written to (or read from) the JSON file named by
+//! `BAML_CASSETTE_PATH`, which lets tests run deterministically without network
+//! access. The file is re-read whenever it changes on disk.
+//!
+//! Supported modes:
+//!
+//!   - `record`: always make the request, and overwrite the recorded response
+//!   - `replay`: never make a request; fail if no response was recorded
+//!   - `record_on_miss`: replay if a response was recorded, otherwise make the
+//!     request and record it
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use indexmap::IndexMap;
+use internal_baml_jinja::RenderedPrompt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    primitive::LLMPrimitiveProvider,
+    traits::{StreamResponse, WithClientProperties},
+    ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
+    OutputMode, OutputSchema,
+};
+use crate::RuntimeContext;
+
+pub const CASSETTE_MODE_ENV_VAR: &str = "BAML_CASSETTE_MODE";
+pub const CASSETTE_PATH_ENV_VAR: &str = "BAML_CASSETTE_PATH";
+const DEFAULT_CASSETTE_PATH: &str = "baml_cassette.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum CassetteMode {
+    Record,
+    Replay,
+    RecordOnMiss,
+}
+
+impl CassetteMode {
+    fn replays(&self) -> bool {
+        matches!(self, CassetteMode::Replay | CassetteMode::RecordOnMiss)
+    }
+
+    fn records(&self) -> bool {
+        matches!(self, CassetteMode::Record | CassetteMode::RecordOnMiss)
+    }
+}
+
+/// Cassette settings, as read from the env vars of a [`RuntimeContext`].
+#[derive(Debug, Clone)]
+pub struct CassetteConfig {
+    pub mode: CassetteMode,
+    pub path: PathBuf,
+}
+
+impl CassetteConfig {
+    pub fn from_env(env: &HashMap<String, String>) -> Result<Option<Self>> {
+        let mode = match env.get(CASSETTE_MODE_ENV_VAR).map(|m| m.trim()) {
+            None | Some("") | Some("off") => return Ok(None),
+            Some(mode) => mode.parse::<CassetteMode>().map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid {CASSETTE_MODE_ENV_VAR}={mode}. Expected one of: record, replay, record_on_miss, off"
+                )
+            })?,
+        };
+        let path = env
+            .get(CASSETTE_PATH_ENV_VAR)
+            .filter(|p| !p.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CASSETTE_PATH));
+
+        Ok(Some(Self { mode, path }))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CassetteEntry {
+    client: String,
+    model: Option<String>,
+    #[serde(flatten)]
+    outcome: RecordedOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+enum RecordedOutcome {
+    Success {
+        /// The response content, split into the chunks that were streamed.
+        /// Non-streaming calls record a single chunk.
+        chunks: Vec<String>,
+        baml_is_complete: bool,
+        finish_reason: Option<String>,
+        prompt_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+        total_tokens: Option<u64>,
+    },
+    Failure {
+        message: String,
+        code: u16,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    version: u32,
+    interactions: IndexMap<String, CassetteEntry>,
+}
+
+/// An on-disk cassette. Cassettes are shared process-wide, so that concurrent
+/// requests recording to the same file don't clobber each other.
+struct Cassette {
+    path: PathBuf,
+    interactions: Mutex<IndexMap<String, CassetteEntry>>,
+    /// The modification time and length of the file when it was last read or
+    /// written, to tell when something else changed it.
+    version: Mutex<Option<(SystemTime, u64)>>,
+    /// Held while writing the file.
+    writing: tokio::sync::Mutex<()>,
+}
+
+impl Cassette {
+    fn open(path: &Path) -> Result<Arc<Self>> {
+        static CASSETTES: OnceLock<Mutex<HashMap<PathBuf, Arc<Cassette>>>> = OnceLock::new();
+
+        let mut cassettes = CASSETTES.get_or_init(Default::default).lock().unwrap();
+        if let Some(cassette) = cassettes.get(path) {
+            cassette.reload_if_changed()?;
+            return Ok(cassette.clone());
+        }
+
+        let version = Self::file_version(path);
+        let cassette = Arc::new(Self {
+            path: path.to_path_buf(),
+            interactions: Mutex::new(Self::read(path)?),
+            version: Mutex::new(version),
+            writing: Default::default(),
+        });
+        cassettes.insert(path.to_path_buf(), cassette.clone());
+        Ok(cassette)
+    }
+
+    fn read(path: &Path) -> Result<IndexMap<String, CassetteEntry>> {
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+        Ok(serde_json::from_str::<CassetteFile>(&contents)
+            .with_context(|| format!("Failed to parse cassette {}", path.display()))?
+            .interactions)
+    }
+
+    fn file_version(path: &Path) -> Option<(SystemTime, u64)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Re-reads the file if it changed since it was last read or written, e.g.
+    /// because it was re-recorded by another process or edited by hand.
+    fn reload_if_changed(&self) -> Result<()> {
+        // A write in progress changes the file, but the interactions in
+        // memory are already the newest.
+        let Ok(_writing) = self.writing.try_lock() else {
+            return Ok(());
+        };
+        let mut version = self.version.lock().unwrap();
+        let current = Self::file_version(&self.path);
+        if current == *version {
+            return Ok(());
+        }
+        log::debug!("Reloading cassette {}", self.path.display());
+        *self.interactions.lock().unwrap() = Self::read(&self.path)?;
+        *version = current;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Option<CassetteEntry> {
+        self.interactions.lock().unwrap().get(key).cloned()
+    }
+
+    async fn insert(&self, key: String, entry: CassetteEntry) {
+        self.interactions.lock().unwrap().insert(key, entry);
+
+        // Writes take turns, and each writes every interaction recorded so
+        // far, so the file ends up with all of them.
+        let _writing = self.writing.lock().await;
+        let file = CassetteFile {
+            version: 1,
+            interactions: self.interactions.lock().unwrap().clone(),
+        };
+        let result = match serde_json::to_string_pretty(&file) {
+            Ok(contents) => tokio::fs::write(&self.path, contents)
+                .await
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e.into()),
+        };
+        match result {
+            Ok(()) => *self.version.lock().unwrap() = Self::file_version(&self.path),
+            Err(e) => log::warn!("Failed to write cassette {}: {:#}", self.path.display(), e),
+        }
+    }
+}
+
+/// A single request, as seen by the cassette.
+pub(crate) struct CassetteSession<'a> {
+    mode: CassetteMode,
+    cassette: Arc<Cassette>,
+    key: String,
+    client: &'a LLMPrimitiveProvider,
+    prompt: &'a RenderedPrompt,
+}
+
+impl<'a> CassetteSession<'a> {
+    /// Returns `None` if cassettes are not enabled for this context.
+    pub(crate) fn new(
+        ctx: &RuntimeContext,
+        client: &'a LLMPrimitiveProvider,
+        prompt: &'a RenderedPrompt,
+    ) -> Result<Option<Self>> {
+        let Some(config) = CassetteConfig::from_env(&ctx.env)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            mode: config.mode,
+            cassette: Cassette::open(&config.path)?,
+            key: cassette_key(
+                client.name(),
+                client.request_options(),
+                client.output_mode(),
+                ctx.output_schema.as_ref(),
+                prompt,
+            )?,
+            client,
+            prompt,
+        }))
+    }
+
+    /// The recorded response, if this request should be replayed.
+    ///
+    /// Returns `None` if the request should be sent to the provider.
+    pub(crate) fn replay(&self) -> Option<LLMResponse> {
+        self.replay_chunks()
+            .map(|mut responses| responses.pop().unwrap_or_else(|| self.miss()))
+    }
+
+    /// Like [`Self::replay`], but replays every recorded chunk as a stream.
+    pub(crate) fn replay_stream(&self) -> Option<StreamResponse> {
+        self.replay_chunks().map(|responses| {
+            let stream: StreamResponse = Ok(Box::pin(futures::stream::iter(responses)));
+            stream
+        })
+    }
+
+    fn replay_chunks(&self) -> Option<Vec<LLMResponse>> {
+        if !self.mode.replays() {
+            return None;
+        }
+        match self.cassette.get(&self.key) {
+            Some(entry) => {
+                log::debug!(
+                    "Replaying {} from cassette ({})",
+                    self.client.name(),
+                    self.key
+                );
+                Some(self.to_responses(entry))
+            }
+            None if self.mode == CassetteMode::Replay => Some(vec![self.miss()]),
+            None => None,
+        }
+    }
+
+    fn miss(&self) -> LLMResponse {
+        LLMResponse::InternalFailure(format!(
+            "No response recorded for client {} in cassette {} ({CASSETTE_MODE_ENV_VAR}=replay). Re-record it with {CASSETTE_MODE_ENV_VAR}=record_on_miss.",
+            self.client.name(),
+            self.cassette.path.display(),
+        ))
+    }
+
+    pub(crate) async fn record(&self, response: &LLMResponse) {
+        self.record_chunks(std::slice::from_ref(response)).await;
+    }
+
+    /// Records a stream as it is consumed, writing to the cassette once the
+    /// stream completes.
+    pub(crate) async fn record_stream(&self, stream: StreamResponse) -> StreamResponse {
+        if !self.mode.records() {
+            return stream;
+        }
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(response) => {
+                self.record(&response).await;
+                return Err(response);
+            }
+        };
+
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let session = RecordingSession {
+            cassette: self.cassette.clone(),
+            key: self.key.clone(),
+            client: self.client.name().to_string(),
+        };
+
+        let tap = {
+            let recorded = recorded.clone();
+            stream.inspect(move |response| recorded.lock().unwrap().push(response.clone()))
+        };
+        let finish = futures::stream::once(async move {
+            let responses = std::mem::take(&mut *recorded.lock().unwrap());
+            session.record(&responses).await;
+        })
+        .filter_map(|_| futures::future::ready(None));
+
+        Ok(Box::pin(tap.chain(finish)))
+    }
+
+    async fn record_chunks(&self, responses: &[LLMResponse]) {
+        if !self.mode.records() {
+            return;
+        }
+        RecordingSession {
+            cassette: self.cassette.clone(),
+            key: self.key.clone(),
+            client: self.client.name().to_string(),
+        }
+        .record(responses)
+        .await;
+    }
+
+    fn to_responses(&self, entry: CassetteEntry) -> Vec<LLMResponse> {
+        let request_options = self.client.request_options().clone();
+        let start_time = web_time::SystemTime::now();
+        match entry.outcome {
+            RecordedOutcome::Success {
+                chunks,
+                baml_is_complete,
+                finish_reason,
+                prompt_tokens,
+                output_tokens,
+                total_tokens,
+            } => {
+                let last = chunks.len().saturating_sub(1);
+                let mut content = String::new();
+                chunks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        content.push_str(chunk);
+                        let is_last = i == last;
+                        LLMResponse::Success(LLMCompleteResponse {
+                            client: entry.client.clone(),
+                            model: entry.model.clone().unwrap_or_default(),
+                            prompt: self.prompt.clone(),
+                            request_options: request_options.clone(),
+                            content: content.clone(),
+                            start_time,
+                            latency: web_time::Duration::ZERO,
+                            metadata: LLMCompleteResponseMetadata {
+                                baml_is_complete: is_last && baml_is_complete,
+                                finish_reason: finish_reason.clone().filter(|_| is_last),
+                                prompt_tokens: prompt_tokens.filter(|_| is_last),
+                                output_tokens: output_tokens.filter(|_| is_last),
+                                total_tokens: total_tokens.filter(|_| is_last),
+                            },
+                        })
+                    })
+                    .collect()
+            }
+            RecordedOutcome::Failure { message, code } => {
+                vec![LLMResponse::LLMFailure(LLMErrorResponse {
+                    client: entry.client,
+                    model: entry.model,
+                    prompt: self.prompt.clone(),
+                    request_options,
+                    start_time,
+                    latency: web_time::Duration::ZERO,
+                    message,
+                    code: ErrorCode::from_u16(code),
+                    retry_after: None,
+                })]
+            }
+        }
+    }
+}
+
+/// The parts of a [`CassetteSession`] needed to record a response, which
+/// (unlike the session) can outlive the request.
+struct RecordingSession {
+    cassette: Arc<Cassette>,
+    key: String,
+    client: String,
+}
+
+impl RecordingSession {
+    async fn record(&self, responses: &[LLMResponse]) {
+        let Some(last) = responses.last() else {
+            return;
+        };
+        let entry = match last {
+            LLMResponse::Success(complete) => {
+                // Streamed responses carry the accumulated content so far, so
+                // only the new suffix of each chunk is stored.
+                let mut chunks = Vec::new();
+                let mut previous = "";
+                for response in responses {
+                    if let LLMResponse::Success(partial) = response {
+                        let chunk = partial
+                            .content
+                            .strip_prefix(previous)
+                            .unwrap_or(&partial.content);
+                        chunks.push(chunk.to_string());
+                        previous = &partial.content;
+                    }
+                }
+                CassetteEntry {
+                    client: complete.client.clone(),
+                    model: Some(complete.model.clone()),
+                    outcome: RecordedOutcome::Success {
+                        chunks,
+                        baml_is_complete: complete.metadata.baml_is_complete,
+                        finish_reason: complete.metadata.finish_reason.clone(),
+                        prompt_tokens: complete.metadata.prompt_tokens,
+                        output_tokens: complete.metadata.output_tokens,
+                        total_tokens: complete.metadata.total_tokens,
+                    },
+                }
+            }
+            LLMResponse::LLMFailure(failure) => CassetteEntry {
+                client: failure.client.clone(),
+                model: failure.model.clone(),
+                outcome: RecordedOutcome::Failure {
+                    message: failure.message.clone(),
+                    code: failure.code.to_u16(),
+                },
+            },
+            // These never reached the provider, so there is nothing to replay.
+            LLMResponse::UserFailure(_) | LLMResponse::InternalFailure(_) => return,
+        };
+
+        log::debug!("Recording {} to cassette ({})", self.client, self.key);
+        self.cassette.insert(self.key.clone(), entry).await;
+    }
+}
+
+/// Computes the cassette key for a request. The key must be stable across
+/// processes, so objects are serialized with their keys sorted.
+fn cassette_key(
+    client: &str,
+    request_options: &HashMap<String, serde_json::Value>,
+    output_mode: OutputMode,
+    output_schema: Option<&OutputSchema>,
+    prompt: &RenderedPrompt,
+) -> Result<String> {
+    let model = request_options
+        .get("model")
+        .or_else(|| request_options.get("model_id"))
+        .cloned()
+        .unwrap_or(serde_json::Value::Null);
+    // In `prompt` mode the schema is already part of the prompt; in the
+    // others it's sent as a tool or response format.
+    let output_schema = match (output_mode, output_schema) {
+        (OutputMode::Prompt, _) | (_, None) => serde_json::Value::Null,
+        (_, Some(schema)) => serde_json::json!({
+            "name": schema.name(),
+            "schema": schema.json_schema()?,
+        }),
+    };
+    let request = serde_json::json!({
+        "client": client,
+        "model": model,
+        "output_mode": output_mode.as_str(),
+        "output_schema": output_schema,
+        "prompt": serde_json::to_value(prompt)?,
+        "request_options": serde_json::to_value(request_options)?,
+    });
+
+    let canonical = serde_json::to_string(&canonicalize(request))?;
+    Ok(format!("{:x}", Sha256::digest(canonical.as_bytes())))
+}
+
+fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(canonicalize).collect())
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use internal_baml_jinja::{ChatMessagePart, RenderedChatMessage};
+
+    fn prompt() -> RenderedPrompt {
+        RenderedPrompt::Chat(vec![RenderedChatMessage {
+            role: "user".to_string(),
+            allow_duplicate_role: false,
+            parts: vec![ChatMessagePart::Text("hello".to_string())],
+        }])
+    }
+
+    #[test]
+    fn test_cassette_key_ignores_option_order() {
+        let a: HashMap<String, serde_json::Value> = [
+            ("model".to_string(), serde_json::json!("gpt-4o")),
+            ("temperature".to_string(), serde_json::json!(0)),
+            ("a".to_string(), serde_json::json!({"y": 1, "x": 2})),
+        ]
+        .into_iter()
+        .collect();
+        let b: HashMap<String, serde_json::Value> = [
+            ("a".to_string(), serde_json::json!({"x": 2, "y": 1})),
+            ("temperature".to_string(), serde_json::json!(0)),
+            ("model".to_string(), serde_json::json!("gpt-4o")),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            cassette_key("GPT4o", &a, OutputMode::Prompt, None, &prompt()).unwrap(),
+            cassette_key("GPT4o", &b, OutputMode::Prompt, None, &prompt()).unwrap()
+        );
+        assert_ne!(
+            cassette_key("GPT4o", &a, OutputMode::Prompt, None, &prompt()).unwrap(),
+            cassette_key("GPT4", &a, OutputMode::Prompt, None, &prompt()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cassette_key_depends_on_output_mode() {
+        let options: HashMap<String, serde_json::Value> =
+            [("model".to_string(), serde_json::json!("gpt-4o"))]
+                .into_iter()
+                .collect();
+
+        assert_ne!(
+            cassette_key("GPT4o", &options, OutputMode::Prompt, None, &prompt()).unwrap(),
+            cassette_key("GPT4o", &options, OutputMode::Tool, None, &prompt()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cassette_config_from_env() {
+        let env = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        assert!(CassetteConfig::from_env(&env(&[])).unwrap().is_none());
+        assert!(
+            CassetteConfig::from_env(&env(&[(CASSETTE_MODE_ENV_VAR, "off")]))
+                .unwrap()
+                .is_none()
+        );
+        assert!(CassetteConfig::from_env(&env(&[(CASSETTE_MODE_ENV_VAR, "sometimes")])).is_err());
+
+        let config = CassetteConfig::from_env(&env(&[
+            (CASSETTE_MODE_ENV_VAR, "record_on_miss"),
+            (CASSETTE_PATH_ENV_VAR, "/tmp/cassette.json"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.mode, CassetteMode::RecordOnMiss);
+        assert_eq!(config.path, PathBuf::from("/tmp/cassette.json"));
+    }
+}
@@ -14,7 +14,7 @@ use super::{
     },
     primitive::LLMPrimitiveProvider,
     strategy::LLMStrategyProvider,
-    traits::WithRetryPolicy,
+    traits::{WithClientProperties, WithRetryPolicy},
 };
 
 pub enum LLMProvider {
@@ -31,6 +31,16 @@ impl std::fmt::Debug for LLMProvider {
     }
 }
 
+impl LLMProvider {
+    /// The deadline for a whole call to this client, see [`super::RequestTimeouts::total`].
+    pub fn total_timeout(&self) -> Option<web_time::Duration> {
+        match self {
+            LLMProvider::Primitive(provider) => provider.timeouts().total,
+            LLMProvider::Strategy(provider) => provider.timeouts().total,
+        }
+    }
+}
+
 impl WithRetryPolicy for LLMProvider {
     fn retry_policy_name(&self) -> Option<&str> {
         match self {
@@ -80,13 +90,14 @@ impl IterOrchestrator for Arc<LLMProvider> {
     ) -> Result<OrchestratorNodeIterator> {
         if let Some(retry_policy) = self.retry_policy_name() {
             let policy = client_lookup.get_retry_policy(retry_policy, ctx)?;
+            let shared_policy = Arc::new(policy.clone());
             Ok(policy
                 .into_iter()
                 .enumerate()
                 .map(move |(idx, node)| {
                     previous
                         .clone()
-                        .extend(ExecutionScope::Retry(retry_policy.into(), idx, node))
+                        .extend(ExecutionScope::Retry(shared_policy.clone(), idx, node))
                 })
                 .map(|scope| {
                     // repeat the same provider for each retry policy
@@ -1,13 +1,18 @@
 use std::collections::{HashMap, HashSet};
 
 use colored::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cassette;
 pub mod llm_provider;
 pub mod orchestrator;
+mod output_schema;
 pub mod primitive;
 
 mod properties_hander;
+pub mod rate_limit;
 pub mod retry_policy;
 mod strategy;
+mod timeouts;
 pub mod traits;
 
 use anyhow::Result;
@@ -19,6 +24,9 @@ use jsonish::BamlValueWithFlags;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 
+pub use output_schema::{OutputMode, OutputSchema};
+pub use timeouts::RequestTimeouts;
+
 use reqwest::StatusCode;
 
 #[cfg(target_arch = "wasm32")]
@@ -92,6 +100,8 @@ pub enum AllowedMetadata {
 pub struct SupportedRequestModes {
     // If unset, treat as auto
     pub stream: Option<bool>,
+    #[serde(default)]
+    pub output: OutputMode,
 }
 
 impl AllowedMetadata {
@@ -188,12 +198,16 @@ pub struct LLMErrorResponse {
     // Short error message
     pub message: String,
     pub code: ErrorCode,
+    /// How long the provider asked us to wait before trying again
+    /// (`Retry-After`), if it did.
+    pub retry_after: Option<web_time::Duration>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub enum ErrorCode {
     InvalidAuthentication, // 401
     NotSupported,          // 403
+    Timeout,               // 408
     RateLimited,           // 429
     ServerError,           // 500
     ServiceUnavailable,    // 503
@@ -210,6 +224,7 @@ impl ErrorCode {
         match self {
             ErrorCode::InvalidAuthentication => "InvalidAuthentication (401)".into(),
             ErrorCode::NotSupported => "NotSupported (403)".into(),
+            ErrorCode::Timeout => "Timeout (408)".into(),
             ErrorCode::RateLimited => "RateLimited (429)".into(),
             ErrorCode::ServerError => "ServerError (500)".into(),
             ErrorCode::ServiceUnavailable => "ServiceUnavailable (503)".into(),
@@ -222,6 +237,7 @@ impl ErrorCode {
         match status.as_u16() {
             401 => ErrorCode::InvalidAuthentication,
             403 => ErrorCode::NotSupported,
+            408 => ErrorCode::Timeout,
             429 => ErrorCode::RateLimited,
             500 => ErrorCode::ServerError,
             503 => ErrorCode::ServiceUnavailable,
@@ -233,6 +249,7 @@ impl ErrorCode {
         match code {
             401 => ErrorCode::InvalidAuthentication,
             403 => ErrorCode::NotSupported,
+            408 => ErrorCode::Timeout,
             429 => ErrorCode::RateLimited,
             500 => ErrorCode::ServerError,
             503 => ErrorCode::ServiceUnavailable,
@@ -244,6 +261,7 @@ impl ErrorCode {
         match self {
             ErrorCode::InvalidAuthentication => 401,
             ErrorCode::NotSupported => 403,
+            ErrorCode::Timeout => 408,
             ErrorCode::RateLimited => 429,
             ErrorCode::ServerError => 500,
             ErrorCode::ServiceUnavailable => 503,
@@ -7,14 +7,17 @@ use web_time::Duration;
 use crate::{
     internal::{
         llm_client::{
-            parsed_value_to_response, traits::{WithPrompt, WithSingleCallable}, LLMResponse, ResponseBamlValue
+            parsed_value_to_response,
+            timeouts::{with_timeout, Deadline},
+            traits::{WithPrompt, WithSingleCallable},
+            LLMResponse, ResponseBamlValue,
         },
         prompt_renderer::PromptRenderer,
     },
     RuntimeContext,
 };
 
-use super::{OrchestrationScope, OrchestratorNodeIterator};
+use super::{OrchestrationScope, OrchestratorNodeIterator, RetryState};
 
 pub async fn orchestrate(
     iter: OrchestratorNodeIterator,
@@ -23,6 +26,7 @@ pub async fn orchestrate(
     prompt: &PromptRenderer,
     params: &BamlValue,
     parse_fn: impl Fn(&str) -> Result<BamlValueWithFlags>,
+    total_timeout: Option<Duration>,
 ) -> (
     Vec<(
         OrchestrationScope,
@@ -34,8 +38,13 @@ pub async fn orchestrate(
 ) {
     let mut results = Vec::new();
     let mut total_sleep_duration = std::time::Duration::from_secs(0);
+    let deadline = total_timeout.map(Deadline::after);
+    let mut retry_state = RetryState::default();
 
-    for node in iter {
+    for mut node in iter {
+        if retry_state.skips(&node) {
+            continue;
+        }
         let prompt = match node.render_prompt(ir, prompt, ctx, params).await {
             Ok(p) => p,
             Err(e) => {
@@ -48,13 +57,29 @@ pub async fn orchestrate(
                 continue;
             }
         };
-        let response = node.single_call(&ctx, &prompt).await;
+        let on_timeout = node.provider.timeout_response(&prompt);
+        if let Some(deadline) = deadline.filter(|d| d.has_passed()) {
+            results.push((node.scope, on_timeout(deadline.message()), None, None));
+            break;
+        }
+        let response = match deadline {
+            Some(deadline) => {
+                with_timeout(Some(deadline.remaining()), node.single_call(&ctx, &prompt))
+                    .await
+                    .unwrap_or_else(|| on_timeout(deadline.message()))
+            }
+            None => node.single_call(&ctx, &prompt).await,
+        };
+        let deadline_passed = deadline.map_or(false, |d| d.has_passed());
         let parsed_response = match &response {
             LLMResponse::Success(s) => Some(parse_fn(&s.content)),
             _ => None,
         };
 
-        let sleep_duration = node.error_sleep_duration().cloned();
+        let sleep_duration = match &response {
+            LLMResponse::Success(_) => None,
+            failure => retry_state.on_failure(&mut node, failure),
+        };
         let (parsed_response, response_with_constraints) = match parsed_response {
                 Some(Ok(v)) => (Some(Ok(v.clone())), Some(parsed_value_to_response(&v))),
                 Some(Err(e)) => (None, Some(Err(e))),
@@ -63,13 +88,16 @@ pub async fn orchestrate(
         results.push((node.scope, response, parsed_response, response_with_constraints));
 
         // Currently, we break out of the loop if an LLM responded, even if we couldn't parse the result.
-        if results
-            .last()
-            .map_or(false, |(_, r, _, _)| matches!(r, LLMResponse::Success(_)))
+        // There is no point in trying further clients once the total timeout has passed either.
+        if deadline_passed
+            || results
+                .last()
+                .map_or(false, |(_, r, _, _)| matches!(r, LLMResponse::Success(_)))
         {
             break;
         } else {
             if let Some(duration) = sleep_duration {
+                let duration = deadline.map_or(duration, |d| duration.min(d.remaining()));
                 total_sleep_duration += duration;
                 async_std::task::sleep(duration).await;
             }
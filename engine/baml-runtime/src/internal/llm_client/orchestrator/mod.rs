@@ -9,12 +9,14 @@ use crate::{
     RuntimeContext,
 };
 
+use super::rate_limit;
+use super::retry_policy::CallablePolicy;
 use super::traits::{WithClientProperties, WithRenderRawCurl};
 use super::LLMCompleteResponse;
 use super::{
     strategy::roundrobin::RoundRobinStrategy,
     traits::{StreamResponse, WithPrompt, WithSingleCallable, WithStreamable},
-    LLMResponse,
+    LLMErrorResponse, LLMResponse,
 };
 
 pub use super::primitive::LLMPrimitiveProvider;
@@ -38,7 +40,13 @@ impl std::fmt::Display for ExecutionScope {
         match self {
             ExecutionScope::Direct(s) => write!(f, "{}", s),
             ExecutionScope::Retry(policy, count, delay) => {
-                write!(f, "Retry({}, {}, {}ms)", policy, count, delay.as_millis())
+                write!(
+                    f,
+                    "Retry({}, {}, {}ms)",
+                    policy.name(),
+                    count,
+                    delay.as_millis()
+                )
             }
             ExecutionScope::RoundRobin(strategy, index) => {
                 write!(f, "RoundRobin({}, {})", strategy.name, index)
@@ -75,13 +83,98 @@ impl OrchestratorNode {
         }
     }
 
-    pub fn error_sleep_duration(&self) -> Option<&Duration> {
-        // in reverse find the first retry scope, and return the delay
-        self.scope.scope.iter().rev().find_map(|scope| match scope {
-            ExecutionScope::Retry(_, _, delay) if !delay.is_zero() => Some(delay),
-            _ => None,
+    /// The retry attempts this node is part of, outermost first, along with
+    /// the index of their scope.
+    fn retry_attempts(&self) -> Vec<(usize, RetryAttempt)> {
+        self.scope
+            .scope
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, scope)| match scope {
+                ExecutionScope::Retry(policy, count, _) => Some((
+                    idx,
+                    RetryAttempt {
+                        instance: self.scope.scope[..idx]
+                            .iter()
+                            .map(|scope| match scope {
+                                // The delay differs between the nodes of an attempt once recorded.
+                                ExecutionScope::Retry(policy, count, _) => {
+                                    format!("Retry({}, {})", policy.name(), count)
+                                }
+                                other => other.to_string(),
+                            })
+                            .chain(std::iter::once(policy.name().to_string()))
+                            .collect::<Vec<_>>()
+                            .join(" + "),
+                        count: *count,
+                    },
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// One attempt of a retry policy. The same policy can be used in several
+/// places of a strategy, each of them is a separate `instance`.
+#[derive(Clone, Debug, PartialEq)]
+struct RetryAttempt {
+    instance: String,
+    count: usize,
+}
+
+/// Keeps track of the retry policies that stopped early because a failure
+/// wasn't worth retrying.
+#[derive(Default)]
+pub struct RetryState {
+    given_up: Vec<RetryAttempt>,
+}
+
+impl RetryState {
+    /// Whether `node` is a later attempt of a retry policy that stopped.
+    pub fn skips(&self, node: &OrchestratorNode) -> bool {
+        node.retry_attempts().iter().any(|(_, attempt)| {
+            self.given_up.iter().any(|stopped| {
+                stopped.instance == attempt.instance && attempt.count > stopped.count
+            })
         })
     }
+
+    /// Called after `node` failed with `response`: stops the retry policies
+    /// that don't retry this failure, and returns how long to wait before the
+    /// next attempt.
+    ///
+    /// The delay comes from the innermost retry policy that will try again,
+    /// extended to the provider's `Retry-After` if it asked for longer, and is
+    /// recorded in that policy's scope.
+    pub fn on_failure(
+        &mut self,
+        node: &mut OrchestratorNode,
+        response: &LLMResponse,
+    ) -> Option<Duration> {
+        let mut retrying = None;
+        for (idx, attempt) in node.retry_attempts() {
+            match &node.scope.scope[idx] {
+                ExecutionScope::Retry(policy, _, _) if !policy.retries(response) => {
+                    self.given_up.push(attempt);
+                }
+                ExecutionScope::Retry(_, _, delay) if !delay.is_zero() => retrying = Some(idx),
+                _ => {}
+            }
+        }
+
+        let ExecutionScope::Retry(_, _, delay) = &mut node.scope.scope[retrying?] else {
+            unreachable!("retry_attempts only returns retry scopes");
+        };
+        if let LLMResponse::LLMFailure(LLMErrorResponse {
+            retry_after: Some(retry_after),
+            ..
+        }) = response
+        {
+            *delay = (*delay).max(*retry_after);
+        }
+        Some(*delay)
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize)]
@@ -142,8 +235,8 @@ impl OrchestrationScope {
 #[derive(Clone, Debug, Serialize)]
 pub enum ExecutionScope {
     Direct(String),
-    // PolicyName, RetryCount, RetryDelayMs
-    Retry(String, usize, Duration),
+    // Policy, RetryCount, RetryDelayMs
+    Retry(Arc<CallablePolicy>, usize, Duration),
     // StrategyName, ClientIndex
     RoundRobin(Arc<RoundRobinStrategy>, usize),
     // StrategyName, ClientIndex
@@ -204,6 +297,7 @@ impl WithSingleCallable for OrchestratorNode {
             })
             .map(|a| a.increment_index())
             .for_each(drop);
+        rate_limit::wait_for_client(self.provider.name()).await;
         self.provider.single_call(ctx, prompt).await
     }
 }
@@ -219,6 +313,7 @@ impl WithStreamable for OrchestratorNode {
             })
             .map(|a| a.increment_index())
             .for_each(drop);
+        rate_limit::wait_for_client(self.provider.name()).await;
         self.provider.stream(ctx, prompt).await
     }
 }
@@ -8,14 +8,17 @@ use web_time::Duration;
 use crate::{
     internal::{
         llm_client::{
-            parsed_value_to_response, traits::{WithPrompt, WithStreamable}, LLMErrorResponse, LLMResponse, ResponseBamlValue
+            parsed_value_to_response,
+            timeouts::{with_timeout, Deadline},
+            traits::{WithPrompt, WithStreamable},
+            LLMErrorResponse, LLMResponse, ResponseBamlValue,
         },
         prompt_renderer::PromptRenderer,
     },
     FunctionResult, RuntimeContext,
 };
 
-use super::{OrchestrationScope, OrchestratorNodeIterator};
+use super::{OrchestrationScope, OrchestratorNodeIterator, RetryState};
 
 pub async fn orchestrate_stream<F>(
     iter: OrchestratorNodeIterator,
@@ -26,6 +29,7 @@ pub async fn orchestrate_stream<F>(
     partial_parse_fn: impl Fn(&str) -> Result<BamlValueWithFlags>,
     parse_fn: impl Fn(&str) -> Result<BamlValueWithFlags>,
     on_event: Option<F>,
+    total_timeout: Option<Duration>,
 ) -> (
     Vec<(
         OrchestrationScope,
@@ -40,9 +44,14 @@ where
 {
     let mut results = Vec::new();
     let mut total_sleep_duration = std::time::Duration::from_secs(0);
+    let deadline = total_timeout.map(Deadline::after);
+    let mut retry_state = RetryState::default();
 
     //advanced curl viewing, use render_raw_curl on each node. TODO
-    for node in iter {
+    for mut node in iter {
+        if retry_state.skips(&node) {
+            continue;
+        }
         let prompt = match node.render_prompt(ir, prompt, ctx, params).await {
             Ok(p) => p,
             Err(e) => {
@@ -57,46 +66,62 @@ where
         };
 
         let (system_start, instant_start) = (web_time::SystemTime::now(), web_time::Instant::now());
-        let stream_res = node.stream(ctx, &prompt).await;
-        let final_response = match stream_res {
-            Ok(response) => response
-                .map(|stream_part| {
-                    if let Some(on_event) = on_event.as_ref() {
-                        match &stream_part {
-                            LLMResponse::Success(s) => {
-                                let parsed = partial_parse_fn(&s.content);
-                                let (parsed, response_value) = match parsed {
-                                    Ok(v) => (Some(Ok(v.clone())), Some(parsed_value_to_response(&v))),
-                                    Err(e) => (None, Some(Err(e))),
-                                };
-                                on_event(FunctionResult::new(
-                                    node.scope.clone(),
-                                    LLMResponse::Success(s.clone()),
-                                    parsed,
-                                    response_value,
-                                ));
+        let on_timeout = node.provider.timeout_response(&prompt);
+        if let Some(deadline) = deadline.filter(|d| d.has_passed()) {
+            results.push((node.scope, on_timeout(deadline.message()), None, None));
+            break;
+        }
+
+        let attempt = async {
+            let stream_res = node.stream(ctx, &prompt).await;
+            match stream_res {
+                Ok(response) => response
+                    .map(|stream_part| {
+                        if let Some(on_event) = on_event.as_ref() {
+                            match &stream_part {
+                                LLMResponse::Success(s) => {
+                                    let parsed = partial_parse_fn(&s.content);
+                                    let (parsed, response_value) = match parsed {
+                                        Ok(v) => (Some(Ok(v.clone())), Some(parsed_value_to_response(&v))),
+                                        Err(e) => (None, Some(Err(e))),
+                                    };
+                                    on_event(FunctionResult::new(
+                                        node.scope.clone(),
+                                        LLMResponse::Success(s.clone()),
+                                        parsed,
+                                        response_value,
+                                    ));
+                                }
+                                _ => {}
                             }
-                            _ => {}
                         }
-                    }
-                    stream_part
-                })
-                .fold(None, |_, current| Some(current))
-                .await
-                .unwrap_or_else(|| {
-                    LLMResponse::LLMFailure(LLMErrorResponse {
-                        client: node.provider.name().into(),
-                        model: None,
-                        prompt,
-                        start_time: system_start,
-                        latency: instant_start.elapsed(),
-                        request_options: node.provider.request_options().clone(),
-                        message: "Stream ended without response".to_string(),
-                        code: crate::internal::llm_client::ErrorCode::from_u16(2),
+                        stream_part
                     })
-                }),
-            Err(response) => response,
+                    .fold(None, |_, current| Some(current))
+                    .await
+                    .unwrap_or_else(|| {
+                        LLMResponse::LLMFailure(LLMErrorResponse {
+                            client: node.provider.name().into(),
+                            model: None,
+                            prompt,
+                            start_time: system_start,
+                            latency: instant_start.elapsed(),
+                            request_options: node.provider.request_options().clone(),
+                            message: "Stream ended without response".to_string(),
+                            code: crate::internal::llm_client::ErrorCode::from_u16(2),
+                            retry_after: None,
+                        })
+                    }),
+                Err(response) => response,
+            }
+        };
+        let final_response = match deadline {
+            Some(deadline) => with_timeout(Some(deadline.remaining()), attempt)
+                .await
+                .unwrap_or_else(|| on_timeout(deadline.message())),
+            None => attempt.await,
         };
+        let deadline_passed = deadline.map_or(false, |d| d.has_passed());
 
         let parsed_response = match &final_response {
             LLMResponse::Success(s) => Some(parse_fn(&s.content)),
@@ -108,17 +133,23 @@ where
             None => (None, None),
         };
             // parsed_response.map(|r| r.and_then(|v| parsed_value_to_response(v)));
-        let sleep_duration = node.error_sleep_duration().cloned();
+        let sleep_duration = match &final_response {
+            LLMResponse::Success(_) => None,
+            failure => retry_state.on_failure(&mut node, failure),
+        };
         results.push((node.scope, final_response, parsed_response, response_value));
 
         // Currently, we break out of the loop if an LLM responded, even if we couldn't parse the result.
-        if results
-            .last()
-            .map_or(false, |(_, r, _, _)| matches!(r, LLMResponse::Success(_)))
+        // There is no point in trying further clients once the total timeout has passed either.
+        if deadline_passed
+            || results
+                .last()
+                .map_or(false, |(_, r, _, _)| matches!(r, LLMResponse::Success(_)))
         {
             break;
         } else {
             if let Some(duration) = sleep_duration {
+                let duration = deadline.map_or(duration, |d| duration.min(d.remaining()));
                 total_sleep_duration += duration;
                 async_std::task::sleep(duration).await;
             }
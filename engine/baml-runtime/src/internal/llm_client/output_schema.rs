@@ -0,0 +1,292 @@
+use anyhow::Result;
+use internal_baml_jinja::types::OutputFormatContent;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::RuntimeContext;
+
+/// How a client asks the model for the function's output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// The schema is only described in the prompt (via `ctx.output_format`)
+    /// and the text reply is parsed.
+    #[default]
+    Prompt,
+    /// The schema is sent as a tool the model is forced to call, and the
+    /// tool call arguments are parsed.
+    Tool,
+    /// The schema is sent as the provider's structured output format
+    /// (`response_format` for OpenAI, `responseSchema` for Gemini), and the
+    /// text reply is parsed.
+    JsonSchema,
+}
+
+impl OutputMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputMode::Prompt => "prompt",
+            OutputMode::Tool => "tool",
+            OutputMode::JsonSchema => "json_schema",
+        }
+    }
+
+    /// The schema a request in this mode has to send, if any.
+    pub fn output_schema<'a>(&self, ctx: &'a RuntimeContext) -> Result<Option<&'a OutputSchema>> {
+        match self {
+            OutputMode::Prompt => Ok(None),
+            OutputMode::Tool | OutputMode::JsonSchema => match &ctx.output_schema {
+                Some(schema) => Ok(Some(schema)),
+                None => {
+                    anyhow::bail!(
+                        "output_mode {} can only be used when calling a BAML function",
+                        self.as_str()
+                    )
+                }
+            },
+        }
+    }
+}
+
+/// The output type of the function being called, for clients that hand it
+/// to the provider natively instead of relying on the prompt.
+#[derive(Clone, Debug)]
+pub struct OutputSchema {
+    function_name: String,
+    output_format: OutputFormatContent,
+}
+
+impl OutputSchema {
+    /// Tool parameters and OpenAI response formats must be JSON objects, so
+    /// any other output type is wrapped in an object under this key.
+    const WRAPPER_KEY: &'static str = "output";
+
+    pub fn new(function_name: impl Into<String>, output_format: OutputFormatContent) -> Self {
+        Self {
+            function_name: function_name.into(),
+            output_format,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.function_name
+    }
+
+    pub fn description(&self) -> String {
+        format!("Respond with the result of {}.", self.function_name)
+    }
+
+    /// JSON schema of the output, as is.
+    pub fn json_schema(&self) -> Result<serde_json::Value> {
+        self.output_format.json_schema()
+    }
+
+    /// JSON schema of the output, wrapped under [`Self::WRAPPER_KEY`] unless
+    /// it already describes an object.
+    pub fn object_schema(&self) -> Result<serde_json::Value> {
+        let mut schema = self.output_format.json_schema()?;
+        if self.output_format.json_schema_is_object() {
+            return Ok(schema);
+        }
+
+        // $defs have to stay at the root for references to resolve.
+        let defs = schema.as_object_mut().and_then(|s| s.remove("$defs"));
+        let mut wrapped = json!({
+            "type": "object",
+            "properties": { Self::WRAPPER_KEY: schema },
+            "required": [Self::WRAPPER_KEY],
+        });
+        if let Some(defs) = defs {
+            wrapped["$defs"] = defs;
+        }
+        Ok(wrapped)
+    }
+
+    /// [`Self::json_schema`] in the OpenAPI subset accepted by Gemini, which
+    /// has `nullable` instead of null unions and no references.
+    pub fn openapi_schema(&self) -> Result<serde_json::Value> {
+        self.to_openapi(self.json_schema()?)
+    }
+
+    /// [`Self::object_schema`] in the OpenAPI subset accepted by Gemini.
+    pub fn openapi_object_schema(&self) -> Result<serde_json::Value> {
+        self.to_openapi(self.object_schema()?)
+    }
+
+    fn to_openapi(&self, schema: serde_json::Value) -> Result<serde_json::Value> {
+        if schema.get("$defs").is_some() {
+            anyhow::bail!(
+                "{} returns a recursive type, which this provider can't accept as a schema",
+                self.function_name
+            );
+        }
+        Ok(to_openapi(schema))
+    }
+
+    /// Turns the (possibly partial) JSON matching [`Self::object_schema`],
+    /// e.g. tool call arguments, into the content handed to the parser.
+    pub fn content_from_object_json(&self, arguments: &str) -> String {
+        if self.output_format.json_schema_is_object() {
+            return arguments.to_string();
+        }
+        match serde_json::from_str::<serde_json::Value>(arguments) {
+            Ok(input) => self.content_from_object(&input),
+            // Partial arguments are left for the parser to make sense of.
+            Err(_) => arguments.to_string(),
+        }
+    }
+
+    /// Same as [`Self::content_from_object_json`], for providers that
+    /// return the tool call arguments as a JSON value.
+    pub fn content_from_object(&self, input: &serde_json::Value) -> String {
+        let value = if self.output_format.json_schema_is_object() {
+            input
+        } else {
+            input.get(Self::WRAPPER_KEY).unwrap_or(input)
+        };
+        match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+fn to_openapi(schema: serde_json::Value) -> serde_json::Value {
+    let serde_json::Value::Object(mut schema) = schema else {
+        return schema;
+    };
+
+    // `{"anyOf": [T, {"type": "null"}]}` becomes T with `nullable`.
+    if let Some(serde_json::Value::Array(variants)) = schema.get("anyOf") {
+        let is_null = |v: &serde_json::Value| v.get("type") == Some(&json!("null"));
+        if variants.iter().any(is_null) {
+            let mut rest = variants
+                .iter()
+                .filter(|v| !is_null(v))
+                .cloned()
+                .collect::<Vec<_>>();
+            schema.remove("anyOf");
+            match rest.len() {
+                1 => {
+                    if let serde_json::Value::Object(inner) = to_openapi(rest.remove(0)) {
+                        schema.extend(inner);
+                    }
+                }
+                _ => {
+                    schema.insert("anyOf".into(), rest.into_iter().map(to_openapi).collect());
+                }
+            }
+            schema.insert("nullable".into(), true.into());
+            return serde_json::Value::Object(schema);
+        }
+    }
+
+    // Maps have no equivalent, they are sent as free-form objects.
+    schema.remove("additionalProperties");
+    schema
+        .into_iter()
+        .map(|(k, v)| {
+            let v = match k.as_str() {
+                "items" => to_openapi(v),
+                "anyOf" | "prefixItems" => match v {
+                    serde_json::Value::Array(items) => items.into_iter().map(to_openapi).collect(),
+                    other => other,
+                },
+                "properties" => match v {
+                    serde_json::Value::Object(props) => props
+                        .into_iter()
+                        .map(|(name, prop)| (name, to_openapi(prop)))
+                        .collect(),
+                    other => other,
+                },
+                _ => v,
+            };
+            (k, v)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use baml_types::FieldType;
+
+    use super::*;
+
+    #[test]
+    fn wraps_non_object_outputs() {
+        let schema = OutputSchema::new(
+            "Summarize",
+            OutputFormatContent::target(FieldType::List(Box::new(FieldType::string()))).build(),
+        );
+
+        assert_eq!(
+            schema.object_schema().unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "output": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["output"],
+            })
+        );
+        assert_eq!(
+            schema.content_from_object_json(r#"{"output": ["a", "b"]}"#),
+            r#"["a","b"]"#
+        );
+        assert_eq!(
+            schema.content_from_object(&json!({ "output": "plain text" })),
+            "plain text"
+        );
+    }
+
+    #[test]
+    fn openapi_parameters_use_nullable() {
+        let schema = OutputSchema::new(
+            "Summarize",
+            OutputFormatContent::target(FieldType::Optional(Box::new(FieldType::string()))).build(),
+        );
+
+        assert_eq!(
+            schema.openapi_object_schema().unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "output": { "type": "string", "nullable": true },
+                },
+                "required": ["output"],
+            })
+        );
+    }
+
+    #[test]
+    fn openapi_schema_is_not_wrapped() {
+        let schema = OutputSchema::new(
+            "Summarize",
+            OutputFormatContent::target(FieldType::List(Box::new(FieldType::Optional(Box::new(
+                FieldType::int(),
+            )))))
+            .build(),
+        );
+
+        assert_eq!(
+            schema.openapi_schema().unwrap(),
+            json!({
+                "type": "array",
+                "items": { "type": "integer", "nullable": true },
+            })
+        );
+    }
+
+    #[test]
+    fn partial_arguments_are_passed_through() {
+        let schema = OutputSchema::new(
+            "Summarize",
+            OutputFormatContent::target(FieldType::string()).build(),
+        );
+
+        assert_eq!(
+            schema.content_from_object_json(r#"{"output": "par"#),
+            r#"{"output": "par"#
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::internal::llm_client::{
     properties_hander::PropertiesHandler,
     traits::{ToProviderMessage, ToProviderMessageExt, WithClientProperties},
-    AllowedMetadata, ResolveMediaUrls, SupportedRequestModes,
+    AllowedMetadata, RequestTimeouts, ResolveMediaUrls, SupportedRequestModes,
 };
 use std::collections::HashMap;
 
@@ -26,7 +26,7 @@ use crate::{
             WithRetryPolicy, WithStreamChat,
         },
         ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
-        ModelFeatures,
+        ModelFeatures, OutputMode, OutputSchema,
     },
     request::create_client,
 };
@@ -47,6 +47,7 @@ struct PostRequestProperities {
     // These are passed directly to the Anthropic API.
     properties: HashMap<String, serde_json::Value>,
     supported_request_modes: SupportedRequestModes,
+    timeouts: RequestTimeouts,
 }
 
 // represents client that interacts with the Anthropic API
@@ -84,6 +85,12 @@ fn resolve_properties(
         .or_insert("2023-06-01".to_string());
 
         let supported_request_modes = properties.pull_supported_request_modes()?;
+        let timeouts = properties.pull_timeouts()?;
+    if supported_request_modes.output == OutputMode::JsonSchema {
+        anyhow::bail!(
+            "anthropic does not support output_mode json_schema, use output_mode tool instead"
+        );
+    }
 
     let mut properties = properties.finalize();
     // Anthropic has a very low max_tokens by default, so we increase it to 4096.
@@ -102,6 +109,7 @@ fn resolve_properties(
         properties,
         proxy_url: ctx.env.get("BOUNDARY_PROXY_URL").map(|s| s.to_string()),
         supported_request_modes,
+        timeouts,
     })
 }
 
@@ -116,12 +124,18 @@ impl WithClientProperties for AnthropicClient {
     fn allowed_metadata(&self) -> &AllowedMetadata {
         &self.properties.allowed_metadata
     }
+    fn timeouts(&self) -> &RequestTimeouts {
+        &self.properties.timeouts
+    }
     fn client_properties(&self) -> &HashMap<String, serde_json::Value> {
         &self.properties.properties
     }
     fn supports_streaming(&self) -> bool {
         self.properties.supported_request_modes.stream.unwrap_or(true)
     }
+    fn output_mode(&self) -> OutputMode {
+        self.properties.supported_request_modes.output
+    }
 }
 
 impl WithClient for AnthropicClient {
@@ -142,12 +156,14 @@ impl SseResponseTrait for AnthropicClient {
         &self,
         resp: reqwest::Response,
         prompt: &Vec<RenderedChatMessage>,
+        output_schema: Option<OutputSchema>,
         system_start: web_time::SystemTime,
         instant_start: web_time::Instant,
     ) -> StreamResponse {
         let prompt = prompt.clone();
         let client_name = self.context.name.clone();
         let params = self.properties.properties.clone();
+        let mut tool_arguments = String::new();
 
         Ok(Box::pin(
             resp.bytes_stream()
@@ -195,6 +211,7 @@ impl SseResponseTrait for AnthropicClient {
                                         latency: instant_start.elapsed(),
                                         message: format!("Failed to parse event: {:#?}", e),
                                         code: ErrorCode::UnsupportedResponse(2),
+                                        retry_after: None,
                                     },
                                 )));
                             }
@@ -208,6 +225,7 @@ impl SseResponseTrait for AnthropicClient {
                                     Some(StopReason::StopSequence) | Some(StopReason::EndTurn) => {
                                         true
                                     }
+                                    Some(StopReason::ToolUse) => output_schema.is_some(),
                                     _ => false,
                                 };
                                 inner.finish_reason =
@@ -218,7 +236,15 @@ impl SseResponseTrait for AnthropicClient {
                                     Some(body.usage.input_tokens + body.usage.output_tokens);
                             }
                             MessageChunk::ContentBlockDelta(event) => {
-                                inner.content += &event.delta.text;
+                                match (output_schema.as_ref(), event.delta.partial_json) {
+                                    // The output tool is forced, so it's the only tool_use block.
+                                    (Some(schema), Some(fragment)) => {
+                                        tool_arguments += &fragment;
+                                        inner.content =
+                                            schema.content_from_object_json(&tool_arguments);
+                                    }
+                                    _ => inner.content += &event.delta.text,
+                                }
                             }
                             MessageChunk::ContentBlockStart(_) => (),
                             MessageChunk::ContentBlockStop(_) => (),
@@ -230,6 +256,7 @@ impl SseResponseTrait for AnthropicClient {
                                     Some(StopReason::StopSequence) | Some(StopReason::EndTurn) => {
                                         true
                                     }
+                                    Some(StopReason::ToolUse) => output_schema.is_some(),
                                     _ => false,
                                 };
                                 inner.finish_reason = body
@@ -260,6 +287,7 @@ impl SseResponseTrait for AnthropicClient {
                                         latency: instant_start.elapsed(),
                                         message: err.message,
                                         code: ErrorCode::Other(2),
+                                        retry_after: None,
                                     },
                                 )));
                             }
@@ -277,20 +305,31 @@ impl SseResponseTrait for AnthropicClient {
 impl WithStreamChat for AnthropicClient {
     async fn stream_chat(
         &self,
-        _ctx: &RuntimeContext,
+        ctx: &RuntimeContext,
         prompt: &Vec<RenderedChatMessage>,
     ) -> StreamResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v.cloned(),
+            Err(e) => return Err(LLMResponse::InternalFailure(format!("{:#}", e))),
+        };
         let (response, system_now, instant_now) =
-            match make_request(self, either::Either::Right(prompt), true).await {
+            match make_request(self, ctx, either::Either::Right(prompt), true).await {
                 Ok(v) => v,
                 Err(e) => return Err(e),
             };
-        self.response_stream(response, prompt, system_now, instant_now)
+        self.response_stream(response, prompt, output_schema, system_now, instant_now)
     }
 }
 
 // constructs base client and resolves properties based on context
 impl AnthropicClient {
+    fn output_schema<'a>(&self, ctx: &'a RuntimeContext) -> Result<Option<&'a OutputSchema>> {
+        self.properties
+            .supported_request_modes
+            .output
+            .output_schema(ctx)
+    }
+
     pub fn dynamic_new(client: &ClientProperty, ctx: &RuntimeContext) -> Result<Self> {
         let properties = resolve_properties(client.property_handler()?, ctx)?;
         let default_role = properties.default_role.clone();
@@ -351,6 +390,7 @@ impl RequestBuilder for AnthropicClient {
 
     async fn build_request(
         &self,
+        ctx: &RuntimeContext,
         prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
         allow_proxy: bool,
         stream: bool,
@@ -391,6 +431,21 @@ impl RequestBuilder for AnthropicClient {
             }
         }
 
+        if let Some(schema) = self.output_schema(ctx)? {
+            body_obj.insert(
+                "tools".into(),
+                json!([{
+                    "name": schema.name(),
+                    "description": schema.description(),
+                    "input_schema": schema.object_schema()?,
+                }]),
+            );
+            body_obj.insert(
+                "tool_choice".into(),
+                json!({ "type": "tool", "name": schema.name() }),
+            );
+        }
+
         if stream {
             body_obj.insert("stream".into(), true.into());
         }
@@ -411,19 +466,35 @@ impl WithChat for AnthropicClient {
         ))
     }
 
-    async fn chat(&self, _ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
-        let (response, system_now, instant_now) = match make_parsed_request::<
-            AnthropicMessageResponse,
-        >(
-            self, either::Either::Right(prompt), false
-        )
-        .await
-        {
+    async fn chat(&self, ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+        let output_schema = match self.output_schema(ctx) {
             Ok(v) => v,
-            Err(e) => return e,
+            Err(e) => return LLMResponse::InternalFailure(format!("{:#}", e)),
         };
 
-        if response.content.len() != 1 {
+        let (response, system_now, instant_now) =
+            match make_parsed_request::<AnthropicMessageResponse>(
+                self,
+                ctx,
+                either::Either::Right(prompt),
+                false,
+            )
+            .await
+            {
+                Ok(v) => v,
+                Err(e) => return e,
+            };
+
+        let tool_call = output_schema.and_then(|schema| {
+            response
+                .content
+                .iter()
+                .find(|c| c.name.as_deref() == Some(schema.name()))
+                .and_then(|c| c.input.as_ref())
+                .map(|input| schema.content_from_object(input))
+        });
+
+        if tool_call.is_none() && response.content.len() != 1 {
             return LLMResponse::LLMFailure(LLMErrorResponse {
                 client: self.context.name.to_string(),
                 model: None,
@@ -436,13 +507,14 @@ impl WithChat for AnthropicClient {
                     response.content.len()
                 ),
                 code: ErrorCode::Other(200),
+                retry_after: None,
             });
         }
 
         LLMResponse::Success(LLMCompleteResponse {
             client: self.context.name.to_string(),
             prompt: internal_baml_jinja::RenderedPrompt::Chat(prompt.clone()),
-            content: response.content[0].text.clone(),
+            content: tool_call.unwrap_or_else(|| response.content[0].text.clone()),
             start_time: system_now,
             latency: instant_now.elapsed(),
             request_options: self.properties.properties.clone(),
@@ -450,6 +522,7 @@ impl WithChat for AnthropicClient {
             metadata: LLMCompleteResponseMetadata {
                 baml_is_complete: match response.stop_reason {
                     Some(StopReason::StopSequence) | Some(StopReason::EndTurn) => true,
+                    Some(StopReason::ToolUse) => output_schema.is_some(),
                     _ => false,
                 },
                 finish_reason: response
@@ -4,7 +4,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct AnthropicMessageContent {
     pub r#type: String,
+    #[serde(default)]
     pub text: String,
+    /// The tool called by a `tool_use` block.
+    pub name: Option<String>,
+    /// The arguments of a `tool_use` block.
+    pub input: Option<serde_json::Value>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -32,6 +37,7 @@ pub enum StopReason {
     MaxTokens,
     StopSequence,
     EndTurn,
+    ToolUse,
     #[serde(other)]
     Unknown,
 }
@@ -56,6 +62,8 @@ pub enum ContentType {
     ToolUse,
     /// tool_result
     ToolResult,
+    /// input_json_delta
+    InputJsonDelta,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -63,7 +71,8 @@ pub struct TextContentBlock {
     /// The content type. It is always `text`.
     #[serde(rename = "type")]
     pub _type: ContentType,
-    /// The text content.
+    /// The text content. Empty for `tool_use` blocks.
+    #[serde(default)]
     pub text: String,
 }
 
@@ -144,11 +153,14 @@ pub struct MessageDeltaChunk {
 /// The text delta content block.
 #[derive(Debug, Deserialize, Clone, PartialEq, Serialize)]
 pub struct TextDeltaContentBlock {
-    /// The content type. It is always `text_delta`.
+    /// The content type, `text_delta` or `input_json_delta`.
     #[serde(rename = "type")]
     pub _type: ContentType,
     /// The text delta content.
+    #[serde(default)]
     pub text: String,
+    /// A fragment of the JSON arguments of a `tool_use` block.
+    pub partial_json: Option<String>,
 }
 
 /// The stream stop information.
@@ -181,6 +193,7 @@ mod tests {
             delta: TextDeltaContentBlock {
                 _type: ContentType::TextDelta,
                 text: "Hello".to_string(),
+                partial_json: None,
             },
         });
         println!("serialized = {}", serde_json::to_string(&chunk)?);
@@ -7,7 +7,7 @@ use aws_sdk_bedrockruntime::{self as bedrock, operation::converse::ConverseOutpu
 use anyhow::{Context, Result};
 use aws_smithy_json::serialize::JsonObjectWriter;
 use aws_smithy_runtime_api::client::result::SdkError;
-use aws_smithy_types::Blob;
+use aws_smithy_types::{Blob, Document, Number};
 use baml_types::BamlMediaContent;
 use baml_types::{BamlMedia, BamlMediaType};
 use futures::stream;
@@ -19,7 +19,7 @@ use web_time::Instant;
 use web_time::SystemTime;
 
 use crate::internal::llm_client::traits::{ToProviderMessageExt, WithClientProperties};
-use crate::internal::llm_client::{AllowedMetadata, SupportedRequestModes};
+use crate::internal::llm_client::{AllowedMetadata, RequestTimeouts, SupportedRequestModes};
 use crate::internal::llm_client::{
     primitive::request::RequestBuilder,
     traits::{
@@ -27,7 +27,7 @@ use crate::internal::llm_client::{
         WithStreamChat,
     },
     ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
-    ModelFeatures, ResolveMediaUrls,
+    ModelFeatures, OutputMode, OutputSchema, ResolveMediaUrls,
 };
 
 use crate::{RenderCurlSettings, RuntimeContext};
@@ -45,6 +45,7 @@ struct RequestProperties {
     request_options: HashMap<String, serde_json::Value>,
     ctx_env: HashMap<String, String>,
     supported_request_modes: SupportedRequestModes,
+    timeouts: RequestTimeouts,
 }
 
 // represents client that interacts with the Anthropic API
@@ -90,6 +91,12 @@ fn resolve_properties(client: &ClientWalker, ctx: &RuntimeContext) -> Result<Req
         .unwrap_or_else(|_| ctx.env.get("AWS_REGION").map(|s| s.to_string()));
 
     let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
+    if supported_request_modes.output == OutputMode::JsonSchema {
+        anyhow::bail!(
+            "aws-bedrock does not support output_mode json_schema, use output_mode tool instead"
+        );
+    }
 
     Ok(RequestProperties {
         model_id,
@@ -100,6 +107,7 @@ fn resolve_properties(client: &ClientWalker, ctx: &RuntimeContext) -> Result<Req
         request_options: properties.finalize(),
         ctx_env: ctx.env.clone(),
         supported_request_modes,
+        timeouts,
     })
 }
 
@@ -189,13 +197,37 @@ impl AwsClient {
         Ok(bedrock::Client::new(&config))
     }
 
-    async fn chat_anyhow<'r>(&self, response: &'r ConverseOutput) -> Result<&'r String> {
+    fn output_schema<'a>(&self, ctx: &'a RuntimeContext) -> Result<Option<&'a OutputSchema>> {
+        self.properties
+            .supported_request_modes
+            .output
+            .output_schema(ctx)
+    }
+
+    async fn chat_anyhow(
+        &self,
+        response: &ConverseOutput,
+        output_schema: Option<&OutputSchema>,
+    ) -> Result<String> {
         let Some(bedrock::types::ConverseOutput::Message(ref message)) = response.output else {
             anyhow::bail!(
                 "Expected message output in response, but is type {}",
                 "unknown"
             );
         };
+        if let Some(schema) = output_schema {
+            let tool_use = message.content.iter().find_map(|block| match block {
+                bedrock::types::ContentBlock::ToolUse(tool_use)
+                    if tool_use.name == schema.name() =>
+                {
+                    Some(tool_use)
+                }
+                _ => None,
+            });
+            if let Some(tool_use) = tool_use {
+                return Ok(schema.content_from_object(&document_to_json(&tool_use.input)));
+            }
+        }
         let content = message
             .content
             .get(0)
@@ -214,7 +246,7 @@ impl AwsClient {
             );
         };
 
-        Ok(content)
+        Ok(content.clone())
     }
 
     fn build_request(
@@ -243,16 +275,79 @@ impl AwsClient {
             .map(|m| self.role_to_message(m))
             .collect::<Result<Vec<_>>>()?;
 
+        let tool_config = match self.output_schema(ctx)? {
+            Some(schema) => Some(
+                bedrock::types::ToolConfiguration::builder()
+                    .tools(bedrock::types::Tool::ToolSpec(
+                        bedrock::types::ToolSpecification::builder()
+                            .name(schema.name())
+                            .description(schema.description())
+                            .input_schema(bedrock::types::ToolInputSchema::Json(json_to_document(
+                                &schema.object_schema()?,
+                            )))
+                            .build()
+                            .context("Failed to build output tool")?,
+                    ))
+                    .tool_choice(bedrock::types::ToolChoice::Tool(
+                        bedrock::types::SpecificToolChoice::builder()
+                            .name(schema.name())
+                            .build()
+                            .context("Failed to build output tool choice")?,
+                    ))
+                    .build()
+                    .context("Failed to build output tool configuration")?,
+            ),
+            None => None,
+        };
+
         bedrock::operation::converse::ConverseInput::builder()
             .set_inference_config(self.properties.inference_config.clone())
             .set_model_id(Some(self.properties.model_id.clone()))
             .set_system(system_message)
             .set_messages(Some(converse_messages))
+            .set_tool_config(tool_config)
             .build()
             .context("Failed to convert BAML prompt to AWS Bedrock request")
     }
 }
 
+fn json_to_document(value: &serde_json::Value) -> Document {
+    match value {
+        serde_json::Value::Null => Document::Null,
+        serde_json::Value::Bool(b) => Document::Bool(*b),
+        serde_json::Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => Document::Number(Number::PosInt(u)),
+            (None, Some(i)) => Document::Number(Number::NegInt(i)),
+            _ => Document::Number(Number::Float(n.as_f64().unwrap_or_default())),
+        },
+        serde_json::Value::String(s) => Document::String(s.clone()),
+        serde_json::Value::Array(items) => {
+            Document::Array(items.iter().map(json_to_document).collect())
+        }
+        serde_json::Value::Object(map) => Document::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), json_to_document(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn document_to_json(document: &Document) -> serde_json::Value {
+    match document {
+        Document::Null => serde_json::Value::Null,
+        Document::Bool(b) => (*b).into(),
+        Document::Number(Number::PosInt(u)) => (*u).into(),
+        Document::Number(Number::NegInt(i)) => (*i).into(),
+        Document::Number(Number::Float(f)) => (*f).into(),
+        Document::String(s) => s.clone().into(),
+        Document::Array(items) => items.iter().map(document_to_json).collect(),
+        Document::Object(map) => map
+            .iter()
+            .map(|(k, v)| (k.clone(), document_to_json(v)))
+            .collect(),
+    }
+}
+
 fn try_to_json<
     Ser: Fn(
         &mut JsonObjectWriter,
@@ -305,9 +400,15 @@ impl WithClientProperties for AwsClient {
     fn allowed_metadata(&self) -> &crate::internal::llm_client::AllowedMetadata {
         &self.properties.allowed_metadata
     }
+    fn timeouts(&self) -> &RequestTimeouts {
+        &self.properties.timeouts
+    }
     fn supports_streaming(&self) -> bool {
         self.properties.supported_request_modes.stream.unwrap_or(true)
     }
+    fn output_mode(&self) -> OutputMode {
+        self.properties.supported_request_modes.output
+    }
 }
 
 impl WithClient for AwsClient {
@@ -345,10 +446,16 @@ impl WithStreamChat for AwsClient {
                     latency: web_time::Duration::ZERO,
                     message: format!("{:#?}", e),
                     code: ErrorCode::Other(2),
+                    retry_after: None,
                 }));
             }
         };
 
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v.cloned(),
+            Err(e) => return Err(LLMResponse::InternalFailure(format!("{:#}", e))),
+        };
+
         let request = match self.build_request(ctx, chat_messages) {
             Ok(r) => r,
             Err(e) => {
@@ -361,6 +468,7 @@ impl WithStreamChat for AwsClient {
                     latency: web_time::Duration::ZERO,
                     message: format!("{:#?}", e),
                     code: ErrorCode::Other(2),
+                    retry_after: None,
                 }))
             }
         };
@@ -370,7 +478,8 @@ impl WithStreamChat for AwsClient {
             .set_model_id(request.model_id)
             .set_inference_config(request.inference_config)
             .set_system(request.system)
-            .set_messages(request.messages);
+            .set_messages(request.messages)
+            .set_tool_config(request.tool_config);
 
         let system_start = SystemTime::now();
         let instant_start = Instant::now();
@@ -412,6 +521,7 @@ impl WithStreamChat for AwsClient {
                         }
                         _ => ErrorCode::Other(2),
                     },
+                    retry_after: None,
                 }));
             }
         };
@@ -435,8 +545,10 @@ impl WithStreamChat for AwsClient {
                     },
                 }),
                 response,
+                String::new(),
             ),
-            move |(initial_state, mut response)| {
+            move |(initial_state, mut response, mut tool_arguments)| {
+                let output_schema = output_schema.clone();
                 async move {
                     let Some(mut new_state) = initial_state else {
                         return None;
@@ -448,14 +560,26 @@ impl WithStreamChat for AwsClient {
                                 bedrock::types::ConverseStreamOutput::ContentBlockDelta(
                                     content_block_delta,
                                 ) => {
-                                    if let Some(bedrock::types::ContentBlockDelta::Text(
-                                        ref delta,
-                                    )) = content_block_delta.delta
-                                    {
-                                        new_state.content += delta;
-                                        // TODO- handle
+                                    match (content_block_delta.delta, output_schema.as_ref()) {
+                                        (
+                                            Some(bedrock::types::ContentBlockDelta::Text(delta)),
+                                            _,
+                                        ) => {
+                                            new_state.content += &delta;
+                                        }
+                                        // The output tool is forced, so it's the only tool use.
+                                        (
+                                            Some(bedrock::types::ContentBlockDelta::ToolUse(delta)),
+                                            Some(schema),
+                                        ) => {
+                                            tool_arguments += &delta.input;
+                                            new_state.content =
+                                                schema.content_from_object_json(&tool_arguments);
+                                        }
+                                        _ => {
+                                            // TODO- handle
+                                        }
                                     }
-                                    // TODO- handle
                                 }
                                 bedrock::types::ConverseStreamOutput::ContentBlockStart(_) => {
                                     // TODO- handle
@@ -470,6 +594,9 @@ impl WithStreamChat for AwsClient {
                                     new_state.metadata.baml_is_complete = match stop.stop_reason {
                                         bedrock::types::StopReason::StopSequence
                                         | bedrock::types::StopReason::EndTurn => true,
+                                        bedrock::types::StopReason::ToolUse => {
+                                            output_schema.is_some()
+                                        }
                                         _ => false,
                                     };
                                     // TODO- handle
@@ -491,7 +618,7 @@ impl WithStreamChat for AwsClient {
                             new_state.latency = instant_start.elapsed();
                             Some((
                                 LLMResponse::Success(new_state.clone()),
-                                (Some(new_state), response),
+                                (Some(new_state), response, tool_arguments),
                             ))
                         }
                         Ok(None) => None,
@@ -505,8 +632,9 @@ impl WithStreamChat for AwsClient {
                                 latency: instant_start.elapsed(),
                                 message: format!("Failed to parse event: {:#?}", e),
                                 code: ErrorCode::Other(2),
+                                retry_after: None,
                             }),
-                            (None, response),
+                            (None, response, tool_arguments),
                         )),
                     }
                 }
@@ -625,7 +753,7 @@ impl WithChat for AwsClient {
 
     async fn chat(
         &self,
-        _ctx: &RuntimeContext,
+        ctx: &RuntimeContext,
         chat_messages: &Vec<RenderedChatMessage>,
     ) -> LLMResponse {
         let client = self.context.name.to_string();
@@ -645,11 +773,17 @@ impl WithChat for AwsClient {
                     latency: web_time::Duration::ZERO,
                     message: format!("{:#?}", e),
                     code: ErrorCode::Other(2),
+                    retry_after: None,
                 })
             }
         };
 
-        let request = match self.build_request(_ctx, chat_messages) {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v,
+            Err(e) => return LLMResponse::InternalFailure(format!("{:#}", e)),
+        };
+
+        let request = match self.build_request(ctx, chat_messages) {
             Ok(r) => r,
             Err(e) => {
                 return LLMResponse::LLMFailure(LLMErrorResponse {
@@ -661,6 +795,7 @@ impl WithChat for AwsClient {
                     latency: web_time::Duration::ZERO,
                     message: format!("{:#?}", e),
                     code: ErrorCode::Other(2),
+                    retry_after: None,
                 })
             }
         };
@@ -669,7 +804,8 @@ impl WithChat for AwsClient {
             .set_model_id(request.model_id)
             .set_inference_config(request.inference_config)
             .set_system(request.system)
-            .set_messages(request.messages);
+            .set_messages(request.messages)
+            .set_tool_config(request.tool_config);
 
         let system_start = SystemTime::now();
         let instant_start = Instant::now();
@@ -687,15 +823,16 @@ impl WithChat for AwsClient {
                     message: format!("{:#?}", e),
                     // TODO: derive this from the aws-returned error
                     code: ErrorCode::Other(2),
+                    retry_after: None,
                 });
             }
         };
 
-        match self.chat_anyhow(&response).await {
+        match self.chat_anyhow(&response, output_schema).await {
             Ok(content) => LLMResponse::Success(LLMCompleteResponse {
                 client,
                 prompt,
-                content: content.clone(),
+                content,
                 start_time: system_start.clone(),
                 latency: instant_start.elapsed(),
                 request_options,
@@ -704,6 +841,7 @@ impl WithChat for AwsClient {
                     baml_is_complete: match response.stop_reason {
                         bedrock::types::StopReason::StopSequence
                         | bedrock::types::StopReason::EndTurn => true,
+                        bedrock::types::StopReason::ToolUse => output_schema.is_some(),
                         _ => false,
                     },
                     finish_reason: Some(response.stop_reason().as_str().into()),
@@ -733,6 +871,7 @@ impl WithChat for AwsClient {
                 latency: instant_start.elapsed(),
                 message: format!("{:#?}", e),
                 code: ErrorCode::Other(200),
+                retry_after: None,
             }),
         }
     }
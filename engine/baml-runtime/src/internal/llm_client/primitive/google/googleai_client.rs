@@ -3,12 +3,14 @@ use crate::internal::llm_client::properties_hander::{PropertiesHandler};
 use crate::internal::llm_client::traits::{
     ToProviderMessage, ToProviderMessageExt, WithClientProperties,
 };
-use crate::internal::llm_client::{AllowedMetadata, ResolveMediaUrls, SupportedRequestModes};
+use crate::internal::llm_client::{
+    AllowedMetadata, RequestTimeouts, ResolveMediaUrls, SupportedRequestModes,
+};
 use crate::RuntimeContext;
 use crate::{
     internal::llm_client::{
         primitive::{
-            google::types::{FinishReason, GoogleResponse},
+            google::types::{FinishReason, GoogleResponse, Part},
             request::{make_parsed_request, make_request, RequestBuilder},
         },
         traits::{
@@ -16,7 +18,7 @@ use crate::{
             WithRetryPolicy, WithStreamChat,
         },
         ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
-        ModelFeatures,
+        ModelFeatures, OutputMode, OutputSchema,
     },
     request::create_client,
 };
@@ -39,6 +41,7 @@ struct PostRequestProperities {
     properties: HashMap<String, serde_json::Value>,
     allowed_metadata: AllowedMetadata,
     supported_request_modes: SupportedRequestModes,
+    timeouts: RequestTimeouts,
 }
 
 pub struct GoogleAIClient {
@@ -71,6 +74,7 @@ fn resolve_properties(
     let headers = properties.pull_headers()?;
 
     let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
 
     Ok(PostRequestProperities {
         default_role,
@@ -82,6 +86,7 @@ fn resolve_properties(
         properties: properties.finalize(),
         allowed_metadata,
         supported_request_modes,
+        timeouts,
     })
 }
 
@@ -98,9 +103,15 @@ impl WithClientProperties for GoogleAIClient {
     fn allowed_metadata(&self) -> &crate::internal::llm_client::AllowedMetadata {
         &self.properties.allowed_metadata
     }
+    fn timeouts(&self) -> &RequestTimeouts {
+        &self.properties.timeouts
+    }
     fn supports_streaming(&self) -> bool {
         self.properties.supported_request_modes.stream.unwrap_or(true)
     }
+    fn output_mode(&self) -> OutputMode {
+        self.properties.supported_request_modes.output
+    }
 }
 
 impl WithClient for GoogleAIClient {
@@ -120,6 +131,7 @@ impl SseResponseTrait for GoogleAIClient {
         &self,
         resp: reqwest::Response,
         prompt: &Vec<RenderedChatMessage>,
+        output_schema: Option<OutputSchema>,
         system_start: web_time::SystemTime,
         instant_start: web_time::Instant,
     ) -> StreamResponse {
@@ -178,13 +190,20 @@ impl SseResponseTrait for GoogleAIClient {
                                         latency: instant_start.elapsed(),
                                         message: format!("Failed to parse event: {:#?}", e),
                                         code: ErrorCode::UnsupportedResponse(2),
+                                        retry_after: None,
                                     },
                                 )));
                             }
                         };
 
                         if let Some(choice) = event.candidates.get(0) {
-                            if let Some(content) = choice.content.parts.get(0) {
+                            // Function calls arrive whole rather than in fragments.
+                            let tool_call = output_schema.as_ref().and_then(|schema| {
+                                tool_call_content(schema, &choice.content.parts)
+                            });
+                            if let Some(content) = tool_call {
+                                inner.content = content;
+                            } else if let Some(content) = choice.content.parts.get(0) {
                                 inner.content += &content.text;
                             }
                             match choice.finish_reason.as_ref() {
@@ -208,20 +227,31 @@ impl SseResponseTrait for GoogleAIClient {
 impl WithStreamChat for GoogleAIClient {
     async fn stream_chat(
         &self,
-        _ctx: &RuntimeContext,
+        ctx: &RuntimeContext,
         prompt: &Vec<RenderedChatMessage>,
     ) -> StreamResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v.cloned(),
+            Err(e) => return Err(LLMResponse::InternalFailure(format!("{:#}", e))),
+        };
         //incomplete, streaming response object is returned
         let (response, system_now, instant_now) =
-            match make_request(self, either::Either::Right(prompt), true).await {
+            match make_request(self, ctx, either::Either::Right(prompt), true).await {
                 Ok(v) => v,
                 Err(e) => return Err(e),
             };
-        self.response_stream(response, prompt, system_now, instant_now)
+        self.response_stream(response, prompt, output_schema, system_now, instant_now)
     }
 }
 
 impl GoogleAIClient {
+    fn output_schema<'a>(&self, ctx: &'a RuntimeContext) -> Result<Option<&'a OutputSchema>> {
+        self.properties
+            .supported_request_modes
+            .output
+            .output_schema(ctx)
+    }
+
     pub fn new(client: &ClientWalker, ctx: &RuntimeContext) -> Result<Self> {
         let properties = super::super::resolve_properties_walker(client, ctx)?;
         let properties = resolve_properties(properties, ctx)?;
@@ -282,6 +312,7 @@ impl RequestBuilder for GoogleAIClient {
 
     async fn build_request(
         &self,
+        ctx: &RuntimeContext,
         prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
         allow_proxy: bool,
         stream: bool,
@@ -329,6 +360,49 @@ impl RequestBuilder for GoogleAIClient {
             }
         }
 
+        match (
+            self.properties.supported_request_modes.output,
+            self.output_schema(ctx)?,
+        ) {
+            (OutputMode::JsonSchema, Some(schema)) => {
+                // Users may already set other generation options.
+                let key = if body_obj.contains_key("generation_config") {
+                    "generation_config"
+                } else {
+                    "generationConfig"
+                };
+                let config = body_obj
+                    .entry(key)
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut()
+                    .context(format!("{} must be an object", key))?;
+                config.insert("responseMimeType".into(), json!("application/json"));
+                config.insert("responseSchema".into(), schema.openapi_schema()?);
+            }
+            (_, Some(schema)) => {
+                body_obj.insert(
+                    "tools".into(),
+                    json!([{
+                        "functionDeclarations": [{
+                            "name": schema.name(),
+                            "description": schema.description(),
+                            "parameters": schema.openapi_object_schema()?,
+                        }],
+                    }]),
+                );
+                body_obj.insert(
+                    "toolConfig".into(),
+                    json!({
+                        "functionCallingConfig": {
+                            "mode": "ANY",
+                            "allowedFunctionNames": [schema.name()],
+                        },
+                    }),
+                );
+            }
+            (_, None) => {}
+        }
+
         Ok(req.json(&body))
     }
 
@@ -345,15 +419,24 @@ impl WithChat for GoogleAIClient {
         ))
     }
 
-    async fn chat(&self, _ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+    async fn chat(&self, ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v,
+            Err(e) => return LLMResponse::InternalFailure(format!("{:#}", e)),
+        };
+
         //non-streaming, complete response is returned
-        let (response, system_now, instant_now) =
-            match make_parsed_request::<GoogleResponse>(self, either::Either::Right(prompt), false)
-                .await
-            {
-                Ok(v) => v,
-                Err(e) => return e,
-            };
+        let (response, system_now, instant_now) = match make_parsed_request::<GoogleResponse>(
+            self,
+            ctx,
+            either::Either::Right(prompt),
+            false,
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => return e,
+        };
 
         if response.candidates.len() != 1 {
             return LLMResponse::LLMFailure(LLMErrorResponse {
@@ -368,13 +451,16 @@ impl WithChat for GoogleAIClient {
                     response.candidates.len()
                 ),
                 code: ErrorCode::Other(200),
+                retry_after: None,
             });
         }
 
         LLMResponse::Success(LLMCompleteResponse {
             client: self.context.name.to_string(),
             prompt: internal_baml_jinja::RenderedPrompt::Chat(prompt.clone()),
-            content: response.candidates[0].content.parts[0].text.clone(),
+            content: output_schema
+                .and_then(|schema| tool_call_content(schema, &response.candidates[0].content.parts))
+                .unwrap_or_else(|| response.candidates[0].content.parts[0].text.clone()),
             start_time: system_now,
             latency: instant_now.elapsed(),
             request_options: self.properties.properties.clone(),
@@ -383,7 +469,7 @@ impl WithChat for GoogleAIClient {
                 .properties
                 .get("model")
                 .and_then(|v| v.as_str().map(|s| s.to_string()))
-                .or_else(|| _ctx.env.get("default model").map(|s| s.to_string()))
+                .or_else(|| ctx.env.get("default model").map(|s| s.to_string()))
                 .unwrap_or_else(|| "".to_string()),
             metadata: LLMCompleteResponseMetadata {
                 baml_is_complete: match response.candidates[0].finish_reason {
@@ -402,6 +488,15 @@ impl WithChat for GoogleAIClient {
     }
 }
 
+/// The arguments of the call to the output tool, if the model made one.
+fn tool_call_content(schema: &OutputSchema, parts: &[Part]) -> Option<String> {
+    parts
+        .iter()
+        .filter_map(|part| part.function_call.as_ref())
+        .find(|call| call.name == schema.name())
+        .map(|call| schema.content_from_object(call.args.as_ref().unwrap_or(&json!({}))))
+}
+
 //simple, Map with key "prompt" and value of the prompt string
 fn convert_completion_prompt_to_body(prompt: &String) -> HashMap<String, serde_json::Value> {
     let mut map = HashMap::new();
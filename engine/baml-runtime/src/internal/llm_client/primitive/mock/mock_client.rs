@@ -0,0 +1,518 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use internal_baml_core::ir::ClientWalker;
+use internal_baml_jinja::{RenderContext_Client, RenderedChatMessage, RenderedPrompt};
+use serde::Deserialize;
+
+use crate::client_registry::ClientProperty;
+use crate::internal::llm_client::properties_hander::PropertiesHandler;
+use crate::internal::llm_client::traits::{
+    StreamResponse, WithChat, WithClient, WithClientProperties, WithNoCompletion,
+    WithRenderRawCurl, WithRetryPolicy, WithStreamChat,
+};
+use crate::internal::llm_client::{
+    AllowedMetadata, ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse,
+    LLMResponse, ModelFeatures, OutputMode, RequestTimeouts, ResolveMediaUrls,
+    SupportedRequestModes,
+};
+use crate::{RenderCurlSettings, RuntimeContext};
+
+const DEFAULT_CHUNK_SIZE: usize = 16;
+
+/// Variables available in a mock client's `template`.
+const TEMPLATE_VARIABLES: [&str; 4] = ["prompt", "last_message", "client", "model"];
+
+/// A single canned response, as written in the `responses` option.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum MockResponse {
+    Text(String),
+    Error {
+        error_status: u16,
+        message: Option<String>,
+        /// Reported as the failure's `Retry-After`.
+        retry_after_ms: Option<u64>,
+    },
+}
+
+enum MockSource {
+    Fixed(MockResponse),
+    /// Read from this path on every call, so the file can be edited without a reload.
+    File(String),
+    /// Each call takes the next response, wrapping around at the end.
+    Cycle {
+        responses: Vec<MockResponse>,
+        next: AtomicUsize,
+    },
+    Template(String),
+}
+
+struct PostRequestProperities {
+    default_role: String,
+    model: String,
+    source: MockSource,
+    latency: Duration,
+    chunk_size: usize,
+    chunk_delay: Duration,
+    properties: HashMap<String, serde_json::Value>,
+    allowed_metadata: AllowedMetadata,
+    supported_request_modes: SupportedRequestModes,
+    timeouts: RequestTimeouts,
+}
+
+/// A client that never leaves the process: it answers every request with
+/// canned content from its options. Useful for local development and CI.
+pub struct MockClient {
+    pub name: String,
+    pub retry_policy: Option<String>,
+    pub context: RenderContext_Client,
+    pub features: ModelFeatures,
+    properties: PostRequestProperities,
+}
+
+fn resolve_properties(mut properties: PropertiesHandler) -> Result<PostRequestProperities> {
+    let default_role = properties.pull_default_role("user")?;
+    let model = properties
+        .remove_str("model")?
+        .unwrap_or_else(|| "mock".to_string());
+
+    let response = properties.remove_str("response")?;
+    let response_file = properties.remove_str("response_file")?;
+    let responses = properties.remove_serde::<Vec<MockResponse>>("responses")?;
+    let template = properties.remove_str("template")?;
+    let error_status = properties.remove_serde::<u16>("error_status")?;
+
+    let source = match (response, response_file, responses, template, error_status) {
+        (Some(response), None, None, None, None) => MockSource::Fixed(MockResponse::Text(response)),
+        (None, Some(path), None, None, None) => MockSource::File(path),
+        (None, None, Some(responses), None, None) => {
+            if responses.is_empty() {
+                anyhow::bail!("responses must not be empty");
+            }
+            MockSource::Cycle {
+                responses,
+                next: AtomicUsize::new(0),
+            }
+        }
+        (None, None, None, Some(template), None) => {
+            // Surface unknown variables when the client is created, not on the first call.
+            render_template(&template, &TEMPLATE_VARIABLES.map(|name| (name, "")))?;
+            MockSource::Template(template)
+        }
+        (None, None, None, None, Some(error_status)) => MockSource::Fixed(MockResponse::Error {
+            error_status,
+            message: None,
+            retry_after_ms: None,
+        }),
+        (None, None, None, None, None) => anyhow::bail!(
+            "mock clients require one of: response, response_file, responses, template, error_status"
+        ),
+        _ => anyhow::bail!(
+            "mock clients accept only one of: response, response_file, responses, template, error_status"
+        ),
+    };
+
+    let latency = Duration::from_millis(properties.remove_serde("latency_ms")?.unwrap_or(0));
+    let chunk_size = properties
+        .remove_serde::<usize>("chunk_size")?
+        .unwrap_or(DEFAULT_CHUNK_SIZE);
+    if chunk_size == 0 {
+        anyhow::bail!("chunk_size must be greater than 0");
+    }
+    let chunk_delay =
+        Duration::from_millis(properties.remove_serde("chunk_delay_ms")?.unwrap_or(0));
+
+    let allowed_metadata = properties.pull_allowed_role_metadata()?;
+    let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
+
+    Ok(PostRequestProperities {
+        default_role,
+        model,
+        source,
+        latency,
+        chunk_size,
+        chunk_delay,
+        properties: properties.finalize(),
+        allowed_metadata,
+        supported_request_modes,
+        timeouts,
+    })
+}
+
+impl WithRetryPolicy for MockClient {
+    fn retry_policy_name(&self) -> Option<&str> {
+        self.retry_policy.as_deref()
+    }
+}
+
+impl WithClientProperties for MockClient {
+    fn client_properties(&self) -> &HashMap<String, serde_json::Value> {
+        &self.properties.properties
+    }
+    fn allowed_metadata(&self) -> &AllowedMetadata {
+        &self.properties.allowed_metadata
+    }
+    fn timeouts(&self) -> &RequestTimeouts {
+        &self.properties.timeouts
+    }
+    fn supports_streaming(&self) -> bool {
+        self.properties
+            .supported_request_modes
+            .stream
+            .unwrap_or(true)
+    }
+    fn output_mode(&self) -> OutputMode {
+        self.properties.supported_request_modes.output
+    }
+}
+
+impl WithClient for MockClient {
+    fn context(&self) -> &RenderContext_Client {
+        &self.context
+    }
+
+    fn model_features(&self) -> &ModelFeatures {
+        &self.features
+    }
+}
+
+impl WithNoCompletion for MockClient {}
+
+impl WithRenderRawCurl for MockClient {
+    async fn render_raw_curl(
+        &self,
+        _ctx: &RuntimeContext,
+        _prompt: &Vec<RenderedChatMessage>,
+        _render_settings: RenderCurlSettings,
+    ) -> Result<String> {
+        Ok(format!(
+            "# {} is a mock client and does not make HTTP requests",
+            self.context.name
+        ))
+    }
+}
+
+impl WithChat for MockClient {
+    fn chat_options(&self, _ctx: &RuntimeContext) -> Result<internal_baml_jinja::ChatOptions> {
+        Ok(internal_baml_jinja::ChatOptions::new(
+            self.properties.default_role.clone(),
+            None,
+        ))
+    }
+
+    async fn chat(&self, ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+        let (system_now, instant_now) = (web_time::SystemTime::now(), web_time::Instant::now());
+        let response = self.next_response(ctx, prompt).await;
+        sleep(self.properties.latency).await;
+
+        match response {
+            Ok(MockResponse::Text(content)) => LLMResponse::Success(self.complete_response(
+                prompt,
+                content,
+                system_now,
+                instant_now,
+            )),
+            Ok(MockResponse::Error {
+                error_status,
+                message,
+                retry_after_ms,
+            }) => LLMResponse::LLMFailure(LLMErrorResponse {
+                retry_after: retry_after_ms.map(Duration::from_millis),
+                ..self.error_response(
+                    prompt,
+                    message.unwrap_or_else(|| format!("Mock error ({error_status})")),
+                    ErrorCode::from_u16(error_status),
+                    system_now,
+                    instant_now,
+                )
+            }),
+            Err(e) => LLMResponse::LLMFailure(self.error_response(
+                prompt,
+                format!("{:#?}", e),
+                ErrorCode::Other(2),
+                system_now,
+                instant_now,
+            )),
+        }
+    }
+}
+
+impl WithStreamChat for MockClient {
+    async fn stream_chat(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &Vec<RenderedChatMessage>,
+    ) -> StreamResponse {
+        // The whole response is resolved up front, and then replayed in chunks.
+        let response = match self.chat(ctx, prompt).await {
+            LLMResponse::Success(response) => response,
+            other => return Err(other),
+        };
+
+        let chunk_delay = self.properties.chunk_delay;
+        let instant_now = web_time::Instant::now() - response.latency;
+        let partials = partial_contents(&response.content, self.properties.chunk_size);
+        let last = partials.len() - 1;
+
+        Ok(Box::pin(
+            futures::stream::iter(partials.into_iter().enumerate()).then(move |(i, content)| {
+                let mut partial = response.clone();
+                async move {
+                    if i > 0 {
+                        sleep(chunk_delay).await;
+                    }
+                    partial.content = content;
+                    partial.latency = instant_now.elapsed();
+                    if i < last {
+                        partial.metadata.baml_is_complete = false;
+                        partial.metadata.finish_reason = None;
+                    }
+                    LLMResponse::Success(partial)
+                }
+            }),
+        ))
+    }
+}
+
+impl MockClient {
+    pub fn new(client: &ClientWalker, ctx: &RuntimeContext) -> Result<Self> {
+        let properties = super::super::resolve_properties_walker(client, ctx)?;
+        let properties = resolve_properties(properties)?;
+        let default_role = properties.default_role.clone();
+        Ok(Self {
+            name: client.name().into(),
+            context: RenderContext_Client {
+                name: client.name().into(),
+                provider: client.elem().provider.clone(),
+                default_role,
+            },
+            features: ModelFeatures {
+                chat: true,
+                completion: false,
+                anthropic_system_constraints: false,
+                resolve_media_urls: ResolveMediaUrls::Never,
+                allowed_metadata: properties.allowed_metadata.clone(),
+            },
+            retry_policy: client
+                .elem()
+                .retry_policy_id
+                .as_ref()
+                .map(|s| s.to_string()),
+            properties,
+        })
+    }
+
+    pub fn dynamic_new(client: &ClientProperty, _ctx: &RuntimeContext) -> Result<Self> {
+        let properties = resolve_properties(client.property_handler()?)?;
+        let default_role = properties.default_role.clone();
+
+        Ok(Self {
+            name: client.name.clone(),
+            context: RenderContext_Client {
+                name: client.name.clone(),
+                provider: client.provider.clone(),
+                default_role,
+            },
+            features: ModelFeatures {
+                chat: true,
+                completion: false,
+                anthropic_system_constraints: false,
+                resolve_media_urls: ResolveMediaUrls::Never,
+                allowed_metadata: properties.allowed_metadata.clone(),
+            },
+            retry_policy: client.retry_policy.clone(),
+            properties,
+        })
+    }
+
+    pub fn request_options(&self) -> &HashMap<String, serde_json::Value> {
+        &self.properties.properties
+    }
+
+    async fn next_response(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &[RenderedChatMessage],
+    ) -> Result<MockResponse> {
+        match &self.properties.source {
+            MockSource::Fixed(response) => Ok(response.clone()),
+            MockSource::File(path) => read_response_file(ctx, path).await.map(MockResponse::Text),
+            MockSource::Cycle { responses, next } => {
+                let index = next.fetch_add(1, Ordering::Relaxed) % responses.len();
+                Ok(responses[index].clone())
+            }
+            MockSource::Template(template) => {
+                let messages = prompt.iter().map(message_text).collect::<Vec<_>>();
+                let prompt = messages.join("\n");
+                let last_message = messages.last().cloned().unwrap_or_default();
+                render_template(
+                    template,
+                    &[
+                        ("prompt", prompt.as_str()),
+                        ("last_message", last_message.as_str()),
+                        ("client", self.context.name.as_str()),
+                        ("model", self.properties.model.as_str()),
+                    ],
+                )
+                .map(MockResponse::Text)
+            }
+        }
+    }
+
+    fn complete_response(
+        &self,
+        prompt: &[RenderedChatMessage],
+        content: String,
+        system_now: web_time::SystemTime,
+        instant_now: web_time::Instant,
+    ) -> LLMCompleteResponse {
+        LLMCompleteResponse {
+            client: self.context.name.clone(),
+            model: self.properties.model.clone(),
+            prompt: RenderedPrompt::Chat(prompt.to_vec()),
+            request_options: self.properties.properties.clone(),
+            content,
+            start_time: system_now,
+            latency: instant_now.elapsed(),
+            metadata: LLMCompleteResponseMetadata {
+                baml_is_complete: true,
+                finish_reason: Some("stop".to_string()),
+                prompt_tokens: None,
+                output_tokens: None,
+                total_tokens: None,
+            },
+        }
+    }
+
+    fn error_response(
+        &self,
+        prompt: &[RenderedChatMessage],
+        message: String,
+        code: ErrorCode,
+        system_now: web_time::SystemTime,
+        instant_now: web_time::Instant,
+    ) -> LLMErrorResponse {
+        LLMErrorResponse {
+            client: self.context.name.clone(),
+            model: Some(self.properties.model.clone()),
+            prompt: RenderedPrompt::Chat(prompt.to_vec()),
+            start_time: system_now,
+            request_options: self.properties.properties.clone(),
+            latency: instant_now.elapsed(),
+            message,
+            code,
+            retry_after: None,
+        }
+    }
+}
+
+async fn sleep(duration: Duration) {
+    if !duration.is_zero() {
+        async_std::task::sleep(duration).await;
+    }
+}
+
+async fn read_response_file(ctx: &RuntimeContext, path: &str) -> Result<String> {
+    let bytes = match *ctx.baml_src {
+        Some(ref baml_src_reader) => baml_src_reader(path).await,
+        #[cfg(not(target_arch = "wasm32"))]
+        None => std::fs::read(path).map_err(Into::into),
+        #[cfg(target_arch = "wasm32")]
+        None => Err(anyhow::anyhow!(
+            "Internal error: no baml src reader provided"
+        )),
+    }
+    .context(format!("Failed to read response_file {}", path))?;
+
+    String::from_utf8(bytes).context(format!("response_file {} is not valid UTF-8", path))
+}
+
+fn message_text(message: &RenderedChatMessage) -> String {
+    message
+        .parts
+        .iter()
+        .filter_map(|part| part.as_text())
+        .map(String::as_str)
+        .collect()
+}
+
+/// Renders a mock client's `template`. Unknown names are an error so that
+/// typos don't silently produce empty responses.
+fn render_template(template: &str, variables: &[(&str, &str)]) -> Result<String> {
+    internal_baml_jinja::render_template(template, variables).with_context(|| {
+        format!(
+            "Failed to render mock template. Available variables are: {}",
+            TEMPLATE_VARIABLES.join(", ")
+        )
+    })
+}
+
+/// The accumulated content after each streamed chunk. Always has at least one entry.
+fn partial_contents(content: &str, chunk_size: usize) -> Vec<String> {
+    let chars = content.chars().collect::<Vec<_>>();
+    let mut partial = String::with_capacity(content.len());
+    let mut partials = chars
+        .chunks(chunk_size)
+        .map(|chunk| {
+            partial.extend(chunk);
+            partial.clone()
+        })
+        .collect::<Vec<_>>();
+    if partials.is_empty() {
+        partials.push(String::new());
+    }
+    partials
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let variables = [("last_message", "hello"), ("model", "mock")];
+        assert_eq!(
+            render_template("{{ model }} says {{last_message}}!", &variables).unwrap(),
+            "mock says hello!"
+        );
+        assert_eq!(
+            render_template(
+                "{% if model %}{{ last_message | upper }}{% endif %}",
+                &variables
+            )
+            .unwrap(),
+            "HELLO"
+        );
+        assert!(render_template("{{ nope }}", &variables).is_err());
+        assert!(render_template("{{ model", &variables).is_err());
+    }
+
+    #[test]
+    fn test_partial_contents() {
+        assert_eq!(partial_contents("abcde", 2), vec!["ab", "abcd", "abcde"]);
+        assert_eq!(partial_contents("", 2), vec![""]);
+    }
+
+    #[test]
+    fn test_responses_option() {
+        let responses: Vec<MockResponse> = serde_json::from_value(serde_json::json!([
+            "ok",
+            { "error_status": 429 }
+        ]))
+        .unwrap();
+        assert!(matches!(&responses[0], MockResponse::Text(s) if s == "ok"));
+        assert!(matches!(
+            responses[1],
+            MockResponse::Error {
+                error_status: 429,
+                message: None,
+                retry_after_ms: None
+            }
+        ));
+    }
+}
@@ -10,8 +10,8 @@ use crate::{
 };
 
 use self::{
-    anthropic::AnthropicClient, aws::AwsClient, google::GoogleAIClient, openai::OpenAIClient,
-    request::RequestBuilder, vertex::VertexClient,
+    anthropic::AnthropicClient, aws::AwsClient, google::GoogleAIClient, mock::MockClient,
+    openai::OpenAIClient, request::RequestBuilder, vertex::VertexClient,
 };
 
 use super::{
@@ -19,16 +19,21 @@ use super::{
         ExecutionScope, IterOrchestrator, OrchestrationScope, OrchestrationState, OrchestratorNode,
         OrchestratorNodeIterator,
     },
+    timeouts::{stream_with_timeouts, with_timeout, RequestTimeouts},
     traits::{
         WithClient, WithClientProperties, WithPrompt, WithRenderRawCurl, WithRetryPolicy,
         WithSingleCallable, WithStreamable,
     },
-    LLMResponse,
+    ErrorCode, LLMErrorResponse, LLMResponse,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+use super::cassette::CassetteSession;
+
 mod anthropic;
 mod aws;
 mod google;
+mod mock;
 mod openai;
 pub(super) mod request;
 mod vertex;
@@ -44,6 +49,7 @@ pub enum LLMPrimitive2 {
     GoogleAIClient,
     VertexClient,
     AwsClient,
+    MockClient,
 }
 
 // #[derive(Delegate)]
@@ -55,6 +61,7 @@ pub enum LLMPrimitiveProvider {
     Google(GoogleAIClient),
     Vertex(VertexClient),
     Aws(aws::AwsClient),
+    Mock(MockClient),
 }
 
 macro_rules! match_llm_provider {
@@ -66,6 +73,7 @@ macro_rules! match_llm_provider {
             LLMPrimitiveProvider::Google(client) => client.$method($($args),*).await,
             LLMPrimitiveProvider::Aws(client) => client.$method($($args),*).await,
             LLMPrimitiveProvider::Vertex(client) => client.$method($($args),*).await,
+            LLMPrimitiveProvider::Mock(client) => client.$method($($args),*).await,
         }
     };
 
@@ -76,6 +84,7 @@ macro_rules! match_llm_provider {
             LLMPrimitiveProvider::Google(client) => client.$method($($args),*),
             LLMPrimitiveProvider::Aws(client) => client.$method($($args),*),
             LLMPrimitiveProvider::Vertex(client) => client.$method($($args),*),
+            LLMPrimitiveProvider::Mock(client) => client.$method($($args),*),
         }
     };
 }
@@ -93,9 +102,15 @@ impl WithClientProperties for LLMPrimitiveProvider {
     fn allowed_metadata(&self) -> &super::AllowedMetadata {
         match_llm_provider!(self, allowed_metadata)
     }
+    fn timeouts(&self) -> &super::RequestTimeouts {
+        match_llm_provider!(self, timeouts)
+    }
     fn supports_streaming(&self) -> bool {
         match_llm_provider!(self, supports_streaming)
     }
+    fn output_mode(&self) -> super::OutputMode {
+        match_llm_provider!(self, output_mode)
+    }
 }
 
 impl TryFrom<(&ClientProperty, &RuntimeContext)> for LLMPrimitiveProvider {
@@ -110,12 +125,14 @@ impl TryFrom<(&ClientProperty, &RuntimeContext)> for LLMPrimitiveProvider {
             "anthropic" => AnthropicClient::dynamic_new(value, ctx).map(Into::into),
             "google-ai" => GoogleAIClient::dynamic_new(value, ctx).map(Into::into),
             "vertex-ai" => VertexClient::dynamic_new(value, ctx).map(Into::into),
+            "mock" => MockClient::dynamic_new(value, ctx).map(Into::into),
             // dynamic_new is not implemented for aws::AwsClient
             other => {
                 let options = [
                     "anthropic",
                     "azure-openai",
                     "google-ai",
+                    "mock",
                     "openai",
                     "openai-generic",
                     "vertex-ai",
@@ -149,12 +166,14 @@ impl TryFrom<(&ClientWalker<'_>, &RuntimeContext)> for LLMPrimitiveProvider {
             "google-ai" => GoogleAIClient::new(client, ctx).map(Into::into),
             "aws-bedrock" => aws::AwsClient::new(client, ctx).map(Into::into),
             "vertex-ai" => VertexClient::new(client, ctx).map(Into::into),
+            "mock" => MockClient::new(client, ctx).map(Into::into),
             other => {
                 let options = [
                     "anthropic",
                     "aws-bedrock",
                     "azure-openai",
                     "google-ai",
+                    "mock",
                     "openai",
                     "openai-generic",
                     "vertex-ai",
@@ -201,7 +220,35 @@ impl WithSingleCallable for LLMPrimitiveProvider {
         ctx: &RuntimeContext,
         prompt: &internal_baml_jinja::RenderedPrompt,
     ) -> LLMResponse {
-        match_llm_provider!(self, single_call, async, ctx, prompt)
+        #[cfg(not(target_arch = "wasm32"))]
+        let cassette = match CassetteSession::new(ctx, self, prompt) {
+            Ok(cassette) => cassette,
+            Err(e) => return LLMResponse::InternalFailure(format!("{:#}", e)),
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(replayed) = cassette.as_ref().and_then(|c| c.replay()) {
+            return replayed;
+        }
+
+        let on_timeout = self.timeout_response(prompt);
+        let request_timeout = self.timeouts().request;
+        let response = with_timeout(request_timeout, async {
+            match_llm_provider!(self, single_call, async, ctx, prompt)
+        })
+        .await
+        .unwrap_or_else(|| {
+            on_timeout(format!(
+                "Exceeded {} of {}ms",
+                RequestTimeouts::REQUEST_KEY,
+                request_timeout.unwrap_or_default().as_millis()
+            ))
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(cassette) = &cassette {
+            cassette.record(&response).await;
+        }
+        response
     }
 }
 
@@ -211,7 +258,48 @@ impl WithStreamable for LLMPrimitiveProvider {
         ctx: &RuntimeContext,
         prompt: &internal_baml_jinja::RenderedPrompt,
     ) -> super::traits::StreamResponse {
-        match_llm_provider!(self, stream, async, ctx, prompt)
+        #[cfg(not(target_arch = "wasm32"))]
+        let cassette = match CassetteSession::new(ctx, self, prompt) {
+            Ok(cassette) => cassette,
+            Err(e) => return Err(LLMResponse::InternalFailure(format!("{:#}", e))),
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(replayed) = cassette.as_ref().and_then(|c| c.replay_stream()) {
+            return replayed;
+        }
+
+        let started = web_time::Instant::now();
+        let on_timeout = self.timeout_response(prompt);
+        let timeouts = *self.timeouts();
+        let first_limit = crate::internal::llm_client::timeouts::min_limit(
+            timeouts.request,
+            timeouts.time_to_first_token,
+        );
+        // The first chunk may only arrive once the request resolves, so it
+        // is bound by the same limits as the chunks themselves.
+        let stream = match with_timeout(first_limit, async {
+            match_llm_provider!(self, stream, async, ctx, prompt)
+        })
+        .await
+        {
+            Some(stream) => stream_with_timeouts(stream, timeouts, started, on_timeout),
+            None => Err(on_timeout(format!(
+                "Exceeded {} of {}ms",
+                if first_limit == timeouts.request {
+                    RequestTimeouts::REQUEST_KEY
+                } else {
+                    RequestTimeouts::TIME_TO_FIRST_TOKEN_KEY
+                },
+                first_limit.unwrap_or_default().as_millis()
+            ))),
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let stream = match &cassette {
+            Some(cassette) => cassette.record_stream(stream).await,
+            None => stream,
+        };
+        stream
     }
 }
 
@@ -238,6 +326,7 @@ impl std::fmt::Display for LLMPrimitiveProvider {
             LLMPrimitiveProvider::Google(_) => write!(f, "Google"),
             LLMPrimitiveProvider::Aws(_) => write!(f, "AWS"),
             LLMPrimitiveProvider::Vertex(_) => write!(f, "Vertex"),
+            LLMPrimitiveProvider::Mock(_) => write!(f, "Mock"),
         }
     }
 }
@@ -250,6 +339,31 @@ impl LLMPrimitiveProvider {
     pub fn request_options(&self) -> &std::collections::HashMap<String, serde_json::Value> {
         match_llm_provider!(self, request_options)
     }
+
+    /// Builds the failure reported when a request with `prompt` times out,
+    /// given a message saying which limit was exceeded.
+    pub fn timeout_response(
+        &self,
+        prompt: &internal_baml_jinja::RenderedPrompt,
+    ) -> impl Fn(String) -> LLMResponse + Send + Sync + 'static {
+        let (start_time, started) = (web_time::SystemTime::now(), web_time::Instant::now());
+        let client = self.name().to_string();
+        let prompt = prompt.clone();
+        let request_options = self.request_options().clone();
+        move |message| {
+            LLMResponse::LLMFailure(LLMErrorResponse {
+                client: client.clone(),
+                model: None,
+                prompt: prompt.clone(),
+                request_options: request_options.clone(),
+                start_time,
+                latency: started.elapsed(),
+                message,
+                code: ErrorCode::Timeout,
+                retry_after: None,
+            })
+        }
+    }
 }
 
 use super::resolve_properties_walker;
@@ -24,7 +24,7 @@ use crate::internal::llm_client::traits::{
 };
 use crate::internal::llm_client::{
     traits::{WithChat, WithClient, WithNoCompletion, WithRetryPolicy},
-    LLMResponse, ModelFeatures,
+    LLMResponse, ModelFeatures, OutputMode, OutputSchema,
 };
 
 use crate::request::create_client;
@@ -57,6 +57,9 @@ impl WithClientProperties for OpenAIClient {
     fn allowed_metadata(&self) -> &crate::internal::llm_client::AllowedMetadata {
         &self.properties.allowed_metadata
     }
+    fn timeouts(&self) -> &crate::internal::llm_client::RequestTimeouts {
+        &self.properties.timeouts
+    }
     fn supports_streaming(&self) -> bool {
         match self.properties.supported_request_modes.stream {
             Some(v) => v,
@@ -71,6 +74,9 @@ impl WithClientProperties for OpenAIClient {
             }
         }
     }
+    fn output_mode(&self) -> OutputMode {
+        self.properties.supported_request_modes.output
+    }
 }
 
 impl WithClient for OpenAIClient {
@@ -163,10 +169,16 @@ impl WithChat for OpenAIClient {
         ))
     }
 
-    async fn chat(&self, _ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+    async fn chat(&self, ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v,
+            Err(e) => return LLMResponse::InternalFailure(format!("{:#}", e)),
+        };
+
         let (response, system_start, instant_start) =
             match make_parsed_request::<ChatCompletionResponse>(
                 self,
+                ctx,
                 either::Either::Right(prompt),
                 false,
             )
@@ -189,20 +201,35 @@ impl WithChat for OpenAIClient {
                     response.choices.len()
                 ),
                 code: ErrorCode::Other(200),
+                retry_after: None,
             });
         }
 
         let usage = response.usage.as_ref();
+        let message = &response.choices[0].message;
+        let text = message.content.as_ref().map_or("", |s| s.as_str());
+
+        let content = match (
+            self.properties.supported_request_modes.output,
+            output_schema,
+        ) {
+            // If the model answered in text instead of calling the output
+            // tool, fall back to parsing the text.
+            (OutputMode::Tool, Some(schema)) => message
+                .tool_calls
+                .iter()
+                .flatten()
+                .find(|call| call.function.name == schema.name())
+                .map(|call| schema.content_from_object_json(&call.function.arguments))
+                .unwrap_or_else(|| text.to_string()),
+            (OutputMode::JsonSchema, Some(schema)) => schema.content_from_object_json(text),
+            _ => text.to_string(),
+        };
 
         LLMResponse::Success(LLMCompleteResponse {
             client: self.context.name.to_string(),
             prompt: internal_baml_jinja::RenderedPrompt::Chat(prompt.clone()),
-            content: response.choices[0]
-                .message
-                .content
-                .as_ref()
-                .map_or("", |s| s.as_str())
-                .to_string(),
+            content,
             start_time: system_start,
             latency: instant_start.elapsed(),
             model: response.model,
@@ -211,6 +238,7 @@ impl WithChat for OpenAIClient {
                 baml_is_complete: match response.choices.get(0) {
                     Some(c) => match c.finish_reason {
                         Some(FinishReason::Stop) => true,
+                        Some(FinishReason::ToolCalls) => output_schema.is_some(),
                         _ => false,
                     },
                     None => false,
@@ -238,6 +266,7 @@ impl RequestBuilder for OpenAIClient {
 
     async fn build_request(
         &self,
+        ctx: &RuntimeContext,
         prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
         allow_proxy: bool,
         stream: bool,
@@ -285,6 +314,46 @@ impl RequestBuilder for OpenAIClient {
             }
         }
 
+        match (
+            self.properties.supported_request_modes.output,
+            self.output_schema(ctx)?,
+        ) {
+            (OutputMode::JsonSchema, Some(schema)) => {
+                body_obj.insert(
+                    "response_format".into(),
+                    json!({
+                        "type": "json_schema",
+                        "json_schema": {
+                            "name": schema.name(),
+                            "description": schema.description(),
+                            "schema": schema.object_schema()?,
+                        },
+                    }),
+                );
+            }
+            (_, Some(schema)) => {
+                body_obj.insert(
+                    "tools".into(),
+                    json!([{
+                        "type": "function",
+                        "function": {
+                            "name": schema.name(),
+                            "description": schema.description(),
+                            "parameters": schema.object_schema()?,
+                        },
+                    }]),
+                );
+                body_obj.insert(
+                    "tool_choice".into(),
+                    json!({
+                        "type": "function",
+                        "function": { "name": schema.name() },
+                    }),
+                );
+            }
+            (_, None) => {}
+        }
+
         if stream {
             body_obj.insert("stream".into(), json!(true));
             if self.provider == "openai" {
@@ -310,12 +379,16 @@ impl SseResponseTrait for OpenAIClient {
         &self,
         resp: reqwest::Response,
         prompt: &Vec<RenderedChatMessage>,
+        output_schema: Option<OutputSchema>,
         system_start: web_time::SystemTime,
         instant_start: web_time::Instant,
     ) -> StreamResponse {
         let prompt = prompt.clone();
         let client_name = self.context.name.clone();
         let params = self.properties.properties.clone();
+        let output_mode = self.properties.supported_request_modes.output;
+        // The raw JSON object sent by the model when the output is wrapped.
+        let mut object_json = String::new();
         Ok(Box::pin(
             resp.bytes_stream()
                 .eventsource()
@@ -369,13 +442,39 @@ impl SseResponseTrait for OpenAIClient {
                                         latency: instant_start.elapsed(),
                                         message: format!("Failed to parse event: {:#?}", e),
                                         code: ErrorCode::UnsupportedResponse(2),
+                                        retry_after: None,
                                     },
                                 )));
                             }
                         };
                         if let Some(choice) = event.choices.get(0) {
-                            if let Some(content) = choice.delta.content.as_ref() {
-                                inner.content += content.as_str();
+                            match (output_mode, output_schema.as_ref()) {
+                                (OutputMode::JsonSchema, Some(schema)) => {
+                                    if let Some(content) = choice.delta.content.as_ref() {
+                                        object_json += content.as_str();
+                                        inner.content =
+                                            schema.content_from_object_json(&object_json);
+                                    }
+                                }
+                                (_, schema) => {
+                                    if let Some(content) = choice.delta.content.as_ref() {
+                                        inner.content += content.as_str();
+                                    }
+                                    if let Some(schema) = schema {
+                                        // The output tool is forced, so it's the only call.
+                                        let arguments =
+                                            choice.delta.tool_calls.iter().flatten().filter_map(
+                                                |call| call.function.as_ref()?.arguments.as_ref(),
+                                            );
+                                        for fragment in arguments {
+                                            object_json += fragment;
+                                        }
+                                        if !object_json.is_empty() {
+                                            inner.content =
+                                                schema.content_from_object_json(&object_json);
+                                        }
+                                    }
+                                }
                             }
                             inner.model = event.model;
                             match choice.finish_reason.as_ref() {
@@ -384,6 +483,11 @@ impl SseResponseTrait for OpenAIClient {
                                     inner.metadata.finish_reason =
                                         Some(FinishReason::Stop.to_string());
                                 }
+                                Some(FinishReason::ToolCalls) if output_schema.is_some() => {
+                                    inner.metadata.baml_is_complete = true;
+                                    inner.metadata.finish_reason =
+                                        Some(FinishReason::ToolCalls.to_string());
+                                }
                                 finish_reason => {
                                     inner.metadata.baml_is_complete = false;
                                     inner.metadata.finish_reason =
@@ -408,15 +512,19 @@ impl SseResponseTrait for OpenAIClient {
 impl WithStreamChat for OpenAIClient {
     async fn stream_chat(
         &self,
-        _ctx: &RuntimeContext,
+        ctx: &RuntimeContext,
         prompt: &Vec<RenderedChatMessage>,
     ) -> StreamResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v.cloned(),
+            Err(e) => return Err(LLMResponse::InternalFailure(format!("{:#}", e))),
+        };
         let (resp, system_start, instant_start) =
-            match make_request(self, either::Either::Right(prompt), true).await {
+            match make_request(self, ctx, either::Either::Right(prompt), true).await {
                 Ok(v) => v,
                 Err(e) => return Err(e),
             };
-        self.response_stream(resp, prompt, system_start, instant_start)
+        self.response_stream(resp, prompt, output_schema, system_start, instant_start)
     }
 }
 
@@ -470,6 +578,13 @@ macro_rules! make_openai_client {
 }
 
 impl OpenAIClient {
+    fn output_schema<'a>(&self, ctx: &'a RuntimeContext) -> Result<Option<&'a OutputSchema>> {
+        self.properties
+            .supported_request_modes
+            .output
+            .output_schema(ctx)
+    }
+
     pub fn new(client: &ClientWalker, ctx: &RuntimeContext) -> Result<OpenAIClient> {
         let properties = super::super::resolve_properties_walker(client, ctx)?;
         let properties = properties::openai::resolve_properties(properties, ctx)?;
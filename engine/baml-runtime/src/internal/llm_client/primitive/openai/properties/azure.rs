@@ -49,6 +49,7 @@ pub fn resolve_properties(
     };
 
     let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
 
 
     let properties = {
@@ -71,5 +72,6 @@ pub fn resolve_properties(
         proxy_url: ctx.env.get("BOUNDARY_PROXY_URL").map(|s| s.to_string()),
         query_params,
         supported_request_modes,
+        timeouts,
     })
 }
@@ -28,6 +28,7 @@ pub fn resolve_properties(
         _ => None,
     };
     let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
 
     let properties = properties.finalize();
 
@@ -41,5 +42,6 @@ pub fn resolve_properties(
         query_params: Default::default(),
         allowed_metadata,
         supported_request_modes,
+        timeouts,
     })
 }
@@ -3,7 +3,7 @@ pub(crate) mod generic;
 pub(crate) mod ollama;
 pub(crate) mod openai;
 
-use crate::internal::llm_client::{AllowedMetadata, SupportedRequestModes};
+use crate::internal::llm_client::{AllowedMetadata, RequestTimeouts, SupportedRequestModes};
 use std::collections::HashMap;
 
 pub struct PostRequestProperties {
@@ -17,4 +17,5 @@ pub struct PostRequestProperties {
     pub properties: HashMap<String, serde_json::Value>,
     pub allowed_metadata: AllowedMetadata,
     pub supported_request_modes: SupportedRequestModes,
+    pub timeouts: RequestTimeouts,
 }
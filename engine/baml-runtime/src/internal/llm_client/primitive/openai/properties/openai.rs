@@ -26,6 +26,7 @@ pub fn resolve_properties(
     let headers = properties.pull_headers()?;
 
     let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
 
     Ok(PostRequestProperties {
         default_role,
@@ -41,5 +42,6 @@ pub fn resolve_properties(
             .unwrap_or(None),
         query_params: Default::default(),
         supported_request_modes,
+        timeouts,
     })
 }
@@ -2,15 +2,17 @@ use std::collections::HashMap;
 
 use anyhow::{Context, Result};
 use internal_baml_jinja::RenderedChatMessage;
-use reqwest::Response;
+use reqwest::{header::HeaderMap, Response};
 use serde::de::DeserializeOwned;
 
 use crate::internal::llm_client::{traits::WithClient, ErrorCode, LLMErrorResponse, LLMResponse};
+use crate::RuntimeContext;
 
 pub trait RequestBuilder {
     #[allow(async_fn_in_trait)]
     async fn build_request(
         &self,
+        ctx: &RuntimeContext,
         prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
         allow_proxy: bool,
         stream: bool,
@@ -32,13 +34,14 @@ fn to_prompt(
 
 pub async fn make_request(
     client: &(impl WithClient + RequestBuilder),
+    ctx: &RuntimeContext,
     prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
     stream: bool,
 ) -> Result<(Response, web_time::SystemTime, web_time::Instant), LLMResponse> {
     let (system_now, instant_now) = (web_time::SystemTime::now(), web_time::Instant::now());
 
     let req = match client
-        .build_request(prompt, true, stream)
+        .build_request(ctx, prompt, true, stream)
         .await
         .context("Failed to build request")
     {
@@ -53,6 +56,7 @@ pub async fn make_request(
                 latency: instant_now.elapsed(),
                 message: format!("{:#?}", e),
                 code: ErrorCode::Other(2),
+                retry_after: None,
             }));
         }
     };
@@ -69,6 +73,7 @@ pub async fn make_request(
                 latency: instant_now.elapsed(),
                 message: format!("{:#?}", e),
                 code: ErrorCode::Other(2),
+                retry_after: None,
             }));
         }
     };
@@ -85,12 +90,14 @@ pub async fn make_request(
                 latency: instant_now.elapsed(),
                 message: format!("{:?}", e),
                 code: ErrorCode::Other(2),
+                retry_after: None,
             }));
         }
     };
 
     let status = response.status();
     if !status.is_success() {
+        let retry_after = retry_after(response.headers());
         return Err(LLMResponse::LLMFailure(LLMErrorResponse {
             client: client.context().name.to_string(),
             model: None,
@@ -103,18 +110,76 @@ pub async fn make_request(
                 response.text().await.unwrap_or("<no response>".into())
             ),
             code: ErrorCode::from_status(status),
+            retry_after,
         }));
     }
 
     Ok((response, system_now, instant_now))
 }
 
+/// How long the provider asked us to wait before retrying, from the standard
+/// `Retry-After` header (seconds or an HTTP date), or the `retry-after-ms` and
+/// `x-ratelimit-reset-*` headers some providers send instead.
+fn retry_after(headers: &HeaderMap) -> Option<web_time::Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return web_time::Duration::try_from_secs_f64(ms / 1000.0).ok();
+    }
+    if let Some(value) = header("retry-after") {
+        let value = value.trim();
+        if let Ok(secs) = value.parse::<f64>() {
+            return web_time::Duration::try_from_secs_f64(secs).ok();
+        }
+        if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+            return (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                .to_std()
+                .ok()
+                .or(Some(web_time::Duration::ZERO));
+        }
+    }
+    ["x-ratelimit-reset-requests", "x-ratelimit-reset-tokens"]
+        .into_iter()
+        .filter_map(|name| header(name).and_then(parse_reset_duration))
+        .max()
+}
+
+/// Parses durations like `1s`, `6m0s` or `20ms`.
+fn parse_reset_duration(value: &str) -> Option<web_time::Duration> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number = rest[..number_len].parse::<f64>().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let secs_per_unit = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += number * secs_per_unit;
+    }
+    web_time::Duration::try_from_secs_f64(total).ok()
+}
+
 pub async fn make_parsed_request<T: DeserializeOwned>(
     client: &(impl WithClient + RequestBuilder),
+    ctx: &RuntimeContext,
     prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
     stream: bool,
 ) -> Result<(T, web_time::SystemTime, web_time::Instant), LLMResponse> {
-    let (response, system_now, instant_now) = make_request(client, prompt, stream).await?;
+    let (response, system_now, instant_now) = make_request(client, ctx, prompt, stream).await?;
     let j = match response.json::<serde_json::Value>().await {
         Ok(response) => response,
         Err(e) => {
@@ -127,6 +192,7 @@ pub async fn make_parsed_request<T: DeserializeOwned>(
                 latency: instant_now.elapsed(),
                 message: e.to_string(),
                 code: ErrorCode::Other(2),
+                retry_after: None,
             }))
         }
     };
@@ -146,6 +212,57 @@ pub async fn make_parsed_request<T: DeserializeOwned>(
             latency: instant_now.elapsed(),
             message: format!("{:?}", e),
             code: ErrorCode::Other(2),
+            retry_after: None,
         })),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+    use web_time::Duration;
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(k, v)| (*k, HeaderValue::from_str(v).unwrap()))
+            .map(|(k, v)| (reqwest::header::HeaderName::from_static(k), v))
+            .collect()
+    }
+
+    #[test]
+    fn retry_after_headers() {
+        assert_eq!(
+            retry_after(&headers(&[("retry-after", "2")])),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            retry_after(&headers(&[("retry-after", "2"), ("retry-after-ms", "150")])),
+            Some(Duration::from_millis(150))
+        );
+        assert_eq!(
+            retry_after(&headers(&[(
+                "retry-after",
+                "Wed, 21 Oct 2015 07:28:00 GMT"
+            )])),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            retry_after(&headers(&[
+                ("x-ratelimit-reset-requests", "1m0.5s"),
+                ("x-ratelimit-reset-tokens", "20ms")
+            ])),
+            Some(Duration::from_millis(60_500))
+        );
+        assert_eq!(retry_after(&headers(&[])), None);
+    }
+
+    #[test]
+    fn rejects_unknown_reset_units() {
+        assert_eq!(parse_reset_duration("5d"), None);
+        assert_eq!(parse_reset_duration(""), None);
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+    }
+}
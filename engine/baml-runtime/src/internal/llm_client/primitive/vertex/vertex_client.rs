@@ -3,20 +3,22 @@ use crate::internal::llm_client::properties_hander::{ PropertiesHandler};
 use crate::internal::llm_client::traits::{
     ToProviderMessage, ToProviderMessageExt, WithClientProperties,
 };
-use crate::internal::llm_client::{AllowedMetadata, ResolveMediaUrls, SupportedRequestModes};
+use crate::internal::llm_client::{
+    AllowedMetadata, RequestTimeouts, ResolveMediaUrls, SupportedRequestModes,
+};
 use crate::RuntimeContext;
 use crate::{
     internal::llm_client::{
         primitive::{
             request::{make_parsed_request, make_request, RequestBuilder},
-            vertex::types::{FinishReason, VertexResponse},
+            vertex::types::{FinishReason, Part, VertexResponse},
         },
         traits::{
             SseResponseTrait, StreamResponse, WithChat, WithClient, WithNoCompletion,
             WithRetryPolicy, WithStreamChat,
         },
         ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
-        ModelFeatures,
+        ModelFeatures, OutputMode, OutputSchema,
     },
     request::create_client,
 };
@@ -64,6 +66,7 @@ struct PostRequestProperties {
     location: Option<String>,
     allowed_metadata: AllowedMetadata,
     supported_request_modes: SupportedRequestModes,
+    timeouts: RequestTimeouts,
 }
 
 pub struct VertexClient {
@@ -164,6 +167,7 @@ fn resolve_properties(
     };
 
     let supported_request_modes = properties.pull_supported_request_modes()?;
+    let timeouts = properties.pull_timeouts()?;
 
     Ok(PostRequestProperties {
         default_role,
@@ -177,6 +181,7 @@ fn resolve_properties(
         proxy_url: ctx.env.get("BOUNDARY_PROXY_URL").map(|s| s.to_string()),
         allowed_metadata,
         supported_request_modes,
+        timeouts,
     })
 }
 
@@ -193,9 +198,15 @@ impl WithClientProperties for VertexClient {
     fn allowed_metadata(&self) -> &crate::internal::llm_client::AllowedMetadata {
         &self.properties.allowed_metadata
     }
+    fn timeouts(&self) -> &RequestTimeouts {
+        &self.properties.timeouts
+    }
     fn supports_streaming(&self) -> bool {
         self.properties.supported_request_modes.stream.unwrap_or(true)
     }
+    fn output_mode(&self) -> OutputMode {
+        self.properties.supported_request_modes.output
+    }
 }
 
 impl WithClient for VertexClient {
@@ -215,6 +226,7 @@ impl SseResponseTrait for VertexClient {
         &self,
         resp: reqwest::Response,
         prompt: &Vec<RenderedChatMessage>,
+        output_schema: Option<OutputSchema>,
         system_start: web_time::SystemTime,
         instant_start: web_time::Instant,
     ) -> StreamResponse {
@@ -273,13 +285,20 @@ impl SseResponseTrait for VertexClient {
                                         latency: instant_start.elapsed(),
                                         message: format!("Failed to parse event: {:#?}", e),
                                         code: ErrorCode::UnsupportedResponse(2),
+                                        retry_after: None,
                                     },
                                 )));
                             }
                         };
 
                         if let Some(choice) = event.candidates.get(0) {
-                            if let Some(content) = choice.content.parts.get(0) {
+                            // Function calls arrive whole rather than in fragments.
+                            let tool_call = output_schema.as_ref().and_then(|schema| {
+                                tool_call_content(schema, &choice.content.parts)
+                            });
+                            if let Some(content) = tool_call {
+                                inner.content = content;
+                            } else if let Some(content) = choice.content.parts.get(0) {
                                 inner.content += &content.text;
                             }
                             match choice.finish_reason.as_ref() {
@@ -306,17 +325,28 @@ impl WithStreamChat for VertexClient {
         ctx: &RuntimeContext,
         prompt: &Vec<RenderedChatMessage>,
     ) -> StreamResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v.cloned(),
+            Err(e) => return Err(LLMResponse::InternalFailure(format!("{:#}", e))),
+        };
         //incomplete, streaming response object is returned
         let (response, system_now, instant_now) =
-            match make_request(self, either::Either::Right(prompt), true).await {
+            match make_request(self, ctx, either::Either::Right(prompt), true).await {
                 Ok(v) => v,
                 Err(e) => return Err(e),
             };
-        self.response_stream(response, prompt, system_now, instant_now)
+        self.response_stream(response, prompt, output_schema, system_now, instant_now)
     }
 }
 
 impl VertexClient {
+    fn output_schema<'a>(&self, ctx: &'a RuntimeContext) -> Result<Option<&'a OutputSchema>> {
+        self.properties
+            .supported_request_modes
+            .output
+            .output_schema(ctx)
+    }
+
     pub fn new(client: &ClientWalker, ctx: &RuntimeContext) -> Result<Self> {
         let properties = super::super::resolve_properties_walker(client, ctx)?;
         let properties = resolve_properties(properties, ctx)?;
@@ -416,6 +446,7 @@ impl RequestBuilder for VertexClient {
 
     async fn build_request(
         &self,
+        ctx: &RuntimeContext,
         prompt: either::Either<&String, &Vec<RenderedChatMessage>>,
         allow_proxy: bool,
         stream: bool,
@@ -514,6 +545,49 @@ impl RequestBuilder for VertexClient {
             either::Either::Right(messages) => body_obj.extend(self.chat_to_message(messages)?),
         }
 
+        match (
+            self.properties.supported_request_modes.output,
+            self.output_schema(ctx)?,
+        ) {
+            (OutputMode::JsonSchema, Some(schema)) => {
+                // Users may already set other generation options.
+                let key = if body_obj.contains_key("generation_config") {
+                    "generation_config"
+                } else {
+                    "generationConfig"
+                };
+                let config = body_obj
+                    .entry(key)
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut()
+                    .context(format!("{} must be an object", key))?;
+                config.insert("responseMimeType".into(), json!("application/json"));
+                config.insert("responseSchema".into(), schema.openapi_schema()?);
+            }
+            (_, Some(schema)) => {
+                body_obj.insert(
+                    "tools".into(),
+                    json!([{
+                        "functionDeclarations": [{
+                            "name": schema.name(),
+                            "description": schema.description(),
+                            "parameters": schema.openapi_object_schema()?,
+                        }],
+                    }]),
+                );
+                body_obj.insert(
+                    "toolConfig".into(),
+                    json!({
+                        "functionCallingConfig": {
+                            "mode": "ANY",
+                            "allowedFunctionNames": [schema.name()],
+                        },
+                    }),
+                );
+            }
+            (_, None) => {}
+        }
+
         Ok(req.json(&body))
     }
     fn request_options(&self) -> &HashMap<String, serde_json::Value> {
@@ -529,15 +603,24 @@ impl WithChat for VertexClient {
         ))
     }
 
-    async fn chat(&self, _ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+    async fn chat(&self, ctx: &RuntimeContext, prompt: &Vec<RenderedChatMessage>) -> LLMResponse {
+        let output_schema = match self.output_schema(ctx) {
+            Ok(v) => v,
+            Err(e) => return LLMResponse::InternalFailure(format!("{:#}", e)),
+        };
+
         //non-streaming, complete response is returned
-        let (response, system_now, instant_now) =
-            match make_parsed_request::<VertexResponse>(self, either::Either::Right(prompt), false)
-                .await
-            {
-                Ok(v) => v,
-                Err(e) => return e,
-            };
+        let (response, system_now, instant_now) = match make_parsed_request::<VertexResponse>(
+            self,
+            ctx,
+            either::Either::Right(prompt),
+            false,
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => return e,
+        };
 
         if response.candidates.len() != 1 {
             return LLMResponse::LLMFailure(LLMErrorResponse {
@@ -552,6 +635,7 @@ impl WithChat for VertexClient {
                     response.candidates.len()
                 ),
                 code: ErrorCode::Other(200),
+                retry_after: None,
             });
         }
         let usage_metadata = response.usage_metadata.clone().unwrap();
@@ -559,7 +643,9 @@ impl WithChat for VertexClient {
         LLMResponse::Success(LLMCompleteResponse {
             client: self.context.name.to_string(),
             prompt: internal_baml_jinja::RenderedPrompt::Chat(prompt.clone()),
-            content: response.candidates[0].content.parts[0].text.clone(),
+            content: output_schema
+                .and_then(|schema| tool_call_content(schema, &response.candidates[0].content.parts))
+                .unwrap_or_else(|| response.candidates[0].content.parts[0].text.clone()),
             start_time: system_now,
             latency: instant_now.elapsed(),
             request_options: self.properties.properties.clone(),
@@ -586,6 +672,15 @@ impl WithChat for VertexClient {
     }
 }
 
+/// The arguments of the call to the output tool, if the model made one.
+fn tool_call_content(schema: &OutputSchema, parts: &[Part]) -> Option<String> {
+    parts
+        .iter()
+        .filter_map(|part| part.function_call.as_ref())
+        .find(|call| call.name == schema.name())
+        .map(|call| schema.content_from_object(call.args.as_ref().unwrap_or(&json!({}))))
+}
+
 //simple, Map with key "prompt" and value of the prompt string
 fn convert_completion_prompt_to_body(prompt: &String) -> HashMap<String, serde_json::Value> {
     let mut map = HashMap::new();
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 
-use super::{AllowedMetadata, SupportedRequestModes};
+use web_time::Duration;
+
+use super::{AllowedMetadata, OutputMode, RequestTimeouts, SupportedRequestModes};
 
 pub(super) struct PropertiesHandler {
     properties: HashMap<String, serde_json::Value>,
@@ -28,7 +30,11 @@ impl PropertiesHandler {
             | "base_url"
             | "api_key"
             | "headers"
-            | "default_role" => {
+            | "default_role"
+            | "output_mode"
+            | RequestTimeouts::REQUEST_KEY
+            | RequestTimeouts::TIME_TO_FIRST_TOKEN_KEY
+            | RequestTimeouts::TOTAL_KEY => {
                 unreachable!("{} is a reserved key in options", key)
             }
             _ => self.properties.remove(key),
@@ -147,10 +153,53 @@ impl PropertiesHandler {
             None => None,
         };
 
+        let output = match self.get("output_mode") {
+            Some(v) => serde_json::from_value(v.clone()).context(format!(
+                "output_mode must be one of \"prompt\", \"tool\" or \"json_schema\": Got {:?}",
+                v
+            ))?,
+            None => OutputMode::default(),
+        };
+
         Ok(SupportedRequestModes {
             stream: supports_streaming,
+            output,
+        })
+    }
+
+    fn pull_timeout(&mut self, key: &str) -> Result<Option<Duration>> {
+        match self.get(key) {
+            Some(v) => match v.as_u64() {
+                Some(0) => anyhow::bail!("{} must be greater than 0", key),
+                Some(ms) => Ok(Some(Duration::from_millis(ms))),
+                None => anyhow::bail!("{} must be a positive integer: Got {:?}", key, v),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn pull_timeouts(&mut self) -> Result<RequestTimeouts> {
+        Ok(RequestTimeouts {
+            request: self.pull_timeout(RequestTimeouts::REQUEST_KEY)?,
+            time_to_first_token: self.pull_timeout(RequestTimeouts::TIME_TO_FIRST_TOKEN_KEY)?,
+            total: self.pull_timeout(RequestTimeouts::TOTAL_KEY)?,
         })
     }
+
+    /// Strategies only make requests through their clients, so only the
+    /// overall deadline can be set on them.
+    pub fn pull_strategy_timeouts(&mut self) -> Result<RequestTimeouts> {
+        let timeouts = self.pull_timeouts()?;
+        if timeouts.request.is_some() || timeouts.time_to_first_token.is_some() {
+            anyhow::bail!(
+                "Only {} can be set on a strategy, set {} and {} on its clients instead",
+                RequestTimeouts::TOTAL_KEY,
+                RequestTimeouts::REQUEST_KEY,
+                RequestTimeouts::TIME_TO_FIRST_TOKEN_KEY
+            );
+        }
+        Ok(timeouts)
+    }
 }
 
 impl crate::client_registry::ClientProperty {
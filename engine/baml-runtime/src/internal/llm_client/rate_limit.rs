@@ -0,0 +1,129 @@
+//! Limits on how many requests per minute are sent to a client.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
+
+use web_time::{Duration, Instant};
+
+/// A token bucket: holds up to `capacity` requests' worth of tokens, and is
+/// refilled at a steady rate.
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Allows `requests_per_minute` on average, and up to `burst` at once.
+    /// Without `burst`, allows a second's worth at once.
+    pub fn per_minute(requests_per_minute: u32, burst: Option<u32>) -> Self {
+        let requests_per_minute = requests_per_minute.max(1);
+        let burst = burst
+            .unwrap_or_else(|| requests_per_minute.div_ceil(60))
+            .max(1);
+        Self {
+            capacity: burst as f64,
+            per_second: requests_per_minute as f64 / 60.0,
+            state: Mutex::new(BucketState {
+                tokens: burst as f64,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token if there is one, or says how long until there will be.
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let refill = now.duration_since(state.updated).as_secs_f64() * self.per_second;
+        state.tokens = (state.tokens + refill).min(self.capacity);
+        state.updated = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - state.tokens) / self.per_second,
+            ))
+        }
+    }
+
+    /// Puts back a token taken by `try_acquire`, for a request that wasn't
+    /// let through after all.
+    pub fn refund(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.tokens = (state.tokens + 1.0).min(self.capacity);
+    }
+
+    /// Waits until there is a token, and takes it.
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            async_std::task::sleep(wait).await;
+        }
+    }
+}
+
+fn client_limits() -> &'static RwLock<HashMap<String, Arc<TokenBucket>>> {
+    static CLIENT_LIMITS: OnceLock<RwLock<HashMap<String, Arc<TokenBucket>>>> = OnceLock::new();
+    CLIENT_LIMITS.get_or_init(Default::default)
+}
+
+/// Limits the requests sent to `client`, for every function that uses it,
+/// for the rest of the process.
+pub fn limit_client(client: &str, bucket: TokenBucket) {
+    client_limits()
+        .write()
+        .unwrap()
+        .insert(client.to_string(), Arc::new(bucket));
+}
+
+/// Waits until a request can be sent to `client` without exceeding its limit.
+pub(super) async fn wait_for_client(client: &str) {
+    let bucket = client_limits().read().unwrap().get(client).cloned();
+    if let Some(bucket) = bucket {
+        bucket.acquire().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_a_burst_then_waits_for_refill() {
+        let bucket = TokenBucket::per_minute(60, Some(2));
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+
+        let wait = bucket.try_acquire().unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn refunded_tokens_can_be_taken_again() {
+        let bucket = TokenBucket::per_minute(60, Some(1));
+        assert!(bucket.try_acquire().is_ok());
+        bucket.refund();
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_err());
+    }
+
+    #[test]
+    fn burst_defaults_to_a_seconds_worth() {
+        let bucket = TokenBucket::per_minute(600, None);
+        for _ in 0..10 {
+            assert!(bucket.try_acquire().is_ok());
+        }
+        assert!(bucket.try_acquire().is_err());
+    }
+}
@@ -1,20 +1,29 @@
 use internal_baml_core::{
     internal_baml_parser_database::RetryPolicyStrategy, ir::RetryPolicyWalker,
 };
+use serde::Serialize;
 
-#[derive(Clone)]
+use super::LLMResponse;
+
+#[derive(Clone, Debug, Serialize)]
 pub struct CallablePolicy {
+    name: String,
     max_retries: u32,
     strategy: RetryPolicyStrategy,
+    retry_on: Option<Vec<u16>>,
+    #[serde(skip)]
     current: std::time::Duration,
+    #[serde(skip)]
     counter: u32,
 }
 
 impl From<RetryPolicyWalker<'_>> for CallablePolicy {
     fn from(policy: RetryPolicyWalker<'_>) -> Self {
         CallablePolicy {
+            name: policy.name().to_string(),
             max_retries: policy.max_retries(),
             strategy: policy.strategy().clone(),
+            retry_on: policy.retry_on().map(|codes| codes.to_vec()),
             current: match policy.strategy() {
                 RetryPolicyStrategy::ExponentialBackoff(strategy) => {
                     std::time::Duration::from_millis(strategy.delay_ms as u64)
@@ -28,6 +37,53 @@ impl From<RetryPolicyWalker<'_>> for CallablePolicy {
     }
 }
 
+impl CallablePolicy {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this policy retries after `response`.
+    ///
+    /// With `retry_on`, only failures with one of the listed status codes are
+    /// retried. Failures that never got a status code (e.g. a dropped
+    /// connection) are always retried.
+    pub fn retries(&self, response: &LLMResponse) -> bool {
+        match (&self.retry_on, response) {
+            (_, LLMResponse::Success(_)) => false,
+            (None, _) => true,
+            (Some(codes), LLMResponse::LLMFailure(e)) => {
+                let code = e.code.to_u16();
+                code < 100 || codes.contains(&code)
+            }
+            (Some(_), LLMResponse::UserFailure(_) | LLMResponse::InternalFailure(_)) => true,
+        }
+    }
+
+    fn jitter(&self) -> f32 {
+        match &self.strategy {
+            RetryPolicyStrategy::ExponentialBackoff(strategy) => strategy.jitter,
+            RetryPolicyStrategy::ConstantDelay(strategy) => strategy.jitter,
+        }
+    }
+
+    /// Randomly shortens or lengthens `delay` by up to `jitter` times itself,
+    /// so that clients failing together don't all retry at the same time.
+    fn with_jitter(&self, delay: std::time::Duration) -> std::time::Duration {
+        let jitter = self.jitter() as f64;
+        if jitter == 0.0 {
+            return delay;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let offset = fastrand::f64() * 2.0 - 1.0;
+        // Keep delays predictable in the playground.
+        #[cfg(target_arch = "wasm32")]
+        let offset = 0.0;
+
+        delay.mul_f64(1.0 + jitter * offset)
+    }
+}
+
 impl Iterator for CallablePolicy {
     type Item = std::time::Duration;
 
@@ -56,6 +112,67 @@ impl Iterator for CallablePolicy {
         }
         self.counter += 1;
 
-        Some(self.current)
+        Some(self.with_jitter(self.current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use internal_baml_core::internal_baml_parser_database::{
+        ContantDelayStrategy, ExponentialBackoffStrategy,
+    };
+    use std::time::Duration;
+
+    use super::*;
+
+    fn policy(strategy: RetryPolicyStrategy, retry_on: Option<Vec<u16>>) -> CallablePolicy {
+        CallablePolicy {
+            name: "Policy".into(),
+            max_retries: 3,
+            strategy,
+            retry_on,
+            current: Duration::from_millis(100),
+            counter: 0,
+        }
+    }
+
+    #[test]
+    fn exponential_backoff_without_jitter() {
+        let delays = policy(
+            RetryPolicyStrategy::ExponentialBackoff(ExponentialBackoffStrategy {
+                delay_ms: 100,
+                multiplier: 2.0,
+                max_delay_ms: 300,
+                jitter: 0.0,
+            }),
+            None,
+        )
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            delays,
+            vec![200, 300, 300, 0]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let delays = policy(
+            RetryPolicyStrategy::ConstantDelay(ContantDelayStrategy {
+                delay_ms: 100,
+                jitter: 0.5,
+            }),
+            None,
+        )
+        .collect::<Vec<_>>();
+
+        assert_eq!(delays.len(), 4);
+        for delay in &delays[..3] {
+            assert!(*delay >= Duration::from_millis(50) && *delay <= Duration::from_millis(150));
+        }
+        assert_eq!(delays[3], Duration::ZERO);
     }
 }
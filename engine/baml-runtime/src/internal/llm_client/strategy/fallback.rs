@@ -9,6 +9,7 @@ use crate::{
     internal::llm_client::{
         orchestrator::{ExecutionScope, IterOrchestrator, OrchestrationScope, OrchestrationState},
         properties_hander::PropertiesHandler,
+        RequestTimeouts,
     },
     runtime_interface::InternalClientLookup,
     RuntimeContext,
@@ -17,6 +18,7 @@ use crate::{
 pub struct FallbackStrategy {
     pub name: String,
     pub(super) retry_policy: Option<String>,
+    pub(super) timeouts: RequestTimeouts,
     // TODO: We can add conditions to each client
     client_specs: Vec<ClientSpec>,
 }
@@ -24,7 +26,7 @@ pub struct FallbackStrategy {
 fn resolve_strategy(
     mut properties: PropertiesHandler,
     _ctx: &RuntimeContext,
-) -> Result<Vec<ClientSpec>> {
+) -> Result<(Vec<ClientSpec>, RequestTimeouts)> {
     let strategy = properties
         .remove_serde::<Vec<String>>("strategy")
         .context("Failed to resolve strategy into string[]")?;
@@ -38,9 +40,11 @@ fn resolve_strategy(
         anyhow::bail!("Missing a strategy field");
     };
 
+    let timeouts = properties.pull_strategy_timeouts()?;
+
     let properties = properties.finalize();
     if !properties.is_empty() {
-        let supported_keys = ["strategy"];
+        let supported_keys = ["strategy", RequestTimeouts::TOTAL_KEY];
         let unknown_keys = properties.keys().map(String::from).collect::<Vec<_>>();
         anyhow::bail!(
             "Unknown keys: {}. Supported keys are: {}",
@@ -49,7 +53,10 @@ fn resolve_strategy(
         );
     }
 
-    Ok(strategy.into_iter().map(ClientSpec::new_from_id).collect())
+    Ok((
+        strategy.into_iter().map(ClientSpec::new_from_id).collect(),
+        timeouts,
+    ))
 }
 
 impl TryFrom<(&ClientProperty, &RuntimeContext)> for FallbackStrategy {
@@ -58,10 +65,11 @@ impl TryFrom<(&ClientProperty, &RuntimeContext)> for FallbackStrategy {
     fn try_from(
         (client, ctx): (&ClientProperty, &RuntimeContext),
     ) -> std::result::Result<Self, Self::Error> {
-        let strategy = resolve_strategy(client.property_handler()?, ctx)?;
+        let (strategy, timeouts) = resolve_strategy(client.property_handler()?, ctx)?;
         Ok(Self {
             name: client.name.clone(),
             retry_policy: client.retry_policy.clone(),
+            timeouts,
             client_specs: strategy,
         })
     }
@@ -72,10 +80,11 @@ impl TryFrom<(&ClientWalker<'_>, &RuntimeContext)> for FallbackStrategy {
 
     fn try_from((client, ctx): (&ClientWalker, &RuntimeContext)) -> Result<Self> {
         let properties = super::super::resolve_properties_walker(client, ctx)?;
-        let strategy = resolve_strategy(properties, ctx)?;
+        let (strategy, timeouts) = resolve_strategy(properties, ctx)?;
         Ok(Self {
             name: client.item.elem.name.clone(),
             retry_policy: client.retry_policy().as_ref().map(String::from),
+            timeouts,
             client_specs: strategy,
         })
     }
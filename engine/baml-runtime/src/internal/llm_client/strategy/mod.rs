@@ -17,6 +17,7 @@ use super::{
         IterOrchestrator, OrchestrationScope, OrchestrationState, OrchestratorNodeIterator,
     },
     traits::WithRetryPolicy,
+    RequestTimeouts,
 };
 
 pub enum LLMStrategyProvider {
@@ -83,6 +84,15 @@ impl TryFrom<(&ClientProperty, &RuntimeContext)> for LLMStrategyProvider {
     }
 }
 
+impl LLMStrategyProvider {
+    pub fn timeouts(&self) -> &RequestTimeouts {
+        match self {
+            LLMStrategyProvider::RoundRobin(strategy) => &strategy.timeouts,
+            LLMStrategyProvider::Fallback(strategy) => &strategy.timeouts,
+        }
+    }
+}
+
 impl WithRetryPolicy for LLMStrategyProvider {
     fn retry_policy_name(&self) -> Option<&str> {
         match self {
@@ -17,6 +17,7 @@ use crate::{
             OrchestratorNodeIterator,
         },
         properties_hander::PropertiesHandler,
+        RequestTimeouts,
     },
     runtime_interface::InternalClientLookup,
     RuntimeContext,
@@ -28,6 +29,8 @@ use serde::Serializer;
 pub struct RoundRobinStrategy {
     pub name: String,
     pub(super) retry_policy: Option<String>,
+    #[serde(skip)]
+    pub(super) timeouts: RequestTimeouts,
     // TODO: We can add conditions to each client
     client_specs: Vec<ClientSpec>,
     #[serde(serialize_with = "serialize_atomic")]
@@ -56,7 +59,7 @@ impl RoundRobinStrategy {
 fn resolve_strategy(
     mut properties: PropertiesHandler,
     _ctx: &RuntimeContext,
-) -> Result<(Vec<ClientSpec>, usize)> {
+) -> Result<(Vec<ClientSpec>, usize, RequestTimeouts)> {
     let strategy = properties
         .remove_serde::<Vec<String>>("strategy")
         .context("Failed to parse strategy into string[]")?;
@@ -74,9 +77,11 @@ fn resolve_strategy(
         .remove_serde::<usize>("start")
         .context("Failed to parse start: not a number")?;
 
+    let timeouts = properties.pull_strategy_timeouts()?;
+
     let properties = properties.finalize();
     if !properties.is_empty() {
-        let supported_keys = ["strategy", "start"];
+        let supported_keys = ["strategy", "start", RequestTimeouts::TOTAL_KEY];
         let unknown_keys = properties.keys().map(String::from).collect::<Vec<_>>();
         anyhow::bail!(
             "Unknown keys: {}. Supported keys are: {}",
@@ -105,6 +110,7 @@ fn resolve_strategy(
     Ok((
         strategy.into_iter().map(ClientSpec::new_from_id).collect(),
         start,
+        timeouts,
     ))
 }
 
@@ -114,11 +120,12 @@ impl TryFrom<(&ClientProperty, &RuntimeContext)> for RoundRobinStrategy {
     fn try_from(
         (client, ctx): (&ClientProperty, &RuntimeContext),
     ) -> std::result::Result<Self, Self::Error> {
-        let (strategy, start) = resolve_strategy(client.property_handler()?, ctx)?;
+        let (strategy, start, timeouts) = resolve_strategy(client.property_handler()?, ctx)?;
 
         Ok(RoundRobinStrategy {
             name: client.name.clone(),
             retry_policy: client.retry_policy.clone(),
+            timeouts,
             client_specs: strategy,
             current_index: AtomicUsize::new(start),
         })
@@ -130,10 +137,11 @@ impl TryFrom<(&ClientWalker<'_>, &RuntimeContext)> for RoundRobinStrategy {
 
     fn try_from((client, ctx): (&ClientWalker, &RuntimeContext)) -> Result<Self> {
         let properties = super::super::resolve_properties_walker(client, ctx)?;
-        let (strategy, start) = resolve_strategy(properties, ctx)?;
+        let (strategy, start, timeouts) = resolve_strategy(properties, ctx)?;
         Ok(Self {
             name: client.item.elem.name.clone(),
             retry_policy: client.retry_policy().as_ref().map(String::from),
+            timeouts,
             client_specs: strategy,
             current_index: AtomicUsize::new(start),
         })
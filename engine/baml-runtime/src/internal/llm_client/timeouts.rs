@@ -0,0 +1,194 @@
+use std::future::Future;
+
+use futures::StreamExt;
+use web_time::{Duration, Instant};
+
+use super::{traits::StreamResponse, LLMResponse};
+
+/// Timeouts configured on a client with `request_timeout_ms`,
+/// `time_to_first_token_timeout_ms` and `total_timeout_ms`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RequestTimeouts {
+    /// Limit on a single request, until the whole response has been received.
+    pub request: Option<Duration>,
+    /// Limit on a streaming request, until its first chunk has been received.
+    pub time_to_first_token: Option<Duration>,
+    /// Limit on a whole call to the client, across retries and fallbacks.
+    /// Only applies to the client a function calls directly.
+    pub total: Option<Duration>,
+}
+
+impl RequestTimeouts {
+    pub const REQUEST_KEY: &'static str = "request_timeout_ms";
+    pub const TIME_TO_FIRST_TOKEN_KEY: &'static str = "time_to_first_token_timeout_ms";
+    pub const TOTAL_KEY: &'static str = "total_timeout_ms";
+}
+
+/// A point in time after which a call is abandoned.
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+impl Deadline {
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: Instant::now() + timeout,
+            timeout,
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    pub fn has_passed(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    pub fn message(&self) -> String {
+        format!(
+            "Exceeded {} of {}ms",
+            RequestTimeouts::TOTAL_KEY,
+            self.timeout.as_millis()
+        )
+    }
+}
+
+/// Runs `fut` until it completes or `limit` elapses, whichever comes first.
+pub async fn with_timeout<F: Future>(limit: Option<Duration>, fut: F) -> Option<F::Output> {
+    match limit {
+        Some(limit) => async_std::future::timeout(limit, fut).await.ok(),
+        None => Some(fut.await),
+    }
+}
+
+/// The smaller of two optional limits.
+pub fn min_limit(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Ends `stream` with the response built by `on_timeout` if its first chunk
+/// takes longer than `time_to_first_token`, or the whole stream takes longer
+/// than `request`.
+pub fn stream_with_timeouts(
+    stream: StreamResponse,
+    timeouts: RequestTimeouts,
+    started: Instant,
+    on_timeout: impl Fn(String) -> LLMResponse + Send + Sync + 'static,
+) -> StreamResponse {
+    let stream = stream?;
+    if timeouts.request.is_none() && timeouts.time_to_first_token.is_none() {
+        return Ok(stream);
+    }
+
+    let limit_for_next = move |received_first: bool| {
+        let request = timeouts.request.map(|t| {
+            (
+                t.saturating_sub(started.elapsed()),
+                format!(
+                    "Exceeded {} of {}ms",
+                    RequestTimeouts::REQUEST_KEY,
+                    t.as_millis()
+                ),
+            )
+        });
+        let first = timeouts
+            .time_to_first_token
+            .filter(|_| !received_first)
+            .map(|t| {
+                (
+                    t.saturating_sub(started.elapsed()),
+                    format!(
+                        "Exceeded {} of {}ms",
+                        RequestTimeouts::TIME_TO_FIRST_TOKEN_KEY,
+                        t.as_millis()
+                    ),
+                )
+            });
+        match (request, first) {
+            (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+            (a, b) => a.or(b),
+        }
+    };
+
+    let on_timeout = std::sync::Arc::new(on_timeout);
+    Ok(Box::pin(futures::stream::unfold(
+        (Some(stream), false),
+        move |(stream, received_first)| {
+            let on_timeout = on_timeout.clone();
+            let limit = limit_for_next(received_first);
+            async move {
+                let mut stream = stream?;
+                match limit {
+                    Some((limit, message)) => {
+                        match async_std::future::timeout(limit, stream.next()).await {
+                            Ok(Some(item)) => Some((item, (Some(stream), true))),
+                            Ok(None) => None,
+                            Err(_) => Some((on_timeout(message), (None, true))),
+                        }
+                    }
+                    None => stream.next().await.map(|item| (item, (Some(stream), true))),
+                }
+            }
+        },
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_limit_prefers_the_smaller_limit() {
+        let short = Some(Duration::from_millis(10));
+        let long = Some(Duration::from_millis(20));
+
+        assert_eq!(min_limit(short, long), short);
+        assert_eq!(min_limit(long, short), short);
+        assert_eq!(min_limit(None, long), long);
+        assert_eq!(min_limit(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn with_timeout_gives_up() {
+        let slow = async_std::task::sleep(Duration::from_millis(200));
+        assert_eq!(
+            with_timeout(Some(Duration::from_millis(10)), slow).await,
+            None
+        );
+        assert_eq!(with_timeout(None, async { 1 }).await, Some(1));
+    }
+
+    #[test]
+    fn deadline_reports_the_total_timeout() {
+        let deadline = Deadline::after(Duration::from_millis(0));
+        assert!(deadline.has_passed());
+        assert_eq!(deadline.message(), "Exceeded total_timeout_ms of 0ms");
+    }
+
+    #[tokio::test]
+    async fn stream_times_out_before_first_chunk() {
+        let timeouts = RequestTimeouts {
+            time_to_first_token: Some(Duration::from_millis(10)),
+            ..Default::default()
+        };
+        let stream = stream_with_timeouts(
+            Ok(Box::pin(futures::stream::pending::<LLMResponse>())),
+            timeouts,
+            Instant::now(),
+            LLMResponse::InternalFailure,
+        );
+
+        let responses = stream.unwrap().collect::<Vec<_>>().await;
+        assert_eq!(responses.len(), 1);
+        assert!(matches!(
+            &responses[0],
+            LLMResponse::InternalFailure(m) if m == "Exceeded time_to_first_token_timeout_ms of 10ms"
+        ));
+    }
+}
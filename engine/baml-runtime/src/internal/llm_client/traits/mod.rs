@@ -35,7 +35,9 @@ pub trait WithRetryPolicy {
 pub trait WithClientProperties {
     fn client_properties(&self) -> &HashMap<String, serde_json::Value>;
     fn allowed_metadata(&self) -> &super::AllowedMetadata;
+    fn timeouts(&self) -> &super::RequestTimeouts;
     fn supports_streaming(&self) -> bool;
+    fn output_mode(&self) -> super::OutputMode;
 }
 
 pub trait WithSingleCallable {
@@ -283,7 +285,7 @@ where
         .await?;
 
         let request_builder = self
-            .build_request(either::Right(&chat_messages), false, render_settings.stream && self.supports_streaming())
+            .build_request(ctx, either::Right(&chat_messages), false, render_settings.stream && self.supports_streaming())
             .await?;
         let mut request = request_builder.build()?;
         let url_header_value = {
@@ -314,6 +316,7 @@ pub trait SseResponseTrait {
         &self,
         resp: reqwest::Response,
         prompt: &Vec<internal_baml_jinja::RenderedChatMessage>,
+        output_schema: Option<super::OutputSchema>,
         system_start: web_time::SystemTime,
         instant_start: web_time::Instant,
     ) -> StreamResponse;
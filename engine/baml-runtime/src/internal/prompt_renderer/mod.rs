@@ -16,7 +16,7 @@ use internal_baml_jinja::{
     TemplateStringMacro,
 };
 
-use crate::RuntimeContext;
+use crate::{internal::llm_client::OutputSchema, RuntimeContext};
 
 pub struct PromptRenderer {
     function_name: String,
@@ -51,6 +51,10 @@ impl PromptRenderer {
         &self.client_spec
     }
 
+    pub fn output_schema(&self) -> OutputSchema {
+        OutputSchema::new(&self.function_name, self.output_defs.clone())
+    }
+
     pub fn parse(&self, raw_string: &str, allow_partials: bool) -> Result<BamlValueWithFlags> {
         jsonish::from_str(
             &self.output_defs,
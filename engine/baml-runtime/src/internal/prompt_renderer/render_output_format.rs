@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use anyhow::Result;
 use baml_types::BamlValue;
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use internal_baml_core::ir::{
     repr::IntermediateRepr, ClassWalker, EnumWalker, FieldType, IRHelper,
 };
@@ -18,15 +18,49 @@ pub fn render_output_format(
     ctx: &RuntimeContext,
     output: &FieldType,
 ) -> Result<OutputFormatContent> {
-    let (enums, classes, recursive_classes) = relevant_data_models(ir, output, ctx)?;
+    let (enums, classes, recursive_classes, structural_recursive_aliases) =
+        relevant_data_models(ir, output, ctx)?;
+    let field_defaults = field_defaults(ir, &classes, ctx)?;
 
     return Ok(OutputFormatContent::target(output.clone())
         .enums(enums)
         .classes(classes)
         .recursive_classes(recursive_classes)
+        .structural_recursive_aliases(structural_recursive_aliases)
+        .field_defaults(field_defaults)
         .build());
 }
 
+/// The `@default` values of the fields of `classes`, by class name and then
+/// field name.
+fn field_defaults(
+    ir: &IntermediateRepr,
+    classes: &[Class],
+    ctx: &RuntimeContext,
+) -> Result<IndexMap<String, IndexMap<String, BamlValue>>> {
+    let mut field_defaults = IndexMap::new();
+
+    for class in classes {
+        // Classes only defined through the type builder have no defaults.
+        let Ok(walker) = ir.find_class(class.name.real_name()) else {
+            continue;
+        };
+
+        let mut defaults = IndexMap::new();
+        for field in walker.walk_fields() {
+            if let Some(default) = field.default_value(&ctx.env)? {
+                defaults.insert(field.name().to_string(), default);
+            }
+        }
+
+        if !defaults.is_empty() {
+            field_defaults.insert(class.name.real_name().to_string(), defaults);
+        }
+    }
+
+    Ok(field_defaults)
+}
+
 enum OverridableValue<T> {
     Unset,
     SetEmpty,
@@ -207,11 +241,17 @@ fn relevant_data_models<'a>(
     ir: &'a IntermediateRepr,
     output: &'a FieldType,
     ctx: &RuntimeContext,
-) -> Result<(Vec<Enum>, Vec<Class>, IndexSet<String>)> {
+) -> Result<(
+    Vec<Enum>,
+    Vec<Class>,
+    IndexSet<String>,
+    IndexMap<String, FieldType>,
+)> {
     let mut checked_types = HashSet::new();
     let mut enums = Vec::new();
     let mut classes = Vec::new();
     let mut recursive_classes = IndexSet::new();
+    let mut structural_recursive_aliases = IndexMap::new();
     let mut start: Vec<baml_types::FieldType> = vec![output.clone()];
 
     while let Some(output) = start.pop() {
@@ -363,6 +403,24 @@ fn relevant_data_models<'a>(
                     recursive_classes.insert(cls.to_owned());
                 }
             }
+            (FieldType::RecursiveTypeAlias(name), _) => {
+                if checked_types.insert(output.to_string()) {
+                    // Every alias in the cycle is rendered alongside this one,
+                    // so collect them all at once.
+                    for cycle in ir.structural_recursive_alias_cycles() {
+                        if cycle.contains_key(name) {
+                            for (alias, target) in cycle {
+                                if structural_recursive_aliases
+                                    .insert(alias.to_owned(), target.to_owned())
+                                    .is_none()
+                                {
+                                    start.push(target.to_owned());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
             (FieldType::Literal(_), _) => {}
             (FieldType::Primitive(_), _) => {}
             (FieldType::Constrained { .. }, _) => {
@@ -371,7 +429,12 @@ fn relevant_data_models<'a>(
         }
     }
 
-    Ok((enums, classes, recursive_classes))
+    Ok((
+        enums,
+        classes,
+        recursive_classes,
+        structural_recursive_aliases,
+    ))
 }
 
 #[cfg(test)]
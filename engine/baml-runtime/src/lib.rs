@@ -225,9 +225,20 @@ impl BamlRuntime {
                                 Ok(mut stream) => {
                                     let (response, span) =
                                         stream.run(on_event, ctx, None, None).await;
-                                    response.map(|res| TestResponse {
-                                        function_response: res,
-                                        function_span: span,
+                                    let constraints = self.inner.get_test_constraints(
+                                        function_name,
+                                        test_name,
+                                        &rctx,
+                                    );
+                                    response.and_then(|res| {
+                                        Ok(TestResponse {
+                                            constraints_result: TestConstraintsResult::evaluate(
+                                                &constraints?,
+                                                &res,
+                                            ),
+                                            function_response: res,
+                                            function_span: span,
+                                        })
                                     })
                                 }
                                 Err(e) => Err(e),
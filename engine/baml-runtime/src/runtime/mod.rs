@@ -2,7 +2,10 @@ mod ir_features;
 pub(crate) mod runtime_interface;
 
 use anyhow::Result;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 cfg_if::cfg_if!(
     if #[cfg(target_arch = "wasm32")] {
@@ -21,6 +24,17 @@ use std::sync::Arc;
 
 use crate::internal::llm_client::{llm_provider::LLMProvider, retry_policy::CallablePolicy};
 
+/// Generated clients embed their files with paths relative to `baml_src`, but
+/// namespaces are derived from the path of a file within `directory`.
+fn source_path(directory: &Path, path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_relative() && !path.starts_with(directory) {
+        directory.join(path)
+    } else {
+        path
+    }
+}
+
 pub struct InternalBamlRuntime {
     pub(crate) ir: Arc<IntermediateRepr>,
     diagnostics: Diagnostics,
@@ -33,16 +47,17 @@ impl InternalBamlRuntime {
         directory: &str,
         files: &HashMap<T, T>,
     ) -> Result<Self> {
+        let directory = PathBuf::from(directory);
         let contents = files
             .iter()
             .map(|(path, contents)| {
                 Ok(SourceFile::from((
-                    PathBuf::from(path.as_ref()),
+                    source_path(&directory, path.as_ref()),
                     contents.as_ref().to_string(),
                 )))
             })
             .collect::<Result<Vec<_>>>()?;
-        let mut schema = validate(&PathBuf::from(directory), contents);
+        let mut schema = validate(&directory, contents);
         schema.diagnostics.to_result()?;
 
         let ir = IntermediateRepr::from_parser_database(&schema.db, schema.configuration)?;
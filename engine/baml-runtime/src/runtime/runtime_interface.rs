@@ -24,7 +24,7 @@ use crate::{
     RuntimeContext, RuntimeInterface,
 };
 use anyhow::{Context, Result};
-use baml_types::{BamlMap, BamlValue};
+use baml_types::{BamlMap, BamlValue, Constraint};
 use internal_baml_core::{
     internal_baml_diagnostics::SourceFile,
     ir::{
@@ -215,9 +215,10 @@ impl InternalRuntimeInterface for InternalBamlRuntime {
         }
 
         let node = selected.swap_remove(node_index);
+        let ctx = ctx.clone().with_output_schema(renderer.output_schema());
         return node
             .provider
-            .render_raw_curl(ctx, prompt, render_settings)
+            .render_raw_curl(&ctx, prompt, render_settings)
             .await;
     }
 
@@ -281,6 +282,17 @@ impl InternalRuntimeInterface for InternalBamlRuntime {
             Err(e) => return Err(anyhow::anyhow!("Unable to resolve test params: {:?}", e)),
         }
     }
+
+    fn get_test_constraints(
+        &self,
+        function_name: &str,
+        test_name: &str,
+        ctx: &RuntimeContext,
+    ) -> Result<Vec<Constraint>> {
+        let func = self.get_function(function_name, ctx)?;
+        let test = self.ir().find_test(&func, test_name)?;
+        Ok(test.constraints().clone())
+    }
 }
 
 impl RuntimeConstructor for InternalBamlRuntime {
@@ -288,16 +300,16 @@ impl RuntimeConstructor for InternalBamlRuntime {
         root_path: &str,
         files: &HashMap<T, T>,
     ) -> Result<InternalBamlRuntime> {
+        let directory = PathBuf::from(root_path);
         let contents = files
             .iter()
             .map(|(path, contents)| {
                 Ok(SourceFile::from((
-                    PathBuf::from(path.as_ref()),
+                    super::source_path(&directory, path.as_ref()),
                     contents.as_ref().to_string(),
                 )))
             })
             .collect::<Result<Vec<_>>>()?;
-        let directory = PathBuf::from(root_path);
         let mut schema = validate(&PathBuf::from(directory), contents);
         schema.diagnostics.to_result()?;
 
@@ -371,13 +383,23 @@ impl RuntimeInterface for InternalBamlRuntime {
 
         let renderer = PromptRenderer::from_function(&func, self.ir(), &ctx)?;
         let orchestrator = self.orchestration_graph(renderer.client_spec(), &ctx)?;
-
-        // Now actually execute the code.
-        let (history, _) =
-            orchestrate_call(orchestrator, self.ir(), &ctx, &renderer, &baml_args, |s| {
-                renderer.parse(s, false)
-            })
-            .await;
+        let total_timeout = self
+            .get_llm_provider(renderer.client_spec(), &ctx)?
+            .total_timeout();
+        let ctx = ctx.with_output_schema(renderer.output_schema());
+
+        // Now actually execute the code. The call is boxed to keep the futures of callers
+        // from nesting too deeply for the compiler to compute their layout.
+        let (history, _) = Box::pin(orchestrate_call(
+            orchestrator,
+            self.ir(),
+            &ctx,
+            &renderer,
+            &baml_args,
+            |s| renderer.parse(s, false),
+            total_timeout,
+        ))
+        .await;
 
         FunctionResult::new_chain(history)
     }
@@ -393,6 +415,9 @@ impl RuntimeInterface for InternalBamlRuntime {
         let func = self.get_function(&function_name, &ctx)?;
         let renderer = PromptRenderer::from_function(&func, self.ir(), &ctx)?;
         let orchestrator = self.orchestration_graph(renderer.client_spec(), &ctx)?;
+        let total_timeout = self
+            .get_llm_provider(renderer.client_spec(), &ctx)?
+            .total_timeout();
         let Some(baml_args) = self
             .ir
             .check_function_params(
@@ -412,6 +437,7 @@ impl RuntimeInterface for InternalBamlRuntime {
             ir: self.ir.clone(),
             params: baml_args,
             orchestrator,
+            total_timeout,
             tracer,
             renderer,
             #[cfg(not(target_arch = "wasm32"))]
@@ -136,6 +136,7 @@ impl RuntimeContextManager {
             client_overrides: Default::default(),
             class_override: cls,
             enum_overrides: enm,
+            output_schema: None,
         };
 
         let client_overrides = match cb {
@@ -168,6 +169,7 @@ impl RuntimeContextManager {
             client_overrides: Default::default(),
             class_override: Default::default(),
             enum_overrides: Default::default(),
+            output_schema: None,
         }
     }
 
@@ -6,7 +6,8 @@ use crate::{
 use anyhow::Result;
 use colored::*;
 
-use baml_types::BamlValue;
+use baml_types::{BamlValue, Constraint, ConstraintLevel, ResponseCheck};
+use internal_baml_core::ir::jinja_helpers::evaluate_predicate;
 use jsonish::BamlValueWithFlags;
 
 #[derive(Debug)]
@@ -185,6 +186,77 @@ impl FunctionResult {
 pub struct TestResponse {
     pub function_response: FunctionResult,
     pub function_span: Option<uuid::Uuid>,
+    pub constraints_result: TestConstraintsResult,
+}
+
+/// The outcome of the `@@check` and `@@assert` attributes on a test block.
+#[derive(Debug, Clone, Default)]
+pub struct TestConstraintsResult {
+    /// Every `@@check` that was evaluated. Failed checks don't fail the test.
+    pub checks: Vec<ResponseCheck>,
+    /// The first `@@assert` that failed. Constraints after it are not evaluated.
+    pub failed_assert: Option<FailedAssert>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FailedAssert {
+    pub label: Option<String>,
+    pub expression: String,
+    /// Set if the expression could not be evaluated, rather than evaluating to false.
+    pub error: Option<String>,
+}
+
+impl std::fmt::Display for FailedAssert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "Assert '{}' ", label)?,
+            None => write!(f, "Assert ")?,
+        }
+        match &self.error {
+            Some(error) => write!(
+                f,
+                "{{{{ {} }}}} could not be evaluated: {}",
+                self.expression, error
+            ),
+            None => write!(f, "{{{{ {} }}}} failed", self.expression),
+        }
+    }
+}
+
+impl TestConstraintsResult {
+    /// Evaluates a test's constraints against the function's parsed output,
+    /// which is available to them as `this`. Nothing is evaluated if the
+    /// function failed, since the test fails regardless.
+    pub fn evaluate(constraints: &[Constraint], function_response: &FunctionResult) -> Self {
+        let mut result = Self::default();
+        let Ok(parsed) = function_response.result_with_constraints_content() else {
+            return result;
+        };
+        let this = BamlValue::from(parsed);
+
+        for constraint in constraints {
+            let outcome = evaluate_predicate(&this, &constraint.expression);
+            match (&constraint.level, outcome) {
+                (ConstraintLevel::Check, outcome) => {
+                    result.checks.extend(ResponseCheck::from_check_result((
+                        constraint.clone(),
+                        outcome.unwrap_or(false),
+                    )));
+                }
+                (ConstraintLevel::Assert, Ok(true)) => {}
+                (ConstraintLevel::Assert, outcome) => {
+                    result.failed_assert = Some(FailedAssert {
+                        label: constraint.label.clone(),
+                        expression: constraint.expression.0.clone(),
+                        error: outcome.err().map(|e| format!("{e:#}")),
+                    });
+                    break;
+                }
+            }
+        }
+
+        result
+    }
 }
 
 impl std::fmt::Display for TestResponse {
@@ -213,6 +285,7 @@ pub enum TestFailReason<'a> {
     TestUnspecified(&'a anyhow::Error),
     TestLLMFailure(&'a LLMResponse),
     TestParseFailure(&'a anyhow::Error),
+    TestAssertFailure(&'a FailedAssert),
 }
 
 impl PartialEq for TestFailReason<'_> {
@@ -223,6 +296,9 @@ impl PartialEq for TestFailReason<'_> {
             (Self::TestParseFailure(a), Self::TestParseFailure(b)) => {
                 a.to_string() == b.to_string()
             }
+            (Self::TestAssertFailure(a), Self::TestAssertFailure(b)) => {
+                a.to_string() == b.to_string()
+            }
             _ => false,
         }
     }
@@ -235,7 +311,10 @@ impl TestResponse {
         let func_res = &self.function_response;
         if let Some(parsed) = func_res.result_with_constraints() {
             if parsed.is_ok() {
-                TestStatus::Pass
+                match &self.constraints_result.failed_assert {
+                    Some(failed) => TestStatus::Fail(TestFailReason::TestAssertFailure(failed)),
+                    None => TestStatus::Pass,
+                }
             } else {
                 TestStatus::Fail(TestFailReason::TestParseFailure(
                     parsed.as_ref().unwrap_err(),
@@ -273,3 +352,97 @@ impl Termination for TestResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use baml_types::{BamlValueWithMeta, JinjaExpression};
+
+    use super::*;
+
+    fn response(value: BamlValue) -> FunctionResult {
+        FunctionResult::new(
+            OrchestrationScope { scope: vec![] },
+            LLMResponse::InternalFailure("unused".to_string()),
+            None,
+            Some(Ok(BamlValueWithMeta::with_default_meta(&value))),
+        )
+    }
+
+    fn constraint(level: ConstraintLevel, label: &str, expression: &str) -> Constraint {
+        Constraint {
+            level,
+            expression: JinjaExpression(expression.to_string()),
+            label: Some(label.to_string()),
+        }
+    }
+
+    #[test]
+    fn passing_constraints() {
+        let result = TestConstraintsResult::evaluate(
+            &[
+                constraint(ConstraintLevel::Check, "positive", "this > 0"),
+                constraint(ConstraintLevel::Assert, "small", "this < 10"),
+            ],
+            &response(BamlValue::Int(3)),
+        );
+        assert!(result.failed_assert.is_none());
+        assert_eq!(result.checks.len(), 1);
+        assert_eq!(result.checks[0].name, "positive");
+        assert_eq!(result.checks[0].status, "succeeded");
+    }
+
+    #[test]
+    fn failing_assert_stops_evaluation() {
+        let result = TestConstraintsResult::evaluate(
+            &[
+                constraint(ConstraintLevel::Assert, "big", "this > 10"),
+                constraint(ConstraintLevel::Check, "positive", "this > 0"),
+            ],
+            &response(BamlValue::Int(3)),
+        );
+        let failed = result.failed_assert.unwrap();
+        assert_eq!(failed.label.as_deref(), Some("big"));
+        assert_eq!(failed.expression, "this > 10");
+        assert!(failed.error.is_none());
+        assert!(result.checks.is_empty());
+    }
+
+    #[test]
+    fn mixed_constraints() {
+        let result = TestConstraintsResult::evaluate(
+            &[
+                constraint(ConstraintLevel::Check, "positive", "this > 0"),
+                constraint(ConstraintLevel::Check, "even", "this % 2 == 0"),
+                constraint(ConstraintLevel::Assert, "small", "this < 10"),
+                constraint(ConstraintLevel::Assert, "big", "this > 5"),
+                constraint(ConstraintLevel::Check, "odd", "this % 2 == 1"),
+            ],
+            &response(BamlValue::Int(3)),
+        );
+        // Failed checks don't fail the test, but the failed assert does, and
+        // nothing after it is evaluated.
+        let statuses = result
+            .checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.status.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, [("positive", "succeeded"), ("even", "failed")]);
+        assert_eq!(result.failed_assert.unwrap().label.as_deref(), Some("big"));
+    }
+
+    #[test]
+    fn nothing_is_evaluated_when_the_function_failed() {
+        let failed = FunctionResult::new(
+            OrchestrationScope { scope: vec![] },
+            LLMResponse::InternalFailure("boom".to_string()),
+            None,
+            None,
+        );
+        let result = TestConstraintsResult::evaluate(
+            &[constraint(ConstraintLevel::Assert, "big", "this > 10")],
+            &failed,
+        );
+        assert!(result.failed_assert.is_none());
+        assert!(result.checks.is_empty());
+    }
+}
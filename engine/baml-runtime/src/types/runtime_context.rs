@@ -6,7 +6,7 @@ use serde;
 use serde_json;
 use std::{collections::HashMap, sync::Arc};
 
-use crate::internal::llm_client::llm_provider::LLMProvider;
+use crate::internal::llm_client::{llm_provider::LLMProvider, OutputSchema};
 
 #[derive(Debug, Clone)]
 pub struct SpanCtx {
@@ -14,20 +14,20 @@ pub struct SpanCtx {
     pub name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PropertyAttributes {
     pub(crate) alias: Option<BamlValue>,
     pub(crate) skip: Option<bool>,
     pub(crate) meta: IndexMap<String, BamlValue>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RuntimeEnumOverride {
     pub(crate) alias: Option<BamlValue>,
     pub(crate) values: IndexMap<String, PropertyAttributes>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RuntimeClassOverride {
     pub(crate) alias: Option<BamlValue>,
     pub(crate) new_fields: IndexMap<String, (FieldType, PropertyAttributes)>,
@@ -50,6 +50,7 @@ cfg_if::cfg_if!(
 );
 
 // #[derive(Debug)]
+#[derive(Clone)]
 pub struct RuntimeContext {
     // path to baml_src in the local filesystem
     pub baml_src: Arc<BamlSrcReader>,
@@ -58,9 +59,18 @@ pub struct RuntimeContext {
     pub client_overrides: Option<(Option<String>, HashMap<String, Arc<LLMProvider>>)>,
     pub class_override: IndexMap<String, RuntimeClassOverride>,
     pub enum_overrides: IndexMap<String, RuntimeEnumOverride>,
+    // set while calling a function, for clients that send the schema natively
+    pub output_schema: Option<OutputSchema>,
 }
 
 impl RuntimeContext {
+    pub fn with_output_schema(self, output_schema: OutputSchema) -> Self {
+        Self {
+            output_schema: Some(output_schema),
+            ..self
+        }
+    }
+
     pub fn resolve_expression<T: serde::de::DeserializeOwned>(
         &self,
         expr: &Expression,
@@ -26,6 +26,7 @@ pub struct FunctionResultStream {
     pub(crate) renderer: PromptRenderer,
     pub(crate) ir: Arc<IntermediateRepr>,
     pub(crate) orchestrator: OrchestratorNodeIterator,
+    pub(crate) total_timeout: Option<web_time::Duration>,
     pub(crate) tracer: Arc<BamlTracer>,
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) tokio_runtime: Arc<tokio::runtime::Runtime>,
@@ -91,10 +92,13 @@ impl FunctionResultStream {
             .tracer
             .start_span(&self.function_name, ctx, &local_params);
 
-        let rctx = ctx.create_ctx(tb, cb);
+        let rctx = ctx
+            .create_ctx(tb, cb)
+            .map(|rctx| rctx.with_output_schema(self.renderer.output_schema()));
         let res = match rctx {
             Ok(rctx) => {
-                let (history, _) = orchestrate_stream(
+                // Boxed for the same reason as the call in `call_function_impl`.
+                let (history, _) = Box::pin(orchestrate_stream(
                     local_orchestrator,
                     self.ir.as_ref(),
                     &rctx,
@@ -103,7 +107,8 @@ impl FunctionResultStream {
                     |content| self.renderer.parse(content, true),
                     |content| self.renderer.parse(content, false),
                     on_event,
-                )
+                    self.total_timeout,
+                ))
                 .await;
 
                 FunctionResult::new_chain(history)
@@ -0,0 +1,374 @@
+use anyhow::Result;
+use baml_types::ResponseCheck;
+use serde::Serialize;
+use std::fmt::Write;
+use web_time::Duration;
+
+use crate::internal::llm_client::LLMResponse;
+
+use super::response::{TestFailReason, TestResponse, TestStatus};
+
+/// Outcome of a single test, in a form suitable for machine-readable reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestReportStatus {
+    Passed,
+    /// The test ran, but the LLM call or parsing failed.
+    Failed,
+    /// The test could not be run at all (e.g. invalid args, missing env vars).
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestFailureReport {
+    /// One of `TestUnspecified`, `TestLLMFailure`, `TestParseFailure` or `TestAssertFailure`.
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestCaseReport {
+    pub function_name: String,
+    pub test_name: String,
+    pub status: TestReportStatus,
+    pub failure: Option<TestFailureReport>,
+    /// Wall clock time for the whole test, including retries and fallbacks.
+    pub duration_ms: u128,
+    /// Latency of the last LLM request made by the test.
+    pub llm_latency_ms: Option<u128>,
+    /// Name of the client that produced the final response.
+    pub client: Option<String>,
+    pub model: Option<String>,
+    /// The orchestration scope (fallbacks, round robins) that produced the final response.
+    pub scope: Option<String>,
+    /// Number of LLM requests made, including retries and fallbacks.
+    pub attempts: usize,
+    pub prompt_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub total_tokens: Option<u64>,
+    /// Results of the test block's `@@check` attributes.
+    pub checks: Vec<ResponseCheck>,
+}
+
+impl TestCaseReport {
+    pub fn new(
+        function_name: impl Into<String>,
+        test_name: impl Into<String>,
+        duration: Duration,
+        response: &Result<TestResponse>,
+    ) -> Self {
+        let mut report = Self {
+            function_name: function_name.into(),
+            test_name: test_name.into(),
+            status: TestReportStatus::Passed,
+            failure: None,
+            duration_ms: duration.as_millis(),
+            llm_latency_ms: None,
+            client: None,
+            model: None,
+            scope: None,
+            attempts: 0,
+            prompt_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            checks: Vec::new(),
+        };
+
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                report.status = TestReportStatus::Error;
+                report.failure = Some(TestFailReason::TestUnspecified(e).into());
+                return report;
+            }
+        };
+
+        report.checks = response.constraints_result.checks.clone();
+        let function_response = &response.function_response;
+        report.attempts = function_response.event_chain().len();
+        report.scope = Some(function_response.scope().name());
+        match function_response.llm_response() {
+            LLMResponse::Success(complete) => {
+                report.client = Some(complete.client.clone());
+                report.model = Some(complete.model.clone());
+                report.llm_latency_ms = Some(complete.latency.as_millis());
+                report.prompt_tokens = complete.metadata.prompt_tokens;
+                report.output_tokens = complete.metadata.output_tokens;
+                report.total_tokens = complete.metadata.total_tokens;
+            }
+            LLMResponse::LLMFailure(failure) => {
+                report.client = Some(failure.client.clone());
+                report.model = failure.model.clone();
+                report.llm_latency_ms = Some(failure.latency.as_millis());
+            }
+            LLMResponse::UserFailure(_) | LLMResponse::InternalFailure(_) => {}
+        }
+
+        if let TestStatus::Fail(reason) = response.status() {
+            report.status = match reason {
+                TestFailReason::TestUnspecified(_) => TestReportStatus::Error,
+                _ => TestReportStatus::Failed,
+            };
+            report.failure = Some(reason.into());
+        }
+
+        report
+    }
+}
+
+impl From<TestFailReason<'_>> for TestFailureReport {
+    fn from(reason: TestFailReason<'_>) -> Self {
+        let (kind, message) = match reason {
+            TestFailReason::TestUnspecified(e) => ("TestUnspecified", format!("{e:#}")),
+            TestFailReason::TestLLMFailure(response) => ("TestLLMFailure", response.to_string()),
+            TestFailReason::TestParseFailure(e) => ("TestParseFailure", format!("{e:#}")),
+            TestFailReason::TestAssertFailure(failed) => ("TestAssertFailure", failed.to_string()),
+        };
+        Self {
+            kind: kind.to_string(),
+            message,
+        }
+    }
+}
+
+/// Results of a test run, serializable as JSON or JUnit XML.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub duration_ms: u128,
+    pub tests: Vec<TestCaseReport>,
+}
+
+impl TestReport {
+    pub fn new(tests: Vec<TestCaseReport>, duration: Duration) -> Self {
+        let count = |status: TestReportStatus| tests.iter().filter(|t| t.status == status).count();
+        Self {
+            passed: count(TestReportStatus::Passed),
+            failed: count(TestReportStatus::Failed),
+            errors: count(TestReportStatus::Error),
+            duration_ms: duration.as_millis(),
+            tests,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Render the report as JUnit XML, with one `<testsuite>` per BAML function.
+    pub fn to_junit_xml(&self) -> String {
+        let mut suites: indexmap::IndexMap<&str, Vec<&TestCaseReport>> = Default::default();
+        for test in &self.tests {
+            suites.entry(&test.function_name).or_default().push(test);
+        }
+
+        let mut xml = String::new();
+        // Writing to a String cannot fail, so the results below are ignored.
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="baml" tests="{}" failures="{}" errors="{}" time="{}">"#,
+            self.tests.len(),
+            self.failed,
+            self.errors,
+            seconds(self.duration_ms)
+        );
+        for (function_name, tests) in suites {
+            let failed = tests
+                .iter()
+                .filter(|t| t.status == TestReportStatus::Failed)
+                .count();
+            let errors = tests
+                .iter()
+                .filter(|t| t.status == TestReportStatus::Error)
+                .count();
+            let _ = writeln!(
+                xml,
+                r#"  <testsuite name="{}" tests="{}" failures="{failed}" errors="{errors}" time="{}">"#,
+                xml_escape(function_name),
+                tests.len(),
+                seconds(tests.iter().map(|t| t.duration_ms).sum())
+            );
+            for test in tests {
+                write_junit_testcase(&mut xml, test);
+            }
+            let _ = writeln!(xml, "  </testsuite>");
+        }
+        let _ = writeln!(xml, "</testsuites>");
+        xml
+    }
+}
+
+fn write_junit_testcase(xml: &mut String, test: &TestCaseReport) {
+    let _ = writeln!(
+        xml,
+        r#"    <testcase name="{}" classname="{}" time="{}">"#,
+        xml_escape(&test.test_name),
+        xml_escape(&test.function_name),
+        seconds(test.duration_ms)
+    );
+
+    let properties = [
+        ("client", test.client.clone()),
+        ("model", test.model.clone()),
+        ("scope", test.scope.clone()),
+        ("attempts", Some(test.attempts.to_string())),
+        ("llm_latency_ms", test.llm_latency_ms.map(|t| t.to_string())),
+        ("prompt_tokens", test.prompt_tokens.map(|t| t.to_string())),
+        ("output_tokens", test.output_tokens.map(|t| t.to_string())),
+        ("total_tokens", test.total_tokens.map(|t| t.to_string())),
+    ];
+    let _ = writeln!(xml, "      <properties>");
+    for (name, value) in properties {
+        if let Some(value) = value {
+            let _ = writeln!(
+                xml,
+                r#"        <property name="{name}" value="{}"/>"#,
+                xml_escape(&value)
+            );
+        }
+    }
+    for check in &test.checks {
+        let _ = writeln!(
+            xml,
+            r#"        <property name="check.{}" value="{}"/>"#,
+            xml_escape(&check.name),
+            xml_escape(&check.status)
+        );
+    }
+    let _ = writeln!(xml, "      </properties>");
+
+    if let Some(failure) = &test.failure {
+        let tag = match test.status {
+            TestReportStatus::Error => "error",
+            _ => "failure",
+        };
+        let _ = writeln!(
+            xml,
+            r#"      <{tag} type="{}" message="{}">{}</{tag}>"#,
+            xml_escape(&failure.kind),
+            xml_escape(failure.message.lines().next().unwrap_or_default()),
+            xml_escape(&failure.message)
+        );
+    }
+    let _ = writeln!(xml, "    </testcase>");
+}
+
+fn seconds(millis: u128) -> String {
+    format!("{:.3}", millis as f64 / 1000.0)
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 does not allow most control characters, even escaped.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn case(test_name: &str, status: TestReportStatus) -> TestCaseReport {
+        TestCaseReport {
+            function_name: "ExtractResume".to_string(),
+            test_name: test_name.to_string(),
+            failure: match status {
+                TestReportStatus::Passed => None,
+                _ => Some(TestFailureReport {
+                    kind: "TestParseFailure".to_string(),
+                    message: "expected <Resume> & got \"nothing\"\nsecond line".to_string(),
+                }),
+            },
+            status,
+            duration_ms: 1500,
+            llm_latency_ms: Some(1200),
+            client: Some("GPT4o".to_string()),
+            model: Some("gpt-4o".to_string()),
+            scope: Some("GPT4o".to_string()),
+            attempts: 1,
+            prompt_tokens: Some(10),
+            output_tokens: Some(20),
+            total_tokens: Some(30),
+            checks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let report = TestReport::new(
+            vec![
+                case("vaibhav", TestReportStatus::Passed),
+                case("broken", TestReportStatus::Failed),
+            ],
+            Duration::from_millis(3000),
+        );
+
+        assert_eq!(
+            report.to_junit_xml(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="baml" tests="2" failures="1" errors="0" time="3.000">
+  <testsuite name="ExtractResume" tests="2" failures="1" errors="0" time="3.000">
+    <testcase name="vaibhav" classname="ExtractResume" time="1.500">
+      <properties>
+        <property name="client" value="GPT4o"/>
+        <property name="model" value="gpt-4o"/>
+        <property name="scope" value="GPT4o"/>
+        <property name="attempts" value="1"/>
+        <property name="llm_latency_ms" value="1200"/>
+        <property name="prompt_tokens" value="10"/>
+        <property name="output_tokens" value="20"/>
+        <property name="total_tokens" value="30"/>
+      </properties>
+    </testcase>
+    <testcase name="broken" classname="ExtractResume" time="1.500">
+      <properties>
+        <property name="client" value="GPT4o"/>
+        <property name="model" value="gpt-4o"/>
+        <property name="scope" value="GPT4o"/>
+        <property name="attempts" value="1"/>
+        <property name="llm_latency_ms" value="1200"/>
+        <property name="prompt_tokens" value="10"/>
+        <property name="output_tokens" value="20"/>
+        <property name="total_tokens" value="30"/>
+      </properties>
+      <failure type="TestParseFailure" message="expected &lt;Resume&gt; &amp; got &quot;nothing&quot;">expected &lt;Resume&gt; &amp; got &quot;nothing&quot;
+second line</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn test_json_counts() {
+        let report = TestReport::new(
+            vec![
+                case("a", TestReportStatus::Passed),
+                case("b", TestReportStatus::Failed),
+                case("c", TestReportStatus::Error),
+            ],
+            Duration::from_millis(10),
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["passed"], 1);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["errors"], 1);
+        assert_eq!(json["tests"][1]["status"], "failed");
+        assert_eq!(json["tests"][1]["failure"]["kind"], "TestParseFailure");
+    }
+}
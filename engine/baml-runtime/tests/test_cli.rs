@@ -100,6 +100,7 @@ mod test_cli {
             .eventsource()
             .map(|event| match event {
                 Ok(event) => Ok((
+                    event.event,
                     serde_json::from_str::<serde_json::Value>(&event.data)?,
                     stream_start.elapsed(),
                 )),
@@ -110,8 +111,8 @@ mod test_cli {
         let resp = resp.into_iter().collect::<Result<Vec<_>>>()?;
 
         assert!(resp.len() > 2);
-        let (_, time_to_first) = resp[0].clone();
-        let (last_data, time_to_last) = resp.last().context("No last data")?.clone();
+        let (_, _, time_to_first) = resp[0].clone();
+        let (last_event, last_data, time_to_last) = resp.last().context("No last data")?.clone();
         assert!(
             // This is a funky assertion, but the tldr is that it's our heuristic that streaming is working.
             // Specifically, we're saying that:
@@ -126,6 +127,8 @@ mod test_cli {
             time_to_first,
             time_to_last
         );
+        // The stream ends with the final result, parsed as `/call` parses it.
+        assert_eq!(last_event, "final");
         assert_eq!(last_data["name"], "Vaibhav Gupta");
 
         Ok(())
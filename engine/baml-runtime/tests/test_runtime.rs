@@ -507,4 +507,266 @@ test TestTree {
 
         Ok(())
     }
+
+    const TIMEOUT_CLIENTS: &str = r##"
+client<llm> Slow {
+  provider mock
+  options {
+    response "slow"
+    latency_ms 2000
+    request_timeout_ms 50
+  }
+}
+
+client<llm> Fast {
+  provider mock
+  options {
+    response "fast"
+  }
+}
+
+client<llm> SlowThenFast {
+  provider fallback
+  options {
+    strategy [Slow, Fast]
+  }
+}
+
+client<llm> SlowWithDeadline {
+  provider fallback
+  options {
+    strategy [Slow, Slow, Slow, Fast]
+    total_timeout_ms 120
+  }
+}
+
+function FallBack(input: string) -> string {
+  client SlowThenFast
+  prompt #"{{ input }}"#
+}
+
+function RunOutOfTime(input: string) -> string {
+  client SlowWithDeadline
+  prompt #"{{ input }}"#
+}
+"##;
+
+    fn call_timeout_function(function_name: &str) -> anyhow::Result<baml_runtime::FunctionResult> {
+        let runtime = make_test_runtime(TIMEOUT_CLIENTS)?;
+        let ctx = runtime.create_ctx_manager(BamlValue::String("test".to_string()), None);
+        let params = [("input".to_string(), BamlValue::String("hi".to_string()))]
+            .into_iter()
+            .collect();
+        let (res, _) = runtime.async_runtime.block_on(runtime.call_function(
+            function_name.to_string(),
+            &params,
+            &ctx,
+            None,
+            None,
+        ));
+        res
+    }
+
+    #[test]
+    fn test_request_timeout_falls_back() -> anyhow::Result<()> {
+        let res = call_timeout_function("FallBack")?;
+        assert_eq!(res.content()?, "fast");
+        Ok(())
+    }
+
+    #[test]
+    fn test_total_timeout_stops_the_orchestration() -> anyhow::Result<()> {
+        let res = call_timeout_function("RunOutOfTime")?;
+        match res.llm_response() {
+            LLMResponse::LLMFailure(failure) => {
+                assert!(matches!(
+                    failure.code,
+                    baml_runtime::internal::llm_client::ErrorCode::Timeout
+                ));
+                assert!(failure.message.contains("total_timeout_ms"));
+            }
+            other => panic!("Expected a timeout, got: {other}"),
+        }
+        Ok(())
+    }
+
+    const RETRY_CLIENTS: &str = r##"
+retry_policy OnlyRateLimits {
+  max_retries 2
+  strategy {
+    type constant_delay
+    delay_ms 10
+    jitter 0.5
+  }
+  retry_on [429]
+}
+
+client<llm> BadRequestOnce {
+  provider mock
+  retry_policy OnlyRateLimits
+  options {
+    responses [
+      { error_status 400 },
+      "ok"
+    ]
+  }
+}
+
+client<llm> RateLimitedOnce {
+  provider mock
+  retry_policy OnlyRateLimits
+  options {
+    responses [
+      { error_status 429, retry_after_ms 300 },
+      "ok"
+    ]
+  }
+}
+
+function NotRetried(input: string) -> string {
+  client BadRequestOnce
+  prompt #"{{ input }}"#
+}
+
+function WaitsForRetryAfter(input: string) -> string {
+  client RateLimitedOnce
+  prompt #"{{ input }}"#
+}
+"##;
+
+    fn call_retry_function(function_name: &str) -> anyhow::Result<baml_runtime::FunctionResult> {
+        let runtime = make_test_runtime(RETRY_CLIENTS)?;
+        let ctx = runtime.create_ctx_manager(BamlValue::String("test".to_string()), None);
+        let params = [("input".to_string(), BamlValue::String("hi".to_string()))]
+            .into_iter()
+            .collect();
+        let (res, _) = runtime.async_runtime.block_on(runtime.call_function(
+            function_name.to_string(),
+            &params,
+            &ctx,
+            None,
+            None,
+        ));
+        res
+    }
+
+    #[test]
+    fn test_retry_on_skips_other_status_codes() -> anyhow::Result<()> {
+        let res = call_retry_function("NotRetried")?;
+        match res.llm_response() {
+            LLMResponse::LLMFailure(failure) => assert_eq!(failure.code.to_u16(), 400),
+            other => panic!("Expected the first failure, got: {other}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_waits_for_retry_after() -> anyhow::Result<()> {
+        let start = std::time::Instant::now();
+        let res = call_retry_function("WaitsForRetryAfter")?;
+        assert_eq!(res.content()?, "ok");
+        assert!(start.elapsed() >= std::time::Duration::from_millis(300));
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_template_renders_the_prompt() -> anyhow::Result<()> {
+        let runtime = make_test_runtime(
+            r##"
+client<llm> Templated {
+  provider mock
+  options {
+    model "echo-1"
+    template #"{{ model }}: {{ last_message | upper }}"#
+  }
+}
+
+function Shout(input: string) -> string {
+  client Templated
+  prompt #"{{ input }}"#
+}
+"##,
+        )?;
+        let ctx = runtime.create_ctx_manager(BamlValue::String("test".to_string()), None);
+        let params = [("input".to_string(), BamlValue::String("hi".to_string()))]
+            .into_iter()
+            .collect();
+        let (res, _) = runtime.async_runtime.block_on(runtime.call_function(
+            "Shout".to_string(),
+            &params,
+            &ctx,
+            None,
+            None,
+        ));
+        let res = res?;
+        assert_eq!(res.content()?, "echo-1: HI");
+        assert_eq!(
+            BamlValue::from(res.parsed_content()?),
+            BamlValue::String("echo-1: HI".to_string())
+        );
+        Ok(())
+    }
+
+    const CASSETTE_CLIENTS: &str = r##"
+client<llm> Recorded {
+  provider mock
+  options {
+    response "live"
+  }
+}
+
+function Echo(input: string) -> string {
+  client Recorded
+  prompt #"{{ input }}"#
+}
+"##;
+
+    fn call_with_cassette(
+        mode: &str,
+        path: &std::path::Path,
+    ) -> anyhow::Result<baml_runtime::FunctionResult> {
+        let mut files = HashMap::new();
+        files.insert("main.baml", CASSETTE_CLIENTS);
+        let runtime = BamlRuntime::from_file_content(
+            "baml_src",
+            &files,
+            [
+                ("BAML_CASSETTE_MODE", mode),
+                ("BAML_CASSETTE_PATH", path.to_str().unwrap()),
+            ]
+            .into(),
+        )?;
+        let ctx = runtime.create_ctx_manager(BamlValue::String("test".to_string()), None);
+        let params = [("input".to_string(), BamlValue::String("hi".to_string()))]
+            .into_iter()
+            .collect();
+        let (res, _) = runtime.async_runtime.block_on(runtime.call_function(
+            "Echo".to_string(),
+            &params,
+            &ctx,
+            None,
+            None,
+        ));
+        res
+    }
+
+    #[test]
+    fn test_cassette_records_then_replays() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "baml_cassette_round_trip_{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(call_with_cassette("record", &path)?.content()?, "live");
+        let recorded = std::fs::read_to_string(&path)?;
+        assert!(recorded.contains(r#""live""#), "{recorded}");
+
+        // Replays come from the file, which is re-read once it changes.
+        std::fs::write(&path, recorded.replace(r#""live""#, r#""replayed""#))?;
+        let replayed = call_with_cassette("replay", &path);
+        std::fs::remove_file(&path)?;
+        assert_eq!(replayed?.content()?, "replayed");
+        Ok(())
+    }
 }
@@ -420,6 +420,7 @@ pub enum TestStatus {
     LLMFailure,
     ParseFailure,
     UnableToRun,
+    AssertFailure,
 }
 
 #[wasm_bindgen(getter_with_clone, inspectable)]
@@ -584,6 +585,9 @@ impl WasmTestResponse {
                     baml_runtime::TestFailReason::TestUnspecified(_) => TestStatus::UnableToRun,
                     baml_runtime::TestFailReason::TestLLMFailure(_) => TestStatus::LLMFailure,
                     baml_runtime::TestFailReason::TestParseFailure(_) => TestStatus::ParseFailure,
+                    baml_runtime::TestFailReason::TestAssertFailure(_) => {
+                        TestStatus::AssertFailure
+                    }
                 },
             },
             Err(_) => TestStatus::UnableToRun,
@@ -618,6 +622,17 @@ impl WasmTestResponse {
         self.parsed_response_impl().ok()
     }
 
+    /// JSON-string of the results of the test block's `@@check` attributes.
+    #[wasm_bindgen]
+    pub fn test_checks(&self) -> Option<String> {
+        let checks = &self.test_response.as_ref().ok()?.constraints_result.checks;
+        if checks.is_empty() {
+            None
+        } else {
+            serde_json::to_string(checks).ok()
+        }
+    }
+
     #[wasm_bindgen]
     pub fn llm_failure(&self) -> Option<WasmLLMFailure> {
         self.test_response.as_ref().ok().and_then(|r| {
@@ -759,6 +774,7 @@ impl WithRenderError for baml_runtime::TestFailReason<'_> {
             baml_runtime::TestFailReason::TestUnspecified(e) => Some(format!("{e:#}")),
             baml_runtime::TestFailReason::TestLLMFailure(f) => f.render_error(),
             baml_runtime::TestFailReason::TestParseFailure(e) => Some(format!("{e:#}")),
+            baml_runtime::TestFailReason::TestAssertFailure(f) => Some(f.to_string()),
         }
     }
 }
@@ -809,6 +825,9 @@ fn get_dummy_value(
                 TypeValue::Media(BamlMediaType::Audio) => {
                     "{ url \"https://actions.google.com/sounds/v1/emergency/beeper_emergency_call.ogg\"}".to_string()
                 }
+                TypeValue::Message => {
+                    "{ role \"user\" content \"Hello!\" }".to_string()
+                }
             };
 
             Some(dummy)
@@ -816,6 +835,7 @@ fn get_dummy_value(
         baml_runtime::FieldType::Literal(_) => None,
         baml_runtime::FieldType::Enum(_) => None,
         baml_runtime::FieldType::Class(_) => None,
+        baml_runtime::FieldType::RecursiveTypeAlias(_) => None,
         baml_runtime::FieldType::List(item) => {
             let dummy = get_dummy_value(indent + 1, allow_multiline, item);
             // Repeat it 2 times
@@ -1643,9 +1663,9 @@ impl ToJsValue for ExecutionScope {
                 set_property(&obj, "type", JsValue::from_str("Direct"));
                 set_property(&obj, "name", JsValue::from_str(name));
             }
-            ExecutionScope::Retry(name, count, delay) => {
+            ExecutionScope::Retry(policy, count, delay) => {
                 set_property(&obj, "type", JsValue::from_str("Retry"));
-                set_property(&obj, "name", JsValue::from_str(name));
+                set_property(&obj, "name", JsValue::from_str(policy.name()));
                 set_property(&obj, "count", JsValue::from_f64(*count as f64));
                 set_property(&obj, "delay", JsValue::from_f64(delay.as_millis() as f64));
             }
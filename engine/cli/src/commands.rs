@@ -27,6 +27,9 @@ pub(crate) enum Commands {
     #[command(about = "Starts a development server")]
     Dev(baml_runtime::cli::dev::DevArgs),
 
+    #[command(about = "Runs the test blocks in the baml_src directory")]
+    Test(baml_runtime::cli::test::TestArgs),
+
     #[command(subcommand, about = "Authenticate with Boundary Cloud")]
     Auth(crate::auth::AuthCommands),
 
@@ -58,6 +61,10 @@ impl RuntimeCli {
                 args.from = BamlRuntime::parse_baml_src_path(&args.from)?;
                 args.run(defaults)
             }
+            Commands::Test(args) => {
+                args.from = BamlRuntime::parse_baml_src_path(&args.from)?;
+                args.run()
+            }
             Commands::Auth(args) => t.block_on(async { args.run_async().await }),
             Commands::Login(args) => t.block_on(async { args.run_async().await }),
             Commands::Deploy(args) => {
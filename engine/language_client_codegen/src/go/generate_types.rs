@@ -0,0 +1,610 @@
+use anyhow::Result;
+use baml_types::{BamlMediaType, FieldType, TypeValue};
+use indexmap::IndexMap;
+use itertools::Itertools;
+
+use crate::{field_type_attributes, type_check_attributes, TypeCheckAttributes};
+
+use super::go_language_features::{go_name, ToGo};
+use internal_baml_core::ir::{repr::IntermediateRepr, ClassWalker, EnumWalker};
+
+#[derive(askama::Template)]
+#[template(path = "types.go.j2", escape = "none")]
+pub(crate) struct GoTypes {
+    enums: Vec<GoEnum>,
+    classes: Vec<GoStruct>,
+    checks: Vec<GoStruct>,
+    unions: Vec<GoUnion>,
+}
+
+#[derive(askama::Template)]
+#[template(path = "partial_types.go.j2", escape = "none")]
+pub(crate) struct GoPartialTypes {
+    partial_classes: Vec<GoStruct>,
+    unions: Vec<GoUnion>,
+}
+
+struct GoEnum {
+    name: String,
+    // The `const` declarations, aligned.
+    values: Vec<String>,
+}
+
+struct GoStruct {
+    name: String,
+    // The field declarations, aligned.
+    fields: Vec<String>,
+}
+
+/// A struct with one field per member of a union, of which one is set.
+struct GoUnion {
+    name: String,
+    // The field declarations, aligned.
+    fields: Vec<String>,
+    members: Vec<String>,
+}
+
+impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for GoTypes {
+    type Error = anyhow::Error;
+
+    fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
+        let mut unions = IndexMap::new();
+        for t in all_types(ir, false) {
+            collect_unions(&normalize(t), false, &mut unions);
+        }
+
+        let types = GoTypes {
+            enums: ir.walk_enums().map(GoEnum::from).collect(),
+            classes: ir.walk_classes().map(GoStruct::from).collect(),
+            checks: type_check_attributes(ir)
+                .into_iter()
+                .sorted_by_key(checks_name)
+                .map(checks_struct)
+                .collect(),
+            unions: unions.into_values().collect(),
+        };
+        check_unique_names(ir, &types)?;
+        Ok(types)
+    }
+}
+
+impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for GoPartialTypes {
+    type Error = anyhow::Error;
+
+    fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
+        let mut unions = IndexMap::new();
+        for t in all_types(ir, true) {
+            collect_unions(&normalize(t), true, &mut unions);
+        }
+
+        Ok(GoPartialTypes {
+            partial_classes: ir
+                .walk_classes()
+                .map(|c| GoStruct {
+                    name: format!("Partial{}", go_name(c.name())),
+                    fields: align(
+                        c.item
+                            .elem
+                            .static_fields
+                            .iter()
+                            .map(|f| {
+                                vec![
+                                    go_name(&f.elem.name),
+                                    f.elem.r#type.elem.to_partial_go(),
+                                    json_tag(&f.elem.name),
+                                ]
+                            })
+                            .collect(),
+                    ),
+                })
+                .collect(),
+            unions: unions.into_values().collect(),
+        })
+    }
+}
+
+impl From<EnumWalker<'_>> for GoEnum {
+    fn from(e: EnumWalker<'_>) -> GoEnum {
+        let name = go_name(e.name());
+        GoEnum {
+            values: align(
+                e.item
+                    .elem
+                    .values
+                    .iter()
+                    .map(|v| {
+                        vec![
+                            format!("{name}{}", go_name(&v.elem.0)),
+                            name.clone(),
+                            format!("= \"{}\"", v.elem.0),
+                        ]
+                    })
+                    .collect(),
+            ),
+            name,
+        }
+    }
+}
+
+impl From<ClassWalker<'_>> for GoStruct {
+    fn from(c: ClassWalker<'_>) -> GoStruct {
+        GoStruct {
+            name: go_name(c.name()),
+            fields: align(
+                c.item
+                    .elem
+                    .static_fields
+                    .iter()
+                    .map(|f| {
+                        vec![
+                            go_name(&f.elem.name),
+                            f.elem.r#type.elem.to_go(),
+                            json_tag(&f.elem.name),
+                        ]
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn json_tag(name: &str) -> String {
+    format!("`json:\"{name}\"`")
+}
+
+/// Lays out the cells of some declarations in columns, the way `gofmt`
+/// does.
+fn align(rows: Vec<Vec<String>>) -> Vec<String> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    rows.into_iter()
+        .map(|row| {
+            let last = row.len() - 1;
+            row.into_iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    if i == last {
+                        cell
+                    } else {
+                        format!("{cell:<0$}", widths[i])
+                    }
+                })
+                .join(" ")
+        })
+        .collect()
+}
+
+/// The name of the struct with the results of `checks`, e.g.
+/// `ChecksTooLongValidEmail` for `too_long` and `valid_email`.
+pub(super) fn checks_name(checks: &TypeCheckAttributes) -> String {
+    format!(
+        "Checks{}",
+        checks
+            .0
+            .iter()
+            .sorted()
+            .map(|check| go_name(check))
+            .join("")
+    )
+}
+
+fn checks_struct(checks: TypeCheckAttributes) -> GoStruct {
+    GoStruct {
+        name: checks_name(&checks),
+        fields: align(
+            checks
+                .0
+                .iter()
+                .sorted()
+                .map(|check| vec![go_name(check), "Check".to_string(), json_tag(check)])
+                .collect(),
+        ),
+    }
+}
+
+/// Every type that a function's arguments or results may contain, and, for
+/// partial types, just the results.
+fn all_types(ir: &IntermediateRepr, partial: bool) -> Vec<&FieldType> {
+    let mut types: Vec<&FieldType> = vec![];
+    for class in ir.walk_classes() {
+        for field in class.item.elem.static_fields.iter() {
+            types.push(&field.elem.r#type.elem);
+        }
+    }
+    for function in ir.walk_functions() {
+        if !partial {
+            for (_name, parameter) in function.item.elem.inputs.iter() {
+                types.push(parameter);
+            }
+        }
+        types.push(&function.item.elem.output);
+    }
+    types
+}
+
+fn check_unique_names(ir: &IntermediateRepr, types: &GoTypes) -> Result<()> {
+    let mut names: IndexMap<String, String> = [
+        "Client",
+        "NewClient",
+        "Error",
+        "Stream",
+        "Check",
+        "Checked",
+        "Image",
+        "Audio",
+    ]
+    .into_iter()
+    .map(|name| (name.to_string(), "the client".to_string()))
+    .collect();
+
+    let declared = types
+        .enums
+        .iter()
+        .map(|e| (e.name.clone(), format!("enum {}", e.name)))
+        .chain(ir.walk_classes().flat_map(|c| {
+            [
+                (go_name(c.name()), format!("class {}", c.name())),
+                (
+                    format!("Partial{}", go_name(c.name())),
+                    format!("class {}", c.name()),
+                ),
+            ]
+        }))
+        .chain(
+            types
+                .checks
+                .iter()
+                .map(|c| (c.name.clone(), "the results of some checks".to_string())),
+        )
+        .chain(
+            types
+                .unions
+                .iter()
+                .map(|u| (u.name.clone(), "a union".to_string())),
+        );
+    for (name, what) in declared {
+        if let Some(existing) = names.get(&name) {
+            anyhow::bail!("Both {existing} and {what} would be named {name} in Go");
+        }
+        names.insert(name, what);
+    }
+    Ok(())
+}
+
+/// Rewrites `t` into the type it's represented as in Go: literals as their
+/// base type, `T | null` as `T?`, unions of one type as that type, and nested
+/// unions as one.
+pub(super) fn normalize(t: &FieldType) -> FieldType {
+    match t {
+        FieldType::Literal(value) => value.literal_base_type(),
+        FieldType::Union(options) => {
+            let mut nullable = false;
+            let mut members: Vec<FieldType> = vec![];
+            let add = |member: FieldType, members: &mut Vec<FieldType>| {
+                if !members.contains(&member) {
+                    members.push(member);
+                }
+            };
+            for option in options {
+                match normalize(option) {
+                    FieldType::Primitive(TypeValue::Null) => nullable = true,
+                    FieldType::Optional(inner) => {
+                        nullable = true;
+                        match *inner {
+                            FieldType::Union(inner) => {
+                                inner.into_iter().for_each(|m| add(m, &mut members))
+                            }
+                            inner => add(inner, &mut members),
+                        }
+                    }
+                    FieldType::Union(inner) => inner.into_iter().for_each(|m| add(m, &mut members)),
+                    member => add(member, &mut members),
+                }
+            }
+            let union = match members.len() {
+                0 => FieldType::Primitive(TypeValue::Null),
+                1 => members.remove(0),
+                _ => FieldType::Union(members),
+            };
+            match union {
+                FieldType::Primitive(TypeValue::Null) => union,
+                union if nullable => FieldType::Optional(Box::new(union)),
+                union => union,
+            }
+        }
+        FieldType::Optional(inner) => match normalize(inner) {
+            inner @ (FieldType::Optional(_) | FieldType::Primitive(TypeValue::Null)) => inner,
+            inner => FieldType::Optional(Box::new(inner)),
+        },
+        FieldType::List(item) => FieldType::List(Box::new(normalize(item))),
+        FieldType::Map(key, value) => {
+            FieldType::Map(Box::new(normalize(key)), Box::new(normalize(value)))
+        }
+        FieldType::Tuple(items) => FieldType::Tuple(items.iter().map(normalize).collect()),
+        FieldType::Constrained { base, constraints } => match normalize(base) {
+            // One `Checked` holds the results of all of a value's checks.
+            FieldType::Constrained {
+                base,
+                constraints: inner,
+            } => FieldType::Constrained {
+                base,
+                constraints: constraints.iter().chain(inner.iter()).cloned().collect(),
+            },
+            base => FieldType::Constrained {
+                base: Box::new(base),
+                constraints: constraints.clone(),
+            },
+        },
+        t => t.clone(),
+    }
+}
+
+impl ToGo for FieldType {
+    fn to_go(&self) -> String {
+        go_type(&normalize(self))
+    }
+}
+
+pub(super) trait ToPartialGo {
+    /// The type of a field of a partial class, which is null until it's
+    /// streamed.
+    fn to_partial_go(&self) -> String;
+    /// The type of a partial value, e.g. an item of a list.
+    fn to_partial_go_value(&self) -> String;
+}
+
+impl ToPartialGo for FieldType {
+    fn to_partial_go(&self) -> String {
+        partial_field_type(&normalize(self))
+    }
+
+    fn to_partial_go_value(&self) -> String {
+        partial_type(&normalize(self))
+    }
+}
+
+/// Whether a Go type can already be nil, so it doesn't need a pointer to be
+/// optional.
+fn is_nilable(go_type: &str) -> bool {
+    go_type.starts_with("[]") || go_type.starts_with("map[") || go_type == "any"
+}
+
+fn pointer_to(go_type: String) -> String {
+    if is_nilable(&go_type) {
+        go_type
+    } else {
+        format!("*{go_type}")
+    }
+}
+
+fn map_key(key: &FieldType) -> String {
+    match key {
+        FieldType::Enum(name) => go_name(name),
+        _ => "string".to_string(),
+    }
+}
+
+/// Takes a normalized type.
+fn go_type(t: &FieldType) -> String {
+    match t {
+        FieldType::Primitive(value) => value.to_go(),
+        FieldType::Literal(value) => go_type(&value.literal_base_type()),
+        FieldType::Enum(name) | FieldType::Class(name) => go_name(name),
+        FieldType::List(item) => format!("[]{}", go_type(item)),
+        FieldType::Map(key, value) => format!("map[{}]{}", map_key(key), go_type(value)),
+        FieldType::Optional(inner) => pointer_to(go_type(inner)),
+        FieldType::Union(_) => union_name(t),
+        // TODO: typed tuples
+        FieldType::Tuple(_) => "[]any".to_string(),
+        // Go can't declare a type in terms of itself without a struct, so
+        // values of recursive aliases are left as decoded JSON.
+        FieldType::RecursiveTypeAlias(_) => "any".to_string(),
+        FieldType::Constrained { base, .. } => match field_type_attributes(t) {
+            Some(checks) => format!("Checked[{}, {}]", go_type(base), checks_name(&checks)),
+            None => go_type(base),
+        },
+    }
+}
+
+/// Takes a normalized type.
+fn partial_field_type(t: &FieldType) -> String {
+    match t {
+        FieldType::Optional(inner) => partial_field_type(inner),
+        t => pointer_to(partial_type(t)),
+    }
+}
+
+/// Takes a normalized type.
+fn partial_type(t: &FieldType) -> String {
+    match t {
+        FieldType::Class(name) => format!("Partial{}", go_name(name)),
+        FieldType::List(item) => format!("[]{}", partial_type(item)),
+        FieldType::Map(key, value) => format!("map[{}]{}", map_key(key), partial_type(value)),
+        FieldType::Optional(inner) => partial_field_type(inner),
+        FieldType::Union(_) => format!("Partial{}", union_name(t)),
+        FieldType::Constrained { base, .. } => match field_type_attributes(t) {
+            Some(checks) => format!("Checked[{}, {}]", partial_type(base), checks_name(&checks)),
+            None => partial_type(base),
+        },
+        t => go_type(t),
+    }
+}
+
+/// The name of a union's struct, e.g. `UnionIntOrString`.
+fn union_name(t: &FieldType) -> String {
+    match t {
+        FieldType::Union(members) => {
+            format!("Union{}", members.iter().map(member_name).join("Or"))
+        }
+        t => member_name(t),
+    }
+}
+
+/// The name of a union's field for one of its members.
+fn member_name(t: &FieldType) -> String {
+    match t {
+        FieldType::Primitive(TypeValue::String) => "String".to_string(),
+        FieldType::Primitive(TypeValue::Int) => "Int".to_string(),
+        FieldType::Primitive(TypeValue::Float) => "Float".to_string(),
+        FieldType::Primitive(TypeValue::Bool) => "Bool".to_string(),
+        FieldType::Primitive(TypeValue::Null) => "Null".to_string(),
+        FieldType::Primitive(TypeValue::Media(BamlMediaType::Image)) => "Image".to_string(),
+        FieldType::Primitive(TypeValue::Media(BamlMediaType::Audio)) => "Audio".to_string(),
+        FieldType::Primitive(TypeValue::Message) => "Message".to_string(),
+        FieldType::Literal(value) => member_name(&value.literal_base_type()),
+        FieldType::Enum(name) | FieldType::Class(name) | FieldType::RecursiveTypeAlias(name) => {
+            go_name(name)
+        }
+        FieldType::List(item) => format!("ListOf{}", member_name(item)),
+        FieldType::Map(_, value) => format!("MapOf{}", member_name(value)),
+        FieldType::Optional(inner) => format!("Optional{}", member_name(inner)),
+        FieldType::Union(_) => union_name(t),
+        FieldType::Tuple(_) => "Tuple".to_string(),
+        FieldType::Constrained { base, .. } => member_name(base),
+    }
+}
+
+/// Adds the struct for every union in a normalized type to `unions`.
+fn collect_unions(t: &FieldType, partial: bool, unions: &mut IndexMap<String, GoUnion>) {
+    match t {
+        FieldType::Union(members) => {
+            members
+                .iter()
+                .for_each(|m| collect_unions(m, partial, unions));
+            let name = match partial {
+                true => format!("Partial{}", union_name(t)),
+                false => union_name(t),
+            };
+            if unions.contains_key(&name) {
+                return;
+            }
+            let members: Vec<(String, String)> = members
+                .iter()
+                .map(|m| {
+                    let member_type = match partial {
+                        true => partial_type(m),
+                        false => go_type(m),
+                    };
+                    (member_name(m), pointer_to(member_type))
+                })
+                .collect();
+            unions.insert(
+                name.clone(),
+                GoUnion {
+                    name,
+                    fields: align(
+                        members
+                            .iter()
+                            .map(|(name, member_type)| vec![name.clone(), member_type.clone()])
+                            .collect(),
+                    ),
+                    members: members.into_iter().map(|(name, _)| name).collect(),
+                },
+            );
+        }
+        FieldType::List(inner) | FieldType::Optional(inner) => {
+            collect_unions(inner, partial, unions)
+        }
+        FieldType::Map(_, value) => collect_unions(value, partial, unions),
+        FieldType::Tuple(items) => items
+            .iter()
+            .for_each(|item| collect_unions(item, partial, unions)),
+        FieldType::Constrained { base, .. } => collect_unions(base, partial, unions),
+        FieldType::Primitive(_)
+        | FieldType::Literal(_)
+        | FieldType::Enum(_)
+        | FieldType::Class(_)
+        | FieldType::RecursiveTypeAlias(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use baml_types::LiteralValue;
+
+    use super::*;
+
+    fn union(members: Vec<FieldType>) -> FieldType {
+        FieldType::Union(members)
+    }
+
+    #[test]
+    fn maps_types_to_go() {
+        assert_eq!(
+            FieldType::list(FieldType::class("Resume")).to_go(),
+            "[]Resume"
+        );
+        assert_eq!(FieldType::optional(FieldType::int()).to_go(), "*int64");
+        assert_eq!(
+            FieldType::optional(FieldType::list(FieldType::string())).to_go(),
+            "[]string"
+        );
+        assert_eq!(
+            FieldType::map(FieldType::Enum("Category".into()), FieldType::float()).to_go(),
+            "map[Category]float64"
+        );
+        assert_eq!(
+            union(vec![
+                FieldType::Literal(LiteralValue::String("fast".into())),
+                FieldType::Literal(LiteralValue::String("slow".into())),
+                FieldType::null(),
+            ])
+            .to_go(),
+            "*string"
+        );
+        assert_eq!(
+            union(vec![FieldType::int(), FieldType::string()]).to_go(),
+            "UnionIntOrString"
+        );
+        assert_eq!(
+            FieldType::list(FieldType::RecursiveTypeAlias("JsonValue".into())).to_go(),
+            "[]any"
+        );
+    }
+
+    #[test]
+    fn makes_partial_fields_nilable() {
+        assert_eq!(FieldType::class("Resume").to_partial_go(), "*PartialResume");
+        assert_eq!(
+            FieldType::list(FieldType::class("Resume")).to_partial_go(),
+            "[]PartialResume"
+        );
+        assert_eq!(FieldType::string().to_partial_go(), "*string");
+        assert_eq!(FieldType::string().to_partial_go_value(), "string");
+    }
+
+    #[test]
+    fn collects_union_structs() {
+        let mut unions = IndexMap::new();
+        collect_unions(
+            &normalize(&FieldType::list(union(vec![
+                FieldType::class("Resume"),
+                FieldType::list(FieldType::string()),
+            ]))),
+            false,
+            &mut unions,
+        );
+        let union = &unions["UnionResumeOrListOfString"];
+        assert_eq!(union.members, vec!["Resume", "ListOfString"]);
+        assert_eq!(
+            union.fields,
+            vec!["Resume       *Resume", "ListOfString []string"]
+        );
+    }
+
+    #[test]
+    fn converts_names() {
+        assert_eq!(go_name("first_name"), "FirstName");
+        assert_eq!(go_name("CANCEL_ORDER"), "CancelOrder");
+        assert_eq!(go_name("inTransit"), "InTransit");
+        assert_eq!(go_name("billing.Invoice"), "BillingInvoice");
+    }
+}
@@ -0,0 +1,112 @@
+use baml_types::{BamlMediaType, TypeValue};
+
+use crate::dir_writer::LanguageFeatures;
+
+#[derive(Default)]
+pub(super) struct GoLanguageFeatures {}
+
+impl LanguageFeatures for GoLanguageFeatures {
+    // The first line is the marker that Go tools look for to recognize
+    // generated code.
+    const CONTENT_PREFIX: &'static str = r#"
+// Code generated by BAML. DO NOT EDIT.
+//
+// This file was generated by BAML: please do not edit it. Instead, edit the
+// BAML files and re-generate this code.
+//
+// The client calls the functions served by `baml-cli serve`.
+        "#;
+}
+
+pub(super) trait ToGo {
+    fn to_go(&self) -> String;
+}
+
+impl ToGo for TypeValue {
+    fn to_go(&self) -> String {
+        match self {
+            TypeValue::Bool => "bool",
+            TypeValue::Float => "float64",
+            TypeValue::Int => "int64",
+            TypeValue::String => "string",
+            TypeValue::Null => "any",
+            TypeValue::Media(BamlMediaType::Image) => "Image",
+            TypeValue::Media(BamlMediaType::Audio) => "Audio",
+            TypeValue::Message => "map[string]any",
+        }
+        .to_string()
+    }
+}
+
+/// The exported Go name for a BAML name, e.g. `FirstName` for `first_name`
+/// and `CancelOrder` for `CANCEL_ORDER`. Go packages are flat, so namespaced
+/// types are prefixed with their namespace: `BillingInvoice` for
+/// `billing.Invoice`.
+pub(super) fn go_name(name: &str) -> String {
+    name.split(['_', '.'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let shouting = part.chars().all(|c| !c.is_lowercase());
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => {
+                    let rest: String = chars.collect();
+                    format!(
+                        "{}{}",
+                        first.to_uppercase(),
+                        if shouting { rest.to_lowercase() } else { rest }
+                    )
+                }
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// The name of a function parameter, which mustn't be a Go keyword or clash
+/// with the names every client method uses.
+pub(super) fn go_param_name(name: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "type",
+        "var",
+        // Types that parameters may have.
+        "any",
+        "bool",
+        "float64",
+        "int64",
+        "string",
+        // The receiver, the context, and what client methods call.
+        "c",
+        "ctx",
+        "call",
+        "stream",
+    ];
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,104 @@
+mod generate_types;
+mod go_language_features;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use internal_baml_core::ir::repr::IntermediateRepr;
+
+use self::{
+    generate_types::ToPartialGo,
+    go_language_features::{go_name, go_param_name, GoLanguageFeatures, ToGo},
+};
+use crate::dir_writer::FileCollector;
+
+#[derive(askama::Template)]
+#[template(path = "client.go.j2", escape = "none")]
+struct GoClient {
+    funcs: Vec<GoFunction>,
+}
+
+struct GoFunction {
+    /// The name of the client's method, e.g. `ExtractResume`.
+    method: String,
+    /// The name of the BAML function that the method calls.
+    function: String,
+    /// The method's parameters, after its context.
+    params: String,
+    /// The entries of the map of arguments, aligned.
+    args: Vec<String>,
+    return_type: String,
+    partial_type: String,
+}
+
+pub(crate) fn generate(
+    ir: &IntermediateRepr,
+    generator: &crate::GeneratorArgs,
+) -> Result<IndexMap<PathBuf, String>> {
+    let mut collector = FileCollector::<GoLanguageFeatures>::new();
+    collector.add_template::<generate_types::GoTypes>("types.go", (ir, generator))?;
+    collector
+        .add_template::<generate_types::GoPartialTypes>("partial_types.go", (ir, generator))?;
+    collector.add_template::<GoClient>("client.go", (ir, generator))?;
+
+    collector.commit(&generator.output_dir())
+}
+
+impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for GoClient {
+    type Error = anyhow::Error;
+
+    fn try_from((ir, _): (&IntermediateRepr, &crate::GeneratorArgs)) -> Result<Self> {
+        let funcs: Vec<GoFunction> = ir
+            .walk_functions()
+            .map(|f| {
+                let params = f
+                    .inputs()
+                    .iter()
+                    .map(|(name, r#type)| format!("{} {}", go_param_name(name), r#type.to_go()))
+                    .collect::<Vec<_>>();
+                let args = f
+                    .inputs()
+                    .iter()
+                    .map(|(name, _)| format!("\"{name}\":"))
+                    .collect::<Vec<_>>();
+                let width = args.iter().map(|arg| arg.len()).max().unwrap_or(0);
+                GoFunction {
+                    method: go_name(f.name()),
+                    function: f.name().to_string(),
+                    params: std::iter::once("ctx context.Context".to_string())
+                        .chain(params)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    args: args
+                        .into_iter()
+                        .zip(f.inputs().iter())
+                        .map(|(arg, (name, _))| format!("{arg:<width$} {},", go_param_name(name)))
+                        .collect(),
+                    return_type: f.elem().output().to_go(),
+                    partial_type: f.elem().output().to_partial_go_value(),
+                }
+            })
+            .collect();
+
+        // Besides the methods of each function, the client has these fields
+        // and methods.
+        let mut methods: IndexMap<String, String> = ["BaseURL", "APIKey", "HTTPClient"]
+            .into_iter()
+            .map(|name| (name.to_string(), "the client".to_string()))
+            .collect();
+        for func in funcs.iter() {
+            for method in [func.method.clone(), format!("Stream{}", func.method)] {
+                if let Some(existing) = methods.get(&method) {
+                    anyhow::bail!(
+                        "Both {existing} and function {} would have a method named {method} in Go",
+                        func.function
+                    );
+                }
+                methods.insert(method, format!("function {}", func.function));
+            }
+        }
+
+        Ok(GoClient { funcs })
+    }
+}
@@ -1,15 +1,20 @@
 use anyhow::{Context, Result};
-use baml_types::{Constraint, ConstraintLevel, FieldType};
+use baml_types::{BamlValue, Constraint, ConstraintLevel, FieldType, LiteralValue, TypeValue};
 use indexmap::IndexMap;
 use internal_baml_core::{
     configuration::{GeneratorDefaultClientMode, GeneratorOutputType},
-    ir::repr::IntermediateRepr,
+    ir::{
+        repr::{Field, IntermediateRepr, Node},
+        IRHelper,
+    },
 };
-use std::{collections::{BTreeMap, HashSet}, path::PathBuf};
+use std::{collections::{BTreeMap, BTreeSet, HashSet}, path::PathBuf};
 use version_check::{check_version, GeneratorType, VersionCheckMode};
 
 mod dir_writer;
+mod go;
 pub mod openapi;
+pub mod proto;
 mod python;
 mod ruby;
 mod typescript;
@@ -178,8 +183,10 @@ impl GenerateClient for GeneratorOutputType {
         }
 
         let files = match self {
+            GeneratorOutputType::Go => go::generate(ir, gen),
             GeneratorOutputType::OpenApi => openapi::generate(ir, gen),
             GeneratorOutputType::PythonPydantic => python::generate(ir, gen),
+            GeneratorOutputType::Proto => proto::generate(ir, gen),
             GeneratorOutputType::RubySorbet => ruby::generate(ir, gen),
             GeneratorOutputType::Typescript => typescript::generate(ir, gen),
         }?;
@@ -291,6 +298,9 @@ pub fn type_check_attributes(
             all_types_in_ir.push(field_type);
         }
     }
+    for type_alias in ir.walk_type_aliases() {
+        all_types_in_ir.push(type_alias.target());
+    }
     for function in ir.walk_functions() {
         for (_param_name, parameter) in function.item.elem.inputs.iter() {
             all_types_in_ir.push(parameter);
@@ -330,6 +340,105 @@ fn field_type_attributes<'a>(field_type: &FieldType) -> Option<TypeCheckAttribut
     }
 }
 
+/// Splits the name of a class, enum or type alias into its namespace and the
+/// name it was declared with, so `billing.tax.Rate` becomes
+/// `(["billing", "tax"], "Rate")`.
+fn split_namespace(name: &str) -> (Vec<&str>, &str) {
+    let mut segments = name.split('.').collect::<Vec<_>>();
+    let name = segments.pop().unwrap_or(name);
+    (segments, name)
+}
+
+/// Every namespace the given types live in, together with their parent
+/// namespaces. The root namespace always comes first and every namespace is
+/// directly followed by the namespaces nested in it, which is the order
+/// generators that nest namespaces need to emit them in.
+fn namespaces<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<Vec<&'a str>> {
+    let mut namespaces = BTreeSet::from([vec![]]);
+    for name in names {
+        let (namespace, _) = split_namespace(name);
+        for len in 1..=namespace.len() {
+            namespaces.insert(namespace[..len].to_vec());
+        }
+    }
+    namespaces.into_iter().collect()
+}
+
+/// The value of the `@default` of a class field.
+fn field_default(field: &Node<Field>) -> Option<BamlValue> {
+    field
+        .attributes
+        .get("default")?
+        .normalize(&Default::default())
+        .ok()
+}
+
+/// The type within `field_type` that a `@default` value is an instance of,
+/// so generators know whether a string is an enum value, a map is a class
+/// instance or a list is a tuple. Optionals, constraints and recursive aliases
+/// are looked through, and for unions the first option the value fits is
+/// picked.
+fn default_value_type<'a>(
+    ir: &'a IntermediateRepr,
+    value: &BamlValue,
+    field_type: &'a FieldType,
+) -> &'a FieldType {
+    match field_type {
+        FieldType::Optional(inner) if !matches!(value, BamlValue::Null) => {
+            default_value_type(ir, value, inner)
+        }
+        FieldType::Constrained { base, .. } => default_value_type(ir, value, base),
+        FieldType::RecursiveTypeAlias(name) => match ir.find_type_alias(name) {
+            Ok(alias) => default_value_type(ir, value, alias.target()),
+            Err(_) => field_type,
+        },
+        FieldType::Union(options) => options
+            .iter()
+            .find(|option| default_fits(ir, value, option))
+            .map_or(field_type, |option| default_value_type(ir, value, option)),
+        _ => field_type,
+    }
+}
+
+/// Whether a `@default` value has the shape of an instance of `field_type`.
+fn default_fits(ir: &IntermediateRepr, value: &BamlValue, field_type: &FieldType) -> bool {
+    match (default_value_type(ir, value, field_type), value) {
+        (FieldType::Optional(_) | FieldType::Primitive(TypeValue::Null), BamlValue::Null) => true,
+        (FieldType::Primitive(TypeValue::String), BamlValue::String(_)) => true,
+        (FieldType::Primitive(TypeValue::Int), BamlValue::Int(_)) => true,
+        (FieldType::Primitive(TypeValue::Float), BamlValue::Int(_) | BamlValue::Float(_)) => true,
+        (FieldType::Primitive(TypeValue::Bool), BamlValue::Bool(_)) => true,
+        (FieldType::Literal(LiteralValue::String(l)), BamlValue::String(v)) => l == v,
+        (FieldType::Literal(LiteralValue::Int(l)), BamlValue::Int(v)) => l == v,
+        (FieldType::Literal(LiteralValue::Float(l)), BamlValue::Float(v)) => l == v,
+        (FieldType::Literal(LiteralValue::Float(l)), BamlValue::Int(v)) => *l == *v as f64,
+        (FieldType::Literal(LiteralValue::Bool(l)), BamlValue::Bool(v)) => l == v,
+        (FieldType::Enum(name), BamlValue::String(v)) => ir
+            .find_enum(name)
+            .map_or(false, |e| e.walk_values().any(|ev| ev.name() == v)),
+        (FieldType::Class(_) | FieldType::Map(..), BamlValue::Map(_)) => true,
+        (FieldType::List(_), BamlValue::List(_)) => true,
+        (FieldType::Tuple(items), BamlValue::List(values)) => items.len() == values.len(),
+        _ => false,
+    }
+}
+
+/// The type of a field of `class`, for rendering class instances given as a
+/// `@default`.
+fn class_field_type<'a>(
+    ir: &'a IntermediateRepr,
+    class: &str,
+    field: &str,
+) -> Option<&'a FieldType> {
+    ir.find_class(class)
+        .ok()?
+        .elem()
+        .static_fields
+        .iter()
+        .find(|f| f.elem.name == field)
+        .map(|f| &f.elem.r#type.elem)
+}
+
 #[cfg(test)]
 mod tests {
     use internal_baml_core::ir::repr::make_test_ir;
@@ -351,6 +460,30 @@ mod tests {
 
     }
 
+    #[test]
+    fn namespaces_parents_first() {
+        assert_eq!(
+            split_namespace("billing.tax.Rate"),
+            (vec!["billing", "tax"], "Rate")
+        );
+        assert_eq!(split_namespace("Order"), (vec![], "Order"));
+
+        assert_eq!(
+            namespaces([
+                "shipping.Parcel",
+                "billing.tax.Rate",
+                "Order",
+                "billing.Invoice"
+            ]),
+            vec![
+                vec![],
+                vec!["billing"],
+                vec!["billing", "tax"],
+                vec!["shipping"],
+            ]
+        );
+    }
+
     #[test]
     fn find_type_check_attributes() {
         let ir = make_test_ir(
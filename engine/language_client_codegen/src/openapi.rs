@@ -5,7 +5,7 @@ use baml_types::{BamlMediaType, FieldType, LiteralValue, TypeValue};
 use indexmap::IndexMap;
 use internal_baml_core::ir::{
     repr::{Function, IntermediateRepr, Node, Walker},
-    ClassWalker, EnumWalker,
+    ClassWalker, EnumWalker, TypeAliasWalker,
 };
 use serde::Serialize;
 use serde_json::json;
@@ -183,6 +183,55 @@ impl Serialize for OpenApiSchema<'_> {
                             ],
                         }),
                     ),
+                    (
+                        "BamlMessage",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "role": {
+                                    "type": "string",
+                                },
+                                "content": {
+                                    "oneOf": [
+                                        {
+                                            "type": "string",
+                                        },
+                                        {
+                                            "type": "array",
+                                            "items": {
+                                                "oneOf": [
+                                                    {
+                                                        "type": "string",
+                                                    },
+                                                    {
+                                                        "type": "object",
+                                                        "title": "BamlMessageImage",
+                                                        "properties": {
+                                                            "image": {
+                                                                "$ref": "#/components/schemas/BamlImage"
+                                                            },
+                                                        },
+                                                        "required": ["image"],
+                                                    },
+                                                    {
+                                                        "type": "object",
+                                                        "title": "BamlMessageAudio",
+                                                        "properties": {
+                                                            "audio": {
+                                                                "$ref": "#/components/schemas/BamlAudio"
+                                                            },
+                                                        },
+                                                        "required": ["audio"],
+                                                    }
+                                                ],
+                                            },
+                                        }
+                                    ],
+                                },
+                            },
+                            "required": ["role", "content"],
+                        }),
+                    ),
                     (
                         "BamlOptions",
                         json!({
@@ -339,6 +388,10 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'_ crate::GeneratorArgs)> for OpenApi
                 .into_iter()
                 .chain(ir.walk_enums().map(|e| Ok((e.name(), e.try_into()?))))
                 .chain(ir.walk_classes().map(|c| Ok((c.name(), c.try_into()?))))
+                .chain(
+                    ir.walk_type_aliases()
+                        .map(|a| Ok((a.name(), a.try_into()?))),
+                )
                 .collect::<Result<_>>()?,
         })
     }
@@ -516,6 +569,17 @@ impl<'ir> TryFrom<ClassWalker<'ir>> for TypeSpecWithMeta {
     }
 }
 
+impl<'ir> TryFrom<TypeAliasWalker<'ir>> for TypeSpecWithMeta {
+    type Error = anyhow::Error;
+
+    fn try_from(a: TypeAliasWalker<'ir>) -> Result<Self> {
+        a.target().to_type_spec(a.db).context(format!(
+            "Failed to convert type alias {} to OpenAPI type",
+            a.name()
+        ))
+    }
+}
+
 trait ToTypeReferenceInTypeDefinition<'ir> {
     fn to_type_spec(&self, ir: &'ir IntermediateRepr) -> Result<TypeSpecWithMeta>;
 }
@@ -523,7 +587,9 @@ trait ToTypeReferenceInTypeDefinition<'ir> {
 impl<'ir> ToTypeReferenceInTypeDefinition<'ir> for FieldType {
     fn to_type_spec(&self, ir: &'ir IntermediateRepr) -> Result<TypeSpecWithMeta> {
         Ok(match self {
-            FieldType::Enum(name) | FieldType::Class(name) => TypeSpecWithMeta {
+            FieldType::Enum(name)
+            | FieldType::Class(name)
+            | FieldType::RecursiveTypeAlias(name) => TypeSpecWithMeta {
                 meta: TypeMetadata {
                     title: None,
                     r#enum: None,
@@ -543,6 +609,7 @@ impl<'ir> ToTypeReferenceInTypeDefinition<'ir> for FieldType {
                 },
                 type_spec: match v {
                     LiteralValue::Int(_) => TypeSpec::Inline(TypeDef::Int),
+                    LiteralValue::Float(_) => TypeSpec::Inline(TypeDef::Float),
                     LiteralValue::Bool(_) => TypeSpec::Inline(TypeDef::Bool),
                     LiteralValue::String(_) => TypeSpec::Inline(TypeDef::String),
                 },
@@ -597,6 +664,9 @@ impl<'ir> ToTypeReferenceInTypeDefinition<'ir> for FieldType {
                     TypeValue::Media(BamlMediaType::Image) => TypeSpec::Ref {
                         r#ref: format!("#/components/schemas/BamlImage"),
                     },
+                    TypeValue::Message => TypeSpec::Ref {
+                        r#ref: format!("#/components/schemas/BamlMessage"),
+                    },
                 },
             },
             FieldType::Union(union) => {
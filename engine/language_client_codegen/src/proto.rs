@@ -0,0 +1,788 @@
+//! Protobuf definitions of a BAML project, for calling its functions over
+//! gRPC with `baml-cli serve`.
+//!
+//! `baml.proto` has a message for each class, an enum for each enum, and a
+//! `BamlService` with two RPCs for each function `Foo`: `Foo`, which returns
+//! its result, and `StreamFoo`, which streams its partial results and then its
+//! result. Fields are numbered in the order they're declared, so new fields
+//! should be added at the end of a class to keep existing clients working.
+//!
+//! Types that protobuf can't express directly (e.g. unions, or a list of
+//! lists) get a message of their own, named after the type.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use baml_types::{BamlMediaType, FieldType, LiteralValue, TypeValue};
+use indexmap::IndexMap;
+use internal_baml_core::ir::repr::IntermediateRepr;
+
+use crate::dir_writer::{FileCollector, LanguageFeatures};
+
+#[derive(Default)]
+pub(super) struct ProtoLanguageFeatures {}
+
+impl LanguageFeatures for ProtoLanguageFeatures {
+    const CONTENT_PREFIX: &'static str = r#"
+// This file was generated by BAML: please do not edit it. Instead, edit the
+// BAML files and re-generate this code.
+        "#;
+}
+
+/// The package that everything is defined in.
+pub const PACKAGE: &str = "baml";
+
+/// The service with the RPCs for every function.
+pub const SERVICE: &str = "BamlService";
+
+#[derive(Debug)]
+pub struct ProtoSchema {
+    pub messages: IndexMap<String, Message>,
+    /// The values of each enum, numbered from 1.
+    pub enums: IndexMap<String, Vec<String>>,
+    /// The RPCs, by name.
+    pub methods: IndexMap<String, Method>,
+}
+
+#[derive(Debug)]
+pub struct Method {
+    pub function: String,
+    /// Whether the RPC streams partial results before the result.
+    pub streaming: bool,
+    pub request: String,
+    pub response: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub kind: MessageKind,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageKind {
+    /// A class, or a function's arguments.
+    Class,
+    /// A value in its `value` field: a function's result, or a type that
+    /// can't be a field by itself, like the lists in a list of lists.
+    Wrapper,
+    /// A union, with exactly one of its fields set.
+    Union,
+    /// A tuple, with a field for each item.
+    Tuple,
+    Media(BamlMediaType),
+    Null,
+    /// What a streaming RPC sends: either a `partial` result, or the `final`
+    /// one.
+    Stream,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub number: u32,
+    pub label: Label,
+    pub r#type: ProtoType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Label {
+    /// Unset if it has its type's default value.
+    Implicit,
+    Optional,
+    Repeated,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProtoType {
+    String,
+    Int64,
+    Double,
+    Bool,
+    Enum(String),
+    Message(String),
+    /// A `map<string, ...>`.
+    Map(Box<ProtoType>),
+}
+
+impl ProtoSchema {
+    /// The name `value` of the enum `name` has in protobuf, e.g.
+    /// `CATEGORY_REFUND` for `Category.Refund`.
+    pub fn enum_value_name(name: &str, value: &str) -> String {
+        format!(
+            "{}_{}",
+            screaming_snake_case(&proto_name(name)),
+            screaming_snake_case(value)
+        )
+    }
+
+    fn add_message(&mut self, name: &str, message: Message) -> Result<()> {
+        match self.messages.get(name) {
+            Some(existing) if *existing != message => {
+                anyhow::bail!("More than one type would be named {name} in protobuf")
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.messages.insert(name.to_string(), message);
+                Ok(())
+            }
+        }
+    }
+
+    fn add_method(&mut self, name: String, method: Method) -> Result<()> {
+        if self.methods.contains_key(&name) {
+            anyhow::bail!("More than one RPC would be named {name}");
+        }
+        self.methods.insert(name, method);
+        Ok(())
+    }
+
+    /// The field `name` of type `t`, which may be a list, map, or optional.
+    fn field(&mut self, name: &str, number: usize, t: &FieldType) -> Result<Field> {
+        let (label, r#type) = match normalize(t) {
+            FieldType::Optional(inner) => match *inner {
+                // Absent lists and maps are empty, which is as close as
+                // protobuf gets to null.
+                inner @ (FieldType::List(_) | FieldType::Map(..)) => {
+                    let field = self.field(name, number, &inner)?;
+                    (field.label, field.r#type)
+                }
+                inner => (Label::Optional, self.value_type(&inner)?),
+            },
+            FieldType::List(item) => (Label::Repeated, self.value_type(&item)?),
+            FieldType::Map(key, value) => {
+                match normalize(&key) {
+                    FieldType::Primitive(TypeValue::String)
+                    | FieldType::Enum(_)
+                    | FieldType::Literal(LiteralValue::String(_)) => {}
+                    _ => anyhow::bail!("BAML<->protobuf only supports string keys in maps"),
+                }
+                (
+                    Label::Implicit,
+                    ProtoType::Map(Box::new(self.value_type(&value)?)),
+                )
+            }
+            t => (Label::Implicit, self.value_type(&t)?),
+        };
+        Ok(Field {
+            name: name.to_string(),
+            number: number as u32 + 1,
+            label,
+            r#type,
+        })
+    }
+
+    /// The type of a value of type `t` that isn't directly a field, e.g. an
+    /// item of a list, wrapping it in a message if it must be.
+    fn value_type(&mut self, t: &FieldType) -> Result<ProtoType> {
+        let t = normalize(t);
+        Ok(match &t {
+            FieldType::Primitive(TypeValue::String) => ProtoType::String,
+            FieldType::Primitive(TypeValue::Int) => ProtoType::Int64,
+            FieldType::Primitive(TypeValue::Float) => ProtoType::Double,
+            FieldType::Primitive(TypeValue::Bool) => ProtoType::Bool,
+            FieldType::Primitive(TypeValue::Null) => {
+                self.add_message(
+                    "Null",
+                    Message {
+                        kind: MessageKind::Null,
+                        fields: vec![],
+                    },
+                )?;
+                ProtoType::Message("Null".to_string())
+            }
+            FieldType::Primitive(TypeValue::Media(media_type)) => {
+                let name = type_name(&t)?;
+                self.add_message(&name, media_message(*media_type))?;
+                ProtoType::Message(name)
+            }
+            FieldType::Primitive(TypeValue::Message) => {
+                anyhow::bail!("BAML<->protobuf doesn't support messages")
+            }
+            FieldType::Literal(LiteralValue::String(_)) => ProtoType::String,
+            FieldType::Literal(LiteralValue::Int(_)) => ProtoType::Int64,
+            FieldType::Literal(LiteralValue::Float(_)) => ProtoType::Double,
+            FieldType::Literal(LiteralValue::Bool(_)) => ProtoType::Bool,
+            FieldType::Enum(name) => ProtoType::Enum(name.clone()),
+            FieldType::Class(name) => ProtoType::Message(name.clone()),
+            FieldType::RecursiveTypeAlias(name) => {
+                anyhow::bail!("BAML<->protobuf doesn't support recursive type aliases: {name}")
+            }
+            FieldType::Union(options) => {
+                let name = type_name(&t)?;
+                let mut fields: Vec<Field> = vec![];
+                for (i, option) in options.iter().enumerate() {
+                    let mut field_name = snake_case(&proto_name(&type_name(option)?));
+                    if fields.iter().any(|f| f.name == field_name) {
+                        field_name = format!("{field_name}_{}", i + 1);
+                    }
+                    fields.push(Field {
+                        name: field_name,
+                        number: i as u32 + 1,
+                        label: Label::Implicit,
+                        r#type: self.value_type(option)?,
+                    });
+                }
+                self.add_message(
+                    &name,
+                    Message {
+                        kind: MessageKind::Union,
+                        fields,
+                    },
+                )?;
+                ProtoType::Message(name)
+            }
+            FieldType::Tuple(items) => {
+                let name = type_name(&t)?;
+                let fields = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| self.field(&format!("item_{i}"), i, item))
+                    .collect::<Result<_>>()?;
+                self.add_message(
+                    &name,
+                    Message {
+                        kind: MessageKind::Tuple,
+                        fields,
+                    },
+                )?;
+                ProtoType::Message(name)
+            }
+            FieldType::List(_) | FieldType::Map(..) | FieldType::Optional(_) => {
+                let name = type_name(&t)?;
+                let field = self.field("value", 0, &t)?;
+                self.add_message(
+                    &name,
+                    Message {
+                        kind: MessageKind::Wrapper,
+                        fields: vec![field],
+                    },
+                )?;
+                ProtoType::Message(name)
+            }
+            FieldType::Constrained { .. } => unreachable!("normalize removes constraints"),
+        })
+    }
+}
+
+impl TryFrom<&IntermediateRepr> for ProtoSchema {
+    type Error = anyhow::Error;
+
+    fn try_from(ir: &IntermediateRepr) -> Result<Self> {
+        let mut schema = ProtoSchema {
+            messages: IndexMap::new(),
+            enums: IndexMap::new(),
+            methods: IndexMap::new(),
+        };
+
+        for e in ir.walk_enums() {
+            schema.enums.insert(
+                e.name().to_string(),
+                e.item
+                    .elem
+                    .values
+                    .iter()
+                    .map(|v| v.elem.0.clone())
+                    .collect(),
+            );
+        }
+
+        for c in ir.walk_classes() {
+            let fields = c
+                .item
+                .elem
+                .static_fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    schema
+                        .field(&f.elem.name, i, &f.elem.r#type.elem)
+                        .context(format!(
+                            "Failed to convert {}.{} to protobuf",
+                            c.name(),
+                            f.elem.name
+                        ))
+                })
+                .collect::<Result<_>>()?;
+            schema.add_message(
+                c.name(),
+                Message {
+                    kind: MessageKind::Class,
+                    fields,
+                },
+            )?;
+        }
+
+        for f in ir.walk_functions() {
+            let function = f.item.elem.name();
+            let request = format!("{function}Request");
+            let response = format!("{function}Response");
+            let stream_response = format!("{function}StreamResponse");
+
+            let fields = f
+                .item
+                .elem
+                .inputs()
+                .iter()
+                .enumerate()
+                .map(|(i, (name, t))| {
+                    schema.field(name, i, t).context(format!(
+                        "Failed to convert arg {name} (for function {function}) to protobuf"
+                    ))
+                })
+                .collect::<Result<_>>()?;
+            schema.add_message(
+                &request,
+                Message {
+                    kind: MessageKind::Class,
+                    fields,
+                },
+            )?;
+
+            let result = schema
+                .field("value", 0, f.item.elem.output())
+                .context(format!(
+                    "Failed to convert the return type of {function} to protobuf"
+                ))?;
+            schema.add_message(
+                &response,
+                Message {
+                    kind: MessageKind::Wrapper,
+                    fields: vec![result],
+                },
+            )?;
+            schema.add_message(
+                &stream_response,
+                Message {
+                    kind: MessageKind::Stream,
+                    fields: ["partial", "final"]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, name)| Field {
+                            name: name.to_string(),
+                            number: i as u32 + 1,
+                            label: Label::Implicit,
+                            r#type: ProtoType::Message(response.clone()),
+                        })
+                        .collect(),
+                },
+            )?;
+
+            schema.add_method(
+                function.to_string(),
+                Method {
+                    function: function.to_string(),
+                    streaming: false,
+                    request: request.clone(),
+                    response,
+                },
+            )?;
+            schema.add_method(
+                format!("Stream{function}"),
+                Method {
+                    function: function.to_string(),
+                    streaming: true,
+                    request,
+                    response: stream_response,
+                },
+            )?;
+        }
+
+        Ok(schema)
+    }
+}
+
+impl ProtoSchema {
+    /// Renders the schema as a `.proto` file.
+    pub fn render(&self) -> String {
+        let mut out = format!("syntax = \"proto3\";\n\npackage {PACKAGE};\n\n");
+
+        out.push_str(&format!("service {SERVICE} {{\n"));
+        for (name, method) in &self.methods {
+            out.push_str(&format!(
+                "  rpc {name}({}) returns ({}{});\n",
+                method.request,
+                if method.streaming { "stream " } else { "" },
+                method.response
+            ));
+        }
+        out.push_str("}\n");
+
+        for (name, values) in &self.enums {
+            out.push_str(&format!(
+                "\nenum {} {{\n  {} = 0;\n",
+                proto_name(name),
+                Self::enum_value_name(name, "Unspecified")
+            ));
+            for (i, value) in values.iter().enumerate() {
+                out.push_str(&format!(
+                    "  {} = {};\n",
+                    Self::enum_value_name(name, value),
+                    i + 1
+                ));
+            }
+            out.push_str("}\n");
+        }
+
+        for (name, message) in &self.messages {
+            out.push_str(&format!("\nmessage {} {{\n", proto_name(name)));
+            let oneof = match message.kind {
+                MessageKind::Union => Some("value"),
+                MessageKind::Stream => Some("result"),
+                _ => None,
+            };
+            let indent = if oneof.is_some() { "    " } else { "  " };
+            if let Some(oneof) = oneof {
+                out.push_str(&format!("  oneof {oneof} {{\n"));
+            }
+            for field in &message.fields {
+                out.push_str(&format!(
+                    "{indent}{}{} {} = {};\n",
+                    match field.label {
+                        Label::Implicit => "",
+                        Label::Optional => "optional ",
+                        Label::Repeated => "repeated ",
+                    },
+                    render_type(&field.r#type),
+                    field.name,
+                    field.number
+                ));
+            }
+            if oneof.is_some() {
+                out.push_str("  }\n");
+            }
+            out.push_str("}\n");
+        }
+
+        out
+    }
+}
+
+fn render_type(t: &ProtoType) -> String {
+    match t {
+        ProtoType::String => "string".to_string(),
+        ProtoType::Int64 => "int64".to_string(),
+        ProtoType::Double => "double".to_string(),
+        ProtoType::Bool => "bool".to_string(),
+        ProtoType::Enum(name) | ProtoType::Message(name) => proto_name(name),
+        ProtoType::Map(value) => format!("map<string, {}>", render_type(value)),
+    }
+}
+
+fn media_message(media_type: BamlMediaType) -> Message {
+    let field = |name: &str, number| Field {
+        name: name.to_string(),
+        number,
+        label: Label::Optional,
+        r#type: ProtoType::String,
+    };
+    Message {
+        kind: MessageKind::Media(media_type),
+        fields: vec![field("url", 1), field("base64", 2), field("media_type", 3)],
+    }
+}
+
+/// Drops constraints, and rewrites unions into the simplest type that
+/// protobuf can treat them as: `T | null` as `T?`, a union of one type as
+/// that type, and a union of literals of one kind as that kind.
+fn normalize(t: &FieldType) -> FieldType {
+    match t {
+        FieldType::Constrained { base, .. } => normalize(base),
+        FieldType::Union(options) => {
+            let (nulls, options): (Vec<_>, Vec<_>) = options
+                .iter()
+                .map(normalize)
+                .partition(|o| matches!(o, FieldType::Primitive(TypeValue::Null)));
+            let union = match options.as_slice() {
+                [] => FieldType::Primitive(TypeValue::Null),
+                [only] => only.clone(),
+                [FieldType::Literal(first), rest @ ..]
+                    if rest.iter().all(|o| match o {
+                        FieldType::Literal(l) => {
+                            std::mem::discriminant(l) == std::mem::discriminant(first)
+                        }
+                        _ => false,
+                    }) =>
+                {
+                    FieldType::Primitive(match first {
+                        LiteralValue::String(_) => TypeValue::String,
+                        LiteralValue::Int(_) => TypeValue::Int,
+                        LiteralValue::Float(_) => TypeValue::Float,
+                        LiteralValue::Bool(_) => TypeValue::Bool,
+                    })
+                }
+                _ => FieldType::Union(options),
+            };
+            match (nulls.is_empty(), union) {
+                (false, union @ FieldType::Primitive(TypeValue::Null)) => union,
+                (false, union) => FieldType::Optional(Box::new(union)),
+                (true, union) => union,
+            }
+        }
+        FieldType::Optional(inner) => match normalize(inner) {
+            inner @ FieldType::Optional(_) => inner,
+            inner => FieldType::Optional(Box::new(inner)),
+        },
+        t => t.clone(),
+    }
+}
+
+/// The name of the message for `t`, if it needs one.
+fn type_name(t: &FieldType) -> Result<String> {
+    Ok(match normalize(t) {
+        FieldType::Primitive(TypeValue::String) | FieldType::Literal(LiteralValue::String(_)) => {
+            "String".to_string()
+        }
+        FieldType::Primitive(TypeValue::Int) | FieldType::Literal(LiteralValue::Int(_)) => {
+            "Int".to_string()
+        }
+        FieldType::Primitive(TypeValue::Bool) | FieldType::Literal(LiteralValue::Bool(_)) => {
+            "Bool".to_string()
+        }
+        FieldType::Primitive(TypeValue::Float) | FieldType::Literal(LiteralValue::Float(_)) => {
+            "Float".to_string()
+        }
+        FieldType::Primitive(TypeValue::Null) => "Null".to_string(),
+        FieldType::Primitive(TypeValue::Media(BamlMediaType::Image)) => "Image".to_string(),
+        FieldType::Primitive(TypeValue::Media(BamlMediaType::Audio)) => "Audio".to_string(),
+        FieldType::Primitive(TypeValue::Message) => {
+            anyhow::bail!("BAML<->protobuf doesn't support messages")
+        }
+        FieldType::Enum(name) | FieldType::Class(name) => name,
+        FieldType::RecursiveTypeAlias(name) => {
+            anyhow::bail!("BAML<->protobuf doesn't support recursive type aliases: {name}")
+        }
+        FieldType::List(item) => format!("List_{}", type_name(&item)?),
+        FieldType::Map(_, value) => format!("Map_{}", type_name(&value)?),
+        FieldType::Optional(inner) => format!("Optional_{}", type_name(&inner)?),
+        FieldType::Union(options) => format!(
+            "OneOf_{}",
+            options
+                .iter()
+                .map(type_name)
+                .collect::<Result<Vec<_>>>()?
+                .join("_Or_")
+        ),
+        FieldType::Tuple(items) => format!(
+            "Tuple_{}",
+            items
+                .iter()
+                .map(type_name)
+                .collect::<Result<Vec<_>>>()?
+                .join("_And_")
+        ),
+        FieldType::Constrained { .. } => unreachable!("normalize removes constraints"),
+    })
+}
+
+/// Dots in protobuf names refer to packages, so namespaced types are
+/// flattened: `billing.Invoice` is the message `billing_Invoice`.
+fn proto_name(name: &str) -> String {
+    name.replace('.', "_")
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if c.is_uppercase() {
+            if matches!(prev, Some(p) if p.is_lowercase() || p.is_ascii_digit()) {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+        prev = Some(c);
+    }
+    out
+}
+
+fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
+
+pub(crate) fn generate(
+    ir: &IntermediateRepr,
+    generator: &crate::GeneratorArgs,
+) -> Result<IndexMap<PathBuf, String>> {
+    let mut collector = FileCollector::<ProtoLanguageFeatures>::new();
+
+    let schema = ProtoSchema::try_from(ir)?;
+    collector.add_file(format!("{PACKAGE}.proto"), schema.render());
+
+    collector.commit(&generator.output_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use internal_baml_core::ir::repr::make_test_ir;
+
+    use super::*;
+
+    fn schema(source: &str) -> ProtoSchema {
+        let ir = make_test_ir(source).expect("Valid source");
+        ProtoSchema::try_from(&ir).unwrap()
+    }
+
+    const CLIENT: &str = r#"
+client<llm> GPT4 {
+  provider openai
+  options {
+    model gpt-4o
+    api_key env.OPENAI_API_KEY
+  }
+}
+"#;
+
+    #[test]
+    fn renders_classes_enums_and_a_service() {
+        let schema = schema(&format!(
+            r##"{CLIENT}
+enum Category {{
+  Refund
+  CancelOrder
+}}
+
+class Resume {{
+  name string
+  skills string[]
+  email string?
+  category Category
+  scores map<string, float>
+}}
+
+function ExtractResume(resume: string) -> Resume {{
+  client GPT4
+  prompt #""#
+}}
+"##
+        ));
+
+        assert_eq!(
+            schema.render(),
+            r#"syntax = "proto3";
+
+package baml;
+
+service BamlService {
+  rpc ExtractResume(ExtractResumeRequest) returns (ExtractResumeResponse);
+  rpc StreamExtractResume(ExtractResumeRequest) returns (stream ExtractResumeStreamResponse);
+}
+
+enum Category {
+  CATEGORY_UNSPECIFIED = 0;
+  CATEGORY_REFUND = 1;
+  CATEGORY_CANCEL_ORDER = 2;
+}
+
+message Resume {
+  string name = 1;
+  repeated string skills = 2;
+  optional string email = 3;
+  Category category = 4;
+  map<string, double> scores = 5;
+}
+
+message ExtractResumeRequest {
+  string resume = 1;
+}
+
+message ExtractResumeResponse {
+  Resume value = 1;
+}
+
+message ExtractResumeStreamResponse {
+  oneof result {
+    ExtractResumeResponse partial = 1;
+    ExtractResumeResponse final = 2;
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn wraps_types_protobuf_cannot_nest() {
+        let schema = schema(&format!(
+            r##"{CLIENT}
+function Group(items: string[][], choice: int | string, mode: "fast" | "slow") -> map<string, int[]> {{
+  client GPT4
+  prompt #""#
+}}
+"##
+        ));
+
+        assert_eq!(
+            schema.messages["GroupRequest"].fields,
+            vec![
+                Field {
+                    name: "items".to_string(),
+                    number: 1,
+                    label: Label::Repeated,
+                    r#type: ProtoType::Message("List_String".to_string()),
+                },
+                Field {
+                    name: "choice".to_string(),
+                    number: 2,
+                    label: Label::Implicit,
+                    r#type: ProtoType::Message("OneOf_Int_Or_String".to_string()),
+                },
+                Field {
+                    name: "mode".to_string(),
+                    number: 3,
+                    label: Label::Implicit,
+                    r#type: ProtoType::String,
+                },
+            ]
+        );
+        assert_eq!(schema.messages["List_String"].kind, MessageKind::Wrapper);
+        assert_eq!(
+            schema.messages["OneOf_Int_Or_String"]
+                .fields
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["int", "string"]
+        );
+        assert_eq!(
+            schema.messages["GroupResponse"].fields[0].r#type,
+            ProtoType::Map(Box::new(ProtoType::Message("List_Int".to_string())))
+        );
+    }
+
+    #[test]
+    fn rejects_rpc_name_collisions() {
+        let ir = make_test_ir(&format!(
+            r##"{CLIENT}
+function Foo(a: string) -> string {{
+  client GPT4
+  prompt #""#
+}}
+
+function StreamFoo(a: string) -> string {{
+  client GPT4
+  prompt #""#
+}}
+"##
+        ))
+        .expect("Valid source");
+        assert!(ProtoSchema::try_from(&ir).is_err());
+    }
+
+    #[test]
+    fn converts_names_to_screaming_snake_case() {
+        assert_eq!(
+            ProtoSchema::enum_value_name("OrderStatus", "InTransit2Go"),
+            "ORDER_STATUS_IN_TRANSIT2_GO"
+        );
+        assert_eq!(
+            ProtoSchema::enum_value_name("Category", "ALREADY_UPPER"),
+            "CATEGORY_ALREADY_UPPER"
+        );
+        assert_eq!(
+            ProtoSchema::enum_value_name("billing.Status", "Paid"),
+            "BILLING_STATUS_PAID"
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::Result;
-use baml_types::LiteralValue;
+use baml_types::{BamlValue, LiteralValue, TypeValue};
 use itertools::Itertools;
 use std::borrow::Cow;
 
-use crate::{field_type_attributes, type_check_attributes, TypeCheckAttributes};
+use crate::{
+    class_field_type, default_value_type, field_default, field_type_attributes, namespaces,
+    split_namespace, type_check_attributes, TypeCheckAttributes,
+};
 
 use super::python_language_features::ToPython;
 use internal_baml_core::ir::{
@@ -13,8 +16,49 @@ use internal_baml_core::ir::{
 #[derive(askama::Template)]
 #[template(path = "types.py.j2", escape = "none")]
 pub(crate) struct PythonTypes<'ir> {
+    namespaces: Vec<PythonNamespace<PythonTypesInNamespace<'ir>>>,
+}
+
+/// Namespaces are rendered as plain classes holding the types declared in
+/// them, so `billing.Invoice` is the class `Invoice` nested in `billing`.
+struct PythonNamespace<T> {
+    /// The `class billing:` line opening the namespace, indented to its
+    /// parent. Empty for the root namespace.
+    header: String,
+    /// The indentation of the types declared in the namespace.
+    indent: String,
+    items: T,
+}
+
+impl<T> PythonNamespace<T> {
+    fn group<'a>(
+        names: impl IntoIterator<Item = &'a str>,
+        items_in: impl Fn(&[&str]) -> T,
+    ) -> Vec<Self> {
+        namespaces(names)
+            .into_iter()
+            .map(|namespace| PythonNamespace {
+                header: match namespace.split_last() {
+                    Some((name, parents)) => {
+                        format!("{}class {name}:", "    ".repeat(parents.len()))
+                    }
+                    None => String::new(),
+                },
+                indent: "    ".repeat(namespace.len()),
+                items: items_in(&namespace),
+            })
+            .collect()
+    }
+}
+
+struct PythonTypesInNamespace<'ir> {
     enums: Vec<PythonEnum<'ir>>,
     classes: Vec<PythonClass<'ir>>,
+    type_aliases: Vec<PythonTypeAlias<'ir>>,
+}
+
+fn in_namespace(name: &str, namespace: &[&str]) -> bool {
+    split_namespace(name).0 == namespace
 }
 
 #[derive(askama::Template)]
@@ -31,6 +75,30 @@ struct PythonEnum<'ir> {
     dynamic: bool,
 }
 
+impl PythonEnum<'_> {
+    /// The name of the enum within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(self.name).1
+    }
+
+    /// A Python identifier for the enum, used to name its type builder.
+    fn ident(&self) -> String {
+        self.name.replace('.', "_")
+    }
+}
+
+struct PythonTypeAlias<'ir> {
+    name: &'ir str,
+    target: String,
+}
+
+impl PythonTypeAlias<'_> {
+    /// The name of the alias within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(self.name).1
+    }
+}
+
 struct PythonClass<'ir> {
     name: Cow<'ir, str>,
     // the name, and the type of the field
@@ -38,10 +106,27 @@ struct PythonClass<'ir> {
     dynamic: bool,
 }
 
+impl PythonClass<'_> {
+    /// The name of the class within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(&self.name).1
+    }
+
+    /// A Python identifier for the class, used to name its type builder.
+    fn ident(&self) -> String {
+        self.name.replace('.', "_")
+    }
+}
+
 #[derive(askama::Template)]
 #[template(path = "partial_types.py.j2", escape = "none")]
 pub(crate) struct PythonStreamTypes<'ir> {
+    namespaces: Vec<PythonNamespace<PartialPythonTypesInNamespace<'ir>>>,
+}
+
+struct PartialPythonTypesInNamespace<'ir> {
     partial_classes: Vec<PartialPythonClass<'ir>>,
+    partial_type_aliases: Vec<PythonTypeAlias<'ir>>,
 }
 
 /// The Python class corresponding to Partial<TypeDefinedInBaml>
@@ -52,15 +137,46 @@ struct PartialPythonClass<'ir> {
     fields: Vec<(&'ir str, String)>,
 }
 
+impl PartialPythonClass<'_> {
+    /// The name of the class within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(self.name).1
+    }
+}
+
 impl<'ir> TryFrom<(&'ir IntermediateRepr, &'_ crate::GeneratorArgs)> for PythonTypes<'ir> {
     type Error = anyhow::Error;
 
     fn try_from(
         (ir, _): (&'ir IntermediateRepr, &'_ crate::GeneratorArgs),
     ) -> Result<PythonTypes<'ir>> {
+        let names = ir
+            .walk_enums()
+            .map(|e| e.name())
+            .chain(ir.walk_classes().map(|c| c.name()))
+            .chain(ir.walk_type_aliases().map(|alias| alias.name()));
+
         Ok(PythonTypes {
-            enums: ir.walk_enums().map(PythonEnum::from).collect::<Vec<_>>(),
-            classes: ir.walk_classes().map(PythonClass::from).collect::<Vec<_>>(),
+            namespaces: PythonNamespace::group(names, |namespace| PythonTypesInNamespace {
+                enums: ir
+                    .walk_enums()
+                    .filter(|e| in_namespace(e.name(), namespace))
+                    .map(PythonEnum::from)
+                    .collect::<Vec<_>>(),
+                classes: ir
+                    .walk_classes()
+                    .filter(|c| in_namespace(c.name(), namespace))
+                    .map(PythonClass::from)
+                    .collect::<Vec<_>>(),
+                type_aliases: ir
+                    .walk_type_aliases()
+                    .filter(|alias| in_namespace(alias.name(), namespace))
+                    .map(|alias| PythonTypeAlias {
+                        name: alias.name(),
+                        target: alias.target().to_type_ref(ir),
+                    })
+                    .collect::<Vec<_>>(),
+            }),
         })
     }
 }
@@ -115,6 +231,8 @@ impl<'ir> From<ClassWalker<'ir>> for PythonClass<'ir> {
                         add_default_value(
                             &f.elem.r#type.elem,
                             &f.elem.r#type.elem.to_type_ref(&c.db),
+                            field_default(f)
+                                .map(|value| python_default(c.db, &value, &f.elem.r#type.elem)),
                         ),
                     )
                 })
@@ -127,11 +245,29 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'_ crate::GeneratorArgs)> for PythonS
     type Error = anyhow::Error;
 
     fn try_from((ir, _): (&'ir IntermediateRepr, &'_ crate::GeneratorArgs)) -> Result<Self> {
+        // Enums aren't redefined for streaming, so only namespaces with
+        // classes or type aliases in them are needed.
+        let names = ir
+            .walk_classes()
+            .map(|c| c.name())
+            .chain(ir.walk_type_aliases().map(|alias| alias.name()));
+
         Ok(Self {
-            partial_classes: ir
-                .walk_classes()
-                .map(PartialPythonClass::from)
-                .collect::<Vec<_>>(),
+            namespaces: PythonNamespace::group(names, |namespace| PartialPythonTypesInNamespace {
+                partial_classes: ir
+                    .walk_classes()
+                    .filter(|c| in_namespace(c.name(), namespace))
+                    .map(PartialPythonClass::from)
+                    .collect::<Vec<_>>(),
+                partial_type_aliases: ir
+                    .walk_type_aliases()
+                    .filter(|alias| in_namespace(alias.name(), namespace))
+                    .map(|alias| PythonTypeAlias {
+                        name: alias.name(),
+                        target: alias.target().to_partial_type_ref(ir, false),
+                    })
+                    .collect::<Vec<_>>(),
+            }),
         })
     }
 }
@@ -152,6 +288,7 @@ impl<'ir> From<ClassWalker<'ir>> for PartialPythonClass<'ir> {
                         add_default_value(
                             &f.elem.r#type.elem,
                             &f.elem.r#type.elem.to_partial_type_ref(&c.db, false),
+                            None,
                         ),
                     )
                 })
@@ -160,7 +297,10 @@ impl<'ir> From<ClassWalker<'ir>> for PartialPythonClass<'ir> {
     }
 }
 
-pub fn add_default_value(node: &FieldType, type_str: &String) -> String {
+pub fn add_default_value(node: &FieldType, type_str: &String, default: Option<String>) -> String {
+    if let Some(default) = default {
+        return format!("{} = {}", type_str, default);
+    }
     if type_str.starts_with("Optional[") {
         return format!("{} = None", type_str);
     } else {
@@ -168,6 +308,78 @@ pub fn add_default_value(node: &FieldType, type_str: &String) -> String {
     }
 }
 
+/// Renders the `@default` of a field as the right-hand side of its
+/// declaration. Enums, class instances and mutable values are built by a
+/// `default_factory`, since the types they refer to may be declared further
+/// down the module.
+fn python_default(ir: &IntermediateRepr, value: &BamlValue, field_type: &FieldType) -> String {
+    let expr = to_python_value(ir, value, field_type);
+    match (default_value_type(ir, value, field_type), value) {
+        (FieldType::Enum(_), _) | (_, BamlValue::List(_) | BamlValue::Map(_)) => {
+            format!("Field(default_factory=lambda: {expr})")
+        }
+        _ => expr,
+    }
+}
+
+/// Renders a `@default` value as a Python expression of type `field_type`.
+fn to_python_value(ir: &IntermediateRepr, value: &BamlValue, field_type: &FieldType) -> String {
+    match (default_value_type(ir, value, field_type), value) {
+        (_, BamlValue::Null) => "None".to_string(),
+        (_, BamlValue::Bool(true)) => "True".to_string(),
+        (_, BamlValue::Bool(false)) => "False".to_string(),
+        (FieldType::Primitive(TypeValue::Float), BamlValue::Int(i)) => format!("{i}.0"),
+        (_, BamlValue::Int(i)) => i.to_string(),
+        (_, BamlValue::Float(f)) => format!("{f:?}"),
+        (FieldType::Enum(name), BamlValue::String(v)) => format!("{name}.{v}"),
+        (_, BamlValue::String(s)) => serde_json::Value::from(s.as_str()).to_string(),
+        (FieldType::Class(name), BamlValue::Map(fields)) => format!(
+            "{name}({})",
+            fields
+                .iter()
+                .filter_map(|(k, v)| {
+                    let field_type = class_field_type(ir, name, k)?;
+                    Some(format!("{k}={}", to_python_value(ir, v, field_type)))
+                })
+                .join(", ")
+        ),
+        (FieldType::Tuple(item_types), BamlValue::List(items)) => {
+            let items = item_types
+                .iter()
+                .zip(items)
+                .map(|(item_type, item)| to_python_value(ir, item, item_type))
+                .collect::<Vec<_>>();
+            match items.as_slice() {
+                [item] => format!("({item},)"),
+                items => format!("({})", items.join(", ")),
+            }
+        }
+        (FieldType::List(item_type), BamlValue::List(items)) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| to_python_value(ir, item, item_type))
+                .join(", ")
+        ),
+        (FieldType::Map(_, value_type), BamlValue::Map(entries)) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}: {}",
+                        serde_json::Value::from(k.as_str()),
+                        to_python_value(ir, v, value_type)
+                    )
+                })
+                .join(", ")
+        ),
+        // `@default` is validated against the field type, so nothing else
+        // can show up here.
+        _ => "None".to_string(),
+    }
+}
+
 pub fn type_name_for_checks(checks: &TypeCheckAttributes) -> String {
     let check_names = checks
         .0
@@ -193,6 +405,11 @@ fn type_def_for_checks(checks: TypeCheckAttributes) -> PythonClass<'static> {
 
 /// Returns the Python `Literal` representation of `self`.
 pub fn to_python_literal(literal: &LiteralValue) -> String {
+    // `Literal[...]` doesn't accept floats, so those are typed as plain floats.
+    if let LiteralValue::Float(_) = literal {
+        return String::from("float");
+    }
+
     // Python bools are a little special...
     let value = match literal {
         LiteralValue::Bool(bool) => String::from(match *bool {
@@ -228,6 +445,7 @@ impl ToTypeReferenceInTypeDefinition for FieldType {
             }
             FieldType::Literal(value) => to_python_literal(value),
             FieldType::Class(name) => format!("\"{name}\""),
+            FieldType::RecursiveTypeAlias(name) => format!("\"{name}\""),
             FieldType::List(inner) => format!("List[{}]", inner.to_type_ref(ir)),
             FieldType::Map(key, value) => {
                 format!("Dict[{}, {}]", key.to_type_ref(ir), value.to_type_ref(ir))
@@ -270,6 +488,13 @@ impl ToTypeReferenceInTypeDefinition for FieldType {
                     format!("Optional[\"{name}\"]")
                 }
             }
+            FieldType::RecursiveTypeAlias(name) => {
+                if wrapped {
+                    format!("\"{name}\"")
+                } else {
+                    format!("Optional[\"{name}\"]")
+                }
+            }
             FieldType::Enum(name) => {
                 if ir
                     .find_enum(name)
@@ -322,3 +547,90 @@ impl ToTypeReferenceInTypeDefinition for FieldType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use askama::Template;
+    use internal_baml_core::{configuration::GeneratorDefaultClientMode, ir::repr::make_test_ir};
+
+    use super::*;
+
+    #[test]
+    fn recursive_aliases_validate_with_pydantic() {
+        let has_pydantic = Command::new("python3")
+            .args(["-c", "import pydantic"])
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !has_pydantic {
+            eprintln!("Skipping: python3 with pydantic is not installed");
+            return;
+        }
+
+        let ir = make_test_ir(
+            r#"
+type JsonValue = int | string | bool | float | JsonValue[] | map<string, JsonValue> | null
+
+class Doc {
+  data JsonValue
+}
+"#,
+        )
+        .expect("Valid source");
+        let args = crate::GeneratorArgs::new(
+            "baml_client",
+            "baml_src",
+            std::iter::empty(),
+            "0.0.0".to_string(),
+            true,
+            GeneratorDefaultClientMode::Sync,
+            vec![],
+        )
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("baml_python_aliases_{}", std::process::id()));
+        let package = dir.join("baml_client");
+        std::fs::create_dir_all(&package).unwrap();
+        // The generated types only need `baml_py` for media.
+        std::fs::write(dir.join("baml_py.py"), "").unwrap();
+        std::fs::write(package.join("__init__.py"), "").unwrap();
+        std::fs::write(
+            package.join("types.py"),
+            PythonTypes::try_from((&ir, &args))
+                .unwrap()
+                .render()
+                .unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            package.join("partial_types.py"),
+            PythonStreamTypes::try_from((&ir, &args))
+                .unwrap()
+                .render()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let output = Command::new("python3")
+            .current_dir(&dir)
+            .args([
+                "-c",
+                r#"
+from baml_client import partial_types, types
+data = {"a": [1, "two", {"b": None}]}
+assert types.Doc.model_validate({"data": data}).data == data
+assert partial_types.Doc.model_validate({"data": data}).data == data
+"#,
+            ])
+            .output()
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
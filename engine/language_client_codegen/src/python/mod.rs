@@ -202,6 +202,7 @@ impl ToTypeReferenceInClientDefinition for FieldType {
             }
             FieldType::Literal(value) => to_python_literal(value),
             FieldType::Class(name) => format!("types.{name}"),
+            FieldType::RecursiveTypeAlias(name) => format!("types.{name}"),
             FieldType::List(inner) => format!("List[{}]", inner.to_type_ref(ir, with_checked)),
             FieldType::Map(key, value) => {
                 format!(
@@ -255,6 +256,7 @@ impl ToTypeReferenceInClientDefinition for FieldType {
                 }
             }
             FieldType::Class(name) => format!("partial_types.{name}"),
+            FieldType::RecursiveTypeAlias(name) => format!("partial_types.{name}"),
             FieldType::Literal(value) => to_python_literal(value),
             FieldType::List(inner) => {
                 format!("List[{}]", inner.to_partial_type_ref(ir, with_checked))
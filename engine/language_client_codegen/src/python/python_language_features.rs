@@ -38,6 +38,7 @@ impl ToPython for TypeValue {
             TypeValue::Null => "None",
             TypeValue::Media(BamlMediaType::Image) => "baml_py.Image",
             TypeValue::Media(BamlMediaType::Audio) => "baml_py.Audio",
+            TypeValue::Message => "typing.Dict[str, typing.Any]",
         }
         .to_string()
     }
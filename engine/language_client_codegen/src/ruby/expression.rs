@@ -51,6 +51,7 @@ impl ToRuby for TypeValue {
             TypeValue::Null => "null",
             TypeValue::Media(BamlMediaType::Image) => "Image",
             TypeValue::Media(BamlMediaType::Audio) => "Audio",
+            TypeValue::Message => "message",
         }
         .to_string()
     }
@@ -3,13 +3,16 @@ use baml_types::{BamlMediaType, FieldType, TypeValue};
 
 use crate::field_type_attributes;
 
-use super::ruby_language_features::ToRuby;
+use super::{ruby_constant, ruby_language_features::ToRuby};
 
 impl ToRuby for FieldType {
     fn to_ruby(&self) -> String {
         match self {
-            FieldType::Class(name) => format!("Baml::Types::{}", name.clone()),
-            FieldType::Enum(name) => format!("T.any(Baml::Types::{}, String)", name.clone()),
+            FieldType::Class(name) => format!("Baml::Types::{}", ruby_constant(name)),
+            FieldType::RecursiveTypeAlias(name) => {
+                format!("Baml::Types::{}", ruby_constant(name))
+            }
+            FieldType::Enum(name) => format!("T.any(Baml::Types::{}, String)", ruby_constant(name)),
             // TODO: Temporary solution until we figure out Ruby literals.
             FieldType::Literal(value) => value.literal_base_type().to_ruby(),
             // https://sorbet.org/docs/stdlib-generics
@@ -27,6 +30,7 @@ impl ToRuby for FieldType {
                 // TODO: Create Baml::Types::Image
                 TypeValue::Media(BamlMediaType::Image) => "Baml::Image",
                 TypeValue::Media(BamlMediaType::Audio) => "Baml::Audio",
+                TypeValue::Message => "T::Hash[String, T.untyped]",
             }
             .to_string(),
             FieldType::Union(inner) => format!(
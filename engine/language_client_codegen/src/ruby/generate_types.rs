@@ -2,18 +2,25 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 
 use anyhow::Result;
+use baml_types::{BamlValue, TypeValue};
 use itertools::Itertools;
 
-use crate::{field_type_attributes, type_check_attributes, TypeCheckAttributes};
+use crate::{
+    class_field_type, default_value_type, field_default, field_type_attributes, namespaces,
+    type_check_attributes, TypeCheckAttributes,
+};
 
-use super::ruby_language_features::ToRuby;
+use super::{ruby_constant, ruby_language_features::ToRuby};
 use internal_baml_core::ir::{repr::IntermediateRepr, ClassWalker, EnumWalker, FieldType};
 
 #[derive(askama::Template)]
 #[template(path = "types.rb.j2", escape = "none")]
 pub(crate) struct RubyTypes<'ir> {
+    /// The modules of every namespace, parents first.
+    namespaces: Vec<String>,
     enums: Vec<RubyEnum<'ir>>,
     classes: Vec<RubyStruct<'ir>>,
+    type_aliases: Vec<RubyTypeAlias<'ir>>,
 }
 
 struct RubyEnum<'ir> {
@@ -22,16 +29,56 @@ struct RubyEnum<'ir> {
     dynamic: bool,
 }
 
+impl RubyEnum<'_> {
+    /// The constant path of the enum within `Baml::Types`.
+    fn constant(&self) -> String {
+        ruby_constant(self.name)
+    }
+
+    /// A Ruby method name for the enum, used by the type builder.
+    fn ident(&self) -> String {
+        self.name.replace('.', "_")
+    }
+}
+
+struct RubyTypeAlias<'ir> {
+    name: &'ir str,
+    target: String,
+}
+
+impl RubyTypeAlias<'_> {
+    /// The constant path of the alias within its module.
+    fn constant(&self) -> String {
+        ruby_constant(self.name)
+    }
+}
+
 struct RubyStruct<'ir> {
     name: Cow<'ir, str>,
-    fields: Vec<(Cow<'ir, str>, String)>,
+    /// Name, type and the `default:` or `factory:` option of the field.
+    fields: Vec<(Cow<'ir, str>, String, Option<String>)>,
     dynamic: bool,
 }
 
+impl RubyStruct<'_> {
+    /// The constant path of the struct within `Baml::Types`.
+    fn constant(&self) -> String {
+        ruby_constant(&self.name)
+    }
+
+    /// A Ruby method name for the struct, used by the type builder.
+    fn ident(&self) -> String {
+        self.name.replace('.', "_")
+    }
+}
+
 #[derive(askama::Template)]
 #[template(path = "partial-types.rb.j2", escape = "none")]
 pub(crate) struct RubyStreamTypes<'ir> {
+    /// The modules of every namespace, parents first.
+    namespaces: Vec<String>,
     partial_classes: Vec<PartialRubyStruct<'ir>>,
+    partial_type_aliases: Vec<RubyTypeAlias<'ir>>,
 }
 
 /// The Python class corresponding to Partial<TypeDefinedjInBaml>
@@ -41,6 +88,23 @@ struct PartialRubyStruct<'ir> {
     fields: Vec<(&'ir str, String)>,
 }
 
+impl PartialRubyStruct<'_> {
+    /// The constant path of the struct within `Baml::PartialTypes`.
+    fn constant(&self) -> String {
+        ruby_constant(self.name)
+    }
+}
+
+/// The modules to declare for the namespaces of the given types, without the
+/// root namespace.
+fn namespace_modules<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    namespaces(names)
+        .into_iter()
+        .filter(|namespace| !namespace.is_empty())
+        .map(|namespace| ruby_constant(&namespace.join(".")))
+        .collect()
+}
+
 #[derive(askama::Template)]
 #[template(path = "type-registry.rb.j2", escape = "none")]
 pub(crate) struct TypeRegistry<'ir> {
@@ -53,8 +117,21 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for RubyTy
 
     fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
         Ok(RubyTypes {
+            namespaces: namespace_modules(
+                ir.walk_enums()
+                    .map(|e| e.name())
+                    .chain(ir.walk_classes().map(|c| c.name()))
+                    .chain(ir.walk_type_aliases().map(|alias| alias.name())),
+            ),
             enums: ir.walk_enums().map(|e| e.into()).collect(),
             classes: ir.walk_classes().map(|c| c.into()).collect(),
+            type_aliases: ir
+                .walk_type_aliases()
+                .map(|alias| RubyTypeAlias {
+                    name: alias.name(),
+                    target: alias.target().to_type_ref(),
+                })
+                .collect(),
         })
     }
 }
@@ -85,18 +162,108 @@ impl<'ir> From<ClassWalker<'ir>> for RubyStruct<'ir> {
                 .elem
                 .static_fields
                 .iter()
-                .map(|f| (Cow::Borrowed(f.elem.name.as_str()), f.elem.r#type.elem.to_type_ref()))
+                .map(|f| {
+                    (
+                        Cow::Borrowed(f.elem.name.as_str()),
+                        f.elem.r#type.elem.to_type_ref(),
+                        field_default(f)
+                            .map(|value| ruby_default(c.db, &value, &f.elem.r#type.elem)),
+                    )
+                })
                 .collect(),
         }
     }
 }
 
+/// Renders the `@default` of a field as a `T::Struct` prop option. Values
+/// that could be mutated, or that refer to other types, are built by a
+/// `factory` so that every instance gets its own.
+fn ruby_default(ir: &IntermediateRepr, value: &BamlValue, field_type: &FieldType) -> String {
+    let expr = to_ruby_value(ir, value, field_type);
+    match (default_value_type(ir, value, field_type), value) {
+        (FieldType::Enum(_), _)
+        | (_, BamlValue::String(_) | BamlValue::List(_) | BamlValue::Map(_)) => {
+            format!("factory: -> {{ {expr} }}")
+        }
+        _ => format!("default: {expr}"),
+    }
+}
+
+/// Renders a `@default` value as a Ruby expression of type `field_type`.
+fn to_ruby_value(ir: &IntermediateRepr, value: &BamlValue, field_type: &FieldType) -> String {
+    match (default_value_type(ir, value, field_type), value) {
+        (_, BamlValue::Null) => "nil".to_string(),
+        (_, BamlValue::Bool(b)) => b.to_string(),
+        (FieldType::Primitive(TypeValue::Float), BamlValue::Int(i)) => format!("{i}.0"),
+        (_, BamlValue::Int(i)) => i.to_string(),
+        (_, BamlValue::Float(f)) => format!("{f:?}"),
+        (FieldType::Enum(name), BamlValue::String(v)) => {
+            format!("Baml::Types::{}::{v}", ruby_constant(name))
+        }
+        (_, BamlValue::String(s)) => ruby_string(s),
+        (FieldType::Class(name), BamlValue::Map(fields)) => format!(
+            "Baml::Types::{}.new({})",
+            ruby_constant(name),
+            fields
+                .iter()
+                .filter_map(|(k, v)| {
+                    let field_type = class_field_type(ir, name, k)?;
+                    Some(format!("{k}: {}", to_ruby_value(ir, v, field_type)))
+                })
+                .join(", ")
+        ),
+        (FieldType::Tuple(item_types), BamlValue::List(items)) => format!(
+            "[{}]",
+            item_types
+                .iter()
+                .zip(items)
+                .map(|(item_type, item)| to_ruby_value(ir, item, item_type))
+                .join(", ")
+        ),
+        (FieldType::List(item_type), BamlValue::List(items)) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| to_ruby_value(ir, item, item_type))
+                .join(", ")
+        ),
+        (FieldType::Map(_, value_type), BamlValue::Map(entries)) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|(k, v)| format!("{} => {}", ruby_string(k), to_ruby_value(ir, v, value_type)))
+                .join(", ")
+        ),
+        // `@default` is validated against the field type, so nothing else
+        // can show up here.
+        _ => "nil".to_string(),
+    }
+}
+
+/// A double quoted Ruby string literal. `#` is escaped so that the value is
+/// never interpolated.
+fn ruby_string(s: &str) -> String {
+    format!("{s:?}").replace('#', "\\#")
+}
+
 impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for RubyStreamTypes<'ir> {
     type Error = anyhow::Error;
 
     fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
         Ok(RubyStreamTypes {
+            namespaces: namespace_modules(
+                ir.walk_classes()
+                    .map(|c| c.name())
+                    .chain(ir.walk_type_aliases().map(|alias| alias.name())),
+            ),
             partial_classes: ir.walk_classes().map(|c| c.into()).collect(),
+            partial_type_aliases: ir
+                .walk_type_aliases()
+                .map(|alias| RubyTypeAlias {
+                    name: alias.name(),
+                    target: alias.target().to_partial_type_ref(),
+                })
+                .collect(),
         })
     }
 }
@@ -134,8 +301,11 @@ impl ToTypeReferenceInTypeDefinition for FieldType {
 
     fn to_partial_type_ref(&self) -> String {
         match self {
-            FieldType::Class(name) => format!("Baml::PartialTypes::{}", name.clone()),
-            FieldType::Enum(name) => format!("T.nilable(Baml::Types::{})", name.clone()),
+            FieldType::Class(name) => format!("Baml::PartialTypes::{}", ruby_constant(name)),
+            FieldType::RecursiveTypeAlias(name) => {
+                format!("Baml::PartialTypes::{}", ruby_constant(name))
+            }
+            FieldType::Enum(name) => format!("T.nilable(Baml::Types::{})", ruby_constant(name)),
             // TODO: Temporary solution until we figure out Ruby literals.
             FieldType::Literal(value) => value.literal_base_type().to_partial_type_ref(),
             // https://sorbet.org/docs/stdlib-generics
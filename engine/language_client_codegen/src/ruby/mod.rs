@@ -35,6 +35,22 @@ struct InlinedBaml {
     file_map: Vec<(String, String)>,
 }
 
+/// The Ruby constant path of a class, enum or type alias relative to
+/// `Baml::Types`. Namespaces become modules, so `billing.Invoice` is
+/// `Billing::Invoice`.
+fn ruby_constant(name: &str) -> String {
+    name.split('.')
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
 pub(crate) fn generate(
     ir: &IntermediateRepr,
     generator: &crate::GeneratorArgs,
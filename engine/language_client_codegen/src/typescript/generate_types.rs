@@ -5,7 +5,10 @@ use itertools::Itertools;
 
 use internal_baml_core::ir::{repr::IntermediateRepr, ClassWalker, EnumWalker};
 
-use crate::{type_check_attributes, GeneratorArgs, TypeCheckAttributes};
+use crate::{
+    field_default, namespaces, split_namespace, type_check_attributes, GeneratorArgs,
+    TypeCheckAttributes,
+};
 
 use super::ToTypeReferenceInClientDefinition;
 
@@ -19,8 +22,19 @@ pub(crate) struct TypeBuilder<'ir> {
 #[derive(askama::Template)]
 #[template(path = "types.ts.j2", escape = "none")]
 pub(crate) struct TypescriptTypes<'ir> {
+    namespaces: Vec<TypescriptNamespace<'ir>>,
+}
+
+/// The types declared in a namespace, which is rendered as a TypeScript
+/// `namespace` so that `billing.Invoice` can be used as is.
+struct TypescriptNamespace<'ir> {
+    /// The dotted name of the namespace. Empty for the root namespace.
+    name: String,
+    /// The indentation of the types declared in the namespace.
+    indent: &'static str,
     enums: Vec<TypescriptEnum<'ir>>,
     classes: Vec<TypescriptClass<'ir>>,
+    type_aliases: Vec<TypescriptTypeAlias<'ir>>,
 }
 
 struct TypescriptEnum<'ir> {
@@ -29,28 +43,92 @@ struct TypescriptEnum<'ir> {
     pub dynamic: bool,
 }
 
+impl TypescriptEnum<'_> {
+    /// The name of the enum within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(self.name).1
+    }
+
+    /// A TypeScript identifier for the enum, used to name its type builder.
+    fn ident(&self) -> String {
+        self.name.replace('.', "_")
+    }
+}
+
+struct TypescriptTypeAlias<'ir> {
+    name: &'ir str,
+    target: String,
+}
+
+impl TypescriptTypeAlias<'_> {
+    /// The name of the alias within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(self.name).1
+    }
+}
+
 pub struct TypescriptClass<'ir> {
     pub name: Cow<'ir, str>,
-    pub fields: Vec<(Cow<'ir, str>, bool, String)>,
+    /// Name, whether the field is optional, type and the JSON of its
+    /// `@default` value.
+    pub fields: Vec<(Cow<'ir, str>, bool, String, Option<String>)>,
     pub dynamic: bool,
 }
 
+impl TypescriptClass<'_> {
+    /// The name of the class within its namespace.
+    fn short_name(&self) -> &str {
+        split_namespace(&self.name).1
+    }
+
+    /// A TypeScript identifier for the class, used to name its type builder.
+    fn ident(&self) -> String {
+        self.name.replace('.', "_")
+    }
+}
+
 impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir GeneratorArgs)> for TypescriptTypes<'ir> {
     type Error = anyhow::Error;
 
     fn try_from(
         (ir, _): (&'ir IntermediateRepr, &'ir GeneratorArgs),
     ) -> Result<TypescriptTypes<'ir>> {
-        Ok(TypescriptTypes {
-            enums: ir
-                .walk_enums()
-                .map(|e| Into::<TypescriptEnum>::into(&e))
-                .collect::<Vec<_>>(),
-            classes: ir
-                .walk_classes()
-                .map(|e| Into::<TypescriptClass>::into(&e))
-                .collect::<Vec<_>>(),
-        })
+        let in_namespace = |name: &str, namespace: &[&str]| split_namespace(name).0 == namespace;
+        let names = ir
+            .walk_enums()
+            .map(|e| e.name())
+            .chain(ir.walk_classes().map(|c| c.name()))
+            .chain(ir.walk_type_aliases().map(|alias| alias.name()));
+
+        // TypeScript allows `namespace billing.tax {}`, so namespaces don't
+        // have to be nested in their parents.
+        let namespaces = namespaces(names)
+            .into_iter()
+            .map(|namespace| TypescriptNamespace {
+                name: namespace.join("."),
+                indent: if namespace.is_empty() { "" } else { "  " },
+                enums: ir
+                    .walk_enums()
+                    .filter(|e| in_namespace(e.name(), &namespace))
+                    .map(|e| Into::<TypescriptEnum>::into(&e))
+                    .collect::<Vec<_>>(),
+                classes: ir
+                    .walk_classes()
+                    .filter(|c| in_namespace(c.name(), &namespace))
+                    .map(|e| Into::<TypescriptClass>::into(&e))
+                    .collect::<Vec<_>>(),
+                type_aliases: ir
+                    .walk_type_aliases()
+                    .filter(|alias| in_namespace(alias.name(), &namespace))
+                    .map(|alias| TypescriptTypeAlias {
+                        name: alias.name(),
+                        target: alias.target().to_type_ref(ir),
+                    })
+                    .collect::<Vec<_>>(),
+            })
+            .collect();
+
+        Ok(TypescriptTypes { namespaces })
     }
 }
 
@@ -102,6 +180,7 @@ impl<'ir> From<&ClassWalker<'ir>> for TypescriptClass<'ir> {
                         Cow::Borrowed(f.elem.name.as_str()),
                         f.elem.r#type.elem.is_optional(),
                         f.elem.r#type.elem.to_type_ref(&c.db),
+                        field_default(f).and_then(|value| serde_json::to_string(&value).ok()),
                     )
                 })
                 .collect(),
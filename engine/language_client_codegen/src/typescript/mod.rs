@@ -10,6 +10,7 @@ use internal_baml_core::{
     configuration::GeneratorDefaultClientMode,
     ir::{repr::IntermediateRepr, FieldType, IRHelper},
 };
+use itertools::Itertools;
 
 use self::typescript_language_features::{ToTypescript, TypescriptLanguageFeatures};
 use crate::{dir_writer::FileCollector, field_type_attributes};
@@ -153,10 +154,15 @@ impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for TypescriptCli
             .flatten()
             .collect();
 
+        // Namespaced types are reached through the namespace they are
+        // declared in, e.g. `billing.Invoice`, so that is what gets imported.
         let types = ir
             .walk_classes()
-            .map(|c| c.name().to_string())
-            .chain(ir.walk_enums().map(|e| e.name().to_string()))
+            .map(|c| c.name())
+            .chain(ir.walk_enums().map(|e| e.name()))
+            .chain(ir.walk_type_aliases().map(|a| a.name()))
+            .map(|name| name.split('.').next().unwrap_or(name).to_string())
+            .unique()
             .collect();
         Ok(TypescriptClient {
             funcs: functions,
@@ -266,6 +272,7 @@ impl ToTypeReferenceInClientDefinition for FieldType {
                 }
             }
             FieldType::Class(name) => format!("{name}"),
+            FieldType::RecursiveTypeAlias(name) => format!("{name}"),
             FieldType::List(inner) => match inner.as_ref() {
                 FieldType::Union(_) | FieldType::Optional(_) => {
                     format!("({})[]", inner.to_type_ref(ir))
@@ -40,6 +40,7 @@ impl ToTypescript for TypeValue {
             TypeValue::Null => "null",
             TypeValue::Media(BamlMediaType::Image) => "Image",
             TypeValue::Media(BamlMediaType::Audio) => "Audio",
+            TypeValue::Message => "{ role: string, content: string | (string | Image | Audio)[] }",
         };
         var_name.to_string()
     }
@@ -82,6 +82,7 @@ impl BamlError {
                     baml_runtime::internal::llm_client::ErrorCode::Other(_)
                     | baml_runtime::internal::llm_client::ErrorCode::InvalidAuthentication
                     | baml_runtime::internal::llm_client::ErrorCode::NotSupported
+                    | baml_runtime::internal::llm_client::ErrorCode::Timeout
                     | baml_runtime::internal::llm_client::ErrorCode::RateLimited
                     | baml_runtime::internal::llm_client::ErrorCode::ServerError
                     | baml_runtime::internal::llm_client::ErrorCode::ServiceUnavailable
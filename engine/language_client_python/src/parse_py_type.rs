@@ -5,7 +5,7 @@ use baml_types::{BamlMap, BamlValue};
 use pyo3::{
     exceptions::{PyRuntimeError, PyTypeError},
     prelude::{PyAnyMethods, PyTypeMethods},
-    types::{PyBool, PyBoolMethods, PyDict, PyList},
+    types::{PyBool, PyBoolMethods, PyDict, PyList, PyTuple},
     PyErr, PyObject, PyResult, Python, ToPyObject,
 };
 
@@ -227,7 +227,7 @@ pub fn parse_py_type(
 
             if t.is_subclass(&enum_type).unwrap_or(false) {
                 let name = t
-                    .name()
+                    .qualname()
                     .map(|n| {
                         if let Some(x) = n.rfind("baml_client.types.") {
                             n[x + "baml_client.types.".len()..].to_string()
@@ -241,7 +241,7 @@ pub fn parse_py_type(
                 Ok(MappedPyType::Enum(name, value))
             } else if t.is_subclass(&base_model).unwrap_or(false) {
                 let name = t
-                    .name()
+                    .qualname()
                     .map(|n| {
                         if let Some(x) = n.rfind("baml_client.types.") {
                             n[x + "baml_client.types.".len()..].to_string()
@@ -293,6 +293,14 @@ pub fn parse_py_type(
                     items.push(list.get_item(idx)?.to_object(py));
                 }
                 Ok(MappedPyType::List(items))
+            } else if let Ok(tuple) = any.downcast_bound::<PyTuple>(py) {
+                // Tuples are passed to BAML as lists, like they are in JSON.
+                let mut items = vec![];
+                let len = tuple.len()?;
+                for idx in 0..len {
+                    items.push(tuple.get_item(idx)?.to_object(py));
+                }
+                Ok(MappedPyType::List(items))
             } else if let Ok(kv) = any.extract::<HashMap<String, PyObject>>(py) {
                 Ok(MappedPyType::Map(kv))
             } else if let Ok(b) = any.downcast_bound::<PyBool>(py) {
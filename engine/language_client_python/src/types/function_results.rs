@@ -44,6 +44,15 @@ impl FunctionResult {
     }
 }
 
+/// Looks up a class or enum in a generated module. Namespaced types are nested
+/// in a class per namespace, so `billing.Invoice` is `module.billing.Invoice`.
+fn generated_type<'py>(module: &Bound<'py, PyModule>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    name.split('.')
+        .try_fold(module.as_any().clone(), |parent, segment| {
+            parent.getattr(segment)
+        })
+}
+
 fn pythonize_checks<'a>(
     py: Python<'a>,
     types_module: &Bound<'_, PyModule>,
@@ -100,8 +109,25 @@ fn pythonize_strict(
                 Ok(BamlAudioPy::from(baml_media.clone()).into_py(py))
             }
         },
+        BamlValueWithMeta::Message(message, _) => {
+            let content = message
+                .content
+                .into_iter()
+                .map(|part| match part {
+                    baml_types::BamlMessagePart::Text(text) => text.into_py(py),
+                    baml_types::BamlMessagePart::Media(media) => match media.media_type {
+                        baml_types::BamlMediaType::Image => BamlImagePy::from(media).into_py(py),
+                        baml_types::BamlMediaType::Audio => BamlAudioPy::from(media).into_py(py),
+                    },
+                })
+                .collect::<Vec<_>>();
+            let dict = pyo3::types::PyDict::new_bound(py);
+            dict.set_item("role", message.role)?;
+            dict.set_item("content", pyo3::types::PyList::new_bound(py, content))?;
+            Ok(dict.into())
+        }
         BamlValueWithMeta::Enum(enum_name, ref value, _) => {
-            let enum_type = match enum_module.getattr(enum_name.as_str()) {
+            let enum_type = match generated_type(enum_module, enum_name.as_str()) {
                 Ok(e) => e,
                 // This can be true in the case of dynamic types.
                 /*
@@ -157,7 +183,7 @@ fn pythonize_strict(
                 }
             }
 
-            let class_type = match cls_module.getattr(class_name.as_str()) {
+            let class_type = match generated_type(cls_module, class_name.as_str()) {
                 Ok(class) => class,
                 // This can be true in the case of dynamic types.
                 /*
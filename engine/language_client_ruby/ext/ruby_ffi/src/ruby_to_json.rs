@@ -11,6 +11,65 @@ use crate::types::{
     media::{Audio, Image},
 };
 
+/// The constant path of a generated type relative to `Baml::Types`. Namespaces
+/// are modules, so `billing.Invoice` is `Billing::Invoice`.
+fn ruby_constant(name: &str) -> String {
+    name.split('.')
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// The inverse of [`ruby_constant`] for a fully qualified Ruby class name.
+/// Namespaces always start with a lowercase letter in BAML.
+fn baml_type_name(ruby_name: &str) -> String {
+    let Some(relative) = ruby_name
+        .strip_prefix("Baml::Types::")
+        .or_else(|| ruby_name.strip_prefix("Baml::PartialTypes::"))
+    else {
+        return match ruby_name.rsplit_once("::") {
+            Some((_, class_name)) => class_name.to_string(),
+            None => ruby_name.to_string(),
+        };
+    };
+
+    let mut segments = relative.split("::").map(str::to_string).collect::<Vec<_>>();
+    let name = segments.pop().unwrap_or_default();
+    segments
+        .into_iter()
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Looks up the generated type for a BAML name in `Baml::Types`. `const_get`
+/// only resolves a single constant, so the modules of the namespace are
+/// walked one segment at a time.
+fn find_type(types: RModule, name: &str) -> Result<RClass, Error> {
+    let constant = ruby_constant(name);
+    let mut segments = constant.split("::").collect::<Vec<_>>();
+    let type_name = segments.pop().unwrap_or_default();
+
+    let mut module = types;
+    for segment in segments {
+        module = module.const_get::<_, RModule>(segment)?;
+    }
+    module.const_get::<_, RClass>(type_name)
+}
+
 struct SerializationError {
     position: Vec<String>,
     message: String,
@@ -75,7 +134,7 @@ impl<'rb> RubyToJson<'rb> {
                         let v = RubyToJson::serialize_baml(ruby, types, v)?;
                         hash.aset(k, v)?;
                     }
-                    match types.const_get::<_, RClass>(class_name.as_str()) {
+                    match find_type(types, &class_name) {
                         Ok(class_type) => class_type.funcall("new", (hash,)),
                         Err(_) => {
                             let dynamic_class_type = ruby.eval::<RClass>("Baml::DynamicStruct")?;
@@ -84,7 +143,7 @@ impl<'rb> RubyToJson<'rb> {
                     }
                 }
                 BamlValueWithMeta::Enum(enum_name, enum_value, _) => {
-                    if let Ok(enum_type) = types.const_get::<_, RClass>(enum_name.as_str()) {
+                    if let Ok(enum_type) = find_type(types, &enum_name) {
                         let enum_value = ruby.str_new(&enum_value);
                         if let Ok(enum_instance) = enum_type.funcall("deserialize", (enum_value,)) {
                             return Ok(enum_instance);
@@ -486,11 +545,7 @@ impl<'rb> RubyToJson<'rb> {
         }
 
         let fully_qualified_class_name = unsafe { any.class().name() }.into_owned();
-        let class_name = match fully_qualified_class_name.rsplit_once("::") {
-            Some((_, class_name)) => class_name.to_string(),
-            None => fully_qualified_class_name,
-        };
-        Ok(BamlValue::Class(class_name, map))
+        Ok(BamlValue::Class(baml_type_name(&fully_qualified_class_name), map))
 
         //Ok(BamlValue::Map(map))
     }
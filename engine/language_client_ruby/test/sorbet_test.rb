@@ -47,6 +47,14 @@ module Baml
       const :foo_or_bar, T.any(Foo, Bar)
       const :color, Color
     end
+
+    module Billing
+      class Invoice < T::Struct
+        prepend Baml::Serializable
+
+        const :total, Integer
+      end
+    end
   end
 end
 
@@ -67,6 +75,12 @@ describe "learning ruby and sorbet" do
     assert_equal(foo.serialize.foo, 1)
   end
 
+  it "serializes a namespaced struct into its own class" do
+    invoice = Baml::Types::Billing::Invoice.new(total: 1).serialize
+    assert_instance_of(Baml::Types::Billing::Invoice, invoice)
+    assert_equal(invoice.total, 1)
+  end
+
   it "forwards each correctly" do
     class FakeStream
       include Enumerable
@@ -40,6 +40,7 @@ pub fn from_anyhow_error(err: anyhow::Error) -> napi::Error {
                 baml_runtime::internal::llm_client::ErrorCode::Other(_)
                 | baml_runtime::internal::llm_client::ErrorCode::InvalidAuthentication
                 | baml_runtime::internal::llm_client::ErrorCode::NotSupported
+                | baml_runtime::internal::llm_client::ErrorCode::Timeout
                 | baml_runtime::internal::llm_client::ErrorCode::RateLimited
                 | baml_runtime::internal::llm_client::ErrorCode::ServerError
                 | baml_runtime::internal::llm_client::ErrorCode::ServiceUnavailable
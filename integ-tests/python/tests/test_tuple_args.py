@@ -0,0 +1,32 @@
+import types
+
+import baml_py
+
+BAML_SRC = {
+    "baml_src/main.baml": '''
+client<llm> Echo {
+  provider mock
+  options {
+    template #"{{ last_message }}"#
+  }
+}
+
+function DescribePair(pair: (int, string)) -> string {
+  client Echo
+  prompt #"{{ pair[0] }} is {{ pair[1] }}"#
+}
+''',
+}
+
+
+def test_tuple_args_are_passed_as_lists():
+    runtime = baml_py.BamlRuntime.from_files("baml_src", BAML_SRC, {})
+    ctx = runtime.create_context_manager()
+
+    result = runtime.call_function_sync(
+        "DescribePair", {"pair": (1, "one")}, ctx, None, None
+    )
+
+    # A string result doesn't need the generated modules to be cast.
+    empty = types.ModuleType("empty")
+    assert result.cast_to(empty, empty) == "1 is one"